@@ -1,10 +1,22 @@
 use crate::ai::Summary;
 use crate::config::Config;
 use crate::error::Result;
+use crate::git::diff_cache::DiffStatCache;
+use crate::git::github_cache::GitHubResponseCache;
 use chrono::{DateTime, Duration, Utc};
 use sled::Db;
 use std::path::Path;
 
+/// sled tree holding per-commit diff stats
+const DIFF_STATS_TREE: &str = "diff_stats";
+
+/// sled tree holding GitHub API responses and their ETags
+const GITHUB_TREE: &str = "github_responses";
+
+/// sled tree holding the most recent summary of each repository, with the
+/// commits it covers
+const LATEST_TREE: &str = "latest_summaries";
+
 /// Cache for AI-generated summaries
 pub struct SummaryCache {
     db: Db,
@@ -29,6 +41,16 @@ impl SummaryCache {
         Self::new(&cache_dir, config.cache_ttl_hours)
     }
 
+    /// Per-commit diff stats stored alongside the summaries
+    pub fn diff_stat_cache(&self) -> Result<DiffStatCache> {
+        Ok(DiffStatCache::new(self.db.open_tree(DIFF_STATS_TREE)?))
+    }
+
+    /// GitHub API responses stored alongside the summaries
+    pub fn github_cache(&self) -> Result<GitHubResponseCache> {
+        Ok(GitHubResponseCache::new(self.db.open_tree(GITHUB_TREE)?))
+    }
+
     /// Generate a cache key from repository path and commit hashes
     pub fn generate_key(repo_path: &str, commit_hashes: &[String]) -> String {
         use std::collections::hash_map::DefaultHasher;
@@ -75,6 +97,33 @@ impl SummaryCache {
         Ok(())
     }
 
+    /// Remember `summary` as the most recent one under `key` (a repository
+    /// and its prompt settings), generated from `commits`
+    pub fn set_latest(&self, key: &str, commits: &[String], summary: &Summary) -> Result<()> {
+        let latest = LatestSummary {
+            commits: commits.to_vec(),
+            summary: summary.clone(),
+            cached_at: Utc::now(),
+        };
+        self.db.open_tree(LATEST_TREE)?.insert(key, serde_json::to_vec(&latest)?)?;
+        Ok(())
+    }
+
+    /// The most recent summary under `key` that hasn't expired, with
+    /// `new_commits` set to how many of `commits` it doesn't cover
+    pub fn latest(&self, key: &str, commits: &[String]) -> Result<Option<Summary>> {
+        let Some(data) = self.db.open_tree(LATEST_TREE)?.get(key)? else {
+            return Ok(None);
+        };
+        let latest: LatestSummary = serde_json::from_slice(&data)?;
+        if self.is_expired(&latest.cached_at) {
+            return Ok(None);
+        }
+        let mut summary = latest.summary;
+        summary.new_commits = commits.iter().filter(|hash| !latest.commits.contains(hash)).count();
+        Ok(Some(summary))
+    }
+
     /// Check if a cache entry is expired
     fn is_expired(&self, cached_at: &DateTime<Utc>) -> bool {
         let now = Utc::now();
@@ -82,10 +131,12 @@ impl SummaryCache {
         now - *cached_at > ttl
     }
 
-    /// Clear all cache entries
-    #[allow(dead_code)]
+    /// Clear all cache entries (summaries, diff stats and GitHub responses)
     pub fn clear(&self) -> Result<()> {
         self.db.clear()?;
+        self.db.open_tree(DIFF_STATS_TREE)?.clear()?;
+        self.db.open_tree(GITHUB_TREE)?.clear()?;
+        self.db.open_tree(LATEST_TREE)?.clear()?;
         self.db.flush()?;
         Ok(())
     }
@@ -93,16 +144,23 @@ impl SummaryCache {
     /// Get cache statistics
     pub fn stats(&self) -> CacheStats {
         let total_entries = self.db.len();
+        let diff_stat_entries = self
+            .db
+            .open_tree(DIFF_STATS_TREE)
+            .map(|tree| tree.len())
+            .unwrap_or(0);
+        let github_entries = self.db.open_tree(GITHUB_TREE).map(|tree| tree.len()).unwrap_or(0);
         let db_size = self.db.size_on_disk().unwrap_or(0);
 
         CacheStats {
             total_entries,
+            diff_stat_entries,
+            github_entries,
             db_size_bytes: db_size,
         }
     }
 
     /// Remove expired entries
-    #[allow(dead_code)]
     pub fn cleanup_expired(&self) -> Result<usize> {
         let mut removed = 0;
 
@@ -129,10 +187,21 @@ struct CachedSummary {
     cached_at: DateTime<Utc>,
 }
 
+/// Most recent summary of a repository
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+struct LatestSummary {
+    /// Hashes of the commits it was generated from
+    commits: Vec<String>,
+    summary: Summary,
+    cached_at: DateTime<Utc>,
+}
+
 /// Cache statistics
 #[derive(Debug)]
 pub struct CacheStats {
     pub total_entries: usize,
+    pub diff_stat_entries: usize,
+    pub github_entries: usize,
     pub db_size_bytes: u64,
 }
 
@@ -165,9 +234,9 @@ mod tests {
 
     #[test]
     fn test_cache_key_generation() {
-        let key1 = SummaryCache::generate_key("/path/to/repo", &vec!["abc123".to_string()]);
-        let key2 = SummaryCache::generate_key("/path/to/repo", &vec!["abc123".to_string()]);
-        let key3 = SummaryCache::generate_key("/path/to/repo", &vec!["def456".to_string()]);
+        let key1 = SummaryCache::generate_key("/path/to/repo", &["abc123".to_string()]);
+        let key2 = SummaryCache::generate_key("/path/to/repo", &["abc123".to_string()]);
+        let key3 = SummaryCache::generate_key("/path/to/repo", &["def456".to_string()]);
 
         // Same inputs should produce same key
         assert_eq!(key1, key2);
@@ -217,6 +286,28 @@ mod tests {
         assert!(retrieved.is_none());
     }
 
+    #[test]
+    fn test_cache_latest() {
+        let temp_dir = TempDir::new().unwrap();
+        let cache = SummaryCache::new(temp_dir.path(), 24).unwrap();
+        let summary = Summary::new("api".to_string(), "Test".to_string(), vec![], vec![]);
+        let hashes = |names: &[&str]| names.iter().map(|name| name.to_string()).collect::<Vec<_>>();
+
+        assert!(cache.latest("api", &hashes(&["a"])).unwrap().is_none());
+        cache.set_latest("api", &hashes(&["a", "b"]), &summary).unwrap();
+
+        let current = cache.latest("api", &hashes(&["a", "b"])).unwrap().unwrap();
+        assert_eq!(current.new_commits, 0);
+        let stale = cache.latest("api", &hashes(&["a", "b", "c", "d", "e"])).unwrap().unwrap();
+        assert_eq!(stale.new_commits, 3);
+        assert!(stale
+            .to_markdown()
+            .contains("includes 3 new commits not in this cached summary, shown because summarizing failed; rerun to refresh it"));
+
+        cache.clear().unwrap();
+        assert!(cache.latest("api", &hashes(&["a"])).unwrap().is_none());
+    }
+
     #[test]
     fn test_cache_clear() {
         let temp_dir = TempDir::new().unwrap();
@@ -232,13 +323,33 @@ mod tests {
         cache.set("key1", summary.clone()).unwrap();
         cache.set("key2", summary).unwrap();
 
+        let oid = git2::Oid::from_str("0123456789abcdef0123456789abcdef01234567").unwrap();
+        let diff_stats = crate::git::diff_cache::DiffStats {
+            files_changed: vec![],
+            file_churn: vec![],
+            insertions: 1,
+            deletions: 0,
+            patch_id: None,
+        };
+        cache.diff_stat_cache().unwrap().set(oid, &diff_stats).unwrap();
+        let response = crate::git::github_cache::CachedResponse {
+            etag: "\"abc\"".to_string(),
+            body: "{}".to_string(),
+            next: None,
+        };
+        cache.github_cache().unwrap().set("https://api.github.com/x", &response).unwrap();
+
         let stats = cache.stats();
         assert_eq!(stats.total_entries, 2);
+        assert_eq!(stats.diff_stat_entries, 1);
+        assert_eq!(stats.github_entries, 1);
 
         cache.clear().unwrap();
 
         let stats = cache.stats();
         assert_eq!(stats.total_entries, 0);
+        assert_eq!(stats.diff_stat_entries, 0);
+        assert_eq!(stats.github_entries, 0);
     }
 
     #[test]
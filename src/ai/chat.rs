@@ -0,0 +1,79 @@
+//! Follow-up questions about a finished recap (`--chat`)
+//!
+//! The recap and the context of every summarized repository stay in the
+//! system prompt; each question is sent along with the earlier turns, so
+//! "make achievement 2 more concrete" refers to what was already said.
+
+use crate::ai::claude::Message;
+
+/// Question/answer pairs sent with each question; older ones are dropped
+/// so a long conversation doesn't keep growing the prompt
+const MAX_TURNS: usize = 20;
+
+/// A conversation about one recap
+#[derive(Debug, Clone)]
+pub struct Chat {
+    system: String,
+    turns: Vec<(String, String)>,
+}
+
+impl Chat {
+    /// Start a conversation with `system` as its system prompt
+    pub fn new(system: String) -> Self {
+        Self {
+            system,
+            turns: Vec::new(),
+        }
+    }
+
+    /// The system prompt
+    pub fn system(&self) -> &str {
+        &self.system
+    }
+
+    /// The recent turns followed by `question`
+    pub fn messages(&self, question: &str) -> Vec<Message> {
+        let skipped = self.turns.len().saturating_sub(MAX_TURNS);
+        let mut messages = Vec::new();
+        for (asked, answer) in &self.turns[skipped..] {
+            messages.push(Message::user(asked.as_str()));
+            messages.push(Message::assistant(answer.as_str()));
+        }
+        messages.push(Message::user(question));
+        messages
+    }
+
+    /// Record an answered question
+    pub fn record(&mut self, question: String, answer: String) {
+        self.turns.push((question, answer));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_messages() {
+        let mut chat = Chat::new("recap".to_string());
+        assert_eq!(chat.messages("Expand on the auth work"), vec![Message::user("Expand on the auth work")]);
+
+        chat.record("Expand on the auth work".to_string(), "The auth work...".to_string());
+        assert_eq!(
+            chat.messages("Shorter, please"),
+            vec![
+                Message::user("Expand on the auth work"),
+                Message::assistant("The auth work..."),
+                Message::user("Shorter, please"),
+            ]
+        );
+
+        // Only the most recent turns are kept
+        for i in 0..MAX_TURNS {
+            chat.record(format!("question {}", i), format!("answer {}", i));
+        }
+        let messages = chat.messages("last");
+        assert_eq!(messages.len(), MAX_TURNS * 2 + 1);
+        assert_eq!(messages[0], Message::user("question 0"));
+    }
+}
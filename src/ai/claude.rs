@@ -1,17 +1,18 @@
+use crate::ai::provider::Provider;
 use crate::error::{DevRecapError, Result};
-use reqwest::Client;
+use reqwest::{Client, RequestBuilder};
 use serde::{Deserialize, Serialize};
 use std::time::Duration;
 
-const DEFAULT_BASE_URL: &str = "https://api.anthropic.com";
 const CLAUDE_VERSION: &str = "2023-06-01";
-const DEFAULT_MODEL: &str = "claude-sonnet-4-5-20250929";
 const DEFAULT_MAX_TOKENS: u32 = 4096;
 
-/// Claude API client
+/// Claude API client, or the client of another provider's chat API
 pub struct ClaudeClient {
+    provider: Provider,
     api_key: String,
     api_url: String,
+    models_url: String,
     client: Client,
     model: String,
     max_tokens: u32,
@@ -19,7 +20,6 @@ pub struct ClaudeClient {
 
 impl ClaudeClient {
     /// Create a new Claude API client
-    #[allow(dead_code)]
     pub fn new(api_key: String) -> Result<Self> {
         Self::with_base_url(api_key, None, None)
     }
@@ -31,73 +31,188 @@ impl ClaudeClient {
         api_key: String,
         base_url: Option<String>,
         model: Option<String>,
+    ) -> Result<Self> {
+        Self::for_provider(Provider::Anthropic, api_key, base_url, model)
+    }
+
+    /// Create a client for `provider`, with its default base URL and model
+    /// unless given; OpenAI and Ollama get "/v1/chat/completions" appended
+    ///
+    /// A base URL already ending in `/v1` (as `OPENAI_BASE_URL` usually does)
+    /// doesn't get a second one.
+    pub fn for_provider(
+        provider: Provider,
+        api_key: String,
+        base_url: Option<String>,
+        model: Option<String>,
     ) -> Result<Self> {
         let client = Client::builder()
             .timeout(Duration::from_secs(120))
             .build()?;
 
         // Construct the full messages endpoint URL
-        let base = base_url.unwrap_or_else(|| DEFAULT_BASE_URL.to_string());
-        let api_url = format!("{}/v1/messages", base.trim_end_matches('/'));
+        let base = base_url.unwrap_or_else(|| provider.default_base_url().to_string());
+        let base = base.trim_end_matches('/');
+        let base = base.strip_suffix("/v1").unwrap_or(base);
+        let endpoint = match provider {
+            Provider::Anthropic => "v1/messages",
+            Provider::OpenAI | Provider::Ollama => "v1/chat/completions",
+        };
+        let api_url = format!("{}/{}", base, endpoint);
+        let models_url = format!("{}/v1/models", base);
 
         Ok(Self {
+            provider,
             api_key,
             api_url,
+            models_url,
             client,
-            model: model.unwrap_or_else(|| DEFAULT_MODEL.to_string()),
+            model: model.unwrap_or_else(|| provider.default_model().to_string()),
             max_tokens: DEFAULT_MAX_TOKENS,
         })
     }
 
     /// Set the model to use
-    #[allow(dead_code)]
     pub fn with_model(mut self, model: String) -> Self {
         self.model = model;
         self
     }
 
     /// Set max tokens
-    #[allow(dead_code)]
     pub fn with_max_tokens(mut self, max_tokens: u32) -> Self {
         self.max_tokens = max_tokens;
         self
     }
 
+    /// Get the model requests are sent to
+    pub fn model(&self) -> &str {
+        &self.model
+    }
+
+    /// Get the provider requests are sent to
+    pub fn provider(&self) -> Provider {
+        self.provider
+    }
+
+    /// Add the provider's authentication headers to a request
+    fn authorize(&self, request: RequestBuilder) -> RequestBuilder {
+        match self.provider {
+            Provider::Anthropic => request
+                .header("x-api-key", &self.api_key)
+                .header("anthropic-version", CLAUDE_VERSION),
+            // A local Ollama takes no key
+            _ if self.api_key.is_empty() => request,
+            Provider::OpenAI | Provider::Ollama => request.bearer_auth(&self.api_key),
+        }
+    }
+
+    /// Models the endpoint offers (`GET /v1/models`), following pagination
+    pub async fn list_models(&self) -> Result<Vec<ModelInfo>> {
+        let mut models = Vec::new();
+        let mut after_id: Option<String> = None;
+
+        loop {
+            let mut request = self
+                .authorize(self.client.get(&self.models_url))
+                .query(&[("limit", "1000")]);
+            if let Some(ref id) = after_id {
+                request = request.query(&[("after_id", id)]);
+            }
+
+            let response = request.send().await?;
+            if !response.status().is_success() {
+                let status = response.status();
+                let error_text = response.text().await.unwrap_or_else(|_| "Unknown error".to_string());
+                return Err(DevRecapError::ClaudeStatus {
+                    status: status.as_u16(),
+                    message: format!("Listing models failed with status {}: {}", status, error_text),
+                });
+            }
+
+            let page: ModelPage = response.json().await?;
+            models.extend(page.data);
+            match page.last_id {
+                Some(last_id) if page.has_more => after_id = Some(last_id),
+                _ => break,
+            }
+        }
+
+        Ok(models)
+    }
+
     /// Generate a summary from a prompt
     pub async fn generate_summary(&self, prompt: String) -> Result<String> {
-        let request = ClaudeRequest {
-            model: self.model.clone(),
-            max_tokens: self.max_tokens,
-            messages: vec![Message {
-                role: "user".to_string(),
-                content: prompt,
-            }],
-        };
+        self.complete(prompt).await.map(|completion| completion.text)
+    }
+
+    /// Send a prompt, returning the response text and the tokens it used
+    pub async fn complete(&self, prompt: String) -> Result<Completion> {
+        self.converse(None, vec![Message::user(prompt)]).await
+    }
 
-        let response = self
-            .client
-            .post(&self.api_url)
-            .header("x-api-key", &self.api_key)
-            .header("anthropic-version", CLAUDE_VERSION)
-            .header("content-type", "application/json")
-            .json(&request)
-            .send()
-            .await?;
+    /// Continue a conversation: `messages` alternate between user and
+    /// assistant, starting and ending with the user
+    pub async fn converse(&self, system: Option<String>, messages: Vec<Message>) -> Result<Completion> {
+        tracing::debug!(
+            provider = %self.provider,
+            model = %self.model,
+            url = %self.api_url,
+            prompt_bytes = messages.iter().map(|m| m.content.len()).sum::<usize>(),
+            "sending Claude request"
+        );
+
+        let request = self
+            .authorize(self.client.post(&self.api_url))
+            .header("content-type", "application/json");
+        let request = match self.provider {
+            Provider::Anthropic => request.json(&ClaudeRequest {
+                model: self.model.clone(),
+                max_tokens: self.max_tokens,
+                system,
+                messages,
+            }),
+            // The system prompt is the first message in the chat completions API
+            Provider::OpenAI | Provider::Ollama => request.json(&ChatCompletionRequest {
+                model: self.model.clone(),
+                max_tokens: self.max_tokens,
+                messages: system.map(Message::system).into_iter().chain(messages).collect(),
+            }),
+        };
+        let response = request.send().await?;
 
         if !response.status().is_success() {
             let status = response.status();
             let error_text = response.text().await.unwrap_or_else(|_| "Unknown error".to_string());
-            return Err(DevRecapError::claude_api(format!(
-                "API request failed with status {}: {}",
-                status, error_text
-            )));
+            tracing::warn!(%status, body = %error_text, "Claude request failed");
+            return Err(DevRecapError::ClaudeStatus {
+                status: status.as_u16(),
+                message: format!("API request failed with status {}: {}", status, error_text),
+            });
+        }
+
+        if self.provider != Provider::Anthropic {
+            let chat_response: ChatCompletionResponse = response.json().await?;
+            let usage = chat_response.usage.unwrap_or_default();
+            return match chat_response.choices.into_iter().next() {
+                Some(choice) => Ok(Completion {
+                    text: choice.message.content,
+                    input_tokens: usage.prompt_tokens,
+                    output_tokens: usage.completion_tokens,
+                }),
+                None => Err(DevRecapError::claude_api(format!("No choices in {} response", self.provider))),
+            };
         }
 
         let claude_response: ClaudeResponse = response.json().await?;
 
         // Extract text from first content block
         if let Some(content) = claude_response.content.first() {
-            Ok(content.text.clone())
+            let usage = claude_response.usage.unwrap_or_default();
+            Ok(Completion {
+                text: content.text.clone(),
+                input_tokens: usage.input_tokens,
+                output_tokens: usage.output_tokens,
+            })
         } else {
             Err(DevRecapError::claude_api(
                 "No content in Claude response".to_string(),
@@ -110,18 +225,134 @@ impl ClaudeClient {
 struct ClaudeRequest {
     model: String,
     max_tokens: u32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    system: Option<String>,
     messages: Vec<Message>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
-struct Message {
-    role: String,
-    content: String,
+/// One turn of a conversation
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Message {
+    /// `user` or `assistant`
+    pub role: String,
+    pub content: String,
+}
+
+impl Message {
+    /// A turn by the user
+    pub fn user(content: impl Into<String>) -> Self {
+        Self {
+            role: "user".to_string(),
+            content: content.into(),
+        }
+    }
+
+    /// Instructions for the whole conversation (chat completions API only;
+    /// Claude takes them apart from the messages)
+    fn system(content: String) -> Self {
+        Self {
+            role: "system".to_string(),
+            content,
+        }
+    }
+
+    /// A turn by Claude
+    pub fn assistant(content: impl Into<String>) -> Self {
+        Self {
+            role: "assistant".to_string(),
+            content: content.into(),
+        }
+    }
+}
+
+/// A Claude response with its token usage
+#[derive(Debug, Clone)]
+pub struct Completion {
+    pub text: String,
+    /// Prompt tokens (0 if the API didn't report usage)
+    pub input_tokens: u64,
+    /// Response tokens (0 if the API didn't report usage)
+    pub output_tokens: u64,
+}
+
+/// A model offered by the endpoint
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct ModelInfo {
+    /// Model ID, as used for `claude_model`
+    pub id: String,
+    /// Human-readable name (not reported by every proxy)
+    #[serde(default)]
+    pub display_name: Option<String>,
+}
+
+/// Whether `model` is among `models`
+///
+/// Aliases without a date (`claude-sonnet-4-5`) and `-latest` aliases match
+/// the dated IDs the API lists (`claude-sonnet-4-5-20250929`).
+pub fn is_model_available(models: &[ModelInfo], model: &str) -> bool {
+    let alias = model.strip_suffix("-latest").unwrap_or(model);
+    models.iter().any(|info| {
+        info.id == model
+            || info
+                .id
+                .strip_prefix(alias)
+                .and_then(|rest| rest.strip_prefix('-'))
+                .is_some_and(|date| date.len() == 8 && date.chars().all(|c| c.is_ascii_digit()))
+    })
+}
+
+/// One page of `GET /v1/models`
+#[derive(Debug, Deserialize)]
+struct ModelPage {
+    data: Vec<ModelInfo>,
+    #[serde(default)]
+    has_more: bool,
+    #[serde(default)]
+    last_id: Option<String>,
 }
 
 #[derive(Debug, Deserialize)]
 struct ClaudeResponse {
     content: Vec<ContentBlock>,
+    // Some proxies leave usage out
+    #[serde(default)]
+    usage: Option<Usage>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct Usage {
+    #[serde(default)]
+    input_tokens: u64,
+    #[serde(default)]
+    output_tokens: u64,
+}
+
+/// Request of the OpenAI chat completions API
+#[derive(Debug, Serialize)]
+struct ChatCompletionRequest {
+    model: String,
+    max_tokens: u32,
+    messages: Vec<Message>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ChatCompletionResponse {
+    choices: Vec<ChatChoice>,
+    #[serde(default)]
+    usage: Option<ChatUsage>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ChatChoice {
+    message: Message,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct ChatUsage {
+    #[serde(default)]
+    prompt_tokens: u64,
+    #[serde(default)]
+    completion_tokens: u64,
 }
 
 #[derive(Debug, Deserialize)]
@@ -139,7 +370,7 @@ mod tests {
     #[test]
     fn test_client_creation() {
         let client = ClaudeClient::new("sk-ant-test-key".to_string()).unwrap();
-        assert_eq!(client.model, DEFAULT_MODEL);
+        assert_eq!(client.model, Provider::Anthropic.default_model());
         assert_eq!(client.max_tokens, DEFAULT_MAX_TOKENS);
     }
 
@@ -187,4 +418,103 @@ mod tests {
         .unwrap();
         assert_eq!(client.api_url, "https://api.anthropic.com/v1/messages");
     }
+
+    #[test]
+    fn test_is_model_available() {
+        let models = vec![
+            ModelInfo {
+                id: "claude-sonnet-4-5-20250929".to_string(),
+                display_name: Some("Claude Sonnet 4.5".to_string()),
+            },
+            ModelInfo {
+                id: "claude-3-5-haiku-20241022".to_string(),
+                display_name: None,
+            },
+        ];
+        assert!(is_model_available(&models, "claude-sonnet-4-5-20250929"));
+        assert!(is_model_available(&models, "claude-sonnet-4-5"));
+        assert!(is_model_available(&models, "claude-3-5-haiku-latest"));
+        assert!(!is_model_available(&models, "claude-sonnet-4"));
+        assert!(!is_model_available(&models, "claude-sonet-4-5-20250929"));
+    }
+
+    #[tokio::test]
+    async fn test_openai_chat_completions() {
+        use axum::http::HeaderMap;
+        use axum::routing::post;
+        use axum::Json;
+
+        async fn completions(headers: HeaderMap, Json(body): Json<serde_json::Value>) -> Json<serde_json::Value> {
+            assert_eq!(headers["authorization"], "Bearer sk-openai");
+            assert_eq!(body["model"], "gpt-4o-mini");
+            assert_eq!(body["messages"][0]["role"], "system");
+            assert_eq!(body["messages"][1]["content"], "Summarize");
+            Json(serde_json::json!({
+                "choices": [{ "index": 0, "message": { "role": "assistant", "content": "## Summary\nDone." } }],
+                "usage": { "prompt_tokens": 12, "completion_tokens": 3 },
+            }))
+        }
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base_url = format!("http://{}", listener.local_addr().unwrap());
+        let app = axum::Router::new().route("/v1/chat/completions", post(completions));
+        tokio::spawn(async move { axum::serve(listener, app).await });
+
+        let client = ClaudeClient::for_provider(
+            Provider::OpenAI,
+            "sk-openai".to_string(),
+            Some(base_url),
+            Some("gpt-4o-mini".to_string()),
+        )
+        .unwrap();
+        let completion = client
+            .converse(Some("Be brief".to_string()), vec![Message::user("Summarize")])
+            .await
+            .unwrap();
+        assert_eq!(completion.text, "## Summary\nDone.");
+        assert_eq!((completion.input_tokens, completion.output_tokens), (12, 3));
+
+        let ollama = ClaudeClient::for_provider(Provider::Ollama, String::new(), None, None).unwrap();
+        assert_eq!(ollama.api_url, "http://localhost:11434/v1/chat/completions");
+        assert_eq!(ollama.model(), "llama3.1");
+
+        // OPENAI_BASE_URL style, ending in /v1
+        let base_url = Some("https://api.example.com/v1/".to_string());
+        let openai = ClaudeClient::for_provider(Provider::OpenAI, String::new(), base_url, None).unwrap();
+        assert_eq!(openai.api_url, "https://api.example.com/v1/chat/completions");
+        assert_eq!(openai.models_url, "https://api.example.com/v1/models");
+    }
+
+    #[tokio::test]
+    async fn test_list_models_paginates() {
+        use axum::extract::Query;
+        use axum::routing::get;
+        use axum::Json;
+        use std::collections::HashMap;
+
+        async fn models(Query(query): Query<HashMap<String, String>>) -> Json<serde_json::Value> {
+            Json(match query.get("after_id").map(String::as_str) {
+                None => serde_json::json!({
+                    "data": [{ "id": "claude-opus-4-1-20250805", "display_name": "Claude Opus 4.1" }],
+                    "has_more": true,
+                    "last_id": "claude-opus-4-1-20250805",
+                }),
+                // Proxies answer in the OpenAI format, without pagination
+                Some(_) => serde_json::json!({ "data": [{ "id": "claude-sonnet-4-5-20250929", "object": "model" }] }),
+            })
+        }
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base_url = format!("http://{}", listener.local_addr().unwrap());
+        let app = axum::Router::new().route("/v1/models", get(models));
+        tokio::spawn(async move { axum::serve(listener, app).await });
+
+        let client = ClaudeClient::with_base_url("test-key".to_string(), Some(base_url), None).unwrap();
+        let ids: Vec<String> = client
+            .list_models()
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|model| model.id)
+            .collect();
+        assert_eq!(ids, vec!["claude-opus-4-1-20250805", "claude-sonnet-4-5-20250929"]);
+    }
 }
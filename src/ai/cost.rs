@@ -0,0 +1,181 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Expected response size per summary request (tokens)
+const EXPECTED_OUTPUT_TOKENS: u64 = 1000;
+
+/// Price of a model in USD per million tokens (`[pricing."MODEL"]` in config)
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ModelPricing {
+    /// Price of a million input (prompt) tokens
+    pub input: f64,
+    /// Price of a million output tokens
+    pub output: f64,
+}
+
+/// Rough token and cost estimate for a batch of summary requests
+#[derive(Debug, Clone)]
+pub struct CostEstimate {
+    /// Model the requests will be sent to
+    pub model: String,
+    /// Number of API requests that will be made
+    pub requests: usize,
+    /// Number of repositories served from cache (no request)
+    pub cached: usize,
+    /// Estimated input tokens across all requests
+    pub input_tokens: u64,
+    /// Estimated output tokens across all requests
+    pub output_tokens: u64,
+    /// Configured pricing of the model, over the built-in Claude pricing
+    pub pricing: Option<ModelPricing>,
+}
+
+impl CostEstimate {
+    /// Create an empty estimate for a model
+    pub fn new(model: &str) -> Self {
+        Self {
+            model: model.to_string(),
+            requests: 0,
+            cached: 0,
+            input_tokens: 0,
+            output_tokens: 0,
+            pricing: None,
+        }
+    }
+
+    /// Price the requests with configured pricing instead of the built-in one
+    pub fn with_pricing(mut self, pricing: Option<ModelPricing>) -> Self {
+        self.pricing = pricing;
+        self
+    }
+
+    /// Account for one prompt that will be sent to the API
+    pub fn add_prompt(&mut self, prompt: &str) {
+        self.requests += 1;
+        self.input_tokens += estimate_tokens(prompt);
+        self.output_tokens += EXPECTED_OUTPUT_TOKENS;
+    }
+
+    /// Account for the overall recap request across `repo_count` summaries
+    ///
+    /// Its prompt is built from the summaries, so its size is estimated from
+    /// the expected summary length.
+    pub fn add_overall(&mut self, repo_count: usize) {
+        self.requests += 1;
+        self.input_tokens += repo_count as u64 * EXPECTED_OUTPUT_TOKENS;
+        self.output_tokens += EXPECTED_OUTPUT_TOKENS;
+    }
+
+    /// Account for one repository whose summary is already cached
+    pub fn add_cached(&mut self) {
+        self.cached += 1;
+    }
+
+    /// Estimated cost in USD (None if the model's pricing is unknown)
+    pub fn cost_usd(&self) -> Option<f64> {
+        let pricing = self.pricing.or_else(|| pricing_per_million(&self.model))?;
+        Some(
+            self.input_tokens as f64 / 1_000_000.0 * pricing.input
+                + self.output_tokens as f64 / 1_000_000.0 * pricing.output,
+        )
+    }
+
+    /// Tokens and cost without the request count, e.g. for one repository of
+    /// a dry run: "~1200 input / ~1000 output tokens, ~$0.0186"
+    pub fn tokens_and_cost(&self) -> String {
+        if self.requests == 0 && self.cached > 0 {
+            return "no API request (summary cached)".to_string();
+        }
+        let tokens = format!("~{} input / ~{} output tokens", self.input_tokens, self.output_tokens);
+        match self.cost_usd() {
+            Some(cost) => format!("{}, ~${:.4}", tokens, cost),
+            None => format!("{}, cost unknown for {}", tokens, self.model),
+        }
+    }
+}
+
+impl fmt::Display for CostEstimate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} API request(s) to {} (~{} input / ~{} output tokens)",
+            self.requests, self.model, self.input_tokens, self.output_tokens
+        )?;
+
+        if self.cached > 0 {
+            write!(f, ", {} cached", self.cached)?;
+        }
+
+        match self.cost_usd() {
+            Some(cost) => write!(f, ", estimated cost ~${:.4}", cost),
+            None => write!(f, ", cost unknown for this model"),
+        }
+    }
+}
+
+/// Estimate token count of a text (~4 characters per token)
+pub fn estimate_tokens(text: &str) -> u64 {
+    (text.len() as u64).div_ceil(4)
+}
+
+/// Built-in price per million tokens, by Claude model family
+fn pricing_per_million(model: &str) -> Option<ModelPricing> {
+    let model = model.to_lowercase();
+    let (input, output) = if model.contains("opus") {
+        (15.0, 75.0)
+    } else if model.contains("sonnet") {
+        (3.0, 15.0)
+    } else if model.contains("haiku") {
+        (0.80, 4.0)
+    } else {
+        return None;
+    };
+    Some(ModelPricing { input, output })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_estimate_tokens() {
+        assert_eq!(estimate_tokens(""), 0);
+        assert_eq!(estimate_tokens("abcd"), 1);
+        assert_eq!(estimate_tokens("abcde"), 2);
+    }
+
+    #[test]
+    fn test_cost_estimate() {
+        let mut estimate = CostEstimate::new("claude-sonnet-4-5-20250929");
+        estimate.add_prompt(&"x".repeat(4000));
+        estimate.add_cached();
+
+        assert_eq!(estimate.requests, 1);
+        assert_eq!(estimate.cached, 1);
+        assert_eq!(estimate.input_tokens, 1000);
+        assert_eq!(estimate.output_tokens, EXPECTED_OUTPUT_TOKENS);
+
+        // 1000 input tokens at $3/M + 1000 output tokens at $15/M
+        let cost = estimate.cost_usd().unwrap();
+        assert!((cost - 0.018).abs() < 1e-9);
+        assert!(estimate.to_string().contains("1 cached"));
+    }
+
+    #[test]
+    fn test_cost_estimate_unknown_model() {
+        let mut estimate = CostEstimate::new("my-proxy-model");
+        estimate.add_prompt("hello");
+        assert!(estimate.cost_usd().is_none());
+        assert!(estimate.to_string().contains("cost unknown"));
+        assert_eq!(estimate.tokens_and_cost(), "~2 input / ~1000 output tokens, cost unknown for my-proxy-model");
+
+        // Configured pricing prices any model: 2 input tokens at $2/M + 1000 output tokens at $8/M
+        let estimate = estimate.with_pricing(Some(ModelPricing { input: 2.0, output: 8.0 }));
+        assert!((estimate.cost_usd().unwrap() - 0.008004).abs() < 1e-9);
+        assert_eq!(estimate.tokens_and_cost(), "~2 input / ~1000 output tokens, ~$0.0080");
+
+        let mut cached = CostEstimate::new("gpt-4o");
+        cached.add_cached();
+        assert_eq!(cached.tokens_and_cost(), "no API request (summary cached)");
+    }
+}
@@ -0,0 +1,151 @@
+// Impact ranking of key achievements
+// Orders what the model listed by the size of the work behind each item
+
+use crate::ai::{Summary, KEY_ACHIEVEMENTS};
+use crate::git::github::extract_pr_numbers;
+use crate::git::keywords::words;
+use crate::git::{Commit, Repository};
+use std::collections::HashSet;
+
+/// Words an achievement must share with a commit (subject, body or changed
+/// paths) for the commit to count towards it, unless it names the commit's PR
+const MIN_SHARED_WORDS: usize = 2;
+
+/// Score added per distinct PR behind an achievement
+const PR_WEIGHT: f64 = 3.0;
+
+/// Score added per hotspot file touched by the work behind an achievement
+const HOTSPOT_WEIGHT: f64 = 2.0;
+
+/// Likely impact of an achievement, from the commits it describes
+///
+/// Each matching commit adds the logarithm of its changed lines, so one
+/// huge commit doesn't outweigh steady work; distinct PRs and hotspot files
+/// touched add a fixed bonus. An achievement matching no commit scores 0.
+pub fn impact_score(achievement: &str, repo: &Repository) -> f64 {
+    let achievement_words = words(achievement);
+    let achievement_prs = extract_pr_numbers(achievement);
+    let matched: Vec<&Commit> = repo
+        .commits
+        .iter()
+        .filter(|commit| describes(&achievement_words, &achievement_prs, commit))
+        .collect();
+
+    let lines: f64 = matched
+        .iter()
+        .map(|commit| f64::from(commit.insertions + commit.deletions).ln_1p())
+        .sum();
+    let prs: HashSet<u32> = matched
+        .iter()
+        .flat_map(|commit| commit.pr_numbers.iter().copied())
+        .collect();
+    let hotspots = repo
+        .hotspots
+        .iter()
+        .filter(|hotspot| {
+            matched
+                .iter()
+                .any(|commit| commit.files_changed.contains(&hotspot.path))
+        })
+        .count();
+
+    lines + PR_WEIGHT * prs.len() as f64 + HOTSPOT_WEIGHT * hotspots as f64
+}
+
+/// Reorder a summary's key achievements by likely impact, highest first,
+/// and keep the first `top` of them
+///
+/// Ties keep the model's order.
+pub fn rank_achievements(summary: &mut Summary, repo: &Repository, top: Option<usize>) {
+    let Some(section) = summary
+        .sections
+        .iter_mut()
+        .find(|section| section.title.eq_ignore_ascii_case(KEY_ACHIEVEMENTS))
+    else {
+        return;
+    };
+
+    let mut scored: Vec<(f64, String)> = section
+        .items
+        .drain(..)
+        .map(|achievement| (impact_score(&achievement, repo), achievement))
+        .collect();
+    scored.sort_by(|a, b| b.0.total_cmp(&a.0));
+    if let Some(top) = top {
+        scored.truncate(top);
+    }
+    section.items = scored.into_iter().map(|(_, achievement)| achievement).collect();
+}
+
+/// Whether an achievement with these words and PR references describes `commit`
+fn describes(achievement_words: &HashSet<String>, achievement_prs: &[u32], commit: &Commit) -> bool {
+    if commit.pr_numbers.iter().any(|number| achievement_prs.contains(number)) {
+        return true;
+    }
+
+    let mut commit_words = words(&commit.message);
+    for file in &commit.files_changed {
+        commit_words.extend(words(file));
+    }
+    achievement_words.intersection(&commit_words).count() >= MIN_SHARED_WORDS
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::git::stats::Hotspot;
+
+    fn commit(message: &str, files: &[&str], lines: u32, prs: &[u32]) -> Commit {
+        Commit {
+            pr_numbers: prs.to_vec(),
+            ..Commit::test("abc1234def", message).with_files(files, lines)
+        }
+    }
+
+    fn repo(commits: Vec<Commit>) -> Repository {
+        Repository {
+            hotspots: vec![Hotspot {
+                path: "src/billing/invoice.rs".to_string(),
+                changes: 2,
+                churn: 900,
+            }],
+            ..Repository::test("api", commits)
+        }
+    }
+
+    #[test]
+    fn test_rank_achievements() {
+        let repo = repo(vec![
+            commit("Fix typo in README", &["README.md"], 2, &[]),
+            commit("Rewrite invoice generation", &["src/billing/invoice.rs"], 600, &[41]),
+            commit("Invoice totals in multiple currencies", &["src/billing/invoice.rs"], 300, &[]),
+        ]);
+        let mut summary = Summary::new(
+            "api".to_string(),
+            "Work".to_string(),
+            vec![
+                "Fixed a README typo".to_string(),
+                "Polished the onboarding copy".to_string(),
+                "Rewrote invoice generation with multi-currency totals".to_string(),
+                "Shipped PR #41".to_string(),
+            ],
+            vec!["Demo invoices".to_string()],
+        );
+
+        rank_achievements(&mut summary, &repo, None);
+        assert_eq!(
+            summary.key_achievements(),
+            [
+                "Rewrote invoice generation with multi-currency totals",
+                "Shipped PR #41",
+                "Fixed a README typo",
+                "Polished the onboarding copy",
+            ]
+        );
+        assert!(impact_score("Polished the onboarding copy", &repo) == 0.0);
+
+        rank_achievements(&mut summary, &repo, Some(2));
+        assert_eq!(summary.key_achievements().len(), 2);
+        assert_eq!(summary.presentation_tips(), ["Demo invoices"]);
+    }
+}
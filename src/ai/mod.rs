@@ -1,65 +1,276 @@
 pub mod cache;
+pub mod chat;
 pub mod claude;
+pub mod cost;
+pub mod impact;
 pub mod prompt;
+pub mod provider;
 
+use crate::error::DevRecapError;
 use chrono::{DateTime, Utc};
+use prompt::PromptVersion;
 use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+/// Heading of the achievements section
+pub const KEY_ACHIEVEMENTS: &str = "Key Achievements";
+/// Heading of the presentation tips section
+pub const PRESENTATION_TIPS: &str = "Presentation Tips";
+/// Heading of the section added for breaking commits
+pub const BREAKING_CHANGES: &str = "Breaking Changes & Migrations";
+/// Heading of the section added with `--watch-outs`
+pub const WATCH_OUTS: &str = "Watch-outs";
+/// Heading of the section added with `--goals`
+pub const GOAL_ALIGNMENT: &str = "Goal Alignment";
+/// Heading of the day-by-day section added with `--group-by day`
+pub const TIMELINE: &str = "Timeline";
+
+/// A titled list in a summary, e.g. "Key Achievements"
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Section {
+    /// Heading, as configured
+    pub title: String,
+    /// List items
+    pub items: Vec<String>,
+    /// Rendered as a numbered list instead of bullets
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub numbered: bool,
+}
+
+impl Section {
+    /// A bulleted section
+    pub fn bullets(title: &str, items: Vec<String>) -> Self {
+        Self {
+            title: title.to_string(),
+            items,
+            numbered: false,
+        }
+    }
+
+    /// A numbered section
+    pub fn numbered(title: &str, items: Vec<String>) -> Self {
+        Self {
+            numbered: true,
+            ..Self::bullets(title, items)
+        }
+    }
+
+    /// The section as markdown, under a `##` heading
+    pub fn to_markdown(&self) -> String {
+        let mut output = format!("## {}\n\n", self.title);
+        for (i, item) in self.items.iter().enumerate() {
+            if self.numbered {
+                output.push_str(&format!("{}. {}\n", i + 1, item));
+            } else {
+                output.push_str(&format!("- {}\n", item));
+            }
+        }
+        output.push('\n');
+        output
+    }
+}
 
 /// AI-generated summary for a repository
+///
+/// Besides the work summary, a summary is an ordered list of sections, by
+/// default Key Achievements and Presentation Tips (see `summary_sections` in
+/// config). The JSON form also carries `key_achievements` and
+/// `presentation_tips`, so caches, history and API clients from before
+/// sections keep working.
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(from = "SummaryData", into = "SummaryData")]
 pub struct Summary {
     /// Repository name
     pub repository: String,
     /// Work summary in markdown format
     pub work_summary: String,
-    /// Key achievements
-    pub key_achievements: Vec<String>,
-    /// Presentation tips
-    pub presentation_tips: Vec<String>,
+    /// Lists after the work summary, in display order
+    pub sections: Vec<Section>,
     /// When this summary was generated
     pub generated_at: DateTime<Utc>,
+    /// Prompt it was generated with (unknown for summaries cached before
+    /// versions were recorded)
+    pub prompt_version: Option<PromptVersion>,
+    /// Model it was generated with (unknown for summaries cached before
+    /// models were recorded)
+    pub model: Option<String>,
+    /// Commits in the timespan this summary doesn't cover: set when an older
+    /// cached summary stands in for one that couldn't be generated
+    pub new_commits: usize,
+    /// Why a fresh summary couldn't be generated, when this older cached one
+    /// stands in for it (not saved with the summary)
+    pub fallback_error: Option<Arc<DevRecapError>>,
+}
+
+/// Serialized form of [`Summary`]
+#[derive(Serialize, Deserialize)]
+struct SummaryData {
+    repository: String,
+    work_summary: String,
+    #[serde(default)]
+    key_achievements: Vec<String>,
+    #[serde(default)]
+    presentation_tips: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    sections: Vec<Section>,
+    generated_at: DateTime<Utc>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    prompt_version: Option<PromptVersion>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    model: Option<String>,
+    #[serde(default, skip_serializing_if = "is_zero")]
+    new_commits: usize,
+}
+
+fn is_zero(count: &usize) -> bool {
+    *count == 0
+}
+
+impl From<SummaryData> for Summary {
+    fn from(data: SummaryData) -> Self {
+        let sections = if data.sections.is_empty() {
+            // Written before sections existed
+            legacy_sections(data.key_achievements, data.presentation_tips)
+        } else {
+            data.sections
+        };
+        Self {
+            repository: data.repository,
+            work_summary: data.work_summary,
+            sections,
+            generated_at: data.generated_at,
+            prompt_version: data.prompt_version,
+            model: data.model,
+            new_commits: data.new_commits,
+            fallback_error: None,
+        }
+    }
+}
+
+impl From<Summary> for SummaryData {
+    fn from(summary: Summary) -> Self {
+        Self {
+            key_achievements: summary.key_achievements().to_vec(),
+            presentation_tips: summary.presentation_tips().to_vec(),
+            repository: summary.repository,
+            work_summary: summary.work_summary,
+            sections: summary.sections,
+            generated_at: summary.generated_at,
+            prompt_version: summary.prompt_version,
+            model: summary.model,
+            new_commits: summary.new_commits,
+        }
+    }
+}
+
+/// The default sections, leaving out empty ones
+fn legacy_sections(key_achievements: Vec<String>, presentation_tips: Vec<String>) -> Vec<Section> {
+    let mut sections = Vec::new();
+    if !key_achievements.is_empty() {
+        sections.push(Section::bullets(KEY_ACHIEVEMENTS, key_achievements));
+    }
+    if !presentation_tips.is_empty() {
+        sections.push(Section::numbered(PRESENTATION_TIPS, presentation_tips));
+    }
+    sections
 }
 
 impl Summary {
-    /// Create a new summary
+    /// Create a summary with the default sections
     pub fn new(
         repository: String,
         work_summary: String,
         key_achievements: Vec<String>,
         presentation_tips: Vec<String>,
     ) -> Self {
+        Self::with_sections(
+            repository,
+            work_summary,
+            legacy_sections(key_achievements, presentation_tips),
+        )
+    }
+
+    /// Create a summary with these sections
+    pub fn with_sections(repository: String, work_summary: String, sections: Vec<Section>) -> Self {
         Self {
             repository,
             work_summary,
-            key_achievements,
-            presentation_tips,
+            sections,
             generated_at: Utc::now(),
+            prompt_version: None,
+            model: None,
+            new_commits: 0,
+            fallback_error: None,
         }
     }
 
+    /// Record the prompt version the summary was generated with
+    pub fn with_prompt_version(mut self, version: PromptVersion) -> Self {
+        self.prompt_version = Some(version);
+        self
+    }
+
+    /// Record the model the summary was generated with
+    pub fn with_model(mut self, model: impl Into<String>) -> Self {
+        self.model = Some(model.into());
+        self
+    }
+
+    /// Items of the section titled `title` (case-insensitive), empty without one
+    pub fn section(&self, title: &str) -> &[String] {
+        self.sections
+            .iter()
+            .find(|section| section.title.eq_ignore_ascii_case(title))
+            .map(|section| section.items.as_slice())
+            .unwrap_or_default()
+    }
+
+    /// Sections with items, except the presentation tips: what was done,
+    /// under the configured headings
+    pub fn content_sections(&self) -> impl Iterator<Item = &Section> {
+        self.sections
+            .iter()
+            .filter(|section| !section.items.is_empty() && !section.title.eq_ignore_ascii_case(PRESENTATION_TIPS))
+    }
+
+    /// Key achievements
+    pub fn key_achievements(&self) -> &[String] {
+        self.section(KEY_ACHIEVEMENTS)
+    }
+
+    /// Presentation tips
+    pub fn presentation_tips(&self) -> &[String] {
+        self.section(PRESENTATION_TIPS)
+    }
+
+    /// Breaking changes and how to migrate, when commits were marked breaking
+    pub fn breaking_changes(&self) -> &[String] {
+        self.section(BREAKING_CHANGES)
+    }
+
+    /// Risks and tech debt worth raising, when watch-outs were asked for
+    pub fn watch_outs(&self) -> &[String] {
+        self.section(WATCH_OUTS)
+    }
+
     /// Format summary as markdown
     pub fn to_markdown(&self) -> String {
         let mut output = String::new();
 
         output.push_str(&format!("# {}\n\n", self.repository));
+        if self.new_commits > 0 {
+            output.push_str(&format!(
+                "*⚠ The timespan includes {} new commit{} not in this cached summary, shown because summarizing failed; rerun to refresh it.*\n\n",
+                self.new_commits,
+                if self.new_commits == 1 { "" } else { "s" }
+            ));
+        }
         output.push_str("## Summary\n\n");
         output.push_str(&self.work_summary);
         output.push_str("\n\n");
 
-        if !self.key_achievements.is_empty() {
-            output.push_str("## Key Achievements\n\n");
-            for achievement in &self.key_achievements {
-                output.push_str(&format!("- {}\n", achievement));
-            }
-            output.push_str("\n");
-        }
-
-        if !self.presentation_tips.is_empty() {
-            output.push_str("## Presentation Tips\n\n");
-            for (i, tip) in self.presentation_tips.iter().enumerate() {
-                output.push_str(&format!("{}. {}\n", i + 1, tip));
-            }
-            output.push_str("\n");
+        for section in self.sections.iter().filter(|section| !section.items.is_empty()) {
+            output.push_str(&section.to_markdown());
         }
 
         output.push_str(&format!(
@@ -71,10 +282,98 @@ impl Summary {
     }
 }
 
+/// AI-generated recap across all repositories of a run
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OverallRecap {
+    /// Themes that span repositories
+    pub themes: Vec<String>,
+    /// Most impressive results overall
+    pub biggest_wins: Vec<String>,
+    /// Repositories in the order they should be demoed, with a reason
+    pub demo_order: Vec<String>,
+    /// Goals (`--goals`) that no repository's work advanced
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub goals_not_addressed: Vec<String>,
+    /// When this recap was generated
+    pub generated_at: DateTime<Utc>,
+}
+
+impl OverallRecap {
+    /// Create a new overall recap
+    pub fn new(themes: Vec<String>, biggest_wins: Vec<String>, demo_order: Vec<String>) -> Self {
+        Self {
+            themes,
+            biggest_wins,
+            demo_order,
+            goals_not_addressed: Vec::new(),
+            generated_at: Utc::now(),
+        }
+    }
+
+    /// Format recap as markdown
+    pub fn to_markdown(&self) -> String {
+        let mut output = String::new();
+
+        output.push_str("# Overall Recap\n\n");
+
+        if !self.themes.is_empty() {
+            output.push_str("## Themes\n\n");
+            for theme in &self.themes {
+                output.push_str(&format!("- {}\n", theme));
+            }
+            output.push('\n');
+        }
+
+        if !self.biggest_wins.is_empty() {
+            output.push_str("## Biggest Wins\n\n");
+            for win in &self.biggest_wins {
+                output.push_str(&format!("- {}\n", win));
+            }
+            output.push('\n');
+        }
+
+        if !self.demo_order.is_empty() {
+            output.push_str("## Suggested Demo Order\n\n");
+            for (i, step) in self.demo_order.iter().enumerate() {
+                output.push_str(&format!("{}. {}\n", i + 1, step));
+            }
+            output.push('\n');
+        }
+
+        if !self.goals_not_addressed.is_empty() {
+            output.push_str("## Goals Not Addressed\n\n");
+            for goal in &self.goals_not_addressed {
+                output.push_str(&format!("- {}\n", goal));
+            }
+            output.push('\n');
+        }
+
+        output
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_overall_recap_markdown() {
+        let mut recap = OverallRecap::new(
+            vec!["Performance".to_string()],
+            vec!["2x faster builds".to_string()],
+            vec!["api - start with the new endpoint".to_string()],
+        );
+        assert!(!recap.to_markdown().contains("## Goals Not Addressed"));
+        recap.goals_not_addressed = vec!["Fewer pages".to_string()];
+
+        let markdown = recap.to_markdown();
+        assert!(markdown.contains("## Goals Not Addressed\n\n- Fewer pages\n"));
+        assert!(markdown.starts_with("# Overall Recap"));
+        assert!(markdown.contains("- Performance"));
+        assert!(markdown.contains("## Biggest Wins"));
+        assert!(markdown.contains("1. api - start with the new endpoint"));
+    }
+
     #[test]
     fn test_summary_creation() {
         let summary = Summary::new(
@@ -86,8 +385,8 @@ mod tests {
 
         assert_eq!(summary.repository, "test-repo");
         assert_eq!(summary.work_summary, "Test summary");
-        assert_eq!(summary.key_achievements.len(), 1);
-        assert_eq!(summary.presentation_tips.len(), 1);
+        assert_eq!(summary.key_achievements().len(), 1);
+        assert_eq!(summary.presentation_tips().len(), 1);
     }
 
     #[test]
@@ -106,5 +405,48 @@ mod tests {
         assert!(markdown.contains("## Presentation Tips"));
         assert!(markdown.contains("- Achievement 1"));
         assert!(markdown.contains("1. Tip 1"));
+        assert!(!markdown.contains("## Breaking Changes"));
+    }
+
+    #[test]
+    fn test_summary_sections() {
+        let summary = Summary::with_sections(
+            "api".to_string(),
+            "Moved to v2.".to_string(),
+            vec![
+                Section::numbered("Metrics", vec!["p99 down 40%".to_string()]),
+                Section::bullets("Next Steps", vec!["Remove v1".to_string()]),
+                Section::bullets(KEY_ACHIEVEMENTS, vec![]),
+            ],
+        );
+
+        let markdown = summary.to_markdown();
+        assert!(markdown.contains("## Summary\n\nMoved to v2.\n\n## Metrics\n\n1. p99 down 40%\n\n## Next Steps\n\n- Remove v1\n\n*Generated"));
+        assert_eq!(summary.section("next steps"), ["Remove v1"]);
+        assert!(summary.presentation_tips().is_empty());
+        let titles: Vec<&str> = summary.content_sections().map(|section| section.title.as_str()).collect();
+        assert_eq!(titles, ["Metrics", "Next Steps"]);
+    }
+
+    #[test]
+    fn test_summary_json_compat() {
+        // Written before sections existed
+        let old = r#"{"repository":"api","work_summary":"Done.","key_achievements":["Shipped"],
+            "presentation_tips":["Show it"],"generated_at":"2025-01-15T09:30:00Z"}"#;
+        let summary: Summary = serde_json::from_str(old).unwrap();
+        assert_eq!(summary.key_achievements(), ["Shipped"]);
+        assert_eq!(summary.sections[1], Section::numbered(PRESENTATION_TIPS, vec!["Show it".to_string()]));
+
+        // The legacy fields are still written, next to the sections
+        let summary = Summary::with_sections(
+            "api".to_string(),
+            "Done.".to_string(),
+            vec![Section::bullets("Next Steps", vec!["Remove v1".to_string()])],
+        );
+        let json = serde_json::to_value(&summary).unwrap();
+        assert_eq!(json["key_achievements"], serde_json::json!([]));
+        assert_eq!(json["sections"][0]["title"], "Next Steps");
+        let round_trip: Summary = serde_json::from_value(json).unwrap();
+        assert_eq!(round_trip.sections, summary.sections);
     }
 }
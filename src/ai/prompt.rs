@@ -1,17 +1,481 @@
-use crate::git::Repository;
+use crate::ai::{
+    Section, Summary, BREAKING_CHANGES, GOAL_ALIGNMENT, KEY_ACHIEVEMENTS, PRESENTATION_TIPS, TIMELINE,
+    WATCH_OUTS,
+};
+use crate::enrich::Enrichment;
+use crate::goals::Goal;
+use crate::git::stats::{
+    commits_by_day, directory_breakdown, format_directory_breakdown, recent_weeks, DIRECTORY_BREAKDOWN_LIMIT,
+};
+use crate::git::keywords::format_keywords;
+use crate::git::{Author, Commit, Repository};
+use crate::year::{MonthRecap, HIGHLIGHTS, MILESTONES, TRENDS};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::fmt;
+
+/// Longest commit body kept in the prompt, in characters
+const MAX_BODY_CHARS: usize = 600;
+
+/// Lines changed from which a commit without a PR counts as a risk signal
+const LARGE_COMMIT_LINES: u32 = 500;
+
+/// Commits listed per kind of risk signal
+const MAX_RISK_SIGNALS: usize = 5;
+
+/// Commit subjects listed per repository in a monthly summary prompt
+const MAX_MONTH_COMMITS: usize = 30;
+
+/// How the commit list of a repository context is laid out; numbered
+/// throughout, and flat when neither applies
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct CommitLayout {
+    /// Under each author, when there are several
+    by_author: bool,
+    /// Under each pull request (within each author), then the commits outside one
+    by_pull_request: bool,
+}
+
+/// Words in a commit message admitting to a shortcut
+const SHORTCUT_WORDS: &[&str] = &["todo", "fixme", "hack", "hacky", "workaround", "xxx"];
+
+/// Version of the summary prompt's instructions
+///
+/// Summaries record the version they were generated with, and each version
+/// is cached separately. Add a version instead of changing one, so
+/// `--compare-prompts` can put the old and new wording side by side.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PromptVersion {
+    /// Summary of the work done, achievements and demo tips
+    #[default]
+    V1,
+    /// Like v1, but leading with the impact of the work rather than the changes
+    V2,
+}
+
+impl PromptVersion {
+    /// Every version, oldest first
+    pub const ALL: [PromptVersion; 2] = [PromptVersion::V1, PromptVersion::V2];
+}
+
+impl fmt::Display for PromptVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::V1 => write!(f, "v1"),
+            Self::V2 => write!(f, "v2"),
+        }
+    }
+}
+
+impl std::str::FromStr for PromptVersion {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|version| version.to_string() == s)
+            .ok_or_else(|| format!("unknown prompt version '{}' (expected v1 or v2)", s))
+    }
+}
+
+/// What a recap is written for (`--format`)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RecapFormat {
+    /// A Demo Day or standup presentation
+    #[default]
+    Demo,
+    /// A brag document for a self-review: impact, collaboration and craft
+    /// over a longer window
+    Brag,
+}
+
+impl RecapFormat {
+    /// Every format
+    pub const ALL: [RecapFormat; 2] = [RecapFormat::Demo, RecapFormat::Brag];
+
+    /// Days looked back unless `--days` or `--since` is given (`None`: the
+    /// configured `default_timespan_days`)
+    pub fn default_days(&self) -> Option<u32> {
+        match self {
+            Self::Demo => None,
+            Self::Brag => Some(BRAG_TIMESPAN_DAYS),
+        }
+    }
+}
+
+impl fmt::Display for RecapFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Demo => write!(f, "demo"),
+            Self::Brag => write!(f, "brag"),
+        }
+    }
+}
+
+impl std::str::FromStr for RecapFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|format| format.to_string() == s)
+            .ok_or_else(|| format!("unknown format '{}' (expected demo or brag)", s))
+    }
+}
+
+/// Default window of a brag document: half a year, a typical review cycle
+pub const BRAG_TIMESPAN_DAYS: u32 = 182;
+
+/// Options shaping the per-repository summary prompt
+#[derive(Debug, Clone, Default)]
+pub struct PromptOptions {
+    /// Leave commits with fewer changed lines (inserted + deleted) out of the
+    /// commit list; they still count in the statistics
+    pub min_changes: Option<u32>,
+    /// Include the message bodies of this many commits, largest first
+    pub commit_bodies: Option<usize>,
+    /// Topics from the code host, added to the project's own keywords
+    pub topics: Vec<String>,
+    /// Context from enrichment plugins, added after the commits
+    pub enrichments: Vec<Enrichment>,
+    /// Wording of the instructions
+    pub version: PromptVersion,
+    /// Recaps in the preferred style, shown after the instructions
+    pub examples: Vec<Example>,
+    /// Point out risk signals and ask for a "Watch-outs" section
+    pub watch_outs: bool,
+    /// Sections after the work summary, in order (default sections when empty)
+    pub sections: Vec<SectionSpec>,
+    /// Presentation or self-review; a brag document has its own sections
+    pub format: RecapFormat,
+    /// Goals to map the work to, in a "Goal Alignment" section (`--goals`)
+    pub goals: Vec<Goal>,
+    /// List the commits by day and ask for a "Timeline" section (`--group-by day`)
+    pub timeline: bool,
+}
+
+/// An example input and the recap a team would want for it (`[[examples]]` in config)
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Example {
+    /// Commits or context the example recap was written from
+    pub input: String,
+    /// The recap, in the preferred terminology and level of detail
+    pub output: String,
+}
+
+/// A list section asked for in each summary (`[[summary_sections]]` in config)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SectionSpec {
+    /// Heading, e.g. "Next Steps"
+    pub title: String,
+    /// What the section should contain; "Key Achievements" and "Presentation
+    /// Tips" fall back to their built-in instructions
+    #[serde(default)]
+    pub instruction: Option<String>,
+    /// A numbered list instead of bullets
+    #[serde(default)]
+    pub numbered: bool,
+}
+
+impl SectionSpec {
+    fn new(title: &str, instruction: Option<&str>, numbered: bool) -> Self {
+        Self {
+            title: title.to_string(),
+            instruction: instruction.map(str::to_string),
+            numbered,
+        }
+    }
+
+    fn is(&self, title: &str) -> bool {
+        self.title.eq_ignore_ascii_case(title)
+    }
+}
+
+/// Key Achievements and Presentation Tips, used unless `summary_sections` is set
+pub fn default_sections() -> Vec<SectionSpec> {
+    vec![
+        SectionSpec::new(KEY_ACHIEVEMENTS, None, false),
+        SectionSpec::new(PRESENTATION_TIPS, None, true),
+    ]
+}
+
+/// Review competencies a brag document is organized around
+pub fn brag_sections() -> Vec<SectionSpec> {
+    vec![
+        SectionSpec::new(
+            "Impact",
+            Some(
+                "3-5 bullet points on outcomes for users, the team or the business: what shipped, what it \
+                 made possible, and the evidence for it (PR numbers, sizes, before and after)",
+            ),
+            false,
+        ),
+        SectionSpec::new(
+            "Collaboration",
+            Some(
+                "2-4 bullet points on working with others: shared or co-authored work, changes spanning \
+                 teams or repositories, reviews, unblocking others; only what the data supports, and say \
+                 so if it shows little",
+            ),
+            false,
+        ),
+        SectionSpec::new(
+            "Craft",
+            Some(
+                "2-4 bullet points on engineering quality: tests, refactoring of hotspots, performance, \
+                 reliability, documentation and tooling",
+            ),
+            false,
+        ),
+    ]
+}
+
+/// Sections asked for in a repository's summary: the configured ones (the
+/// brag sections for a brag document), then breaking changes (when commits
+/// are marked breaking) and watch-outs
+pub fn summary_sections(repo: &Repository, options: &PromptOptions) -> Vec<SectionSpec> {
+    let mut sections = if options.format == RecapFormat::Brag {
+        brag_sections()
+    } else if options.sections.is_empty() {
+        default_sections()
+    } else {
+        options.sections.clone()
+    };
+    if repo.commits.iter().any(Commit::is_breaking) {
+        sections.push(SectionSpec::new(
+            BREAKING_CHANGES,
+            Some(
+                "for each commit marked [BREAKING], what changed and what users of the project \
+                 must do to migrate",
+            ),
+            false,
+        ));
+    }
+    if !options.goals.is_empty() {
+        sections.push(SectionSpec::new(
+            GOAL_ALIGNMENT,
+            Some(
+                "one bullet per goal listed above, in order, starting with the goal: the work here that \
+                 advances it, or \"Not addressed\" when none does; don't stretch unrelated work to fit a goal",
+            ),
+            false,
+        ));
+    }
+    if options.timeline {
+        sections.push(SectionSpec::new(
+            TIMELINE,
+            Some(
+                "one bullet per day with commits, oldest first, starting with the day as listed under \
+                 \"Commits by day\" (e.g. \"Mon 2025-01-06: ...\"): what was worked on that day, in a phrase \
+                 or two",
+            ),
+            false,
+        ));
+    }
+    if options.watch_outs {
+        sections.push(SectionSpec::new(
+            WATCH_OUTS,
+            Some(
+                "2-4 bullet points for a retro: risky areas and tech debt, such as hotspots with \
+                 heavy churn, large commits without a PR, or shortcuts the commits admit to; only \
+                 flag what the data above supports, and say so if nothing stands out",
+            ),
+            false,
+        ));
+    }
+    sections
+}
 
 /// Generate a prompt for Claude to summarize git commits
-pub fn generate_summary_prompt(repo: &Repository) -> String {
+pub fn generate_summary_prompt(repo: &Repository, options: &PromptOptions) -> String {
+    let mut prompt = String::new();
+
+    match options.format {
+        RecapFormat::Demo => prompt.push_str("You are helping a developer prepare for Demo Day presentation.\n\n"),
+        RecapFormat::Brag => prompt.push_str(
+            "You are helping a developer write a brag document for their performance self-review.\n\n",
+        ),
+    }
+
+    let layout = push_repo_context(&mut prompt, repo, options);
+
+    if !options.goals.is_empty() {
+        prompt.push_str("\nGoals for this period:\n");
+        for (i, goal) in options.goals.iter().enumerate() {
+            prompt.push_str(&format!("{}. {}\n", i + 1, goal.describe()));
+        }
+    }
+
+    // Notes on what may be claimed, under the achievements (or the summary without them)
+    let mut notes = Vec::new();
+    if layout.by_author {
+        notes.push("Attribute each achievement to the author(s) who did it by name, e.g. \"(Alice)\", instead of \"the team\"");
+    }
+    notes.push("Only claim improved test coverage if the test code share above supports it");
+    if !repo.hotspots.is_empty() {
+        notes.push("Call out refactoring-heavy areas if the hotspots suggest them");
+    }
+    if layout.by_pull_request {
+        notes.push("Describe each pull request as one piece of work, citing its number, rather than its commits");
+    }
+    let push_notes = |prompt: &mut String, notes: &[&str]| {
+        for note in notes {
+            prompt.push_str(&format!("   ({})\n", note));
+        }
+    };
+
+    // Instructions
+    let sections = summary_sections(repo, options);
+    let has_achievements = sections.iter().any(|section| section.is(KEY_ACHIEVEMENTS));
+    prompt.push_str("\nPlease provide:\n");
+    match (options.format, options.version) {
+        (RecapFormat::Brag, _) => prompt.push_str(
+            "1. A summary of this period's work for a self-review (2-3 paragraphs), in the first person: \
+             the problems owned, what shipped and the results, with numbers from the statistics where \
+             they help; confident but factual, claiming nothing the commits don't show\n",
+        ),
+        (RecapFormat::Demo, PromptVersion::V1) => prompt.push_str("1. A concise summary of the work done (2-3 paragraphs)\n"),
+        (RecapFormat::Demo, PromptVersion::V2) => prompt.push_str(
+            "1. A concise summary of the impact of this work (2-3 paragraphs): what users or the team can do now \
+             that they couldn't before, and why it matters; mention technical changes only to explain how\n",
+        ),
+    }
+    if repo.velocity.len() > 1 {
+        prompt.push_str("   (Mention whether activity ramped up or wound down over the weeks)\n");
+    }
+    if !has_achievements {
+        push_notes(&mut prompt, &notes);
+    }
+    for (i, section) in sections.iter().enumerate() {
+        let number = i + 2;
+        match section.instruction {
+            Some(ref instruction) => {
+                prompt.push_str(&format!("{}. {}: {}\n", number, section.title, instruction.trim()))
+            }
+            None if section.is(KEY_ACHIEVEMENTS) => match options.version {
+                PromptVersion::V1 => prompt.push_str(&format!("{}. Key achievements (3-5 bullet points)\n", number)),
+                PromptVersion::V2 => prompt.push_str(&format!(
+                    "{}. Key achievements (3-5 bullet points), each stating the outcome first and then the change behind it\n",
+                    number
+                )),
+            },
+            None if section.is(PRESENTATION_TIPS) => prompt.push_str(&format!(
+                "{}. Tips for presenting this work in a screenshare demo (3-5 tips)\n",
+                number
+            )),
+            None => prompt.push_str(&format!("{}. {}\n", number, section.title)),
+        }
+        if section.is(KEY_ACHIEVEMENTS) {
+            push_notes(&mut prompt, &notes);
+        }
+    }
+    prompt.push('\n');
+    push_examples(&mut prompt, &options.examples);
+    push_summary_format(&mut prompt, &sections);
+
+    prompt
+}
+
+/// Append the few-shot examples, if any
+fn push_examples(prompt: &mut String, examples: &[Example]) {
+    if examples.is_empty() {
+        return;
+    }
+    prompt.push_str(
+        "Here are examples of recaps in the style preferred here. Match their terminology, tone and \
+         level of detail, but describe only the work above and keep the format below.\n\n",
+    );
+    for example in examples {
+        prompt.push_str("<example>\n<input>\n");
+        prompt.push_str(example.input.trim());
+        prompt.push_str("\n</input>\n<output>\n");
+        prompt.push_str(example.output.trim());
+        prompt.push_str("\n</output>\n</example>\n\n");
+    }
+}
+
+/// Prompt revising an earlier summary following the user's instruction (`--refine`)
+pub fn generate_refine_prompt(summary: &Summary, instruction: &str) -> String {
     let mut prompt = String::new();
 
     prompt.push_str("You are helping a developer prepare for Demo Day presentation.\n\n");
+    prompt.push_str(&format!(
+        "Below is the summary you wrote of their work in the repository {}. Revise it following this instruction:\n\n",
+        summary.repository
+    ));
+    prompt.push_str(instruction.trim());
+    prompt.push_str("\n\nKeep whatever the instruction doesn't ask to change, and don't add work the summary doesn't mention.\n\n");
 
+    prompt.push_str("<summary>\n## Summary\n");
+    prompt.push_str(summary.work_summary.trim());
+    prompt.push('\n');
+    for section in &summary.sections {
+        prompt.push('\n');
+        prompt.push_str(section.to_markdown().replacen("\n\n", "\n", 1).trim_end());
+        prompt.push('\n');
+    }
+    prompt.push_str("</summary>\n\n");
+
+    let sections: Vec<SectionSpec> = summary
+        .sections
+        .iter()
+        .map(|section| SectionSpec::new(&section.title, None, section.numbered))
+        .collect();
+    push_summary_format(&mut prompt, &sections);
+    prompt
+}
+
+/// The response format [`parse_response`] expects
+fn push_summary_format(prompt: &mut String, sections: &[SectionSpec]) {
+    prompt.push_str("Format your response EXACTLY as follows:\n\n");
+    prompt.push_str("## Summary\n");
+    prompt.push_str("[Your 2-3 paragraph summary here]\n");
+    for section in sections {
+        prompt.push_str(&format!("\n## {}\n", section.title));
+        let placeholders: Vec<String> = if section.is(KEY_ACHIEVEMENTS) {
+            (1..=3).map(|i| format!("[Achievement {}]", i)).collect()
+        } else if section.is(PRESENTATION_TIPS) {
+            (1..=3).map(|i| format!("[Tip {}]", i)).collect()
+        } else if section.is(BREAKING_CHANGES) {
+            vec!["[What changed, and how to migrate]".to_string()]
+        } else if section.is(WATCH_OUTS) {
+            vec!["[Risk, and why it matters]".to_string()]
+        } else if section.is(GOAL_ALIGNMENT) {
+            vec!["[Goal]: [The work advancing it, or Not addressed]".to_string()]
+        } else {
+            (1..=2).map(|i| format!("[Point {}]", i)).collect()
+        };
+        for (i, placeholder) in placeholders.iter().enumerate() {
+            if section.numbered {
+                prompt.push_str(&format!("{}. {}\n", i + 1, placeholder));
+            } else {
+                prompt.push_str(&format!("- {}\n", placeholder));
+            }
+        }
+    }
+}
+
+/// What a repository is and the work done in it: project info, statistics,
+/// commits and enrichment context, without instructions
+pub fn repo_context(repo: &Repository, options: &PromptOptions) -> String {
+    let mut context = String::new();
+    push_repo_context(&mut context, repo, options);
+    context
+}
+
+/// Append the repository context, returning how the commits were laid out
+fn push_repo_context(prompt: &mut String, repo: &Repository, options: &PromptOptions) -> CommitLayout {
     // Repository info
     prompt.push_str(&format!("Repository: {}\n", repo.name));
 
-    if let Some(ref url) = repo.remote_url {
+    if let Some(url) = repo.canonical_url() {
         prompt.push_str(&format!("URL: {}\n", url));
     }
+    if let (Some(_), Some(ref fork)) = (&repo.upstream_url, &repo.remote_url) {
+        prompt.push_str(&format!("Worked on in the fork: {}\n", fork));
+    }
 
     // Timespan info
     if let (Some(first), Some(last)) = (repo.commits.first(), repo.commits.last()) {
@@ -22,126 +486,581 @@ pub fn generate_summary_prompt(repo: &Repository) -> String {
         ));
     }
 
+    // What the project is, so the work can be put in context
+    let about = &repo.about;
+    let mut topics = about.topics.clone();
+    for topic in &options.topics {
+        if !topics.contains(topic) {
+            topics.push(topic.clone());
+        }
+    }
+    if !about.is_empty() || !topics.is_empty() {
+        prompt.push_str("\nAbout this project:\n");
+        if let Some(ref title) = about.title {
+            prompt.push_str(&format!("- Title: {}\n", title));
+        }
+        if let Some(ref description) = about.description {
+            prompt.push_str(&format!("- Description: {}\n", description));
+        }
+        if let Some(ref intro) = about.readme_intro {
+            prompt.push_str(&format!("- README: {}\n", intro));
+        }
+        if !topics.is_empty() {
+            prompt.push_str(&format!("- Topics: {}\n", topics.join(", ")));
+        }
+        if !about.stack.is_empty() {
+            prompt.push_str(&format!(
+                "- Tech stack (from manifests changed in this period; use these names): {}\n",
+                about.stack.join(", ")
+            ));
+        }
+    }
+
     // Statistics
-    prompt.push_str(&format!("\nStatistics:\n"));
+    prompt.push_str("\nStatistics:\n");
+    if let Some(cap) = repo.commit_cap {
+        prompt.push_str(&format!(
+            "(Only the {} most recent commits are listed; the statistics cover all {})\n",
+            cap, repo.stats.total_commits
+        ));
+    }
     prompt.push_str(&format!("- Total commits: {}\n", repo.stats.total_commits));
-    prompt.push_str(&format!("- Files changed: {}\n", repo.stats.total_files_changed));
-    prompt.push_str(&format!("- Lines added: {}\n", repo.stats.total_insertions));
-    prompt.push_str(&format!("- Lines deleted: {}\n", repo.stats.total_deletions));
-    prompt.push_str(&format!(
-        "- Net lines: {:+}\n",
-        repo.stats.net_lines_changed()
-    ));
+    // Zero files means diff stats weren't collected (--no-diff-stats)
+    if repo.stats.total_files_changed > 0 {
+        prompt.push_str(&format!("- Files changed: {}\n", repo.stats.total_files_changed));
+        prompt.push_str(&format!("- Lines added: {}\n", repo.stats.total_insertions));
+        prompt.push_str(&format!("- Lines deleted: {}\n", repo.stats.total_deletions));
+        prompt.push_str(&format!(
+            "- Net lines: {:+}\n",
+            repo.stats.net_lines_changed()
+        ));
+    }
 
     if repo.stats.pr_count > 0 {
         prompt.push_str(&format!("- Pull requests: {}\n", repo.stats.pr_count));
     }
 
-    // Commits
-    prompt.push_str(&format!("\nCommits ({}):\n", repo.commits.len()));
-    for (i, commit) in repo.commits.iter().take(50).enumerate() {
-        // Limit to first 50 commits to avoid token limits
-        prompt.push_str(&format!("{}. {} - {}\n", i + 1, commit.short_hash, commit.summary));
-
-        // Add PR links if available
-        if !commit.pr_numbers.is_empty() {
-            let pr_refs: Vec<String> = commit
-                .pr_numbers
-                .iter()
-                .map(|n| format!("#{}", n))
-                .collect();
-            prompt.push_str(&format!("   PRs: {}\n", pr_refs.join(", ")));
-        }
-
-        // Add file changes (limited)
-        if !commit.files_changed.is_empty() {
-            let file_count = commit.files_changed.len();
-            let files: Vec<&String> = commit.files_changed.iter().take(5).collect();
-            let file_list = files
-                .iter()
-                .map(|f| f.as_str())
-                .collect::<Vec<_>>()
-                .join(", ");
-
-            if file_count > 5 {
-                prompt.push_str(&format!(
-                    "   Files: {} (+{} more)\n",
-                    file_list,
-                    file_count - 5
-                ));
+    if repo.test_ratio.test_files > 0 {
+        prompt.push_str(&format!(
+            "- Test code: {:.0}% of changes ({} test file changes)\n",
+            repo.test_ratio.test_percent(),
+            repo.test_ratio.test_files
+        ));
+    }
+
+    if !repo.keywords.is_empty() {
+        prompt.push_str(&format!(
+            "- Recurring topics (commits mentioning them): {}\n",
+            format_keywords(&repo.keywords)
+        ));
+    }
+
+    let breakdown = directory_breakdown(&repo.stats, DIRECTORY_BREAKDOWN_LIMIT);
+    if breakdown.len() > 1 {
+        prompt.push_str(&format!(
+            "- Changes by directory: {}\n",
+            format_directory_breakdown(&breakdown)
+        ));
+    }
+
+    // Velocity (a single week has no trend to show)
+    if repo.velocity.len() > 1 {
+        prompt.push_str("\nWeekly activity (most recent weeks, oldest first):\n");
+        for week in recent_weeks(&repo.velocity) {
+            prompt.push_str(&format!(
+                "- Week of {}: {} commits, {} lines\n",
+                week.start, week.commits, week.lines
+            ));
+        }
+    }
+
+    // Hotspots
+    if !repo.hotspots.is_empty() {
+        prompt.push_str("\nHotspots (files changed repeatedly, by lines inserted + deleted):\n");
+        for hotspot in &repo.hotspots {
+            prompt.push_str(&format!(
+                "- {} ({} commits, {} lines)\n",
+                hotspot.path, hotspot.changes, hotspot.churn
+            ));
+        }
+    }
+
+    // Commits, without trivial ones (only meaningful when diff stats were collected)
+    let min_changes = options
+        .min_changes
+        .filter(|_| repo.stats.total_files_changed > 0)
+        .unwrap_or(0);
+    let commits: Vec<_> = repo
+        .commits
+        .iter()
+        .filter(|commit| commit.insertions + commit.deletions >= min_changes || commit.is_breaking())
+        .collect();
+
+    prompt.push_str(&format!("\nCommits ({}):\n", commits.len()));
+    if commits.len() < repo.commits.len() {
+        prompt.push_str(&format!(
+            "({} trivial commits changing fewer than {} lines are omitted)\n",
+            repo.commits.len() - commits.len(),
+            min_changes
+        ));
+    }
+    // Limit to first 50 commits to avoid token limits
+    let shown = &commits[..commits.len().min(50)];
+    let with_body = commits_with_body(shown, options.commit_bodies.unwrap_or(0));
+    let by_author = group_by_author(shown);
+    let layout = CommitLayout {
+        by_author: by_author.len() > 1,
+        by_pull_request: shown.iter().any(|commit| !commit.pr_numbers.is_empty()),
+    };
+    let mut number = 1;
+    if layout.by_author {
+        // Several authors: group their commits so achievements can be attributed
+        for (author, author_commits) in &by_author {
+            prompt.push_str(&format!(
+                "\n{} <{}> ({} commits):\n",
+                author.name,
+                author.email,
+                author_commits.len()
+            ));
+            if layout.by_pull_request {
+                push_pull_requests(prompt, author_commits, &mut number, &with_body);
             } else {
-                prompt.push_str(&format!("   Files: {}\n", file_list));
+                for commit in author_commits {
+                    push_commit(prompt, number, commit, with_body.contains(commit.hash.as_str()));
+                    number += 1;
+                }
             }
         }
+    } else if layout.by_pull_request {
+        push_pull_requests(prompt, shown, &mut number, &with_body);
+    } else {
+        for (i, commit) in shown.iter().enumerate() {
+            push_commit(prompt, i + 1, commit, with_body.contains(commit.hash.as_str()));
+        }
     }
 
-    if repo.commits.len() > 50 {
+    if commits.len() > 50 {
         prompt.push_str(&format!(
             "\n(Showing first 50 of {} commits)\n",
-            repo.commits.len()
+            commits.len()
+        ));
+    }
+
+    if options.timeline {
+        push_days(prompt, &commits);
+    }
+
+    if options.watch_outs {
+        push_risk_signals(prompt, repo);
+    }
+
+    // Context from enrichment plugins (tickets, deployments, ...)
+    for enrichment in &options.enrichments {
+        prompt.push_str(&format!("\nAdditional context ({}):\n", enrichment.name));
+        prompt.push_str(&enrichment.context);
+        prompt.push('\n');
+    }
+
+    layout
+}
+
+/// Append the commits day by day (each in its author's timezone), oldest first, for a timeline
+fn push_days(prompt: &mut String, commits: &[&Commit]) {
+    let days = commits_by_day(commits.iter().copied());
+    if days.is_empty() {
+        return;
+    }
+    prompt.push_str("\nCommits by day (oldest first):\n");
+    for (day, day_commits) in days {
+        let hashes: Vec<&str> = day_commits.iter().map(|commit| commit.short_hash.as_str()).collect();
+        prompt.push_str(&format!("- {}: {}\n", day.format("%a %Y-%m-%d"), hashes.join(", ")));
+    }
+}
+
+/// Append the commits worth a watch-out: large ones without a PR, and ones
+/// admitting to a shortcut (hotspots are listed already)
+fn push_risk_signals(prompt: &mut String, repo: &Repository) {
+    let mut large: Vec<&Commit> = repo
+        .commits
+        .iter()
+        .filter(|commit| {
+            commit.pr_numbers.is_empty() && commit.insertions + commit.deletions >= LARGE_COMMIT_LINES
+        })
+        .collect();
+    large.sort_by_key(|commit| std::cmp::Reverse(commit.insertions + commit.deletions));
+
+    let shortcuts: Vec<&Commit> = repo
+        .commits
+        .iter()
+        .filter(|commit| {
+            commit
+                .message
+                .to_lowercase()
+                .split(|c: char| !c.is_alphanumeric())
+                .any(|word| SHORTCUT_WORDS.contains(&word))
+        })
+        .take(MAX_RISK_SIGNALS)
+        .collect();
+
+    if large.is_empty() && shortcuts.is_empty() {
+        return;
+    }
+    prompt.push_str("\nRisk signals:\n");
+    if !large.is_empty() {
+        let listed: Vec<String> = large
+            .iter()
+            .take(MAX_RISK_SIGNALS)
+            .map(|commit| format!("{} ({} lines)", commit.short_hash, commit.insertions + commit.deletions))
+            .collect();
+        prompt.push_str(&format!("- Large commits without a PR: {}\n", listed.join(", ")));
+    }
+    if !shortcuts.is_empty() {
+        let listed: Vec<String> = shortcuts.iter().map(|commit| commit.short_desc()).collect();
+        prompt.push_str(&format!(
+            "- Commits mentioning TODOs, hacks or workarounds: {}\n",
+            listed.join("; ")
         ));
     }
+}
+
+/// Group commits by author, keeping commit order and the order in which
+/// authors first appear
+fn group_by_author<'a>(commits: &[&'a Commit]) -> Vec<(&'a Author, Vec<&'a Commit>)> {
+    let mut groups: Vec<(&Author, Vec<&Commit>)> = Vec::new();
+
+    for commit in commits {
+        let email = commit.author.email.to_lowercase();
+        match groups
+            .iter_mut()
+            .find(|(author, _)| author.email.to_lowercase() == email)
+        {
+            Some((_, group)) => group.push(commit),
+            None => groups.push((&commit.author, vec![commit])),
+        }
+    }
+
+    groups
+}
+
+/// Append commits PR by PR, the way reviewers saw the work, then the
+/// commits outside any PR, numbering on from `number`
+fn push_pull_requests(prompt: &mut String, commits: &[&Commit], number: &mut usize, with_body: &HashSet<&str>) {
+    for pr in group_by_pr(commits) {
+        prompt.push_str(&format!("\n{}:\n", pr.describe()));
+        for commit in &pr.commits {
+            push_commit(prompt, *number, commit, with_body.contains(commit.hash.as_str()));
+            *number += 1;
+        }
+    }
+    let outside: Vec<&Commit> = commits
+        .iter()
+        .copied()
+        .filter(|commit| commit.pr_numbers.is_empty())
+        .collect();
+    if !outside.is_empty() {
+        prompt.push_str(&format!("\nNot in a pull request ({} commits):\n", outside.len()));
+        for commit in outside {
+            push_commit(prompt, *number, commit, with_body.contains(commit.hash.as_str()));
+            *number += 1;
+        }
+    }
+}
+
+/// A pull request and the commits referencing it
+struct PullRequestGroup<'a> {
+    number: u32,
+    commits: Vec<&'a Commit>,
+}
+
+impl PullRequestGroup<'_> {
+    /// The PR's title, as far as commits tell: the title GitHub puts in a
+    /// merge commit's body, a squashed commit's subject without its `(#N)`,
+    /// or else the subject of its first commit
+    fn title(&self) -> String {
+        let merge_prefix = format!("Merge pull request #{} ", self.number);
+        let squash_suffix = format!(" (#{})", self.number);
+        self.commits
+            .iter()
+            .find_map(|commit| {
+                commit.summary.starts_with(&merge_prefix).then_some(())?;
+                let title = commit.body.as_deref()?.lines().next()?.trim();
+                (!title.is_empty()).then(|| title.to_string())
+            })
+            .or_else(|| {
+                self.commits
+                    .iter()
+                    .find_map(|commit| commit.summary.strip_suffix(&squash_suffix))
+                    .map(str::to_string)
+            })
+            .or_else(|| self.commits.last().map(|commit| commit.summary.clone()))
+            .unwrap_or_default()
+    }
+
+    /// "PR #42: Add retry queue (3 commits, +120/-30 lines, 4 files)", without
+    /// lines and files when diff stats weren't collected
+    fn describe(&self) -> String {
+        let files: HashSet<&str> = self
+            .commits
+            .iter()
+            .flat_map(|commit| commit.files_changed.iter().map(String::as_str))
+            .collect();
+        let mut totals = format!("{} commits", self.commits.len());
+        if !files.is_empty() {
+            let insertions: u32 = self.commits.iter().map(|commit| commit.insertions).sum();
+            let deletions: u32 = self.commits.iter().map(|commit| commit.deletions).sum();
+            totals.push_str(&format!(", +{}/-{} lines, {} files", insertions, deletions, files.len()));
+        }
+        format!("PR #{}: {} ({})", self.number, self.title(), totals)
+    }
+}
+
+/// Group commits under the first PR each references, in the order PRs first
+/// appear; commits without a PR are left out
+fn group_by_pr<'a>(commits: &[&'a Commit]) -> Vec<PullRequestGroup<'a>> {
+    let mut groups: Vec<PullRequestGroup> = Vec::new();
+
+    for commit in commits {
+        let Some(&number) = commit.pr_numbers.first() else {
+            continue;
+        };
+        match groups.iter_mut().find(|group| group.number == number) {
+            Some(group) => group.commits.push(commit),
+            None => groups.push(PullRequestGroup {
+                number,
+                commits: vec![commit],
+            }),
+        }
+    }
+
+    groups
+}
+
+/// Hashes of the `limit` largest commits (by lines changed) that have a body
+///
+/// Without diff stats every commit is size zero and the most recent win.
+fn commits_with_body<'a>(commits: &[&'a Commit], limit: usize) -> HashSet<&'a str> {
+    let mut candidates: Vec<&Commit> = commits
+        .iter()
+        .copied()
+        .filter(|commit| commit.body.as_deref().is_some_and(|body| !body.trim().is_empty()))
+        .collect();
+    // Stable, so equally large commits keep their (newest first) order
+    candidates.sort_by_key(|commit| std::cmp::Reverse(commit.insertions + commit.deletions));
+    candidates
+        .into_iter()
+        .take(limit)
+        .map(|commit| commit.hash.as_str())
+        .collect()
+}
+
+/// Append one numbered commit with its PRs and files, and its body if asked
+///
+/// Breaking commits are marked, and their body (with the migration notes) is always sent.
+fn push_commit(prompt: &mut String, number: usize, commit: &Commit, with_body: bool) {
+    let breaking = commit.is_breaking();
+    let marker = if breaking { " [BREAKING]" } else { "" };
+    prompt.push_str(&format!("{}. {} - {}{}\n", number, commit.short_hash, commit.summary, marker));
+
+    if let Some(body) = commit.body.as_deref().filter(|_| with_body || breaking) {
+        let body = body.trim();
+        let mut kept: String = body.chars().take(MAX_BODY_CHARS).collect();
+        if kept.len() < body.len() {
+            kept.push_str(" (truncated)");
+        }
+        prompt.push_str("   Details:\n");
+        for line in kept.lines().filter(|line| !line.trim().is_empty()) {
+            prompt.push_str(&format!("     {}\n", line.trim_end()));
+        }
+    }
+
+    // Add PR links if available
+    if !commit.pr_numbers.is_empty() {
+        let pr_refs: Vec<String> = commit
+            .pr_numbers
+            .iter()
+            .map(|n| format!("#{}", n))
+            .collect();
+        prompt.push_str(&format!("   PRs: {}\n", pr_refs.join(", ")));
+    }
+
+    // Add file changes (limited)
+    if !commit.files_changed.is_empty() {
+        let file_count = commit.files_changed.len();
+        let files: Vec<&String> = commit.files_changed.iter().take(5).collect();
+        let file_list = files
+            .iter()
+            .map(|f| f.as_str())
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        if file_count > 5 {
+            prompt.push_str(&format!(
+                "   Files: {} (+{} more)\n",
+                file_list,
+                file_count - 5
+            ));
+        } else {
+            prompt.push_str(&format!("   Files: {}\n", file_list));
+        }
+    }
+}
+
+/// System prompt for follow-up questions about a finished recap
+///
+/// `contexts` are the [`repo_context`]s of the summarized repositories.
+pub fn generate_chat_prompt(report: &str, contexts: &[String]) -> String {
+    let mut prompt = String::new();
+
+    prompt.push_str("You are helping a developer prepare for Demo Day presentation.\n");
+    prompt.push_str("You already wrote the recap below from their git history. ");
+    prompt.push_str("Answer their follow-up questions about it: expand on parts of the work, ");
+    prompt.push_str("make achievements more concrete or rephrase them for an audience. ");
+    prompt.push_str("Only claim what the recap or the repository details support, and say so ");
+    prompt.push_str("when a question can't be answered from them.\n");
+
+    prompt.push_str("\n<recap>\n");
+    prompt.push_str(report.trim());
+    prompt.push_str("\n</recap>\n");
+
+    for context in contexts {
+        prompt.push_str("\n<repository>\n");
+        prompt.push_str(context.trim());
+        prompt.push_str("\n</repository>\n");
+    }
+
+    prompt
+}
+
+/// Parse Claude's response into the work summary and the sections after it
+///
+/// Every `##` heading other than "Summary" starts a section; its bullet or
+/// numbered items are kept, and it counts as numbered if its first item is.
+pub fn parse_response(response: &str) -> (String, Vec<Section>) {
+    let mut summary_lines = Vec::new();
+    let mut sections: Vec<Section> = Vec::new();
+    let mut in_summary = false;
+
+    for line in response.lines() {
+        let trimmed = line.trim();
+
+        if let Some(heading) = trimmed.strip_prefix("## ") {
+            let heading = heading.trim();
+            in_summary = heading.eq_ignore_ascii_case("Summary");
+            if !in_summary {
+                sections.push(Section::bullets(heading, Vec::new()));
+            }
+            continue;
+        }
+
+        if in_summary {
+            if !trimmed.is_empty() && !trimmed.starts_with('#') {
+                summary_lines.push(trimmed.to_string());
+            }
+        } else if let Some(section) = sections.last_mut() {
+            if let Some(item) = trimmed.strip_prefix("- ").or_else(|| trimmed.strip_prefix("* ")) {
+                section.items.push(item.trim().to_string());
+            } else if let Some(item) = numbered_item(trimmed) {
+                section.numbered |= section.items.is_empty();
+                section.items.push(item.to_string());
+            }
+        }
+    }
+
+    sections.retain(|section| !section.items.is_empty());
+    (summary_lines.join(" "), sections)
+}
+
+/// The text of a "1. item" or "1) item" line
+fn numbered_item(line: &str) -> Option<&str> {
+    let digits = line.len() - line.trim_start_matches(|c: char| c.is_ascii_digit()).len();
+    if digits == 0 {
+        return None;
+    }
+    line[digits..]
+        .strip_prefix(". ")
+        .or_else(|| line[digits..].strip_prefix(") "))
+        .map(str::trim)
+}
+
+/// Generate a prompt for Claude to synthesize a recap across repositories
+pub fn generate_overall_prompt(summaries: &[&Summary]) -> String {
+    let mut prompt = String::new();
+
+    prompt.push_str("You are helping a developer prepare for Demo Day presentation.\n");
+    prompt.push_str(&format!(
+        "Below are summaries of their work in {} repositories.\n\n",
+        summaries.len()
+    ));
+
+    for summary in summaries {
+        prompt.push_str(&format!("### {}\n", summary.repository));
+        prompt.push_str(&format!("{}\n", summary.work_summary));
+        for achievement in summary.key_achievements() {
+            prompt.push_str(&format!("- {}\n", achievement));
+        }
+        prompt.push('\n');
+    }
 
     // Instructions
-    prompt.push_str("\nPlease provide:\n");
-    prompt.push_str("1. A concise summary of the work done (2-3 paragraphs)\n");
-    prompt.push_str("2. Key achievements (3-5 bullet points)\n");
-    prompt.push_str("3. Tips for presenting this work in a screenshare demo (3-5 tips)\n\n");
+    prompt.push_str("Please provide an overall recap across all repositories:\n");
+    prompt.push_str("1. Themes that connect the work (2-4 bullet points)\n");
+    prompt.push_str("2. The biggest wins overall (3-5 bullet points)\n");
+    prompt.push_str(
+        "3. A suggested order to demo the repositories, ranked by demo-worthiness: changes users can \
+         see or try (new features, UI, behavior) before internal work, and complete, shipped work before \
+         work in progress; refactors and chores go last. List every repository exactly once, by its name \
+         as given after ###, with a one-line rationale\n\n",
+    );
     prompt.push_str("Format your response EXACTLY as follows:\n\n");
-    prompt.push_str("## Summary\n");
-    prompt.push_str("[Your 2-3 paragraph summary here]\n\n");
-    prompt.push_str("## Key Achievements\n");
-    prompt.push_str("- [Achievement 1]\n");
-    prompt.push_str("- [Achievement 2]\n");
-    prompt.push_str("- [Achievement 3]\n\n");
-    prompt.push_str("## Presentation Tips\n");
-    prompt.push_str("1. [Tip 1]\n");
-    prompt.push_str("2. [Tip 2]\n");
-    prompt.push_str("3. [Tip 3]\n");
+    prompt.push_str("## Themes\n");
+    prompt.push_str("- [Theme 1]\n");
+    prompt.push_str("- [Theme 2]\n\n");
+    prompt.push_str("## Biggest Wins\n");
+    prompt.push_str("- [Win 1]\n");
+    prompt.push_str("- [Win 2]\n");
+    prompt.push_str("- [Win 3]\n\n");
+    prompt.push_str("## Suggested Demo Order\n");
+    prompt.push_str("1. [Repository] - [Rationale]\n");
+    prompt.push_str("2. [Repository] - [Rationale]\n");
 
     prompt
 }
 
-/// Parse Claude's response into structured data
-pub fn parse_response(response: &str) -> (String, Vec<String>, Vec<String>) {
-    let mut achievements = Vec::new();
-    let mut tips = Vec::new();
+/// Parse Claude's overall recap response into (themes, biggest wins, demo order)
+pub fn parse_overall_response(response: &str) -> (Vec<String>, Vec<String>, Vec<String>) {
+    let mut themes = Vec::new();
+    let mut wins = Vec::new();
+    let mut demo_order = Vec::new();
 
     let mut current_section = None;
-    let mut summary_lines = Vec::new();
 
     for line in response.lines() {
         let trimmed = line.trim();
 
-        if trimmed.starts_with("## Summary") {
-            current_section = Some("summary");
+        if trimmed.starts_with("## Themes") {
+            current_section = Some("themes");
             continue;
-        } else if trimmed.starts_with("## Key Achievements") {
-            current_section = Some("achievements");
+        } else if trimmed.starts_with("## Biggest Wins") {
+            current_section = Some("wins");
             continue;
-        } else if trimmed.starts_with("## Presentation Tips") {
-            current_section = Some("tips");
+        } else if trimmed.starts_with("## Suggested Demo Order") {
+            current_section = Some("demo_order");
             continue;
         }
 
+        let bullet = trimmed
+            .strip_prefix("- ")
+            .or_else(|| trimmed.strip_prefix("* "))
+            .map(str::trim);
+
         match current_section {
-            Some("summary") => {
-                if !trimmed.is_empty() && !trimmed.starts_with("##") {
-                    summary_lines.push(trimmed.to_string());
-                }
-            }
-            Some("achievements") => {
-                if let Some(achievement) = trimmed.strip_prefix("- ").or_else(|| trimmed.strip_prefix("* ")) {
-                    achievements.push(achievement.trim().to_string());
-                }
-            }
-            Some("tips") => {
+            Some("themes") => themes.extend(bullet.map(String::from)),
+            Some("wins") => wins.extend(bullet.map(String::from)),
+            Some("demo_order") => {
                 // Match numbered lists: "1. ", "2. ", etc.
-                if let Some(tip) = trimmed.chars().next() {
-                    if tip.is_numeric() && trimmed.contains(". ") {
-                        if let Some(content) = trimmed.split(". ").nth(1) {
-                            tips.push(content.trim().to_string());
-                        }
+                if let Some((number, content)) = trimmed.split_once(". ") {
+                    if !number.is_empty() && number.chars().all(|c| c.is_ascii_digit()) {
+                        demo_order.push(content.trim().to_string());
                     }
                 }
             }
@@ -149,16 +1068,140 @@ pub fn parse_response(response: &str) -> (String, Vec<String>, Vec<String>) {
         }
     }
 
-    let summary = summary_lines.join(" ");
+    (themes, wins, demo_order)
+}
+
+/// The suggested demo order with exactly one entry per repository
+///
+/// Entries are matched to `repositories` by the name they start with
+/// (ignoring case and markdown emphasis); entries naming no repository or
+/// one already listed are dropped, and repositories the model left out are
+/// appended so the ranking still covers every one.
+pub fn rank_demo_order(entries: Vec<String>, repositories: &[&str]) -> Vec<String> {
+    let mut ranked = Vec::new();
+    let mut listed = HashSet::new();
+
+    for entry in entries {
+        let plain = entry.trim_start_matches(['*', '`', '_']).to_lowercase();
+        // The longest name wins, so "api-gateway" isn't taken for "api"
+        let repository = repositories
+            .iter()
+            .filter(|name| plain.starts_with(&name.to_lowercase()))
+            .max_by_key(|name| name.len());
+        if let Some(repository) = repository {
+            if listed.insert(*repository) {
+                ranked.push(entry);
+            }
+        }
+    }
+
+    for repository in repositories {
+        if listed.insert(*repository) {
+            ranked.push(format!("{} - not ranked", repository));
+        }
+    }
+    ranked
+}
+
+/// Prompt for one month of a year in review: the month's commit subjects in
+/// each repository, answered with a summary and highlights (see `parse_response`)
+pub fn generate_month_prompt(month: &MonthRecap) -> String {
+    let mut prompt = String::new();
+
+    prompt.push_str("You are helping a developer look back on their year.\n");
+    prompt.push_str(&format!(
+        "Below is their work in {}: {}.\n\n",
+        month.label(),
+        month.describe()
+    ));
+
+    for repo in &month.repositories {
+        prompt.push_str(&format!(
+            "### {} ({} commits, +{} -{})\n",
+            repo.name, repo.stats.total_commits, repo.stats.total_insertions, repo.stats.total_deletions
+        ));
+        // The largest commits say the most about the month
+        let mut commits: Vec<&Commit> = repo.commits.iter().collect();
+        commits.sort_by_key(|commit| std::cmp::Reverse(commit.insertions + commit.deletions));
+        for commit in commits.iter().take(MAX_MONTH_COMMITS) {
+            prompt.push_str(&format!("- {}\n", commit.summary));
+        }
+        if commits.len() > MAX_MONTH_COMMITS {
+            prompt.push_str(&format!("- ... and {} smaller commits\n", commits.len() - MAX_MONTH_COMMITS));
+        }
+        prompt.push('\n');
+    }
+
+    prompt.push_str("Please provide:\n");
+    prompt.push_str("1. A summary of the month's work (one short paragraph)\n");
+    prompt.push_str(&format!(
+        "2. {}: 2-4 bullet points on the most notable things shipped or changed\n\n",
+        HIGHLIGHTS
+    ));
+    prompt.push_str("Format your response EXACTLY as follows:\n\n");
+    prompt.push_str("## Summary\n");
+    prompt.push_str("[One paragraph]\n\n");
+    prompt.push_str(&format!("## {}\n", HIGHLIGHTS));
+    prompt.push_str("- [Highlight 1]\n");
+    prompt.push_str("- [Highlight 2]\n");
+
+    prompt
+}
+
+/// Prompt for the synthesis of a year in review from its monthly summaries
+/// and totals, answered with an overview, trends and milestones
+pub fn generate_year_prompt(year: i32, months: &[MonthRecap]) -> String {
+    let mut prompt = String::new();
+
+    prompt.push_str("You are helping a developer look back on their year.\n");
+    prompt.push_str(&format!("Below is their work in {}, month by month.\n\n", year));
+
+    for month in months {
+        prompt.push_str(&format!("### {}\n", month.label()));
+        match month.summary {
+            Some(ref summary) => {
+                prompt.push_str(&format!("{}\n", month.describe()));
+                prompt.push_str(&format!("{}\n", summary.work_summary));
+                for highlight in summary.section(HIGHLIGHTS) {
+                    prompt.push_str(&format!("- {}\n", highlight));
+                }
+            }
+            None if month.repositories.is_empty() => prompt.push_str("No commits.\n"),
+            None => prompt.push_str(&format!("{}\n", month.describe())),
+        }
+        prompt.push('\n');
+    }
+
+    prompt.push_str("Please provide a review of the year:\n");
+    prompt.push_str("1. An overview of the year's work (2-3 paragraphs): the main areas, how they grew, and the results\n");
+    prompt.push_str(&format!(
+        "2. {}: 3-5 bullet points on how the work changed over the year (focus, pace, areas picked up or finished)\n",
+        TRENDS
+    ));
+    prompt.push_str(&format!(
+        "3. {}: 3-6 bullet points, each starting with its month, on the most important things shipped\n\n",
+        MILESTONES
+    ));
+    prompt.push_str("Format your response EXACTLY as follows:\n\n");
+    prompt.push_str("## Summary\n");
+    prompt.push_str("[Your overview here]\n\n");
+    prompt.push_str(&format!("## {}\n", TRENDS));
+    prompt.push_str("- [Trend 1]\n");
+    prompt.push_str("- [Trend 2]\n\n");
+    prompt.push_str(&format!("## {}\n", MILESTONES));
+    prompt.push_str("- [Month]: [Milestone 1]\n");
+    prompt.push_str("- [Month]: [Milestone 2]\n");
 
-    (summary, achievements, tips)
+    prompt
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::git::about::ProjectInfo;
+    use crate::git::stats::{Hotspot, TestRatio, WeekBucket};
     use crate::git::{Author, Commit, RepoStats};
-    use chrono::Utc;
+    use chrono::{NaiveDate, Utc};
     use std::path::PathBuf;
 
     fn create_test_repo() -> Repository {
@@ -169,40 +1212,297 @@ mod tests {
                 name: "Test".to_string(),
                 email: "test@example.com".to_string(),
             },
-            timestamp: Utc::now(),
+            timestamp: Utc::now().fixed_offset(),
             message: "Test commit".to_string(),
             summary: "Test commit".to_string(),
             body: None,
             files_changed: vec!["file1.rs".to_string()],
+            file_churn: vec![15],
             insertions: 10,
             deletions: 5,
             pr_numbers: vec![123],
+            patch_id: None,
         };
 
         Repository {
             path: PathBuf::from("/test"),
             name: "test-repo".to_string(),
             remote_url: Some("https://github.com/test/repo".to_string()),
+            upstream_url: None,
             github_info: None,
+            about: Default::default(),
             commits: vec![commit.clone()],
-            stats: RepoStats::from_commits(&vec![commit]),
+            hotspots: vec![Hotspot {
+                path: "file1.rs".to_string(),
+                changes: 2,
+                churn: 30,
+            }],
+            velocity: vec![
+                WeekBucket {
+                    start: NaiveDate::from_ymd_opt(2025, 1, 6).unwrap(),
+                    commits: 0,
+                    lines: 0,
+                },
+                WeekBucket {
+                    start: NaiveDate::from_ymd_opt(2025, 1, 13).unwrap(),
+                    commits: 1,
+                    lines: 15,
+                },
+            ],
+            test_ratio: TestRatio {
+                test_files: 1,
+                source_files: 1,
+                test_lines: 20,
+                source_lines: 60,
+            },
+            keywords: vec![("auth".to_string(), 3)],
+            commit_cap: None,
+            stats: RepoStats::from_commits(&[commit]),
         }
     }
 
     #[test]
     fn test_generate_summary_prompt() {
         let repo = create_test_repo();
-        let prompt = generate_summary_prompt(&repo);
+        let prompt = generate_summary_prompt(&repo, &PromptOptions::default());
 
         assert!(prompt.contains("Repository: test-repo"));
         assert!(prompt.contains("Statistics:"));
         assert!(prompt.contains("Commits (1):"));
         assert!(prompt.contains("Test commit"));
+        assert!(prompt.contains("- file1.rs (2 commits, 30 lines)"));
+        assert!(prompt.contains("topics (commits mentioning them): auth (3)"));
+        assert!(prompt.contains("- Test code: 25% of changes (1 test file changes)"));
+        assert!(prompt.contains("- Week of 2025-01-13: 1 commits, 15 lines"));
         assert!(prompt.contains("## Summary"));
         assert!(prompt.contains("## Key Achievements"));
         assert!(prompt.contains("## Presentation Tips"));
     }
 
+    #[test]
+    fn test_summary_prompt_versions() {
+        let repo = create_test_repo();
+        let v1 = generate_summary_prompt(&repo, &PromptOptions::default());
+        let options = PromptOptions { version: PromptVersion::V2, ..Default::default() };
+        let v2 = generate_summary_prompt(&repo, &options);
+
+        assert!(v1.contains("1. A concise summary of the work done"));
+        assert!(v2.contains("1. A concise summary of the impact of this work"));
+        assert!(v2.contains("each stating the outcome first"));
+        // Same context and response format
+        assert!(v2.contains("Commits (1):") && v2.contains("## Key Achievements"));
+
+        assert_eq!("v2".parse::<PromptVersion>(), Ok(PromptVersion::V2));
+        assert!("v9".parse::<PromptVersion>().is_err());
+    }
+
+    #[test]
+    fn test_summary_prompt_examples() {
+        let repo = create_test_repo();
+        assert!(!generate_summary_prompt(&repo, &PromptOptions::default()).contains("<example>"));
+
+        let options = PromptOptions {
+            examples: vec![Example {
+                input: "- Add retry queue for webhooks\n".to_string(),
+                output: "## Summary\nShipped reliable webhook delivery (WEBHOOK-12).\n".to_string(),
+            }],
+            ..Default::default()
+        };
+        let prompt = generate_summary_prompt(&repo, &options);
+        assert!(prompt.contains(
+            "<example>\n<input>\n- Add retry queue for webhooks\n</input>\n\
+             <output>\n## Summary\nShipped reliable webhook delivery (WEBHOOK-12).\n</output>\n</example>"
+        ));
+        // After the instructions, before the response format
+        let example = prompt.find("<example>").unwrap();
+        assert!(prompt.find("3. Tips for presenting").unwrap() < example);
+        assert!(example < prompt.find("Format your response EXACTLY").unwrap());
+    }
+
+    #[test]
+    fn test_summary_prompt_min_changes() {
+        let repo = create_test_repo();
+
+        // The only commit changes 15 lines
+        let options = PromptOptions { min_changes: Some(20), ..Default::default() };
+        let prompt = generate_summary_prompt(&repo, &options);
+        assert!(prompt.contains("Commits (0):"));
+        assert!(prompt.contains("(1 trivial commits changing fewer than 20 lines are omitted)"));
+        assert!(prompt.contains("- Total commits: 1"));
+
+        let options = PromptOptions { min_changes: Some(15), ..Default::default() };
+        let prompt = generate_summary_prompt(&repo, &options);
+        assert!(prompt.contains("Commits (1):"));
+    }
+
+    #[test]
+    fn test_summary_prompt_commit_bodies() {
+        let mut repo = create_test_repo();
+        repo.commits[0].body = Some("Sessions expired early because\n\nthe clock skew was ignored.".to_string());
+        let mut large = repo.commits[0].clone();
+        large.short_hash = "def456".to_string();
+        large.hash = "def456".to_string();
+        large.body = Some("x".repeat(MAX_BODY_CHARS + 50));
+        large.insertions = 200;
+        repo.commits.push(large);
+
+        // Bodies are left out unless asked for
+        let prompt = generate_summary_prompt(&repo, &PromptOptions::default());
+        assert!(!prompt.contains("Details:"));
+
+        // Only the largest commit gets its body, truncated
+        let options = PromptOptions { commit_bodies: Some(1), ..Default::default() };
+        let prompt = generate_summary_prompt(&repo, &options);
+        assert_eq!(prompt.matches("Details:").count(), 1);
+        assert!(prompt.contains(&format!("2. def456 - Test commit\n   Details:\n     {} (truncated)\n", "x".repeat(MAX_BODY_CHARS))));
+
+        let options = PromptOptions { commit_bodies: Some(5), ..Default::default() };
+        let prompt = generate_summary_prompt(&repo, &options);
+        assert!(prompt.contains("   Details:\n     Sessions expired early because\n     the clock skew was ignored.\n"));
+    }
+
+    #[test]
+    fn test_summary_prompt_about() {
+        // Nothing known, no block
+        let prompt = generate_summary_prompt(&create_test_repo(), &PromptOptions::default());
+        assert!(!prompt.contains("About this project"));
+
+        let mut repo = create_test_repo();
+        repo.about = ProjectInfo {
+            title: Some("Ledger".to_string()),
+            description: Some("Bookkeeping API".to_string()),
+            readme_intro: None,
+            topics: vec!["accounting".to_string()],
+            stack: vec!["Rust".to_string(), "Axum".to_string()],
+        };
+        let options = PromptOptions {
+            topics: vec!["accounting".to_string(), "rust".to_string()],
+            ..Default::default()
+        };
+        let prompt = generate_summary_prompt(&repo, &options);
+        assert!(prompt.contains(
+            "\nAbout this project:\n- Title: Ledger\n- Description: Bookkeeping API\n- Topics: accounting, rust\n\
+             - Tech stack (from manifests changed in this period; use these names): Rust, Axum\n"
+        ));
+        assert!(prompt.find("About this project").unwrap() < prompt.find("Statistics:").unwrap());
+    }
+
+    #[test]
+    fn test_summary_prompt_enrichments() {
+        let options = PromptOptions {
+            enrichments: vec![Enrichment {
+                name: "tickets".to_string(),
+                context: "PROJ-42: Login fails on Safari".to_string(),
+            }],
+            ..Default::default()
+        };
+        let prompt = generate_summary_prompt(&create_test_repo(), &options);
+        assert!(prompt.contains("\nAdditional context (tickets):\nPROJ-42: Login fails on Safari\n"));
+        assert!(
+            prompt.find("Additional context").unwrap() < prompt.find("Please provide").unwrap()
+        );
+    }
+
+    #[test]
+    fn test_summary_prompt_groups_authors() {
+        let mut repo = create_test_repo();
+        repo.commits[0].pr_numbers.clear();
+        let mut other = repo.commits[0].clone();
+        other.author = Author {
+            name: "Bob".to_string(),
+            email: "bob@example.com".to_string(),
+        };
+        other.short_hash = "def456".to_string();
+        repo.commits.push(other);
+
+        let prompt = generate_summary_prompt(&repo, &PromptOptions::default());
+        assert!(prompt.contains("Test <test@example.com> (1 commits):\n1. abc123"));
+        assert!(prompt.contains("Bob <bob@example.com> (1 commits):\n2. def456"));
+        assert!(prompt.contains("Attribute each achievement"));
+
+        // A single author isn't grouped
+        let prompt = generate_summary_prompt(&create_test_repo(), &PromptOptions::default());
+        assert!(!prompt.contains("Attribute each achievement"));
+    }
+
+    #[test]
+    fn test_summary_prompt_groups_pull_requests() {
+        let mut repo = create_test_repo();
+        let base = repo.commits[0].clone();
+        let commit = |hash: &str, summary: &str, body: Option<&str>, prs: Vec<u32>, file: &str| Commit {
+            short_hash: hash.to_string(),
+            hash: hash.to_string(),
+            summary: summary.to_string(),
+            body: body.map(str::to_string),
+            pr_numbers: prs,
+            files_changed: vec![file.to_string()],
+            ..base.clone()
+        };
+        repo.commits = vec![
+            commit("m1", "Merge pull request #7 from jane/retry", Some("Retry failed webhooks"), vec![7], "src/queue.rs"),
+            commit("c2", "Add backoff", None, vec![7], "src/backoff.rs"),
+            commit("c3", "Bump version", None, vec![], "Cargo.toml"),
+            commit("s4", "Fix login redirect (#9)", None, vec![9], "src/auth.rs"),
+        ];
+
+        let prompt = generate_summary_prompt(&repo, &PromptOptions::default());
+        assert!(prompt.contains(
+            "\nPR #7: Retry failed webhooks (2 commits, +20/-10 lines, 2 files):\n1. m1 - Merge pull request"
+        ));
+        assert!(prompt.contains("\nPR #9: Fix login redirect (1 commits, +10/-5 lines, 1 files):\n3. s4"));
+        assert!(prompt.contains("\nNot in a pull request (1 commits):\n4. c3 - Bump version"));
+        assert!(prompt.contains("Describe each pull request as one piece of work"));
+
+        // With several authors, PRs are grouped within each author's commits
+        let mut team = repo.clone();
+        team.commits[3].author = Author {
+            name: "Bob".to_string(),
+            email: "bob@example.com".to_string(),
+        };
+        let prompt = generate_summary_prompt(&team, &PromptOptions::default());
+        assert!(prompt.contains("Test <test@example.com> (3 commits):\n\nPR #7: Retry failed webhooks"));
+        assert!(prompt.contains("\nNot in a pull request (1 commits):\n3. c3 - Bump version"));
+        assert!(prompt.contains("Bob <bob@example.com> (1 commits):\n\nPR #9: Fix login redirect"));
+        assert!(prompt.contains("Attribute each achievement") && prompt.contains("Describe each pull request"));
+
+        // Without PR numbers the list stays flat
+        for commit in &mut repo.commits {
+            commit.pr_numbers.clear();
+        }
+        let prompt = generate_summary_prompt(&repo, &PromptOptions::default());
+        assert!(!prompt.contains("PR #7") && prompt.contains("Commits (4):\n1. m1"));
+    }
+
+    #[test]
+    fn test_generate_chat_prompt() {
+        let repo = create_test_repo();
+        let context = repo_context(&repo, &PromptOptions::default());
+        assert!(context.starts_with("Repository: test-repo\n"));
+        assert!(!context.contains("Please provide"));
+
+        let prompt = generate_chat_prompt("# Dev Recap\n\nShipped login.\n", &[context]);
+        assert!(prompt.contains("<recap>\n# Dev Recap\n\nShipped login.\n</recap>"));
+        assert!(prompt.contains("<repository>\nRepository: test-repo\n"));
+        assert!(prompt.contains("1. abc123 - Test commit"));
+    }
+
+    #[test]
+    fn test_generate_refine_prompt() {
+        let summary = Summary::new(
+            "api".to_string(),
+            "Sped up the billing queries and fixed CI.".to_string(),
+            vec!["Billing queries 3x faster".to_string(), "CI cache".to_string()],
+            vec!["Show the latency graph".to_string()],
+        );
+        let prompt = generate_refine_prompt(&summary, "  emphasize the performance work, drop the CI chores\n");
+
+        assert!(prompt.contains("repository api. Revise it following this instruction:\n\nemphasize the performance work, drop the CI chores\n\n"));
+        assert!(prompt.contains(
+            "<summary>\n## Summary\nSped up the billing queries and fixed CI.\n\n## Key Achievements\n- Billing queries 3x faster\n- CI cache\n\n## Presentation Tips\n1. Show the latency graph\n</summary>"
+        ));
+        assert!(prompt.ends_with("3. [Tip 3]\n"));
+    }
+
     #[test]
     fn test_parse_response() {
         let response = r#"
@@ -221,15 +1521,179 @@ It has multiple lines.
 3. Tip number three
 "#;
 
-        let (summary, achievements, tips) = parse_response(response);
+        let (summary, sections) = parse_response(response);
+        let (achievements, tips) = (&sections[0].items, &sections[1].items);
 
         assert!(summary.contains("test summary"));
+        assert_eq!(sections[0].title, "Key Achievements");
+        assert!(!sections[0].numbered && sections[1].numbered);
         assert_eq!(achievements.len(), 3);
         assert_eq!(achievements[0], "Achievement 1");
         assert_eq!(tips.len(), 3);
         assert_eq!(tips[0], "Tip number one");
     }
 
+    #[test]
+    fn test_parse_response_sections() {
+        let response = r#"
+## Summary
+Moved the API to v2.
+
+## Key Achievements
+- Shipped the v2 API
+
+## Presentation Tips
+1. Show the new endpoints
+
+## Breaking Changes & Migrations
+- The `/v1` endpoints are gone; call `/v2` instead
+* `timeout` is now in seconds
+"#;
+
+        let (work_summary, sections) = parse_response(response);
+        let summary = Summary::with_sections("api".to_string(), work_summary, sections);
+        assert_eq!(summary.key_achievements(), ["Shipped the v2 API"]);
+        assert_eq!(summary.presentation_tips(), ["Show the new endpoints"]);
+        assert_eq!(
+            summary.breaking_changes(),
+            ["The `/v1` endpoints are gone; call `/v2` instead", "`timeout` is now in seconds"]
+        );
+
+        // Unknown headings become sections too; empty ones are dropped
+        let (_, sections) = parse_response("## Summary\nDone.\n\n## Next Steps\n1) Remove v1\n\n## Metrics\n");
+        assert_eq!(sections, vec![Section::numbered("Next Steps", vec!["Remove v1".to_string()])]);
+    }
+
+    #[test]
+    fn test_summary_prompt_breaking_changes() {
+        let repo = create_test_repo();
+        let prompt = generate_summary_prompt(&repo, &PromptOptions::default());
+        assert!(!prompt.contains("[BREAKING]"));
+        assert!(!prompt.contains("Breaking Changes & Migrations"));
+
+        let mut repo = create_test_repo();
+        repo.commits[0].summary = "feat(api)!: drop v1 endpoints".to_string();
+        repo.commits[0].body = Some("BREAKING CHANGE: clients must call /v2".to_string());
+        repo.commits[0].message = format!("{}\n\n{}", repo.commits[0].summary, "BREAKING CHANGE: clients must call /v2");
+        // Kept despite min_changes, with its body although commit_bodies is off
+        let options = PromptOptions { min_changes: Some(100), ..Default::default() };
+        let prompt = generate_summary_prompt(&repo, &options);
+        assert!(prompt.contains("1. abc123 - feat(api)!: drop v1 endpoints [BREAKING]\n   Details:\n     BREAKING CHANGE: clients must call /v2\n"));
+        assert!(prompt.contains("4. Breaking Changes & Migrations: for each commit marked [BREAKING]"));
+        assert!(prompt.ends_with("## Breaking Changes & Migrations\n- [What changed, and how to migrate]\n"));
+    }
+
+    #[test]
+    fn test_summary_prompt_custom_sections() {
+        let repo = create_test_repo();
+        let options = PromptOptions {
+            sections: vec![
+                SectionSpec::new("Metrics", Some("numbers that show the impact, only from the data above"), false),
+                SectionSpec::new("Next Steps", Some("what's still open (2-3 items)"), true),
+            ],
+            ..Default::default()
+        };
+        let prompt = generate_summary_prompt(&repo, &options);
+
+        // The notes on achievements move under the summary
+        assert!(prompt.contains(
+            "(2-3 paragraphs)\n   (Mention whether activity ramped up or wound down over the weeks)\n\
+             \x20  (Only claim improved test coverage if the test code share above supports it)\n"
+        ));
+        assert!(prompt.contains("2. Metrics: numbers that show the impact, only from the data above\n3. Next Steps: what's still open (2-3 items)\n"));
+        assert!(prompt.ends_with("## Summary\n[Your 2-3 paragraph summary here]\n\n## Metrics\n- [Point 1]\n- [Point 2]\n\n## Next Steps\n1. [Point 1]\n2. [Point 2]\n"));
+        assert!(!prompt.contains("Presentation Tips") && !prompt.contains("Key achievements"));
+    }
+
+    #[test]
+    fn test_summary_prompt_timeline() {
+        let repo = create_test_repo();
+        let prompt = generate_summary_prompt(&repo, &PromptOptions::default());
+        assert!(!prompt.contains("Commits by day") && !prompt.contains("Timeline"));
+
+        let options = PromptOptions { timeline: true, ..Default::default() };
+        let prompt = generate_summary_prompt(&repo, &options);
+        let day = repo.commits[0].timestamp.with_timezone(&chrono::Local).format("%a %Y-%m-%d");
+        assert!(prompt.contains(&format!("Commits by day (oldest first):\n- {}: abc123\n", day)));
+        assert!(prompt.contains("4. Timeline: one bullet per day with commits"));
+        assert!(prompt.ends_with("## Timeline\n- [Point 1]\n- [Point 2]\n"));
+    }
+
+    #[test]
+    fn test_summary_prompt_watch_outs() {
+        let mut repo = create_test_repo();
+        let prompt = generate_summary_prompt(&repo, &PromptOptions::default());
+        assert!(!prompt.contains("Watch-outs"));
+
+        let mut large = repo.commits[0].clone();
+        large.short_hash = "def456".to_string();
+        large.summary = "Rewrite the importer".to_string();
+        large.message = "Rewrite the importer\n\nHacky retry loop for now, TODO: backoff".to_string();
+        large.insertions = 900;
+        large.deletions = 300;
+        large.pr_numbers = vec![];
+        repo.commits.push(large);
+
+        let options = PromptOptions { watch_outs: true, ..Default::default() };
+        let prompt = generate_summary_prompt(&repo, &options);
+        assert!(prompt.contains(
+            "Risk signals:\n- Large commits without a PR: def456 (1200 lines)\n\
+             - Commits mentioning TODOs, hacks or workarounds: def456 - Rewrite the importer\n"
+        ));
+        assert!(prompt.contains("4. Watch-outs: 2-4 bullet points for a retro"));
+        assert!(prompt.ends_with("## Watch-outs\n- [Risk, and why it matters]\n"));
+
+        let response = "## Summary\nDone.\n\n## Watch-outs\n- The importer has no backoff yet\n";
+        let (_, sections) = parse_response(response);
+        assert_eq!(sections, vec![Section::bullets(WATCH_OUTS, vec!["The importer has no backoff yet".to_string()])]);
+    }
+
+    #[test]
+    fn test_summary_prompt_brag() {
+        let repo = create_test_repo();
+        let options = PromptOptions {
+            format: RecapFormat::Brag,
+            sections: default_sections(),
+            ..Default::default()
+        };
+        let prompt = generate_summary_prompt(&repo, &options);
+        assert!(prompt.starts_with("You are helping a developer write a brag document"));
+        assert!(prompt.contains("in the first person"));
+        assert!(prompt.contains("2. Impact: 3-5 bullet points"));
+        assert!(prompt.contains("3. Collaboration: "));
+        assert!(prompt.contains("4. Craft: "));
+        assert!(!prompt.contains(PRESENTATION_TIPS));
+
+        assert_eq!("brag".parse(), Ok(RecapFormat::Brag));
+        assert!("review".parse::<RecapFormat>().is_err());
+        assert_eq!(RecapFormat::Brag.default_days(), Some(BRAG_TIMESPAN_DAYS));
+        assert_eq!(RecapFormat::Demo.default_days(), None);
+    }
+
+    #[test]
+    fn test_summary_prompt_goals() {
+        let repo = create_test_repo();
+        let options = PromptOptions {
+            goals: vec![
+                Goal {
+                    title: "Faster checkout".to_string(),
+                    key_results: vec!["p95 under 200ms".to_string()],
+                },
+                Goal {
+                    title: "Self-serve invoices".to_string(),
+                    key_results: vec![],
+                },
+            ],
+            ..Default::default()
+        };
+        let prompt = generate_summary_prompt(&repo, &options);
+        assert!(prompt.contains(
+            "Goals for this period:\n1. Faster checkout (key results: p95 under 200ms)\n2. Self-serve invoices\n"
+        ));
+        assert!(prompt.contains("4. Goal Alignment: one bullet per goal listed above"));
+        assert!(prompt.ends_with("## Goal Alignment\n- [Goal]: [The work advancing it, or Not addressed]\n"));
+    }
+
     #[test]
     fn test_parse_response_with_asterisk_bullets() {
         let response = r#"
@@ -244,10 +1708,96 @@ Test summary
 1. First tip
 "#;
 
-        let (_summary, achievements, tips) = parse_response(response);
+        let (_summary, sections) = parse_response(response);
+        let (achievements, tips) = (&sections[0].items, &sections[1].items);
 
         assert_eq!(achievements.len(), 2);
         assert_eq!(achievements[0], "Achievement with asterisk");
         assert_eq!(tips.len(), 1);
     }
+
+    #[test]
+    fn test_generate_overall_prompt() {
+        let summary = Summary::new(
+            "test-repo".to_string(),
+            "Shipped the exporter".to_string(),
+            vec!["CSV export".to_string()],
+            vec![],
+        );
+        let prompt = generate_overall_prompt(&[&summary]);
+
+        assert!(prompt.contains("### test-repo"));
+        assert!(prompt.contains("- CSV export"));
+        assert!(prompt.contains("## Suggested Demo Order"));
+    }
+
+    #[test]
+    fn test_year_prompts() {
+        let mut repo = create_test_repo();
+        let mid_january = NaiveDate::from_ymd_opt(2024, 1, 15).unwrap().and_hms_opt(12, 0, 0).unwrap();
+        for commit in &mut repo.commits {
+            commit.timestamp = mid_january.and_utc().fixed_offset();
+        }
+        let mut months = crate::year::split_by_month(2024, std::slice::from_ref(&repo));
+        let january = months.remove(0);
+        assert_eq!(january.repositories.len(), 1);
+
+        let prompt = generate_month_prompt(&january);
+        assert!(prompt.contains("Below is their work in January 2024: 1 commits"));
+        assert!(prompt.contains("### test-repo (1 commits, +10 -5)\n- Test commit\n"));
+        assert!(prompt.ends_with("## Highlights\n- [Highlight 1]\n- [Highlight 2]\n"));
+
+        let response = "## Summary\nShipped auth.\n\n## Highlights\n- OAuth login\n";
+        let (work_summary, sections) = parse_response(response);
+        let january = MonthRecap {
+            summary: Some(Summary::with_sections("January 2024".to_string(), work_summary, sections)),
+            ..january
+        };
+
+        let prompt = generate_year_prompt(2024, &[january, months.remove(0)]);
+        assert!(prompt.contains("### January 2024\n1 commits"));
+        assert!(prompt.contains("Shipped auth.\n- OAuth login\n"));
+        assert!(prompt.contains("### February 2024\nNo commits.\n"));
+        assert!(prompt.contains("## Notable Milestones\n- [Month]: [Milestone 1]"));
+    }
+
+    #[test]
+    fn test_parse_overall_response() {
+        let response = r#"
+## Themes
+- Reliability
+* Developer experience
+
+## Biggest Wins
+- Zero-downtime deploys
+
+## Suggested Demo Order
+1. api - the headline feature
+2. cli - quick follow-up
+"#;
+
+        let (themes, wins, demo_order) = parse_overall_response(response);
+
+        assert_eq!(themes, vec!["Reliability", "Developer experience"]);
+        assert_eq!(wins, vec!["Zero-downtime deploys"]);
+        assert_eq!(demo_order, vec!["api - the headline feature", "cli - quick follow-up"]);
+    }
+
+    #[test]
+    fn test_rank_demo_order() {
+        let entries = vec![
+            "**api-gateway** - new public endpoint users can try".to_string(),
+            "docs - unrelated".to_string(),
+            "API - finished retry queue".to_string(),
+            "api-gateway - again".to_string(),
+        ];
+        assert_eq!(
+            rank_demo_order(entries, &["api", "api-gateway", "cli"]),
+            vec![
+                "**api-gateway** - new public endpoint users can try",
+                "API - finished retry queue",
+                "cli - not ranked",
+            ]
+        );
+    }
 }
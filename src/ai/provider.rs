@@ -0,0 +1,122 @@
+//! LLM providers summaries can be generated with (`provider` in config)
+//!
+//! Anthropic is spoken to through its Messages API; OpenAI and Ollama
+//! through the OpenAI chat completions API, which Ollama also serves.
+
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Where summaries are generated
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Provider {
+    /// Claude, through the Anthropic API or a compatible proxy
+    #[default]
+    Anthropic,
+    /// OpenAI, or any endpoint speaking its chat completions API
+    OpenAI,
+    /// A local Ollama server
+    Ollama,
+}
+
+impl Provider {
+    /// Every provider
+    pub const ALL: [Provider; 3] = [Provider::Anthropic, Provider::OpenAI, Provider::Ollama];
+
+    /// API base URL unless one is configured
+    pub fn default_base_url(self) -> &'static str {
+        match self {
+            Self::Anthropic => "https://api.anthropic.com",
+            Self::OpenAI => "https://api.openai.com",
+            Self::Ollama => "http://localhost:11434",
+        }
+    }
+
+    /// Model unless one is configured
+    pub fn default_model(self) -> &'static str {
+        match self {
+            Self::Anthropic => "claude-sonnet-4-5-20250929",
+            Self::OpenAI => "gpt-4o",
+            Self::Ollama => "llama3.1",
+        }
+    }
+
+    /// Whether requests need an API key (a local Ollama doesn't)
+    pub fn needs_api_key(self) -> bool {
+        !matches!(self, Self::Ollama)
+    }
+
+    /// Environment variable holding the API key
+    pub fn api_key_env(self) -> &'static str {
+        match self {
+            Self::Anthropic => "ANTHROPIC_AUTH_TOKEN",
+            Self::OpenAI => "OPENAI_API_KEY",
+            Self::Ollama => "OLLAMA_API_KEY",
+        }
+    }
+
+    /// Environment variable holding the base URL
+    pub fn base_url_env(self) -> &'static str {
+        match self {
+            Self::Anthropic => "ANTHROPIC_BASE_URL",
+            Self::OpenAI => "OPENAI_BASE_URL",
+            Self::Ollama => "OLLAMA_HOST",
+        }
+    }
+
+    /// Base URL from the value of [`Self::base_url_env`]
+    ///
+    /// `OLLAMA_HOST` is usually `host:port` (or just a host) as Ollama itself
+    /// reads it, so it gets `http://` and the default port when they're missing.
+    pub fn base_url_from_env(self, value: &str) -> String {
+        let value = value.trim();
+        match self {
+            Self::Ollama if !value.contains("://") => {
+                let host = value.trim_end_matches('/');
+                // A port follows the last colon, outside an IPv6 address's brackets
+                let has_port = host.rsplit_once(':').is_some_and(|(_, port)| !port.contains(']'));
+                if has_port {
+                    format!("http://{}", host)
+                } else {
+                    format!("http://{}:11434", host)
+                }
+            }
+            _ => value.to_string(),
+        }
+    }
+}
+
+impl fmt::Display for Provider {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Anthropic => write!(f, "anthropic"),
+            Self::OpenAI => write!(f, "openai"),
+            Self::Ollama => write!(f, "ollama"),
+        }
+    }
+}
+
+impl std::str::FromStr for Provider {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|provider| provider.to_string() == s)
+            .ok_or_else(|| format!("unknown provider '{}' (expected anthropic, openai or ollama)", s))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_base_url_from_env() {
+        assert_eq!(Provider::Ollama.base_url_from_env("0.0.0.0:11434"), "http://0.0.0.0:11434");
+        assert_eq!(Provider::Ollama.base_url_from_env("gpu-box"), "http://gpu-box:11434");
+        assert_eq!(Provider::Ollama.base_url_from_env("[::1]"), "http://[::1]:11434");
+        assert_eq!(Provider::Ollama.base_url_from_env("https://ollama.internal"), "https://ollama.internal");
+        assert_eq!(Provider::OpenAI.base_url_from_env("https://api.example.com/v1"), "https://api.example.com/v1");
+    }
+}
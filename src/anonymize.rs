@@ -0,0 +1,425 @@
+//! Author anonymization (`--anonymize`, `private_prompts`)
+//!
+//! Replaces author names and emails with pseudonyms ("Dev A", "Dev B"), and
+//! repositories with "Project A", "Project B", before anything is prompted or
+//! printed, so recaps can be shared outside the team.
+//!
+//! With `private_prompts`, only prompts are anonymized: the real authors are
+//! remembered and put back into Claude's responses.
+
+use crate::git::stats::login_matches;
+use crate::git::{Author, Commit, Repository};
+use regex::Regex;
+use std::collections::{BTreeSet, HashMap};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+/// Maps real authors to stable pseudonyms
+///
+/// Authors passed to `new` get pseudonyms in sorted email order, so the same
+/// team always maps the same way; other authors are assigned sorted by email
+/// too when passed to `assign_authors` together, else in the order they're
+/// first seen.
+pub struct Anonymizer {
+    aliases: Mutex<HashMap<String, usize>>,
+    /// Pseudonym index of each repository path
+    repositories: Mutex<HashMap<PathBuf, usize>>,
+    /// Real name and email behind each alias seen as a commit author
+    identities: Mutex<HashMap<usize, (String, String)>>,
+    trailer: Regex,
+    /// A whole "Dev A" or "dev-a@anonymized.invalid" in text to restore
+    pseudonym: Regex,
+}
+
+impl Anonymizer {
+    /// Create an anonymizer, pre-assigning pseudonyms to `known_emails`
+    pub fn new(known_emails: &[String]) -> Self {
+        let mut emails: Vec<String> = known_emails.iter().map(|email| normalize(email)).collect();
+        emails.sort();
+        emails.dedup();
+
+        let aliases = emails.into_iter().enumerate().map(|(i, email)| (email, i)).collect();
+
+        Self {
+            aliases: Mutex::new(aliases),
+            repositories: Mutex::new(HashMap::new()),
+            identities: Mutex::new(HashMap::new()),
+            trailer: Regex::new(r"(?m)^([A-Za-z-]+-by:)[ \t]*[^<\n]*<([^>\n]+)>")
+                .expect("trailer regex is valid"),
+            pseudonym: Regex::new(r"\bdev-([a-z]+)@anonymized\.invalid\b|\bDev ([A-Z]+)\b")
+                .expect("pseudonym regex is valid"),
+        }
+    }
+
+    /// Pseudonym for an author ("Dev A")
+    pub fn name(&self, email: &str) -> String {
+        format!("Dev {}", letters(self.index(email)))
+    }
+
+    /// Pseudonymous email for an author ("dev-a@anonymized.invalid")
+    pub fn email(&self, email: &str) -> String {
+        format!("dev-{}@anonymized.invalid", letters(self.index(email)).to_lowercase())
+    }
+
+    /// Assign pseudonyms to every author of `commits` and everyone their
+    /// messages credit (`Co-authored-by: ...`), in sorted email order, so they
+    /// don't depend on which repository finished parsing first
+    pub fn assign_authors<'a>(&self, commits: impl IntoIterator<Item = &'a Commit>) {
+        let mut emails = BTreeSet::new();
+        for commit in commits {
+            emails.insert(normalize(&commit.author.email));
+            for caps in self.trailer.captures_iter(&commit.message) {
+                emails.insert(normalize(&caps[2]));
+            }
+        }
+        for email in emails {
+            self.index(&email);
+        }
+    }
+
+    /// Assign pseudonyms to repositories in sorted path order
+    pub fn assign_repositories<'a>(&self, paths: impl IntoIterator<Item = &'a Path>) {
+        let mut paths: Vec<&Path> = paths.into_iter().collect();
+        paths.sort();
+        for path in paths {
+            self.repository_index(path);
+        }
+    }
+
+    /// Pseudonym for the repository at `path` ("Project A")
+    pub fn repository_name(&self, path: &Path) -> String {
+        format!("Project {}", letters(self.repository_index(path)))
+    }
+
+    /// Pseudonym for a GitHub login, e.g. a reviewer's: the pseudonymous user
+    /// name ("dev-a") of the remembered author it belongs to, so it's still
+    /// matched to their commits, or one of its own
+    pub fn github_login(&self, login: &str) -> String {
+        let author = self
+            .identities
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .iter()
+            .find(|(_, (name, email))| {
+                let author = Author {
+                    name: name.clone(),
+                    email: email.clone(),
+                };
+                login_matches(login, &author)
+            })
+            .map(|(index, _)| *index);
+        let index = author.unwrap_or_else(|| self.index(&format!("{}@users.noreply.github.com", login)));
+        format!("dev-{}", letters(index).to_lowercase())
+    }
+
+    /// Replace the paths of repositories with their pseudonyms and known
+    /// authors' emails with theirs, e.g. in error messages shown in reports
+    pub fn scrub_report(&self, text: &str) -> String {
+        let mut repositories: Vec<(PathBuf, usize)> = self
+            .repositories
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .iter()
+            .map(|(path, index)| (path.clone(), *index))
+            .collect();
+        // Longest first, so a nested repository isn't replaced as its parent
+        repositories.sort_by_key(|(path, _)| std::cmp::Reverse(path.as_os_str().len()));
+
+        let mut text = text.to_string();
+        for (path, index) in repositories {
+            text = text.replace(&*path.to_string_lossy(), &format!("Project {}", letters(index)));
+        }
+        let emails: Vec<String> = self.aliases.lock().unwrap_or_else(|e| e.into_inner()).keys().cloned().collect();
+        for email in emails {
+            text = replace_email(&text, &email, &self.email(&email));
+        }
+        self.scrub(&text)
+    }
+
+    /// Replace commit authors, and their names, emails and trailer
+    /// mentions (`Co-authored-by: ...`) in commit messages
+    pub fn anonymize_commits(&self, commits: &mut [Commit]) {
+        for commit in commits {
+            let (real_name, real_email) = (commit.author.name.clone(), commit.author.email.clone());
+            self.remember(&commit.author.name, &commit.author.email);
+            let (name, email) = (self.name(&real_email), self.email(&real_email));
+
+            let scrub = |text: &str| -> String {
+                let text = self
+                    .trailer
+                    .replace_all(text, |caps: &regex::Captures| {
+                        format!("{} {} <{}>", &caps[1], self.name(&caps[2]), self.email(&caps[2]))
+                    })
+                    .into_owned();
+                let text = replace_email(&text, &real_email, &email);
+                if real_name.chars().count() >= 3 {
+                    replace_name(&text, &real_name, &name)
+                } else {
+                    text
+                }
+            };
+
+            commit.message = scrub(&commit.message);
+            commit.summary = scrub(&commit.summary);
+            commit.body = commit.body.as_deref().map(scrub);
+            commit.author.name = name;
+            commit.author.email = email;
+        }
+    }
+
+    /// Copy of a repository safe to prompt with: pseudonymous commits, no remote
+    pub fn anonymize_repository(&self, repo: &Repository) -> Repository {
+        let mut repo = repo.clone();
+        self.anonymize_commits(&mut repo.commits);
+        repo.remote_url = None;
+        repo.upstream_url = None;
+        repo.github_info = None;
+        repo
+    }
+
+    /// Record the authors of `commits`, so `scrub` and `restore` know them
+    /// even when nothing was anonymized (e.g. a cached summary)
+    pub fn remember_authors(&self, commits: &[Commit]) {
+        for commit in commits {
+            self.remember(&commit.author.name, &commit.author.email);
+        }
+    }
+
+    /// Replace the real names and emails of remembered authors with pseudonyms
+    pub fn scrub(&self, text: &str) -> String {
+        let mut text = text.to_string();
+        for (alias, (real_name, real_email)) in self.identities() {
+            text = replace_email(&text, &real_email, &self.email(&real_email));
+            if real_name.chars().count() >= 3 {
+                text = replace_name(&text, &real_name, &format!("Dev {}", alias));
+            }
+        }
+        text
+    }
+
+    /// Put remembered authors' real names and emails back in place of their
+    /// pseudonyms
+    ///
+    /// Only whole pseudonyms are replaced, so "Dev API" stays as it is.
+    pub fn restore(&self, text: &str) -> String {
+        let identities: HashMap<String, (String, String)> = self.identities().into_iter().collect();
+        self.pseudonym
+            .replace_all(text, |caps: &regex::Captures| {
+                let identity = match (caps.get(1), caps.get(2)) {
+                    (Some(alias), _) => identities.get(&alias.as_str().to_uppercase()).map(|(_, email)| email),
+                    (_, Some(alias)) => identities.get(alias.as_str()).map(|(name, _)| name),
+                    _ => None,
+                };
+                identity.cloned().unwrap_or_else(|| caps[0].to_string())
+            })
+            .into_owned()
+    }
+
+    /// Remembered authors by alias letters, longest first so "Dev AB" is
+    /// replaced before "Dev A"
+    fn identities(&self) -> Vec<(String, (String, String))> {
+        let identities = self.identities.lock().unwrap_or_else(|e| e.into_inner());
+        let mut identities: Vec<_> = identities
+            .iter()
+            .map(|(index, identity)| (letters(*index), identity.clone()))
+            .collect();
+        identities.sort_by(|a, b| b.0.len().cmp(&a.0.len()).then(a.0.cmp(&b.0)));
+        identities
+    }
+
+    fn remember(&self, name: &str, email: &str) {
+        let index = self.index(email);
+        self.identities
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .entry(index)
+            .or_insert_with(|| (name.to_string(), email.to_string()));
+    }
+
+    /// Index of a repository, assigning the next one if unseen
+    fn repository_index(&self, path: &Path) -> usize {
+        let mut repositories = self.repositories.lock().unwrap_or_else(|e| e.into_inner());
+        let next = repositories.len();
+        *repositories.entry(path.to_path_buf()).or_insert(next)
+    }
+
+    /// Index of an author, assigning the next one if unseen
+    fn index(&self, email: &str) -> usize {
+        let mut aliases = self.aliases.lock().unwrap_or_else(|e| e.into_inner());
+        let next = aliases.len();
+        *aliases.entry(normalize(email)).or_insert(next)
+    }
+}
+
+/// Emails compare case-insensitively
+fn normalize(email: &str) -> String {
+    email.trim().to_lowercase()
+}
+
+/// Replace `email` in `text`, in any case
+fn replace_email(text: &str, email: &str, pseudonym: &str) -> String {
+    let pattern = Regex::new(&format!("(?i){}", regex::escape(email.trim()))).expect("escaped email is a valid regex");
+    pattern.replace_all(text, regex::NoExpand(pseudonym)).into_owned()
+}
+
+/// Replace `name` in `text` where it's a whole word, so "Ann" leaves
+/// "Announce" alone
+fn replace_name(text: &str, name: &str, pseudonym: &str) -> String {
+    let pattern = Regex::new(&format!(r"\b{}\b", regex::escape(name))).expect("escaped name is a valid regex");
+    pattern.replace_all(text, regex::NoExpand(pseudonym)).into_owned()
+}
+
+/// Spreadsheet-style letters: 0 -> A, 25 -> Z, 26 -> AA
+fn letters(mut index: usize) -> String {
+    let mut letters = Vec::new();
+    loop {
+        letters.push((b'A' + (index % 26) as u8) as char);
+        if index < 26 {
+            break;
+        }
+        index = index / 26 - 1;
+    }
+    letters.iter().rev().collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_letters() {
+        assert_eq!(letters(0), "A");
+        assert_eq!(letters(25), "Z");
+        assert_eq!(letters(26), "AA");
+        assert_eq!(letters(27), "AB");
+    }
+
+    #[test]
+    fn test_known_authors_are_stable() {
+        let anonymizer = Anonymizer::new(&["bob@example.com".to_string(), "Alice@example.com".to_string()]);
+        assert_eq!(anonymizer.name("alice@example.com"), "Dev A");
+        assert_eq!(anonymizer.name("bob@example.com"), "Dev B");
+        assert_eq!(anonymizer.name("carol@example.com"), "Dev C");
+        assert_eq!(anonymizer.email("BOB@example.com"), "dev-b@anonymized.invalid");
+    }
+
+    #[test]
+    fn test_assigned_in_sorted_order() {
+        let commit = |email: &str, message: &str| Commit {
+            author: Author {
+                name: email.to_string(),
+                email: email.to_string(),
+            },
+            ..Commit::test("abc123", message)
+        };
+        let commits = [
+            commit("carol@example.com", "Fix\n\nCo-authored-by: Bob <bob@example.com>"),
+            commit("alice@example.com", "Add"),
+        ];
+
+        // However the repositories are ordered or finish, the same authors
+        // and repositories get the same pseudonyms
+        let (forward, backward) = (Anonymizer::new(&[]), Anonymizer::new(&[]));
+        forward.assign_authors(&commits);
+        backward.assign_authors(commits.iter().rev());
+        for anonymizer in [&forward, &backward] {
+            assert_eq!(anonymizer.name("alice@example.com"), "Dev A");
+            assert_eq!(anonymizer.name("bob@example.com"), "Dev B");
+            assert_eq!(anonymizer.name("carol@example.com"), "Dev C");
+        }
+
+        // Reviewers are matched to the authors they are, or get their own pseudonym
+        forward.anonymize_commits(&mut commits.clone());
+        assert_eq!(forward.github_login("alice"), "dev-a");
+        assert_eq!(forward.github_login("dave"), "dev-d");
+
+        forward.assign_repositories([Path::new("/code/web"), Path::new("/code/api")]);
+        assert_eq!(forward.repository_name(Path::new("/code/api")), "Project A");
+        assert_eq!(forward.repository_name(Path::new("/code/web")), "Project B");
+        assert_eq!(
+            forward.scrub_report("Git error: could not open /code/web for carol@example.com"),
+            "Git error: could not open Project B for dev-c@anonymized.invalid"
+        );
+    }
+
+    #[test]
+    fn test_anonymize_commits() {
+        let message = "Fix login for Alice Smith\n\nCo-authored-by: Bob <bob@example.com>";
+        let mut commits = vec![Commit {
+            author: Author {
+                name: "Alice Smith".to_string(),
+                email: "alice@example.com".to_string(),
+            },
+            body: Some("Co-authored-by: Bob <bob@example.com>".to_string()),
+            ..Commit::test("abc123", message)
+        }];
+
+        let anonymizer = Anonymizer::new(&["alice@example.com".to_string()]);
+        anonymizer.anonymize_commits(&mut commits);
+
+        let commit = &commits[0];
+        assert_eq!(commit.author.name, "Dev A");
+        assert_eq!(commit.author.email, "dev-a@anonymized.invalid");
+        assert_eq!(commit.summary, "Fix login for Dev A");
+        assert_eq!(
+            commit.body.as_deref(),
+            Some("Co-authored-by: Dev B <dev-b@anonymized.invalid>")
+        );
+        assert!(!commit.message.contains("bob@example.com"));
+        assert!(!commit.message.contains("Alice"));
+    }
+
+    #[test]
+    fn test_restore_and_scrub() {
+        let anonymizer = Anonymizer::new(&[]);
+        let commits: Vec<Commit> = ["alice@example.com", "bob@example.com"]
+            .iter()
+            .map(|email| Commit {
+                author: Author {
+                    name: if email.starts_with("alice") { "Alice Smith" } else { "Bob Jones" }.to_string(),
+                    email: email.to_string(),
+                },
+                ..Commit::test("abc123", "Fix login")
+            })
+            .collect();
+        anonymizer.remember_authors(&commits);
+
+        let response = "Dev A fixed login (with Dev B, dev-b@anonymized.invalid)";
+        assert_eq!(
+            anonymizer.restore(response),
+            "Alice Smith fixed login (with Bob Jones, bob@example.com)"
+        );
+        assert_eq!(anonymizer.scrub(&anonymizer.restore(response)), response);
+
+        // Only whole pseudonyms of remembered authors are restored
+        assert_eq!(
+            anonymizer.restore("Dev API docs by Dev A; Dev C reviewed"),
+            "Dev API docs by Alice Smith; Dev C reviewed"
+        );
+    }
+
+    #[test]
+    fn test_whole_names_and_any_case_emails() {
+        let mut commits = vec![Commit {
+            author: Author {
+                name: "Ann".to_string(),
+                email: "Alice@Corp.com".to_string(),
+            },
+            ..Commit::test("abc123", "Announce release, thanks Ann\n\nReported-by: alice@corp.com")
+        }];
+
+        let anonymizer = Anonymizer::new(&[]);
+        anonymizer.anonymize_commits(&mut commits);
+        assert_eq!(commits[0].summary, "Announce release, thanks Dev A");
+        assert!(!commits[0].message.to_lowercase().contains("alice@corp.com"));
+
+        assert_eq!(
+            anonymizer.scrub("Ann: Announce from ALICE@corp.com"),
+            "Dev A: Announce from dev-a@anonymized.invalid"
+        );
+        assert_eq!(
+            anonymizer.scrub_report("push rejected for Alice@Corp.com"),
+            "push rejected for dev-a@anonymized.invalid"
+        );
+    }
+}
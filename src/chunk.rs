@@ -0,0 +1,168 @@
+//! Splitting reports into parts that fit chat messages (`--max-chunk-chars`)
+//!
+//! Slack, Discord and Teams cap the length of a message, so a long report is
+//! cut into numbered parts instead of being truncated. Cuts fall before a
+//! heading or `---` separator where possible, else between paragraphs, then
+//! between lines; only a single line longer than a part is cut mid-line.
+
+use std::path::{Path, PathBuf};
+
+/// Smallest accepted `max_chunk_chars`
+pub const MIN_CHUNK_CHARS: usize = 200;
+
+/// Room kept in each part for its "(i/n)" label
+const LABEL_CHARS: usize = "(999/999)\n".len();
+
+/// `text` cut into parts of at most `max_chars` characters, each starting
+/// with "(i/n)" when there's more than one
+pub fn split(text: &str, max_chars: usize) -> Vec<String> {
+    let text = text.trim_end();
+    if text.chars().count() <= max_chars {
+        return vec![text.to_string()];
+    }
+
+    let limit = max_chars.saturating_sub(LABEL_CHARS).max(1);
+    let mut parts: Vec<String> = Vec::new();
+    let mut current = String::new();
+    for piece in pieces(text, limit, Level::Sections) {
+        if !current.is_empty() && chars(&current) + chars(&piece) > limit {
+            parts.push(std::mem::take(&mut current));
+        }
+        current.push_str(&piece);
+    }
+    parts.push(current);
+
+    let parts: Vec<&str> = parts.iter().map(|part| part.trim()).filter(|part| !part.is_empty()).collect();
+    let count = parts.len();
+    parts
+        .into_iter()
+        .enumerate()
+        .map(|(index, part)| format!("({}/{})\n{}", index + 1, count, part))
+        .collect()
+}
+
+/// Where part `number` (from 1) of the report written to `output` goes:
+/// `recap.md` gives `recap.part1.md`
+pub fn part_path(output: &Path, number: usize) -> PathBuf {
+    let stem = output.file_stem().map(|stem| stem.to_string_lossy()).unwrap_or_default();
+    let name = match output.extension() {
+        Some(extension) => format!("{}.part{}.{}", stem, number, extension.to_string_lossy()),
+        None => format!("{}.part{}", stem, number),
+    };
+    output.with_file_name(name)
+}
+
+/// Remove the parts of an earlier, longer split of `output` beyond the
+/// first `kept`, returning how many were removed
+///
+/// Parts are numbered without gaps, so removal stops at the first missing one.
+pub fn remove_stale_parts(output: &Path, kept: usize) -> std::io::Result<usize> {
+    let mut removed = 0;
+    loop {
+        let path = part_path(output, kept + removed + 1);
+        match std::fs::remove_file(&path) {
+            Ok(()) => removed += 1,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(removed),
+            Err(e) => return Err(e),
+        }
+    }
+}
+
+/// Boundaries to cut at, coarsest first
+#[derive(Clone, Copy)]
+enum Level {
+    Sections,
+    Paragraphs,
+    Lines,
+    Chars,
+}
+
+/// `text` in consecutive pieces of at most `limit` characters, cut at
+/// `level` boundaries and finer ones where a piece is still too long
+fn pieces(text: &str, limit: usize, level: Level) -> Vec<String> {
+    if chars(text) <= limit {
+        return vec![text.to_string()];
+    }
+    let (segments, finer) = match level {
+        Level::Sections => (sections(text), Level::Paragraphs),
+        Level::Paragraphs => (text.split_inclusive("\n\n").map(str::to_string).collect(), Level::Lines),
+        Level::Lines => (text.split_inclusive('\n').map(str::to_string).collect(), Level::Chars),
+        Level::Chars => {
+            let chars: Vec<char> = text.chars().collect();
+            return chars.chunks(limit).map(|chunk| chunk.iter().collect()).collect();
+        }
+    };
+    segments
+        .into_iter()
+        .flat_map(|segment| pieces(&segment, limit, finer))
+        .collect()
+}
+
+/// `text` cut before every heading and `---` separator line
+fn sections(text: &str) -> Vec<String> {
+    let mut sections = vec![String::new()];
+    for line in text.split_inclusive('\n') {
+        let starts_section = line.starts_with('#') || line.trim_end() == "---";
+        if starts_section && !sections.last().is_some_and(|section| section.is_empty()) {
+            sections.push(String::new());
+        }
+        if let Some(section) = sections.last_mut() {
+            section.push_str(line);
+        }
+    }
+    sections
+}
+
+fn chars(text: &str) -> usize {
+    text.chars().count()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split() {
+        assert_eq!(split("# Dev Recap\n\nShort.\n", 200), vec!["# Dev Recap\n\nShort."]);
+
+        let section = |name: &str| format!("## Repository: {}\n\n{}\n\n", name, "Work. ".repeat(20));
+        let report = format!("# Dev Recap\n\n---\n\n{}{}{}", section("api"), section("web"), section("docs"));
+        let parts = split(&report, 250);
+        assert_eq!(parts.len(), 3);
+        assert!(parts.iter().all(|part| part.chars().count() <= 250));
+        assert!(parts[0].starts_with("(1/3)\n# Dev Recap"));
+        assert!(parts[1].starts_with("(2/3)\n## Repository: web"));
+        assert!(parts[2].starts_with("(3/3)\n## Repository: docs"));
+
+        // A section longer than a part is cut between paragraphs, then lines
+        let long = format!("## api\n\n{}\n\n{}", "a".repeat(150), "b\n".repeat(150));
+        let parts = split(&long, 200);
+        assert!(parts.iter().all(|part| part.chars().count() <= 200));
+        assert!(parts.iter().any(|part| part.contains(&"a".repeat(150))));
+        let text: String = parts.iter().map(|part| part.split_once('\n').unwrap().1).collect();
+        assert_eq!(text.matches('b').count(), 150);
+
+        assert_eq!(split(&"é".repeat(500), 250).len(), 3);
+    }
+
+    #[test]
+    fn test_part_path() {
+        assert_eq!(part_path(Path::new("out/recap.md"), 2), PathBuf::from("out/recap.part2.md"));
+        assert_eq!(part_path(Path::new("recap"), 1), PathBuf::from("recap.part1"));
+    }
+
+    #[test]
+    fn test_remove_stale_parts() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let output = temp_dir.path().join("recap.md");
+        for number in 1..=4 {
+            std::fs::write(part_path(&output, number), "part").unwrap();
+        }
+
+        assert_eq!(remove_stale_parts(&output, 2).unwrap(), 2);
+        assert!(part_path(&output, 2).exists());
+        assert!(!part_path(&output, 3).exists() && !part_path(&output, 4).exists());
+        assert_eq!(remove_stale_parts(&output, 0).unwrap(), 2);
+        assert_eq!(remove_stale_parts(&output, 0).unwrap(), 0);
+    }
+}
@@ -1,5 +1,14 @@
-use clap::{Parser, Subcommand};
+use clap::{Parser, Subcommand, ValueEnum};
+use dev_recap::ai::prompt::{PromptVersion, RecapFormat};
+use dev_recap::chunk::MIN_CHUNK_CHARS;
+use dev_recap::git::log_import::StdinFormat;
+use dev_recap::git::stats::{GroupBy, RepoOrder};
+use dev_recap::git::CommitDate;
+use dev_recap::sprint::SprintSelector;
+use dev_recap::theme::Theme;
+use std::io::IsTerminal;
 use std::path::PathBuf;
+use tracing::level_filters::LevelFilter;
 
 #[derive(Parser, Debug)]
 #[command(name = "dev-recap")]
@@ -12,9 +21,14 @@ use std::path::PathBuf;
 )]
 pub struct Cli {
     /// Path to scan for git repositories (default: current directory)
-    #[arg(short, long, value_name = "DIR")]
+    #[arg(short, long, value_name = "DIR", value_parser = parse_path)]
     pub path: Option<PathBuf>,
 
+    /// Analyze exactly this repository instead of scanning (repeatable); the
+    /// path must be a repository's root
+    #[arg(long = "repo", value_name = "DIR", value_parser = parse_path, conflicts_with_all = ["path", "subdirs"])]
+    pub repos: Vec<PathBuf>,
+
     /// Author email to filter commits
     #[arg(short, long)]
     pub author: Option<String>,
@@ -23,15 +37,24 @@ pub struct Cli {
     #[arg(short, long, value_name = "DAYS")]
     pub days: Option<u32>,
 
-    /// Start date (YYYY-MM-DD format)
+    /// Start date (YYYY-MM-DD, "YYYY-MM-DD HH:MM" or RFC3339 timestamp)
     #[arg(long)]
     pub since: Option<String>,
 
-    /// End date (YYYY-MM-DD format)
+    /// End date (YYYY-MM-DD, "YYYY-MM-DD HH:MM" or RFC3339 timestamp)
     #[arg(long)]
     pub until: Option<String>,
 
-    /// Path to config file (default: ~/.config/dev-recap/config.toml)
+    /// Recap a sprint of the `[sprint]` cadence in config: current, previous
+    /// or its number (counting from 1)
+    #[arg(long, value_name = "SPRINT")]
+    pub sprint: Option<SprintSelector>,
+
+    /// Interpret --since/--until values without an offset as UTC instead of local time
+    #[arg(long)]
+    pub utc: bool,
+
+    /// Path to config file (default: <platform config dir>/dev-recap/config.toml)
     #[arg(short, long, value_name = "FILE")]
     pub config: Option<PathBuf>,
 
@@ -39,7 +62,59 @@ pub struct Cli {
     #[arg(short, long, value_name = "FILE")]
     pub output: Option<PathBuf>,
 
-    /// Run in non-interactive mode (skip TUI)
+    /// Open the output file (--output or output_path) in $EDITOR or the default viewer
+    #[arg(long)]
+    pub open: bool,
+
+    /// Split the report into numbered parts of at most CHARS characters for
+    /// chat message limits: printed one after another, written as
+    /// FILE.partN.md next to --output, and posted as separate Slack messages
+    #[arg(long, value_name = "CHARS")]
+    pub max_chunk_chars: Option<usize>,
+
+    /// Render repository paths as file:// links (terminal hyperlinks, markdown links)
+    #[arg(long)]
+    pub file_links: bool,
+
+    /// Colors of the terminal output: default, light (for light backgrounds)
+    /// or none; NO_COLOR also turns them off
+    #[arg(long, value_name = "THEME")]
+    pub theme: Option<Theme>,
+
+    /// Order of the repositories in the report: name, commits, lines or
+    /// recent (most first; default: name)
+    #[arg(long, value_name = "ORDER")]
+    pub order: Option<RepoOrder>,
+
+    /// Revise the summaries of the most recent recap following this instruction,
+    /// without reading any repository again (e.g. "emphasize the performance work")
+    #[arg(long, value_name = "INSTRUCTION")]
+    pub refine: Option<String>,
+
+    /// What the recap is for: demo (presentations) or brag (a self-review
+    /// document around impact, collaboration and craft, 182 days back by default)
+    #[arg(long, value_name = "FORMAT")]
+    pub format: Option<RecapFormat>,
+
+    /// Summarize every repository with each of these prompt versions (e.g. v1,v2)
+    /// and show the results one after another instead of the report
+    #[arg(long, value_name = "VERSIONS", value_delimiter = ',')]
+    pub compare_prompts: Option<Vec<PromptVersion>>,
+
+    /// After the report, keep the recap loaded and answer follow-up questions about it
+    #[arg(long)]
+    pub chat: bool,
+
+    /// Serve the report as HTML on localhost, reloading it when the config file
+    /// changes or another --preview run replaces it
+    #[arg(long)]
+    pub preview: bool,
+
+    /// Port for --preview
+    #[arg(long, value_name = "PORT", default_value_t = dev_recap::preview::DEFAULT_PORT)]
+    pub preview_port: u16,
+
+    /// Never prompt: use flags, config and git defaults, or fail if a value is missing
     #[arg(long)]
     pub non_interactive: bool,
 
@@ -51,22 +126,135 @@ pub struct Cli {
     #[arg(long)]
     pub dry_run: bool,
 
-    /// Team mode - analyze multiple authors
-    #[arg(long)]
-    pub team: bool,
+    /// Skip the cost confirmation before making API calls (required when
+    /// the run can't ask: --output, --preview, --non-interactive, daemon, watch)
+    #[arg(short, long)]
+    pub yes: bool,
+
+    /// Team mode - analyze multiple authors; NAME picks a team defined in config
+    #[arg(long, value_name = "NAME", num_args = 0..=1)]
+    pub team: Option<Option<String>>,
 
     /// Comma-separated list of author emails (for team mode)
     #[arg(long, value_delimiter = ',')]
     pub authors: Option<Vec<String>>,
 
+    /// Number of repositories to analyze and summarize concurrently
+    #[arg(short, long, value_name = "N", default_value_t = 4)]
+    pub jobs: usize,
+
     /// Maximum directory scan depth
     #[arg(long)]
     pub max_depth: Option<u32>,
 
+    /// Scan a Windows drive from WSL (/mnt/c, ...) even though it is very slow
+    #[arg(long)]
+    pub allow_slow_fs: bool,
+
+    /// Summarize commits piped in instead of scanning repositories; git-log
+    /// reads `git log --numstat` output (e.g. from a remote server over SSH)
+    #[arg(long, value_name = "FORMAT", conflicts_with = "repos")]
+    pub stdin_format: Option<StdinFormat>,
+
+    /// Repository name shown for commits read with --stdin-format
+    #[arg(long, value_name = "NAME", requires = "stdin_format")]
+    pub stdin_name: Option<String>,
+
+    /// Skip per-commit diff stats (files, insertions, deletions) for a faster run
+    #[arg(long)]
+    pub no_diff_stats: bool,
+
+    /// Leave commits changing fewer than N lines out of the prompt (still counted in stats)
+    #[arg(long, value_name = "N")]
+    pub min_changes: Option<u32>,
+
+    /// Include the message bodies of the N largest commits in the prompt (truncated)
+    #[arg(long, value_name = "N")]
+    pub commit_bodies: Option<usize>,
+
+    /// Add a "Watch-outs" section flagging risky areas and tech debt (for retros)
+    #[arg(long)]
+    pub watch_outs: bool,
+
+    /// Organize the commit listing (-vv) day by day and add a "Timeline"
+    /// section to each summary, for standups and timesheets
+    #[arg(long, value_name = "GROUP")]
+    pub group_by: Option<GroupBy>,
+
+    /// Map the work to the goals/OKRs in this markdown or TOML file, flagging
+    /// goals no work addressed
+    #[arg(long, value_name = "FILE")]
+    pub goals: Option<PathBuf>,
+
+    /// Keep only the N most impactful key achievements (for short presentations)
+    #[arg(long, value_name = "N")]
+    pub top_achievements: Option<usize>,
+
+    /// List at most N commits per repository (the most recent); statistics still cover all
+    #[arg(long, value_name = "N")]
+    pub max_commits_per_repo: Option<usize>,
+
+    /// Also write every computed statistic as JSON to FILE (for dashboards)
+    #[arg(long, value_name = "FILE")]
+    pub stats_json: Option<PathBuf>,
+
+    /// Also write the recap as a Marp slide deck, with speaker notes and speaking times
+    #[arg(long, value_name = "FILE")]
+    pub slides: Option<PathBuf>,
+
+    /// Demo Day slot length, to check the deck's estimated speaking time against
+    #[arg(long, value_name = "MINUTES")]
+    pub slot: Option<u32>,
+
+    /// Print how long each phase took (scan, parse, diff stats, API, cache)
+    #[arg(long)]
+    pub timings: bool,
+
+    /// Timestamp that places commits in the timespan: author (default) or committer
+    #[arg(long, value_name = "author|committer")]
+    pub date: Option<CommitDate>,
+
+    /// Recap each subdirectory matching PATTERN (e.g. "services/*") of a monorepo
+    /// as its own project, with only the commits touching it
+    #[arg(long, value_name = "PATTERN", value_parser = parse_subdirs)]
+    pub subdirs: Option<String>,
+
+    /// Replace author names and emails with pseudonyms (Dev A, Dev B) in prompts and output
+    #[arg(long)]
+    pub anonymize: bool,
+
+    /// Keep author names, emails and remote URLs out of prompts (the report still names authors)
+    #[arg(long)]
+    pub private_prompts: bool,
+
+    /// Add a per-author leaderboard (commits, lines, PRs, reviews) to team output
+    #[arg(long)]
+    pub leaderboard: bool,
+
+    /// Add a "Work Patterns" section per repository (streaks, weekends, late nights)
+    #[arg(long)]
+    pub work_patterns: bool,
+
+    /// Skip the cross-repository "Overall Recap" at the top of the report
+    #[arg(long)]
+    pub no_overall: bool,
+
+    /// Skip repositories whose parsing or summary takes longer than SECS
+    #[arg(long, value_name = "SECS")]
+    pub repo_timeout: Option<u64>,
+
     /// Verbose output
     #[arg(short, long, action = clap::ArgAction::Count)]
     pub verbose: u8,
 
+    /// Diagnostic log level
+    #[arg(long, value_enum, default_value_t = LogLevel::Warn)]
+    pub log_level: LogLevel,
+
+    /// Append diagnostic logs to this file (overrides log_file in config)
+    #[arg(long, value_name = "FILE")]
+    pub log_file: Option<PathBuf>,
+
     /// Subcommands
     #[command(subcommand)]
     pub command: Option<Commands>,
@@ -81,14 +269,249 @@ pub enum Commands {
         force: bool,
     },
 
-    /// Show current configuration
-    Config,
+    /// Show current configuration, or check it with `config validate`
+    Config {
+        #[command(subcommand)]
+        action: Option<ConfigAction>,
+    },
+
+    /// Manage secrets stored in the OS keyring
+    Auth {
+        #[command(subcommand)]
+        action: AuthAction,
+    },
 
     /// Clear the cache
     ClearCache,
 
     /// Show cache statistics
     CacheStats,
+
+    /// Pre-generate cached summaries, e.g. from a nightly CI job
+    Cache {
+        #[command(subcommand)]
+        action: CacheAction,
+    },
+
+    /// Generate roff man pages
+    Man {
+        /// Write dev-recap.1 plus one page per subcommand into this directory
+        /// instead of printing the main page to stdout
+        #[arg(long, value_name = "DIR")]
+        out_dir: Option<PathBuf>,
+    },
+
+    /// Run an HTTP server that generates recaps on request (POST /recaps)
+    Serve {
+        /// Port to listen on
+        #[arg(long, default_value_t = 8080)]
+        port: u16,
+
+        /// Address to bind; use 0.0.0.0 to accept connections from other hosts
+        #[arg(long, default_value = "127.0.0.1")]
+        bind: std::net::IpAddr,
+    },
+
+    /// Run a Model Context Protocol server on stdin/stdout, exposing
+    /// scan_repos, analyze_repo and generate_recap as tools for AI assistants
+    Mcp,
+
+    /// List the models the configured endpoint offers and check that
+    /// the configured model is one of them
+    Models,
+
+    /// Index commits as they're made, so recaps of hooked repositories skip the history walk
+    Hook {
+        #[command(subcommand)]
+        action: HookAction,
+    },
+
+    /// Run the recap on the `schedule` from config, delivering each report
+    /// to output_path, slack_webhook_url and email_to
+    Daemon {
+        /// Also run once right away instead of waiting for the first scheduled time
+        #[arg(long)]
+        now: bool,
+
+        /// Serve Prometheus metrics on this address (e.g. 127.0.0.1:9090) at /metrics
+        #[arg(long, value_name = "ADDR")]
+        metrics_addr: Option<std::net::SocketAddr>,
+    },
+
+    /// Keep the recap up to date: regenerate it whenever a scanned
+    /// repository's HEAD moves, rewriting the output file and --preview
+    Watch {
+        /// Seconds between checks of the repositories
+        #[arg(long, value_name = "SECS", default_value_t = 30)]
+        interval: u64,
+    },
+
+    /// List or show previously generated recaps
+    History {
+        #[command(subcommand)]
+        action: HistoryAction,
+    },
+
+    /// Generate a fresh summary for one repository of the last recap (or
+    /// --id), updating the saved report in place
+    Regen {
+        /// Repository name as shown in the report (or its path)
+        repo: String,
+
+        /// Recap to update, as printed by `history list` (default: the most recent)
+        #[arg(long)]
+        id: Option<i64>,
+    },
+
+    /// Review a calendar year: a summary per month, then the year's trends,
+    /// totals and notable milestones
+    Year {
+        /// Year to review (default: the current year, up to today)
+        #[arg(long)]
+        year: Option<i32>,
+    },
+
+    /// Compare two recaps: repositories covered, stats and new achievements
+    Diff {
+        /// Older recap: a history id, or a file saved with `history show --json`
+        old: String,
+
+        /// Newer recap: a history id, or a file saved with `history show --json`
+        new: String,
+    },
+}
+
+/// Config subcommands
+#[derive(Subcommand, Debug)]
+pub enum ConfigAction {
+    /// Show current configuration (default)
+    Show,
+
+    /// Check the config file for unknown keys, invalid values and missing paths
+    Validate,
+}
+
+/// Cache subcommands
+#[derive(Subcommand, Debug)]
+pub enum CacheAction {
+    /// Generate and cache the summary of every repository with commits in
+    /// the timespan, without writing a report
+    Warm {
+        /// Path to scan for git repositories (default: --path, or the current directory)
+        #[arg(short, long, value_name = "DIR", value_parser = parse_path)]
+        path: Option<PathBuf>,
+
+        /// Author email to filter commits (default: --author, default_author_email or git user.email)
+        #[arg(short, long)]
+        author: Option<String>,
+
+        /// Number of days to look back (default: --days, or default_timespan_days)
+        #[arg(short, long, value_name = "DAYS")]
+        days: Option<u32>,
+    },
+}
+
+/// Hook subcommands
+#[derive(Subcommand, Debug)]
+pub enum HookAction {
+    /// Install a post-commit hook that records each new commit
+    Install {
+        /// Repository to hook
+        #[arg(default_value = ".", value_parser = parse_path)]
+        repo: PathBuf,
+
+        /// Replace an existing post-commit hook
+        #[arg(long)]
+        force: bool,
+    },
+
+    /// Remove the hook and the recorded commits
+    Uninstall {
+        /// Repository to unhook
+        #[arg(default_value = ".", value_parser = parse_path)]
+        repo: PathBuf,
+    },
+
+    /// Record HEAD (run by the hook after each commit)
+    Record {
+        /// Repository the commit was made in
+        #[arg(default_value = ".", value_parser = parse_path)]
+        repo: PathBuf,
+    },
+}
+
+/// History subcommands
+#[derive(Subcommand, Debug)]
+pub enum HistoryAction {
+    /// List the most recent recaps
+    List {
+        /// Number of recaps to list
+        #[arg(long, default_value_t = 20)]
+        limit: usize,
+    },
+
+    /// Print a recap's report
+    Show {
+        /// Recap id, as printed by `history list`
+        id: i64,
+
+        /// Print the stored recap (parameters and summaries) as JSON instead
+        #[arg(long)]
+        json: bool,
+    },
+}
+
+/// Auth subcommands
+#[derive(Subcommand, Debug)]
+pub enum AuthAction {
+    /// Store a secret in the OS keyring (read from stdin)
+    SetKey {
+        /// Store the GitHub token instead of the Claude API key
+        #[arg(long)]
+        github: bool,
+
+        /// Store the Linear API key instead of the Claude API key
+        #[arg(long, conflicts_with = "github")]
+        linear: bool,
+    },
+
+    /// Remove a secret from the OS keyring
+    DeleteKey {
+        /// Remove the GitHub token instead of the Claude API key
+        #[arg(long)]
+        github: bool,
+
+        /// Remove the Linear API key instead of the Claude API key
+        #[arg(long, conflicts_with = "github")]
+        linear: bool,
+    },
+
+    /// Show where each secret is currently read from
+    Status,
+}
+
+/// Diagnostic log level
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LogLevel {
+    Off,
+    Error,
+    Warn,
+    Info,
+    Debug,
+    Trace,
+}
+
+impl From<LogLevel> for LevelFilter {
+    fn from(level: LogLevel) -> Self {
+        match level {
+            LogLevel::Off => LevelFilter::OFF,
+            LogLevel::Error => LevelFilter::ERROR,
+            LogLevel::Warn => LevelFilter::WARN,
+            LogLevel::Info => LevelFilter::INFO,
+            LogLevel::Debug => LevelFilter::DEBUG,
+            LogLevel::Trace => LevelFilter::TRACE,
+        }
+    }
 }
 
 impl Cli {
@@ -96,10 +519,29 @@ impl Cli {
     pub fn is_non_interactive(&self) -> bool {
         self.non_interactive
             || self.output.is_some()
+            || self.preview
             || self.dry_run
             || self.command.is_some()
     }
 
+    /// Check if the user may be prompted for missing values
+    ///
+    /// Prompts require interactive mode and a terminal on stdin, so piped or
+    /// scheduled runs never block waiting for input.
+    pub fn can_prompt(&self) -> bool {
+        !self.is_non_interactive() && std::io::stdin().is_terminal()
+    }
+
+    /// Whether team mode is on (`--team`, with or without a team name)
+    pub fn team_mode(&self) -> bool {
+        self.team.is_some()
+    }
+
+    /// Name of the configured team passed to `--team`, if any
+    pub fn team_name(&self) -> Option<&str> {
+        self.team.as_ref().and_then(|name| name.as_deref())
+    }
+
     /// Validate CLI arguments
     pub fn validate(&self) -> Result<(), String> {
         // Can't specify both --days and --since/--until
@@ -109,20 +551,121 @@ impl Cli {
             );
         }
 
+        if self.sprint.is_some() && (self.days.is_some() || self.since.is_some() || self.until.is_some()) {
+            return Err("--sprint can't be used with --days, --since or --until".to_string());
+        }
+
+        if self.jobs == 0 {
+            return Err("--jobs must be at least 1".to_string());
+        }
+
+        if self.max_commits_per_repo == Some(0) {
+            return Err("--max-commits-per-repo must be at least 1".to_string());
+        }
+
+        if self.repo_timeout == Some(0) {
+            return Err("--repo-timeout must be at least 1 second".to_string());
+        }
+
+        if self.max_chunk_chars.is_some_and(|max| max < MIN_CHUNK_CHARS) {
+            return Err(format!("--max-chunk-chars must be at least {}", MIN_CHUNK_CHARS));
+        }
+
+        if self.top_achievements == Some(0) {
+            return Err("--top-achievements must be at least 1".to_string());
+        }
+
+        if self.slot.is_some() && self.slides.is_none() {
+            return Err("--slot requires --slides".to_string());
+        }
+
+        if self.slot == Some(0) {
+            return Err("--slot must be at least 1 minute".to_string());
+        }
+
+        if self.slides.is_some() && self.dry_run {
+            return Err("--slides can't be used with --dry-run (there are no summaries to present)".to_string());
+        }
+
+        // --utc only affects --since/--until
+        if self.utc && self.since.is_none() && self.until.is_none() {
+            return Err("--utc requires --since or --until".to_string());
+        }
+
+        if self.preview && !matches!(self.command, None | Some(Commands::Watch { .. })) {
+            return Err("--preview can't be used with a subcommand other than watch".to_string());
+        }
+
+        if matches!(self.command, Some(Commands::Watch { interval: 0 })) {
+            return Err("watch --interval must be at least 1 second".to_string());
+        }
+
+        // These keep making API calls unattended, so they never get to ask
+        let unattended = self.preview || matches!(self.command, Some(Commands::Daemon { .. } | Commands::Watch { .. }));
+        if unattended && !self.yes && !self.dry_run {
+            return Err("--preview, daemon and watch make API calls without asking; pass --yes to allow them".to_string());
+        }
+
+        if self.chat && (self.dry_run || self.preview || self.non_interactive || self.command.is_some()) {
+            return Err("--chat can't be used with --dry-run, --preview, --non-interactive or a subcommand".to_string());
+        }
+
+        if self.refine.is_some() && (self.dry_run || self.preview || self.chat || self.command.is_some()) {
+            return Err("--refine can't be used with --dry-run, --preview, --chat or a subcommand".to_string());
+        }
+
+        if let Some(ref versions) = self.compare_prompts {
+            let distinct: std::collections::HashSet<_> = versions.iter().collect();
+            if distinct.len() < 2 {
+                return Err("--compare-prompts needs at least two different versions (e.g. v1,v2)".to_string());
+            }
+            if self.dry_run
+                || self.preview
+                || self.chat
+                || self.refine.is_some()
+                || self.slides.is_some()
+                || self.command.is_some()
+            {
+                return Err(
+                    "--compare-prompts can't be used with --dry-run, --preview, --chat, --refine, --slides or a subcommand"
+                        .to_string(),
+                );
+            }
+        }
+
+        if self.leaderboard && !self.team_mode() {
+            return Err("--leaderboard requires --team".to_string());
+        }
+
         // If --authors is provided, --team should be enabled
-        if self.authors.is_some() && !self.team {
+        if self.authors.is_some() && !self.team_mode() {
             return Err("--authors requires --team flag".to_string());
         }
 
-        // Team mode requires either --authors or interactive mode
-        if self.team && self.is_non_interactive() && self.authors.is_none() {
-            return Err("Team mode in non-interactive mode requires --authors".to_string());
+        // Team mode requires a team name, --authors or interactive mode
+        if self.team == Some(None) && self.is_non_interactive() && self.authors.is_none() {
+            return Err("Team mode in non-interactive mode requires a team name or --authors".to_string());
         }
 
         Ok(())
     }
 }
 
+/// Parse a user-supplied path, expanding a leading `~` (e.g. from `--path=~/code`)
+fn parse_path(value: &str) -> Result<PathBuf, String> {
+    dev_recap::config::expand_home(value).map_err(|e| e.to_string())
+}
+
+/// Check a `--subdirs` pattern: a glob relative to each repository root
+fn parse_subdirs(value: &str) -> Result<String, String> {
+    let path = std::path::Path::new(value);
+    if path.is_absolute() || path.components().any(|c| c == std::path::Component::ParentDir) {
+        return Err("must be relative to the repository root, e.g. services/*".to_string());
+    }
+    glob::Pattern::new(value).map_err(|e| e.to_string())?;
+    Ok(value.to_string())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -159,7 +702,8 @@ mod tests {
             "--authors",
             "alice@example.com,bob@example.com",
         ]);
-        assert!(cli.team);
+        assert!(cli.team_mode());
+        assert_eq!(cli.team_name(), None);
         assert_eq!(
             cli.authors,
             Some(vec![
@@ -169,12 +713,322 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_cli_named_team() {
+        let cli = Cli::parse_from(vec!["dev-recap", "--team", "platform", "--output", "recap.md"]);
+        assert!(cli.team_mode());
+        assert_eq!(cli.team_name(), Some("platform"));
+        assert!(cli.validate().is_ok());
+
+        // Without a name or --authors there's nobody to analyze non-interactively
+        let cli = Cli::parse_from(vec!["dev-recap", "--team", "--output", "recap.md"]);
+        assert!(cli.validate().is_err());
+    }
+
+    #[test]
+    fn test_cli_path_expands_home() {
+        let cli = Cli::parse_from(vec!["dev-recap", "--path=~/code"]);
+        assert_eq!(cli.path, Some(dirs::home_dir().unwrap().join("code")));
+    }
+
+    #[test]
+    fn test_cli_repo() {
+        let cli = Cli::parse_from(vec!["dev-recap", "--repo", "/code/api", "--repo=/code/web"]);
+        assert_eq!(cli.repos, vec![PathBuf::from("/code/api"), PathBuf::from("/code/web")]);
+        assert!(Cli::parse_from(vec!["dev-recap"]).repos.is_empty());
+
+        // The scanner isn't used, so its options don't apply
+        assert!(Cli::try_parse_from(vec!["dev-recap", "--repo", "/code/api", "--path", "/code"]).is_err());
+        assert!(Cli::try_parse_from(vec!["dev-recap", "--repo", "/code/api", "--subdirs", "services/*"]).is_err());
+        assert!(Cli::try_parse_from(vec!["dev-recap", "--repo", "/code/api", "--stdin-format", "git-log"]).is_err());
+    }
+
+    #[test]
+    fn test_cli_date() {
+        let cli = Cli::parse_from(vec!["dev-recap", "--date", "committer"]);
+        assert_eq!(cli.date, Some(CommitDate::Committer));
+        assert!(Cli::try_parse_from(vec!["dev-recap", "--date", "push"]).is_err());
+    }
+
+    #[test]
+    fn test_cli_subdirs() {
+        let cli = Cli::parse_from(vec!["dev-recap", "--subdirs", "services/*"]);
+        assert_eq!(cli.subdirs.as_deref(), Some("services/*"));
+
+        assert!(Cli::try_parse_from(vec!["dev-recap", "--subdirs", "../other/*"]).is_err());
+        assert!(Cli::try_parse_from(vec!["dev-recap", "--subdirs", "services/[a"]).is_err());
+    }
+
+    #[test]
+    fn test_cli_jobs() {
+        let cli = Cli::parse_from(vec!["dev-recap"]);
+        assert_eq!(cli.jobs, 4);
+
+        let cli = Cli::parse_from(vec!["dev-recap", "--jobs", "0"]);
+        assert!(cli.validate().is_err());
+    }
+
+    #[test]
+    fn test_cli_max_commits_per_repo() {
+        let cli = Cli::parse_from(vec!["dev-recap", "--max-commits-per-repo", "200"]);
+        assert_eq!(cli.max_commits_per_repo, Some(200));
+
+        let cli = Cli::parse_from(vec!["dev-recap", "--max-commits-per-repo", "0"]);
+        assert!(cli.validate().is_err());
+    }
+
+    #[test]
+    fn test_cli_repo_timeout() {
+        let cli = Cli::parse_from(vec!["dev-recap", "--repo-timeout", "90"]);
+        assert_eq!(cli.repo_timeout, Some(90));
+        assert!(cli.validate().is_ok());
+
+        let cli = Cli::parse_from(vec!["dev-recap", "--repo-timeout", "0"]);
+        assert!(cli.validate().is_err());
+    }
+
+    #[test]
+    fn test_cli_log_level() {
+        let cli = Cli::parse_from(vec!["dev-recap"]);
+        assert_eq!(cli.log_level, LogLevel::Warn);
+
+        let cli = Cli::parse_from(vec!["dev-recap", "--log-level", "debug"]);
+        assert_eq!(LevelFilter::from(cli.log_level), LevelFilter::DEBUG);
+    }
+
     #[test]
     fn test_cli_init_command() {
         let cli = Cli::parse_from(vec!["dev-recap", "init"]);
         assert!(matches!(cli.command, Some(Commands::Init { force: false })));
     }
 
+    #[test]
+    fn test_cli_non_interactive_never_prompts() {
+        let cli = Cli::parse_from(vec!["dev-recap", "--non-interactive"]);
+        assert!(cli.is_non_interactive());
+        assert!(!cli.can_prompt());
+
+        let cli = Cli::parse_from(vec!["dev-recap", "--output", "recap.md"]);
+        assert!(!cli.can_prompt());
+    }
+
+    #[test]
+    fn test_cli_config_command() {
+        let cli = Cli::parse_from(vec!["dev-recap", "config"]);
+        assert!(matches!(cli.command, Some(Commands::Config { action: None })));
+
+        let cli = Cli::parse_from(vec!["dev-recap", "config", "validate"]);
+        assert!(matches!(
+            cli.command,
+            Some(Commands::Config { action: Some(ConfigAction::Validate) })
+        ));
+    }
+
+    #[test]
+    fn test_cli_serve_command() {
+        let cli = Cli::parse_from(vec!["dev-recap", "serve", "--port", "9000"]);
+        match cli.command {
+            Some(Commands::Serve { port, bind }) => {
+                assert_eq!(port, 9000);
+                assert!(bind.is_loopback());
+            }
+            other => panic!("unexpected command: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_cli_regen() {
+        let cli = Cli::parse_from(vec!["dev-recap", "regen", "platform/services/billing", "--id", "4"]);
+        match cli.command {
+            Some(Commands::Regen { repo, id }) => {
+                assert_eq!(repo, "platform/services/billing");
+                assert_eq!(id, Some(4));
+            }
+            other => panic!("unexpected command: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_cli_year() {
+        let cli = Cli::parse_from(vec!["dev-recap", "--author", "me@example.com", "year", "--year", "2024"]);
+        match cli.command {
+            Some(Commands::Year { year }) => assert_eq!(year, Some(2024)),
+            other => panic!("unexpected command: {:?}", other),
+        }
+        assert_eq!(cli.author.as_deref(), Some("me@example.com"));
+    }
+
+    #[test]
+    fn test_cli_cache_warm() {
+        let cli = Cli::parse_from(vec!["dev-recap", "cache", "warm", "--path", "/code", "--days", "14"]);
+        match cli.command {
+            Some(Commands::Cache {
+                action: CacheAction::Warm { path, author, days },
+            }) => {
+                assert_eq!(path, Some(PathBuf::from("/code")));
+                assert_eq!(author, None);
+                assert_eq!(days, Some(14));
+            }
+            other => panic!("unexpected command: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_cli_slides() {
+        let cli = Cli::parse_from(vec!["dev-recap", "--slides", "deck.md", "--slot", "10"]);
+        assert_eq!(cli.slides, Some(PathBuf::from("deck.md")));
+        assert_eq!(cli.slot, Some(10));
+        assert!(cli.validate().is_ok());
+
+        assert!(Cli::parse_from(vec!["dev-recap", "--slot", "10"]).validate().is_err());
+        assert!(Cli::parse_from(vec!["dev-recap", "--slides", "deck.md", "--dry-run"]).validate().is_err());
+    }
+
+    #[test]
+    fn test_cli_top_achievements() {
+        let cli = Cli::parse_from(vec!["dev-recap", "--top-achievements", "3"]);
+        assert_eq!(cli.top_achievements, Some(3));
+        assert!(cli.validate().is_ok());
+
+        assert!(Cli::parse_from(vec!["dev-recap", "--top-achievements", "0"]).validate().is_err());
+    }
+
+    #[test]
+    fn test_cli_stdin_format() {
+        let cli = Cli::parse_from(vec!["dev-recap", "--stdin-format", "git-log", "--stdin-name", "billing"]);
+        assert_eq!(cli.stdin_format, Some(StdinFormat::GitLog));
+        assert_eq!(cli.stdin_name.as_deref(), Some("billing"));
+
+        assert!(Cli::try_parse_from(vec!["dev-recap", "--stdin-format", "json"]).is_err());
+        assert!(Cli::try_parse_from(vec!["dev-recap", "--stdin-name", "billing"]).is_err());
+    }
+
+    #[test]
+    fn test_cli_order() {
+        let cli = Cli::parse_from(vec!["dev-recap", "--order", "commits"]);
+        assert_eq!(cli.order, Some(RepoOrder::Commits));
+        assert!(Cli::try_parse_from(vec!["dev-recap", "--order", "size"]).is_err());
+    }
+
+    #[test]
+    fn test_cli_theme() {
+        let cli = Cli::parse_from(vec!["dev-recap", "--theme", "none"]);
+        assert_eq!(cli.theme, Some(Theme::None));
+        assert!(Cli::try_parse_from(vec!["dev-recap", "--theme", "neon"]).is_err());
+    }
+
+    #[test]
+    fn test_cli_group_by() {
+        let cli = Cli::parse_from(vec!["dev-recap", "--group-by", "day", "-vv"]);
+        assert_eq!(cli.group_by, Some(GroupBy::Day));
+        assert!(Cli::try_parse_from(vec!["dev-recap", "--group-by", "week"]).is_err());
+    }
+
+    #[test]
+    fn test_cli_format() {
+        let cli = Cli::parse_from(vec!["dev-recap", "--format", "brag"]);
+        assert_eq!(cli.format, Some(RecapFormat::Brag));
+        assert!(Cli::try_parse_from(vec!["dev-recap", "--format", "slides"]).is_err());
+    }
+
+    #[test]
+    fn test_cli_compare_prompts() {
+        let cli = Cli::parse_from(vec!["dev-recap", "--compare-prompts", "v1,v2"]);
+        assert_eq!(cli.compare_prompts, Some(vec![PromptVersion::V1, PromptVersion::V2]));
+        assert!(cli.validate().is_ok());
+
+        let cli = Cli::parse_from(vec!["dev-recap", "--compare-prompts", "v2,v2"]);
+        assert!(cli.validate().is_err());
+        let cli = Cli::parse_from(vec!["dev-recap", "--compare-prompts", "v1,v2", "--slides", "deck.md"]);
+        assert!(cli.validate().is_err());
+        assert!(Cli::try_parse_from(vec!["dev-recap", "--compare-prompts", "v1,v7"]).is_err());
+    }
+
+    #[test]
+    fn test_cli_models() {
+        let cli = Cli::parse_from(vec!["dev-recap", "models"]);
+        assert!(matches!(cli.command, Some(Commands::Models)));
+    }
+
+    #[test]
+    fn test_cli_watch() {
+        let cli = Cli::parse_from(vec!["dev-recap", "--yes", "--preview", "watch", "--interval", "10"]);
+        assert!(matches!(cli.command, Some(Commands::Watch { interval: 10 })));
+        assert!(cli.validate().is_ok());
+
+        // Updates make API calls unattended
+        let cli = Cli::parse_from(vec!["dev-recap", "watch"]);
+        assert!(matches!(cli.command, Some(Commands::Watch { interval: 30 })));
+        assert!(cli.validate().is_err());
+
+        let cli = Cli::parse_from(vec!["dev-recap", "--yes", "watch", "--interval", "0"]);
+        assert!(cli.validate().is_err());
+        let cli = Cli::parse_from(vec!["dev-recap", "--preview", "models"]);
+        assert!(cli.validate().is_err());
+    }
+
+    #[test]
+    fn test_cli_daemon_metrics_addr() {
+        let cli = Cli::parse_from(vec!["dev-recap", "daemon", "--metrics-addr", "0.0.0.0:9090"]);
+        match cli.command {
+            Some(Commands::Daemon { now, metrics_addr }) => {
+                assert!(!now);
+                assert_eq!(metrics_addr, Some("0.0.0.0:9090".parse().unwrap()));
+            }
+            other => panic!("unexpected command: {:?}", other),
+        }
+        assert!(cli.validate().is_err());
+        assert!(Cli::parse_from(vec!["dev-recap", "-y", "daemon"]).validate().is_ok());
+    }
+
+    #[test]
+    fn test_cli_history_command() {
+        let cli = Cli::parse_from(vec!["dev-recap", "history", "show", "3", "--json"]);
+        assert!(matches!(
+            cli.command,
+            Some(Commands::History { action: HistoryAction::Show { id: 3, json: true } })
+        ));
+        let cli = Cli::parse_from(vec!["dev-recap", "history", "list"]);
+        assert!(matches!(
+            cli.command,
+            Some(Commands::History { action: HistoryAction::List { limit: 20 } })
+        ));
+    }
+
+    #[test]
+    fn test_cli_diff_command() {
+        let cli = Cli::parse_from(vec!["dev-recap", "diff", "3", "last-demo.json"]);
+        match cli.command {
+            Some(Commands::Diff { old, new }) => {
+                assert_eq!(old, "3");
+                assert_eq!(new, "last-demo.json");
+            }
+            other => panic!("unexpected command: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_cli_auth_command() {
+        let cli = Cli::parse_from(vec!["dev-recap", "auth", "set-key", "--github"]);
+        assert!(matches!(
+            cli.command,
+            Some(Commands::Auth { action: AuthAction::SetKey { github: true, linear: false } })
+        ));
+
+        let cli = Cli::parse_from(vec!["dev-recap", "auth", "delete-key", "--linear"]);
+        assert!(matches!(
+            cli.command,
+            Some(Commands::Auth { action: AuthAction::DeleteKey { github: false, linear: true } })
+        ));
+        assert!(Cli::try_parse_from(vec!["dev-recap", "auth", "set-key", "--github", "--linear"]).is_err());
+    }
+
+    #[test]
+    fn test_cli_man_command() {
+        let cli = Cli::parse_from(vec!["dev-recap", "man", "--out-dir", "target/man"]);
+        assert!(matches!(cli.command, Some(Commands::Man { out_dir: Some(_) })));
+    }
+
     #[test]
     fn test_cli_validation_days_and_since() {
         let cli = Cli::parse_from(vec![
@@ -187,6 +1041,62 @@ mod tests {
         assert!(cli.validate().is_err());
     }
 
+    #[test]
+    fn test_cli_max_chunk_chars() {
+        let cli = Cli::parse_from(vec!["dev-recap", "--max-chunk-chars", "4000"]);
+        assert_eq!(cli.max_chunk_chars, Some(4000));
+        assert!(cli.validate().is_ok());
+
+        let cli = Cli::parse_from(vec!["dev-recap", "--max-chunk-chars", "50"]);
+        assert!(cli.validate().is_err());
+    }
+
+    #[test]
+    fn test_cli_sprint() {
+        let cli = Cli::parse_from(vec!["dev-recap", "--sprint", "previous"]);
+        assert_eq!(cli.sprint, Some(SprintSelector::Previous));
+        assert!(cli.validate().is_ok());
+
+        let cli = Cli::parse_from(vec!["dev-recap", "--sprint", "12", "--days", "14"]);
+        assert_eq!(cli.sprint, Some(SprintSelector::Number(12)));
+        assert!(cli.validate().is_err());
+
+        assert!(Cli::try_parse_from(vec!["dev-recap", "--sprint", "next"]).is_err());
+    }
+
+    #[test]
+    fn test_cli_validation_utc_without_dates() {
+        let cli = Cli::parse_from(vec!["dev-recap", "--utc", "--days", "7"]);
+        assert!(cli.validate().is_err());
+
+        let cli = Cli::parse_from(vec!["dev-recap", "--utc", "--since", "2025-01-01"]);
+        assert!(cli.validate().is_ok());
+    }
+
+    #[test]
+    fn test_cli_open() {
+        // --open may rely on output_path from config instead of --output
+        let cli = Cli::parse_from(vec!["dev-recap", "--open"]);
+        assert!(cli.open);
+
+        let cli = Cli::parse_from(vec!["dev-recap", "--open", "--output", "recap.md"]);
+        assert!(cli.open);
+    }
+
+    #[test]
+    fn test_cli_preview() {
+        // Preview reruns happen unattended
+        let cli = Cli::parse_from(vec!["dev-recap", "--preview"]);
+        assert!(cli.is_non_interactive());
+        assert_eq!(cli.preview_port, dev_recap::preview::DEFAULT_PORT);
+        assert!(cli.validate().is_err());
+        assert!(Cli::parse_from(vec!["dev-recap", "--preview", "--yes"]).validate().is_ok());
+        assert!(Cli::parse_from(vec!["dev-recap", "--preview", "--dry-run"]).validate().is_ok());
+
+        let cli = Cli::parse_from(vec!["dev-recap", "--yes", "--preview", "serve"]);
+        assert!(cli.validate().is_err());
+    }
+
     #[test]
     fn test_cli_validation_authors_without_team() {
         let cli = Cli::parse_from(vec![
@@ -1,24 +1,55 @@
+use crate::ai::prompt::{Example, PromptVersion, RecapFormat, SectionSpec};
+use crate::ai::cost::ModelPricing;
+use crate::ai::provider::Provider;
+use crate::ai::{KEY_ACHIEVEMENTS, PRESENTATION_TIPS};
+use crate::chunk::MIN_CHUNK_CHARS;
 use crate::error::{DevRecapError, Result};
+use crate::git::parser::Parser;
+use crate::git::stats::{GroupBy, RepoOrder};
+use crate::git::{CommitDate, GitBackend};
+use crate::goals;
+use crate::schedule::Schedule;
+use crate::secrets::{self, Secret};
+use crate::sprint::SprintConfig;
+use crate::theme::Theme;
+use chrono::{DateTime, Local};
+use regex::Regex;
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::fs;
 use std::path::{Path, PathBuf};
 
+/// Hotspots listed per repository unless `hotspot_limit` says otherwise
+pub const DEFAULT_HOTSPOT_LIMIT: usize = 5;
+
 /// Application configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Config {
     /// Default author email for filtering commits
     pub default_author_email: Option<String>,
 
-    /// Claude API key (can be overridden by ANTHROPIC_AUTH_TOKEN env var)
+    /// Provider summaries are generated with: "anthropic", "openai" or
+    /// "ollama", configured in its `[providers.NAME]` block
+    #[serde(default)]
+    pub provider: Provider,
+
+    /// Claude API key (can be overridden by ANTHROPIC_AUTH_TOKEN env var);
+    /// older spelling of `api_key` in `[providers.anthropic]`
     #[serde(default)]
     pub claude_api_key: Option<String>,
 
+    /// Command whose stdout is used as the Claude API key, e.g. "op read op://work/anthropic/key"
+    /// (used when claude_api_key is not set); older spelling of `api_key_cmd` in `[providers.anthropic]`
+    pub claude_api_key_cmd: Option<String>,
+
     /// Claude API base URL (can be overridden by ANTHROPIC_BASE_URL env var)
     /// Should be the base URL without /v1/messages (e.g., "https://api.anthropic.com" or "http://localhost:4000")
-    /// The /v1/messages endpoint will be appended automatically
+    /// The /v1/messages endpoint will be appended automatically; older
+    /// spelling of `base_url` in `[providers.anthropic]`
     pub claude_api_base_url: Option<String>,
 
-    /// Claude model to use (optional, defaults to claude-sonnet-4-5-20250929)
+    /// Claude model to use (optional, defaults to claude-sonnet-4-5-20250929);
+    /// older spelling of `model` in `[providers.anthropic]`
     pub claude_model: Option<String>,
 
     /// Default timespan in days (default: 14 days / 2 weeks)
@@ -32,6 +63,92 @@ pub struct Config {
     /// Maximum directory depth for scanning (None = unlimited)
     pub max_scan_depth: Option<u32>,
 
+    /// Time budget in seconds for parsing, and again for summarizing, each
+    /// repository; slower repositories are skipped (None = unlimited)
+    pub repo_timeout_secs: Option<u64>,
+
+    /// Library used to read git history: "libgit2" (default) or "gix"
+    /// (faster on large repositories; needs the `gix` build feature)
+    #[serde(default)]
+    pub git_backend: GitBackend,
+
+    /// Timestamp that places commits in the timespan and stats: "author"
+    /// (default; unaffected by rebases) or "committer"
+    #[serde(default)]
+    pub commit_date: CommitDate,
+
+    /// Compute per-commit files/insertions/deletions (disable for faster runs)
+    #[serde(default = "default_true")]
+    pub diff_stats_enabled: bool,
+
+    /// Count a change that appears in several commits (cherry-picks, backports,
+    /// second checkouts) once, matched by patch ID
+    #[serde(default = "default_true")]
+    pub collapse_duplicate_commits: bool,
+
+    /// Maximum commits listed per repository, keeping the most recent; statistics
+    /// still cover all of them (None = unlimited)
+    pub max_commits_per_repo: Option<usize>,
+
+    /// Churn hotspots (files changed repeatedly) listed per repository (0 = none)
+    #[serde(default = "default_hotspot_limit")]
+    pub hotspot_limit: usize,
+
+    /// Case-insensitive regexes; commits whose message matches any are ignored
+    #[serde(default)]
+    pub ignore_commit_patterns: Vec<String>,
+
+    /// Leave commits changing fewer lines out of the prompt (still counted in stats)
+    pub min_changes: Option<u32>,
+
+    /// Include the message bodies (truncated) of the N largest commits in the prompt
+    pub commit_bodies: Option<usize>,
+
+    /// Wording of the summary prompt (see [`PromptVersion`])
+    #[serde(default)]
+    pub prompt_version: PromptVersion,
+
+    /// What recaps are written for: "demo" (presentations) or "brag" (a
+    /// self-review document over a longer window)
+    #[serde(default)]
+    pub format: RecapFormat,
+
+    /// Ask for a "Watch-outs" section flagging risks and tech debt, for retros
+    #[serde(default)]
+    pub watch_outs: bool,
+
+    /// Organize the verbose commit listing by "day", and ask for a day-by-day
+    /// "Timeline" section in each summary
+    #[serde(default)]
+    pub group_by: Option<GroupBy>,
+
+    /// Markdown or TOML file of goals/OKRs each summary maps its work to, in a
+    /// "Goal Alignment" section (`~` is expanded; see [`crate::goals`])
+    pub goals_file: Option<String>,
+
+    /// Keep only the N most impactful key achievements (ranked by the size,
+    /// PRs and hotspots of the commits behind them), for short presentations
+    pub top_achievements: Option<usize>,
+
+    /// Sections of each summary after the work summary, in order
+    /// (`[[summary_sections]]` tables; default: Key Achievements, Presentation Tips)
+    #[serde(default)]
+    pub summary_sections: Vec<SectionSpec>,
+
+    /// Regexes redacted from prompts on top of the built-in secret patterns,
+    /// e.g. private hostnames (see [`crate::redact`])
+    #[serde(default)]
+    pub redact_patterns: Vec<String>,
+
+    /// Keep author names, emails and remote URLs out of prompts; summaries
+    /// still name authors, restored locally (see [`crate::anonymize`])
+    #[serde(default)]
+    pub private_prompts: bool,
+
+    /// Regex patterns marking changed paths as test code
+    #[serde(default = "default_test_patterns")]
+    pub test_patterns: Vec<String>,
+
     /// Enable caching of AI summaries
     #[serde(default = "default_true")]
     pub cache_enabled: bool,
@@ -42,10 +159,209 @@ pub struct Config {
 
     /// GitHub token for API access (optional, increases rate limits)
     pub github_token: Option<String>,
+
+    /// Linear personal API key, to resolve issue identifiers like ENG-123 in
+    /// commit messages (optional)
+    pub linear_api_key: Option<String>,
+
+    /// Linear team keys whose identifiers are looked up (default: any
+    /// `ABC-123`-shaped identifier)
+    #[serde(default)]
+    pub linear_teams: Vec<String>,
+
+    /// File to append diagnostic logs to (optional)
+    pub log_file: Option<PathBuf>,
+
+    /// Default output file when --output isn't given, with date placeholders
+    /// ({date}, {year}, {month}, {day}, {week}, {time}), e.g. "~/recaps/{year}/{date}-recap.md"
+    pub output_path: Option<String>,
+
+    /// Render repository paths as `file://` links that open the folder
+    #[serde(default)]
+    pub file_links: bool,
+
+    /// Colors of the terminal output: "default", "light" or "none"
+    /// (`NO_COLOR` turns colors off regardless)
+    #[serde(default)]
+    pub theme: Theme,
+
+    /// Order of the repositories in the report: "name", "commits", "lines"
+    /// (changed) or "recent" (latest commit first)
+    #[serde(default)]
+    pub order: RepoOrder,
+
+    /// Cron schedule for `dev-recap daemon`, in local time, e.g. "0 17 * * FRI"
+    pub schedule: Option<String>,
+
+    /// Slack incoming webhook the daemon posts each report to (optional)
+    pub slack_webhook_url: Option<String>,
+
+    /// Addresses the daemon emails each report to via `sendmail` (optional)
+    #[serde(default)]
+    pub email_to: Vec<String>,
+
+    /// Split reports posted to Slack, or printed for pasting into chat, into
+    /// numbered parts of at most this many characters (optional)
+    pub max_chunk_chars: Option<usize>,
+
+    /// Signing secret of the Slack app whose `/recap` command `dev-recap serve`
+    /// answers (can be overridden by SLACK_SIGNING_SECRET env var)
+    pub slack_signing_secret: Option<String>,
+
+    /// Bearer token `dev-recap serve` requires on `/recaps` requests
+    /// (can be overridden by DEV_RECAP_SERVE_TOKEN env var)
+    pub serve_token: Option<String>,
+
+    /// Save every generated recap for `dev-recap history` (default: true)
+    #[serde(default = "default_true")]
+    pub history_enabled: bool,
+
+    /// External commands adding context to each repository's prompt
+    /// (`[[enrichers]]` tables, see [`crate::enrich`])
+    #[serde(default)]
+    pub enrichers: Vec<EnricherConfig>,
+
+    /// Example input/output pairs shown in every summary prompt, to teach the
+    /// model the team's terminology and level of detail (`[[examples]]` tables)
+    #[serde(default)]
+    pub examples: Vec<ExampleConfig>,
+
+    /// Commit link templates for remotes GitHub links don't fit, e.g.
+    /// self-hosted GitLab (`[[commit_links]]` tables, see [`crate::links`])
+    #[serde(default)]
+    pub commit_links: Vec<CommitLinkConfig>,
+
+    /// Sprint cadence for `--sprint` (`[sprint]` with `start = "2025-01-06"`
+    /// and `length_days = 14`)
+    #[serde(default)]
+    pub sprint: Option<SprintConfig>,
+
+    /// Price per million tokens by model name (`[pricing."gpt-4o"]`), for
+    /// cost estimates of models the built-in Claude pricing doesn't cover
+    #[serde(default)]
+    pub pricing: BTreeMap<String, ModelPricing>,
+
+    /// Credentials, endpoints and models per provider (`[providers.openai]`, ...)
+    #[serde(default)]
+    pub providers: Providers,
+
+    /// Named teams for `--team NAME` (`[teams.platform] authors = [...]`)
+    #[serde(default)]
+    pub teams: BTreeMap<String, TeamConfig>,
+}
+
+/// An enrichment plugin: a shell command that receives a repository's JSON
+/// on stdin and prints extra context for its summary prompt
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EnricherConfig {
+    /// Label shown with its context in the prompt, e.g. "Jira tickets"
+    pub name: String,
+    /// Command run through the shell
+    pub command: String,
+    /// Seconds before the command is killed and skipped (default: 30)
+    pub timeout_secs: Option<u64>,
+}
+
+/// Where commits of repositories on a host are linked to
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CommitLinkConfig {
+    /// Host of the remote, e.g. `git.corp`, or `*.corp` for any subdomain
+    pub host: String,
+    /// URL of a commit with `{host}`, `{repo}` (the remote's path without
+    /// `.git`) and `{hash}` placeholders
+    pub commit_url: String,
+}
+
+/// Files holding a few-shot example for summary prompts
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExampleConfig {
+    /// Commits or context the example recap was written from (`~` is expanded)
+    pub input: String,
+    /// The recap wanted for that input (`~` is expanded)
+    pub output: String,
+}
+
+impl ExampleConfig {
+    /// Read both files
+    pub fn load(&self) -> Result<Example> {
+        let read = |path: &str| -> Result<String> {
+            fs::read_to_string(expand_home(path)?)
+                .map_err(|e| DevRecapError::config(format!("Failed to read example file {}: {}", path, e)))
+        };
+        Ok(Example {
+            input: read(&self.input)?,
+            output: read(&self.output)?,
+        })
+    }
+}
+
+/// Credentials, endpoint and model of one provider (`[providers.NAME]`)
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProviderConfig {
+    /// API key (overridden by the provider's env var, e.g. OPENAI_API_KEY)
+    pub api_key: Option<String>,
+    /// Command whose stdout is used as the API key (when api_key is not set)
+    pub api_key_cmd: Option<String>,
+    /// API base URL, without the endpoint path (default: the provider's public API,
+    /// or http://localhost:11434 for Ollama)
+    pub base_url: Option<String>,
+    /// Model to use (default: the provider's default model)
+    pub model: Option<String>,
+}
+
+impl ProviderConfig {
+    /// These settings, with unset ones taken from `fallback`
+    fn or(&self, fallback: ProviderConfig) -> ProviderConfig {
+        ProviderConfig {
+            api_key: self.api_key.clone().or(fallback.api_key),
+            api_key_cmd: self.api_key_cmd.clone().or(fallback.api_key_cmd),
+            base_url: self.base_url.clone().or(fallback.base_url),
+            model: self.model.clone().or(fallback.model),
+        }
+    }
+}
+
+/// Settings of every provider, whichever is selected
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Providers {
+    #[serde(default)]
+    pub anthropic: ProviderConfig,
+    #[serde(default)]
+    pub openai: ProviderConfig,
+    #[serde(default)]
+    pub ollama: ProviderConfig,
+}
+
+impl Providers {
+    /// The block of `provider`
+    pub fn get(&self, provider: Provider) -> &ProviderConfig {
+        match provider {
+            Provider::Anthropic => &self.anthropic,
+            Provider::OpenAI => &self.openai,
+            Provider::Ollama => &self.ollama,
+        }
+    }
+
+    /// The block of `provider`, for changes
+    pub fn get_mut(&mut self, provider: Provider) -> &mut ProviderConfig {
+        match provider {
+            Provider::Anthropic => &mut self.anthropic,
+            Provider::OpenAI => &mut self.openai,
+            Provider::Ollama => &mut self.ollama,
+        }
+    }
+}
+
+/// A named group of authors
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TeamConfig {
+    /// Author emails of the team members
+    #[serde(default)]
+    pub authors: Vec<String>,
 }
 
 impl Config {
-    /// Load configuration from the default location (~/.config/dev-recap/config.toml)
+    /// Load configuration from the default location (see `default_config_path`)
     pub fn load() -> Result<Self> {
         let config_path = Self::default_config_path()?;
         Self::load_from(&config_path)
@@ -74,48 +390,182 @@ impl Config {
     fn apply_env_overrides(&mut self) {
         use std::env;
 
-        // ANTHROPIC_AUTH_TOKEN takes precedence over config file
-        if let Ok(api_key) = env::var("ANTHROPIC_AUTH_TOKEN") {
-            self.claude_api_key = Some(api_key);
+        // Each provider's env vars (ANTHROPIC_AUTH_TOKEN, OPENAI_API_KEY, ...)
+        // take precedence over the config file
+        for provider in Provider::ALL {
+            if let Ok(api_key) = env::var(provider.api_key_env()) {
+                self.providers.get_mut(provider).api_key = Some(api_key);
+            }
+            if let Ok(base_url) = env::var(provider.base_url_env()) {
+                self.providers.get_mut(provider).base_url = Some(provider.base_url_from_env(&base_url));
+            }
         }
 
-        // ANTHROPIC_BASE_URL takes precedence over config file
-        if let Ok(base_url) = env::var("ANTHROPIC_BASE_URL") {
-            self.claude_api_base_url = Some(base_url);
+        // SLACK_SIGNING_SECRET keeps the secret out of the config file
+        if let Ok(secret) = env::var("SLACK_SIGNING_SECRET") {
+            self.slack_signing_secret = Some(secret);
+        }
+        if let Ok(token) = env::var("DEV_RECAP_SERVE_TOKEN") {
+            self.serve_token = Some(token);
         }
     }
 
-    /// Get the effective API key (from env or config)
+    /// Settings of the selected provider; for Anthropic, `[providers.anthropic]`
+    /// falls back to the older `claude_*` keys
+    pub fn provider_config(&self) -> ProviderConfig {
+        let config = self.providers.get(self.provider);
+        match self.provider {
+            Provider::Anthropic => config.or(ProviderConfig {
+                api_key: self.claude_api_key.clone(),
+                api_key_cmd: self.claude_api_key_cmd.clone(),
+                base_url: self.claude_api_base_url.clone(),
+                model: self.claude_model.clone(),
+            }),
+            Provider::OpenAI | Provider::Ollama => config.clone(),
+        }
+    }
+
+    /// Get the effective API key of the selected provider (from env, config,
+    /// credential command, or OS keyring for Anthropic)
+    ///
+    /// Empty for an Ollama server that takes no key.
     pub fn get_api_key(&self) -> Result<String> {
-        self.claude_api_key
+        let provider = self.provider_config();
+        if let Some(api_key) = provider.api_key {
+            return Ok(api_key);
+        }
+
+        if let Some(ref command) = provider.api_key_cmd {
+            let key = if self.providers.get(self.provider).api_key_cmd.is_some() {
+                format!("providers.{}.api_key_cmd", self.provider)
+            } else {
+                "claude_api_key_cmd".to_string()
+            };
+            return run_credential_command(&key, command);
+        }
+
+        match self.provider {
+            Provider::Anthropic => secrets::get(Secret::ClaudeApiKey).ok_or_else(|| DevRecapError::MissingConfig(
+                "claude_api_key is required (set ANTHROPIC_AUTH_TOKEN env var, add to config file, or run `dev-recap auth set-key`)".to_string()
+            )),
+            Provider::Ollama => Ok(String::new()),
+            Provider::OpenAI => Err(DevRecapError::MissingConfig(format!(
+                "an API key is required for {} (set the {} env var, or api_key or api_key_cmd in [providers.{}])",
+                self.provider,
+                self.provider.api_key_env(),
+                self.provider
+            ))),
+        }
+    }
+
+    /// Use `api_key` for the selected provider, e.g. once resolved from a
+    /// credential command so it doesn't run again
+    pub fn set_api_key(&mut self, api_key: String) {
+        self.providers.get_mut(self.provider).api_key = Some(api_key);
+    }
+
+    /// Get the effective GitHub token (from config or OS keyring)
+    pub fn get_github_token(&self) -> Option<String> {
+        self.github_token
+            .clone()
+            .or_else(|| secrets::get(Secret::GitHubToken))
+    }
+
+    /// Get the effective Linear API key (from config or OS keyring)
+    pub fn get_linear_api_key(&self) -> Option<String> {
+        self.linear_api_key
             .clone()
-            .ok_or_else(|| DevRecapError::MissingConfig(
-                "claude_api_key is required (set ANTHROPIC_AUTH_TOKEN env var or add to config file)".to_string()
-            ))
+            .or_else(|| secrets::get(Secret::LinearApiKey))
+    }
+
+    /// Author emails of the team named `name`
+    pub fn team_authors(&self, name: &str) -> Result<Vec<String>> {
+        match self.teams.get(name) {
+            Some(team) => Ok(team.authors.clone()),
+            None if self.teams.is_empty() => Err(DevRecapError::config(format!(
+                "unknown team `{}` (define it as [teams.{}] in the config file)",
+                name, name
+            ))),
+            None => Err(DevRecapError::config(format!(
+                "unknown team `{}` (configured teams: {})",
+                name,
+                self.teams.keys().cloned().collect::<Vec<_>>().join(", ")
+            ))),
+        }
     }
 
-    /// Get the effective base URL (from env, config, or default)
+    /// Get the effective base URL of the selected provider (from env or
+    /// config; None for the provider's default)
     pub fn get_base_url(&self) -> Option<String> {
-        self.claude_api_base_url.clone()
+        self.provider_config().base_url
     }
 
-    /// Get the effective model (from config or default)
+    /// Get the effective model of the selected provider (None for the
+    /// provider's default)
     pub fn get_model(&self) -> Option<String> {
-        self.claude_model.clone()
+        self.provider_config().model
     }
 
     /// Get the default config file path
+    ///
+    /// Uses the platform config directory: `$XDG_CONFIG_HOME` (or `~/.config`) on Linux,
+    /// `~/Library/Application Support` on macOS and `%APPDATA%` on Windows.
     pub fn default_config_path() -> Result<PathBuf> {
-        let home = dirs::home_dir()
-            .ok_or_else(|| DevRecapError::config("Could not determine home directory"))?;
-        Ok(home.join(".config").join("dev-recap").join("config.toml"))
+        let config_dir = dirs::config_dir()
+            .ok_or_else(|| DevRecapError::config("Could not determine config directory"))?;
+        Ok(config_dir.join("dev-recap").join("config.toml"))
     }
 
     /// Get the default cache directory path
+    ///
+    /// Uses the platform cache directory: `$XDG_CACHE_HOME` (or `~/.cache`) on Linux,
+    /// `~/Library/Caches` on macOS and `%LOCALAPPDATA%` on Windows.
     pub fn default_cache_dir() -> Result<PathBuf> {
-        let home = dirs::home_dir()
-            .ok_or_else(|| DevRecapError::config("Could not determine home directory"))?;
-        Ok(home.join(".cache").join("dev-recap"))
+        let cache_dir = dirs::cache_dir()
+            .ok_or_else(|| DevRecapError::config("Could not determine cache directory"))?;
+        Ok(cache_dir.join("dev-recap"))
+    }
+
+    /// Get the default data directory path (recap history)
+    ///
+    /// Uses the platform data directory: `$XDG_DATA_HOME` (or `~/.local/share`) on Linux,
+    /// `~/Library/Application Support` on macOS and `%APPDATA%` on Windows.
+    pub fn default_data_dir() -> Result<PathBuf> {
+        let data_dir = dirs::data_dir()
+            .ok_or_else(|| DevRecapError::config("Could not determine data directory"))?;
+        Ok(data_dir.join("dev-recap"))
+    }
+
+    /// Move config and cache from the legacy `~/.config` / `~/.cache` locations
+    /// to the platform directories, if they differ and nothing exists there yet
+    pub fn migrate_legacy_dirs() -> Result<()> {
+        let home = match dirs::home_dir() {
+            Some(home) => home,
+            None => return Ok(()),
+        };
+
+        let config_dir = Self::default_config_path()?;
+        let config_dir = config_dir.parent().unwrap_or(&config_dir);
+        let legacy_config_dir = home.join(".config").join("dev-recap");
+        if migrate_dir(&legacy_config_dir, config_dir)? {
+            tracing::warn!(
+                "moved config from {} to {}",
+                legacy_config_dir.display(),
+                config_dir.display()
+            );
+        }
+
+        let cache_dir = Self::default_cache_dir()?;
+        let legacy_cache_dir = home.join(".cache").join("dev-recap");
+        if migrate_dir(&legacy_cache_dir, &cache_dir)? {
+            tracing::warn!(
+                "moved cache from {} to {}",
+                legacy_cache_dir.display(),
+                cache_dir.display()
+            );
+        }
+
+        Ok(())
     }
 
     /// Create a default configuration file at the default location
@@ -146,15 +596,308 @@ impl Config {
             // No longer validate key format since custom base URLs may use different auth schemes
         }
 
+        if let Some((_, message)) = self.range_errors().into_iter().next() {
+            return Err(DevRecapError::config(message));
+        }
+
+        // Base URLs from the environment are checked like the config file's
+        let is_http = |url: &str| url.starts_with("https://") || url.starts_with("http://");
+        for provider in Provider::ALL {
+            if let Some(ref url) = self.providers.get(provider).base_url {
+                if !is_http(url) {
+                    return Err(DevRecapError::config(format!(
+                        "{} base URL must start with http:// or https:// (got `{}` from {} or providers.{}.base_url)",
+                        provider,
+                        url,
+                        provider.base_url_env(),
+                        provider
+                    )));
+                }
+            }
+        }
+        if let Some(ref url) = self.claude_api_base_url {
+            if !is_http(url) {
+                return Err(DevRecapError::config(format!(
+                    "claude_api_base_url must start with http:// or https:// (got `{}`)",
+                    url
+                )));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Out-of-range values as (key, message) pairs
+    fn range_errors(&self) -> Vec<(&'static str, String)> {
+        let mut errors = Vec::new();
+
         if self.default_timespan_days == 0 {
-            return Err(DevRecapError::config("default_timespan_days must be > 0"));
+            errors.push(("default_timespan_days", "default_timespan_days must be > 0".to_string()));
         }
 
         if self.cache_ttl_hours == 0 {
-            return Err(DevRecapError::config("cache_ttl_hours must be > 0"));
+            errors.push(("cache_ttl_hours", "cache_ttl_hours must be > 0".to_string()));
         }
 
-        Ok(())
+        if self.max_chunk_chars.is_some_and(|max| max < MIN_CHUNK_CHARS) {
+            errors.push((
+                "max_chunk_chars",
+                format!("max_chunk_chars must be at least {}", MIN_CHUNK_CHARS),
+            ));
+        }
+
+        if self.sprint.is_some_and(|sprint| sprint.length_days == 0) {
+            errors.push(("sprint", "sprint.length_days must be > 0".to_string()));
+        }
+
+        errors
+    }
+
+    /// Check a config file and report every problem found, instead of failing on the first
+    ///
+    /// Reports TOML syntax/type errors, unknown keys (which loading silently ignores),
+    /// out-of-range values and referenced paths that don't exist, with line numbers
+    /// where possible. Environment overrides are not applied.
+    pub fn validate_file(path: &Path) -> Result<Vec<ConfigIssue>> {
+        let contents = fs::read_to_string(path)?;
+
+        // Syntax and type errors: toml's message already points at the line
+        let table: toml::Table = match toml::from_str(&contents) {
+            Ok(table) => table,
+            Err(e) => return Ok(vec![ConfigIssue::error(None, e.to_string())]),
+        };
+        let config: Config = match toml::from_str(&contents) {
+            Ok(config) => config,
+            Err(e) => return Ok(vec![ConfigIssue::error(None, e.to_string())]),
+        };
+
+        let line_of = |key: &str| find_key_line(&contents, key);
+        let mut issues = Vec::new();
+
+        // Every key Config understands survives a round trip, so whatever is
+        // missing from the re-serialized table, at any depth, was ignored while loading
+        let known = toml::Table::try_from(&config)?;
+        let mut unknown = Vec::new();
+        unknown_keys(&table, &known, "", &mut unknown);
+        for key in unknown {
+            let line = key
+                .rsplit_once('.')
+                .and_then(|(table, leaf)| find_key_line_in(&contents, table, leaf))
+                .or_else(|| line_of(&key));
+            issues.push(ConfigIssue::error(line, format!("unknown key `{}`", key)));
+        }
+
+        if config.claude_api_key_cmd.as_deref().map(str::trim) == Some("") {
+            issues.push(ConfigIssue::error(
+                line_of("claude_api_key_cmd"),
+                "claude_api_key_cmd cannot be empty".to_string(),
+            ));
+        }
+
+        if config.claude_api_key.as_deref() == Some("") {
+            issues.push(ConfigIssue::error(
+                line_of("claude_api_key"),
+                "claude_api_key cannot be empty".to_string(),
+            ));
+        }
+
+        for provider in Provider::ALL {
+            let settings = config.providers.get(provider);
+            let line = line_of(&format!("providers.{}", provider));
+            if settings.api_key.as_deref() == Some("") {
+                issues.push(ConfigIssue::error(line, format!("providers.{}.api_key cannot be empty", provider)));
+            }
+            if settings.api_key_cmd.as_deref().map(str::trim) == Some("") {
+                issues.push(ConfigIssue::error(line, format!("providers.{}.api_key_cmd cannot be empty", provider)));
+            }
+            if let Some(ref url) = settings.base_url {
+                if !url.starts_with("https://") && !url.starts_with("http://") {
+                    issues.push(ConfigIssue::error(
+                        line,
+                        format!("providers.{}.base_url must start with http:// or https:// (got `{}`)", provider, url),
+                    ));
+                }
+            }
+        }
+
+        for (key, message) in config.range_errors() {
+            issues.push(ConfigIssue::error(line_of(key), message));
+        }
+
+        if config.max_scan_depth == Some(0) {
+            issues.push(ConfigIssue::warning(
+                line_of("max_scan_depth"),
+                "max_scan_depth = 0 disables scanning entirely".to_string(),
+            ));
+        }
+
+        if config.max_commits_per_repo == Some(0) {
+            issues.push(ConfigIssue::warning(
+                line_of("max_commits_per_repo"),
+                "max_commits_per_repo = 0 collects no commits".to_string(),
+            ));
+        }
+
+        if config.git_backend == GitBackend::Gix && !cfg!(feature = "gix") {
+            issues.push(ConfigIssue::error(
+                line_of("git_backend"),
+                "git_backend = \"gix\" requires dev-recap built with the `gix` feature".to_string(),
+            ));
+        }
+
+        for (name, team) in &config.teams {
+            if team.authors.is_empty() {
+                issues.push(ConfigIssue::warning(
+                    line_of(name),
+                    format!("team `{}` has no authors", name),
+                ));
+            }
+        }
+
+        for pattern in &config.ignore_commit_patterns {
+            if let Err(e) = Parser::compile_ignore_patterns(std::slice::from_ref(pattern)) {
+                issues.push(ConfigIssue::error(
+                    line_of("ignore_commit_patterns"),
+                    format!("invalid commit pattern `{}`: {}", pattern, e),
+                ));
+            }
+        }
+
+        for pattern in &config.redact_patterns {
+            if let Err(e) = Regex::new(pattern) {
+                issues.push(ConfigIssue::error(
+                    line_of("redact_patterns"),
+                    format!("invalid redaction pattern `{}`: {}", pattern, e),
+                ));
+            }
+        }
+
+        for pattern in &config.test_patterns {
+            if let Err(e) = Regex::new(pattern) {
+                issues.push(ConfigIssue::error(
+                    line_of("test_patterns"),
+                    format!("invalid test pattern `{}`: {}", pattern, e),
+                ));
+            }
+        }
+
+        if config.top_achievements == Some(0) {
+            issues.push(ConfigIssue::error(
+                line_of("top_achievements"),
+                "top_achievements must be at least 1".to_string(),
+            ));
+        }
+
+        let mut titles = std::collections::HashSet::new();
+        for section in &config.summary_sections {
+            let title = section.title.trim();
+            let built_in = [KEY_ACHIEVEMENTS, PRESENTATION_TIPS]
+                .iter()
+                .any(|built_in| built_in.eq_ignore_ascii_case(title));
+            let message = if title.is_empty() || title.eq_ignore_ascii_case("Summary") {
+                Some(format!("summary section title `{}` is empty or reserved", title))
+            } else if !titles.insert(title.to_lowercase()) {
+                Some(format!("summary section `{}` is listed twice", title))
+            } else if section.instruction.is_none() && !built_in {
+                Some(format!("summary section `{}` needs an instruction", title))
+            } else {
+                None
+            };
+            if let Some(message) = message {
+                issues.push(ConfigIssue::error(line_of("summary_sections"), message));
+            }
+        }
+
+        for enricher in &config.enrichers {
+            if enricher.command.trim().is_empty() {
+                issues.push(ConfigIssue::error(
+                    line_of("enrichers"),
+                    format!("enricher `{}` has an empty command", enricher.name),
+                ));
+            }
+            if enricher.timeout_secs == Some(0) {
+                issues.push(ConfigIssue::error(
+                    line_of("enrichers"),
+                    format!("enricher `{}`: timeout_secs must be > 0", enricher.name),
+                ));
+            }
+        }
+
+        for link in &config.commit_links {
+            if !link.commit_url.contains("{hash}") {
+                issues.push(ConfigIssue::error(
+                    line_of("commit_links"),
+                    format!("commit_url for `{}` has no {{hash}} placeholder", link.host),
+                ));
+            }
+        }
+
+        for example in &config.examples {
+            for path in [&example.input, &example.output] {
+                if !expand_home(path).map(|p| p.is_file()).unwrap_or(false) {
+                    issues.push(ConfigIssue::error(
+                        line_of("examples"),
+                        format!("example file {} does not exist", path),
+                    ));
+                }
+            }
+        }
+
+        if let Some(ref path) = config.goals_file {
+            if let Err(e) = expand_home(path).and_then(|path| goals::load(&path)) {
+                issues.push(ConfigIssue::error(line_of("goals_file"), e.to_string()));
+            }
+        }
+
+        if config.repo_timeout_secs == Some(0) {
+            issues.push(ConfigIssue::warning(
+                line_of("repo_timeout_secs"),
+                "repo_timeout_secs = 0 skips every repository".to_string(),
+            ));
+        }
+
+        if let Some(ref url) = config.claude_api_base_url {
+            if !url.starts_with("http://") && !url.starts_with("https://") {
+                issues.push(ConfigIssue::error(
+                    line_of("claude_api_base_url"),
+                    format!("claude_api_base_url must start with http:// or https:// (got `{}`)", url),
+                ));
+            }
+        }
+
+        if let Some(ref template) = config.output_path {
+            if let Err(e) = expand_output_path(template, Local::now()) {
+                issues.push(ConfigIssue::error(line_of("output_path"), e.to_string()));
+            }
+        }
+
+        if let Some(ref schedule) = config.schedule {
+            if let Err(e) = Schedule::parse(schedule) {
+                issues.push(ConfigIssue::error(line_of("schedule"), e.to_string()));
+            }
+        }
+
+        if let Some(ref url) = config.slack_webhook_url {
+            if !url.starts_with("https://") && !url.starts_with("http://") {
+                issues.push(ConfigIssue::error(
+                    line_of("slack_webhook_url"),
+                    format!("slack_webhook_url must start with https:// (got `{}`)", url),
+                ));
+            }
+        }
+
+        if let Some(ref log_file) = config.log_file {
+            if let Some(parent) = log_file.parent().filter(|p| !p.as_os_str().is_empty()) {
+                if !parent.is_dir() {
+                    issues.push(ConfigIssue::warning(
+                        line_of("log_file"),
+                        format!("log_file directory {} does not exist (it will be created)", parent.display()),
+                    ));
+                }
+            }
+        }
+
+        Ok(issues)
     }
 
     /// Load config from file, or create default if it doesn't exist
@@ -183,19 +926,273 @@ impl Default for Config {
     fn default() -> Self {
         Self {
             default_author_email: None,
+            provider: Provider::default(),
             claude_api_key: None, // Will be read from env or config file
+            claude_api_key_cmd: None,
             claude_api_base_url: None,
             claude_model: None,
             default_timespan_days: default_timespan(),
             exclude_patterns: default_exclude_patterns(),
             max_scan_depth: None,
+            repo_timeout_secs: None,
+            git_backend: GitBackend::default(),
+            commit_date: CommitDate::default(),
+            diff_stats_enabled: default_true(),
+            collapse_duplicate_commits: default_true(),
+            max_commits_per_repo: None,
+            hotspot_limit: default_hotspot_limit(),
+            ignore_commit_patterns: Vec::new(),
+            min_changes: None,
+            commit_bodies: None,
+            prompt_version: PromptVersion::default(),
+            format: RecapFormat::default(),
+            watch_outs: false,
+            group_by: None,
+            goals_file: None,
+            top_achievements: None,
+            summary_sections: Vec::new(),
+            redact_patterns: Vec::new(),
+            private_prompts: false,
+            test_patterns: default_test_patterns(),
             cache_enabled: default_true(),
             cache_ttl_hours: default_cache_ttl(),
             github_token: None,
+            linear_api_key: None,
+            linear_teams: Vec::new(),
+            log_file: None,
+            output_path: None,
+            file_links: false,
+            theme: Theme::default(),
+            order: RepoOrder::default(),
+            schedule: None,
+            slack_webhook_url: None,
+            email_to: Vec::new(),
+            max_chunk_chars: None,
+            slack_signing_secret: None,
+            serve_token: None,
+            history_enabled: default_true(),
+            enrichers: Vec::new(),
+            examples: Vec::new(),
+            commit_links: Vec::new(),
+            sprint: None,
+            pricing: BTreeMap::new(),
+            providers: Providers::default(),
+            teams: BTreeMap::new(),
+        }
+    }
+}
+
+/// Expand `~` and date placeholders in an output path template
+///
+/// Supported placeholders: `{date}` (2025-01-15), `{year}`, `{month}`, `{day}`,
+/// `{week}` (ISO week, 03) and `{time}` (143005).
+pub fn expand_output_path(template: &str, now: DateTime<Local>) -> Result<PathBuf> {
+    let placeholder = Regex::new(r"\{([^{}]*)\}")?;
+
+    let mut expanded = String::with_capacity(template.len());
+    let mut last = 0;
+    for captures in placeholder.captures_iter(template) {
+        let (whole, name) = match (captures.get(0), captures.get(1)) {
+            (Some(whole), Some(name)) => (whole, name.as_str()),
+            _ => continue,
+        };
+
+        let value = match name {
+            "date" => now.format("%Y-%m-%d").to_string(),
+            "year" => now.format("%Y").to_string(),
+            "month" => now.format("%m").to_string(),
+            "day" => now.format("%d").to_string(),
+            "week" => now.format("%V").to_string(),
+            "time" => now.format("%H%M%S").to_string(),
+            other => {
+                return Err(DevRecapError::config(format!(
+                    "Unknown placeholder {{{}}} in output_path (expected date, year, month, day, week or time)",
+                    other
+                )))
+            }
+        };
+
+        expanded.push_str(&template[last..whole.start()]);
+        expanded.push_str(&value);
+        last = whole.end();
+    }
+    expanded.push_str(&template[last..]);
+
+    expand_home(&expanded)
+}
+
+/// Expand a leading `~` to the home directory (`~user` is left alone)
+pub fn expand_home(path: &str) -> Result<PathBuf> {
+    if let Some(rest) = path.strip_prefix('~') {
+        if rest.is_empty() || rest.starts_with('/') || rest.starts_with('\\') {
+            let home = dirs::home_dir()
+                .ok_or_else(|| DevRecapError::config("Could not determine home directory"))?;
+            return Ok(home.join(rest.trim_start_matches(['/', '\\'])));
+        }
+    }
+
+    Ok(PathBuf::from(path))
+}
+
+/// Run a credential helper command through the shell and return its trimmed stdout
+///
+/// `key` names the setting the command came from, for error messages.
+fn run_credential_command(key: &str, command: &str) -> Result<String> {
+    use std::process::Command;
+
+    let output = if cfg!(target_os = "windows") {
+        Command::new("cmd").args(["/C", command]).output()
+    } else {
+        Command::new("sh").args(["-c", command]).output()
+    }
+    .map_err(|e| {
+        DevRecapError::config(format!("Failed to run {} `{}`: {}", key, command, e))
+    })?;
+
+    if !output.status.success() {
+        return Err(DevRecapError::config(format!(
+            "{} `{}` failed ({}): {}",
+            key,
+            command,
+            output.status,
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+
+    let api_key = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    if api_key.is_empty() {
+        return Err(DevRecapError::config(format!("{} `{}` printed nothing", key, command)));
+    }
+
+    Ok(api_key)
+}
+
+/// A problem found by `Config::validate_file`
+#[derive(Debug, Clone)]
+pub struct ConfigIssue {
+    /// 1-based line in the config file, if known
+    pub line: Option<usize>,
+    /// Whether this issue makes the config unusable
+    pub is_error: bool,
+    /// Human-readable description
+    pub message: String,
+}
+
+impl ConfigIssue {
+    fn error(line: Option<usize>, message: String) -> Self {
+        Self { line, is_error: true, message }
+    }
+
+    fn warning(line: Option<usize>, message: String) -> Self {
+        Self { line, is_error: false, message }
+    }
+}
+
+impl std::fmt::Display for ConfigIssue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let level = if self.is_error { "error" } else { "warning" };
+        match self.line {
+            Some(line) => write!(f, "{} (line {}): {}", level, line, self.message),
+            None => write!(f, "{}: {}", level, self.message),
+        }
+    }
+}
+
+/// Find the 1-based line on which a top-level key or table is defined
+fn find_key_line(contents: &str, key: &str) -> Option<usize> {
+    contents
+        .lines()
+        .position(|line| {
+            let line = line.trim_start();
+            let table = line
+                .strip_prefix("[[")
+                .or_else(|| line.strip_prefix('['))
+                .map(|rest| rest.trim_start().starts_with(key));
+            let assignment = line
+                .strip_prefix(key)
+                .map(|rest| rest.trim_start().starts_with('='));
+            table.or(assignment).unwrap_or(false)
+        })
+        .map(|index| index + 1)
+}
+
+/// Line of the `key = ...` assignment first found after the header of `table`
+fn find_key_line_in(contents: &str, table: &str, key: &str) -> Option<usize> {
+    let header = find_key_line(contents, table)?;
+    contents
+        .lines()
+        .enumerate()
+        .skip(header)
+        .find(|(_, line)| {
+            line.trim_start()
+                .strip_prefix(key)
+                .is_some_and(|rest| rest.trim_start().starts_with('='))
+        })
+        .map(|(index, _)| index + 1)
+}
+
+/// Dotted paths of the keys of `table` that are missing from `known`,
+/// descending into tables and arrays of tables present in both
+fn unknown_keys(table: &toml::Table, known: &toml::Table, prefix: &str, unknown: &mut Vec<String>) {
+    for (key, value) in table {
+        let path = if prefix.is_empty() {
+            key.clone()
+        } else {
+            format!("{}.{}", prefix, key)
+        };
+        match (value, known.get(key)) {
+            (_, None) => unknown.push(path),
+            (toml::Value::Table(table), Some(toml::Value::Table(known))) => {
+                unknown_keys(table, known, &path, unknown)
+            }
+            (toml::Value::Array(values), Some(toml::Value::Array(known))) => {
+                for (value, known) in values.iter().zip(known) {
+                    if let (toml::Value::Table(table), toml::Value::Table(known)) = (value, known) {
+                        unknown_keys(table, known, &path, unknown);
+                    }
+                }
+            }
+            _ => {}
         }
     }
 }
 
+/// Move `legacy` to `target` if `legacy` exists and `target` does not
+///
+/// Returns whether anything was moved. Falls back to copy + delete when a
+/// rename isn't possible (e.g. across filesystems).
+fn migrate_dir(legacy: &Path, target: &Path) -> Result<bool> {
+    if legacy == target || !legacy.is_dir() || target.exists() {
+        return Ok(false);
+    }
+
+    if let Some(parent) = target.parent() {
+        fs::create_dir_all(parent)?;
+    }
+
+    if fs::rename(legacy, target).is_err() {
+        copy_dir_recursive(legacy, target)?;
+        fs::remove_dir_all(legacy)?;
+    }
+
+    Ok(true)
+}
+
+/// Recursively copy a directory
+fn copy_dir_recursive(from: &Path, to: &Path) -> Result<()> {
+    fs::create_dir_all(to)?;
+    for entry in fs::read_dir(from)? {
+        let entry = entry?;
+        let dest = to.join(entry.file_name());
+        if entry.file_type()?.is_dir() {
+            copy_dir_recursive(&entry.path(), &dest)?;
+        } else {
+            fs::copy(entry.path(), dest)?;
+        }
+    }
+    Ok(())
+}
+
 // Serde default functions
 fn default_timespan() -> u32 {
     14 // 2 weeks
@@ -220,6 +1217,20 @@ fn default_cache_ttl() -> u32 {
     168 // 7 days in hours
 }
 
+fn default_hotspot_limit() -> usize {
+    DEFAULT_HOTSPOT_LIMIT
+}
+
+/// Default patterns for `test_patterns`, covering the common layouts and
+/// naming schemes of Rust, Go, Python and JavaScript projects
+pub fn default_test_patterns() -> Vec<String> {
+    vec![
+        r"(^|/)(tests?|spec|__tests__)/".to_string(),
+        r"[._-](test|spec)\.[^/]+$".to_string(),
+        r"(^|/)test_[^/]+$".to_string(),
+    ]
+}
+
 fn default_true() -> bool {
     true
 }
@@ -239,16 +1250,20 @@ mod tests {
 
     #[test]
     fn test_config_validation_empty_api_key() {
-        let mut config = Config::default();
-        config.claude_api_key = Some(String::new());
+        let config = Config {
+            claude_api_key: Some(String::new()),
+            ..Default::default()
+        };
         assert!(config.validate().is_err());
     }
 
     #[test]
     fn test_config_validation_any_key_format() {
         // Any non-empty key format is valid (for custom base URLs)
-        let mut config = Config::default();
-        config.claude_api_key = Some(String::from("custom-auth-token-123"));
+        let mut config = Config {
+            claude_api_key: Some(String::from("custom-auth-token-123")),
+            ..Default::default()
+        };
         assert!(config.validate().is_ok());
 
         config.claude_api_key = Some(String::from("sk-ant-valid-key-123"));
@@ -260,20 +1275,99 @@ mod tests {
 
     #[test]
     fn test_config_validation_no_api_key() {
-        let mut config = Config::default();
-        config.claude_api_key = None;
+        let config = Config {
+            claude_api_key: None,
+            ..Default::default()
+        };
         // Should be valid - API key is optional in config (can come from env)
         assert!(config.validate().is_ok());
     }
 
     #[test]
-    fn test_get_api_key_from_config() {
+    fn test_config_validation_base_url() {
         let mut config = Config::default();
-        config.claude_api_key = Some("sk-ant-test-key".to_string());
+        config.providers.get_mut(Provider::OpenAI).base_url = Some("api.example.com/v1".to_string());
+        let message = config.validate().unwrap_err().to_string();
+        assert!(message.contains("OPENAI_BASE_URL"), "{}", message);
+
+        config.providers.get_mut(Provider::OpenAI).base_url = Some("https://api.example.com/v1".to_string());
+        assert!(config.validate().is_ok());
+    }
+
+    #[test]
+    fn test_get_api_key_from_config() {
+        let config = Config {
+            claude_api_key: Some("sk-ant-test-key".to_string()),
+            ..Default::default()
+        };
         assert!(config.get_api_key().is_ok());
         assert_eq!(config.get_api_key().unwrap(), "sk-ant-test-key");
     }
 
+    #[test]
+    fn test_get_api_key_from_command() {
+        let config = Config {
+            claude_api_key_cmd: Some("echo sk-ant-from-cmd".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(config.get_api_key().unwrap(), "sk-ant-from-cmd");
+
+        // A key set directly takes precedence over the command
+        let config = Config {
+            claude_api_key: Some("sk-ant-direct".to_string()),
+            claude_api_key_cmd: Some("echo sk-ant-from-cmd".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(config.get_api_key().unwrap(), "sk-ant-direct");
+    }
+
+    #[test]
+    fn test_provider_config() {
+        let mut config: Config = toml::from_str(
+            "provider = \"openai\"\nclaude_api_key = \"sk-ant-legacy\"\nclaude_model = \"claude-opus-4-1\"\n\n\
+             [providers.anthropic]\nmodel = \"claude-sonnet-4-5\"\n\n\
+             [providers.openai]\napi_key_cmd = \"echo sk-openai\"\nmodel = \"gpt-4o-mini\"\n",
+        )
+        .unwrap();
+        assert_eq!(config.provider, Provider::OpenAI);
+        assert_eq!(config.get_api_key().unwrap(), "sk-openai");
+        assert_eq!(config.get_model().as_deref(), Some("gpt-4o-mini"));
+        assert_eq!(config.get_base_url(), None);
+
+        // The provider block wins over the older claude_* keys, which fill in the rest
+        config.provider = Provider::Anthropic;
+        assert_eq!(config.get_api_key().unwrap(), "sk-ant-legacy");
+        assert_eq!(config.get_model().as_deref(), Some("claude-sonnet-4-5"));
+
+        config.set_api_key("sk-ant-resolved".to_string());
+        assert_eq!(config.get_api_key().unwrap(), "sk-ant-resolved");
+
+        // Ollama runs without a key; OpenAI doesn't
+        config.provider = Provider::Ollama;
+        assert_eq!(config.get_api_key().unwrap(), "");
+        config.provider = Provider::OpenAI;
+        config.providers.openai.api_key_cmd = None;
+        assert!(config.get_api_key().unwrap_err().to_string().contains("OPENAI_API_KEY"));
+        assert!("gemini".parse::<Provider>().is_err());
+    }
+
+    #[test]
+    fn test_get_api_key_command_failure() {
+        let config = Config {
+            claude_api_key_cmd: Some("exit 3".to_string()),
+            ..Default::default()
+        };
+        assert!(config.get_api_key().unwrap_err().to_string().contains("claude_api_key_cmd"));
+
+        let mut config = Config {
+            provider: Provider::OpenAI,
+            ..Default::default()
+        };
+        config.providers.openai.api_key_cmd = Some("true".to_string());
+        let message = config.get_api_key().unwrap_err().to_string();
+        assert!(message.contains("providers.openai.api_key_cmd `true` printed nothing"), "{}", message);
+    }
+
     #[test]
     fn test_get_api_key_missing() {
         let config = Config::default();
@@ -282,8 +1376,10 @@ mod tests {
 
     #[test]
     fn test_config_serialization() {
-        let mut config = Config::default();
-        config.claude_api_key = Some("sk-ant-test".to_string());
+        let config = Config {
+            claude_api_key: Some("sk-ant-test".to_string()),
+            ..Default::default()
+        };
         let toml_str = toml::to_string(&config).unwrap();
         assert!(toml_str.contains("claude_api_key"));
         assert!(toml_str.contains("default_timespan_days"));
@@ -297,6 +1393,255 @@ mod tests {
         assert!(toml_str.contains("default_timespan_days"));
     }
 
+    #[test]
+    fn test_validate_file_reports_all_issues() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let path = temp_dir.path().join("config.toml");
+        fs::write(
+            &path,
+            "default_timespan_days = 0\ncache_ttl = 24\ncache_ttl_hours = 0\nclaude_api_base_url = \"localhost:4000\"\n",
+        )
+        .unwrap();
+
+        let issues = Config::validate_file(&path).unwrap();
+        let messages: Vec<String> = issues.iter().map(|i| i.to_string()).collect();
+
+        assert_eq!(issues.len(), 4, "{:?}", messages);
+        assert!(messages.contains(&"error (line 2): unknown key `cache_ttl`".to_string()));
+        assert!(messages.contains(&"error (line 1): default_timespan_days must be > 0".to_string()));
+        assert!(messages.contains(&"error (line 3): cache_ttl_hours must be > 0".to_string()));
+        assert!(messages.iter().any(|m| m.starts_with("error (line 4): claude_api_base_url")));
+    }
+
+    #[test]
+    fn test_validate_file_nested_unknown_keys() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let path = temp_dir.path().join("config.toml");
+        fs::write(
+            &path,
+            "[providers.openai]\nmodel = \"gpt-4o\"\napi_kye = \"sk-test\"\n\n\
+             [teams.platform]\nauthors = [\"alice@example.com\"]\n\n\
+             [[enrichers]]\nname = \"Jira\"\ncommand = \"jira-context\"\ntimeout = 5\n",
+        )
+        .unwrap();
+
+        let issues = Config::validate_file(&path).unwrap();
+        let messages: Vec<String> = issues.iter().map(|i| i.to_string()).collect();
+
+        assert_eq!(issues.len(), 2, "{:?}", messages);
+        assert!(messages.contains(&"error (line 3): unknown key `providers.openai.api_kye`".to_string()));
+        assert!(messages.contains(&"error (line 11): unknown key `enrichers.timeout`".to_string()));
+    }
+
+    #[test]
+    fn test_examples() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let input = temp_dir.path().join("input.md");
+        let output = temp_dir.path().join("output.md");
+        fs::write(&input, "- Add retry queue\n").unwrap();
+        fs::write(&output, "Webhooks are delivered reliably now.\n").unwrap();
+
+        let example = ExampleConfig {
+            input: input.to_string_lossy().into_owned(),
+            output: output.to_string_lossy().into_owned(),
+        };
+        let loaded = example.load().unwrap();
+        assert_eq!(loaded.input, "- Add retry queue\n");
+        assert_eq!(loaded.output, "Webhooks are delivered reliably now.\n");
+
+        let missing = ExampleConfig {
+            output: temp_dir.path().join("missing.md").to_string_lossy().into_owned(),
+            ..example.clone()
+        };
+        assert!(missing.load().is_err());
+
+        let path = temp_dir.path().join("config.toml");
+        fs::write(
+            &path,
+            format!(
+                "cache_enabled = true\n\n[[examples]]\ninput = {:?}\noutput = {:?}\n",
+                missing.input, missing.output
+            ),
+        )
+        .unwrap();
+        let issues = Config::validate_file(&path).unwrap();
+        assert_eq!(issues.len(), 1);
+        assert!(issues[0].message.contains("missing.md does not exist"));
+        assert_eq!(issues[0].line, Some(3));
+    }
+
+    #[test]
+    fn test_validate_file_summary_sections() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let path = temp_dir.path().join("config.toml");
+        fs::write(
+            &path,
+            "[[summary_sections]]\ntitle = \"Key Achievements\"\n\n\
+             [[summary_sections]]\ntitle = \"Next Steps\"\ninstruction = \"What's still open\"\nnumbered = true\n\n\
+             [[summary_sections]]\ntitle = \"Metrics\"\n\n\
+             [[summary_sections]]\ntitle = \"summary\"\ninstruction = \"x\"\n",
+        )
+        .unwrap();
+
+        let issues = Config::validate_file(&path).unwrap();
+        let messages: Vec<&str> = issues.iter().map(|i| i.message.as_str()).collect();
+        assert_eq!(
+            messages,
+            vec![
+                "summary section `Metrics` needs an instruction",
+                "summary section title `summary` is empty or reserved",
+            ]
+        );
+
+        let config = Config::load_from(&path).unwrap();
+        assert_eq!(config.summary_sections.len(), 4);
+        assert!(config.summary_sections[1].numbered);
+    }
+
+    #[test]
+    fn test_validate_file_syntax_error() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let path = temp_dir.path().join("config.toml");
+        fs::write(&path, "cache_enabled = \"yes\"\n").unwrap();
+
+        let issues = Config::validate_file(&path).unwrap();
+        assert_eq!(issues.len(), 1);
+        assert!(issues[0].is_error);
+        assert!(issues[0].message.contains("line 1"));
+    }
+
+    #[test]
+    fn test_validate_file_invalid_test_pattern() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let path = temp_dir.path().join("config.toml");
+        fs::write(&path, "test_patterns = [\"tests/\", \"(unclosed\"]\n").unwrap();
+
+        let issues = Config::validate_file(&path).unwrap();
+        assert_eq!(issues.len(), 1);
+        assert!(issues[0].is_error);
+        assert!(issues[0].message.contains("invalid test pattern `(unclosed`"));
+    }
+
+    #[test]
+    fn test_validate_file_enrichers() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let path = temp_dir.path().join("config.toml");
+        fs::write(
+            &path,
+            "cache_enabled = true\n\n[[enrichers]]\nname = \"tickets\"\ncommand = \"jira-context\"\n\n[[enrichers]]\nname = \"deploys\"\ncommand = \" \"\n",
+        )
+        .unwrap();
+
+        let issues = Config::validate_file(&path).unwrap();
+        let messages: Vec<String> = issues.iter().map(|i| i.to_string()).collect();
+        assert_eq!(
+            messages,
+            vec!["error (line 3): enricher `deploys` has an empty command".to_string()]
+        );
+    }
+
+    #[test]
+    fn test_validate_file_commit_links() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let path = temp_dir.path().join("config.toml");
+        fs::write(
+            &path,
+            "cache_enabled = true\n\n[[commit_links]]\nhost = \"git.corp\"\ncommit_url = \"https://git.corp/{repo}/-/commit/\"\n",
+        )
+        .unwrap();
+
+        let issues = Config::validate_file(&path).unwrap();
+        let messages: Vec<String> = issues.iter().map(|i| i.to_string()).collect();
+        assert_eq!(
+            messages,
+            vec!["error (line 3): commit_url for `git.corp` has no {hash} placeholder".to_string()]
+        );
+    }
+
+    #[test]
+    fn test_teams() {
+        let config: Config = toml::from_str(
+            "[teams.platform]\nauthors = [\"alice@example.com\", \"bob@example.com\"]\n",
+        )
+        .unwrap();
+
+        assert_eq!(
+            config.team_authors("platform").unwrap(),
+            vec!["alice@example.com".to_string(), "bob@example.com".to_string()]
+        );
+        let err = config.team_authors("web").unwrap_err().to_string();
+        assert!(err.contains("configured teams: platform"), "{}", err);
+
+        // Teams survive a save/load round trip
+        let reloaded: Config = toml::from_str(&toml::to_string_pretty(&config).unwrap()).unwrap();
+        assert_eq!(reloaded.teams["platform"].authors.len(), 2);
+    }
+
+    #[test]
+    fn test_validate_file_valid() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let path = temp_dir.path().join("config.toml");
+        fs::write(&path, toml::to_string(&Config::default()).unwrap()).unwrap();
+
+        assert!(Config::validate_file(&path).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_expand_output_path() {
+        use chrono::TimeZone;
+
+        let now = Local.with_ymd_and_hms(2025, 1, 15, 14, 30, 5).unwrap();
+
+        let path = expand_output_path("recaps/{year}/{date}-recap.md", now).unwrap();
+        assert_eq!(path, PathBuf::from("recaps/2025/2025-01-15-recap.md"));
+
+        let path = expand_output_path("w{week}-{month}{day}-{time}.md", now).unwrap();
+        assert_eq!(path, PathBuf::from("w03-0115-143005.md"));
+
+        let path = expand_output_path("~/recaps/{date}.md", now).unwrap();
+        assert_eq!(path, dirs::home_dir().unwrap().join("recaps/2025-01-15.md"));
+
+        assert!(expand_output_path("{author}.md", now).is_err());
+    }
+
+    #[test]
+    fn test_expand_home() {
+        let home = dirs::home_dir().unwrap();
+        assert_eq!(expand_home("~").unwrap(), home);
+        assert_eq!(expand_home("~/code").unwrap(), home.join("code"));
+        assert_eq!(expand_home("~alice/code").unwrap(), PathBuf::from("~alice/code"));
+        assert_eq!(expand_home("/srv/code").unwrap(), PathBuf::from("/srv/code"));
+    }
+
+    #[test]
+    fn test_migrate_dir() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let legacy = temp_dir.path().join("legacy").join("dev-recap");
+        let target = temp_dir.path().join("new").join("dev-recap");
+        fs::create_dir_all(legacy.join("summaries.sled")).unwrap();
+        fs::write(legacy.join("config.toml"), "cache_enabled = true").unwrap();
+
+        assert!(migrate_dir(&legacy, &target).unwrap());
+        assert!(!legacy.exists());
+        assert!(target.join("config.toml").exists());
+        assert!(target.join("summaries.sled").is_dir());
+
+        // Nothing left to migrate
+        assert!(!migrate_dir(&legacy, &target).unwrap());
+    }
+
+    #[test]
+    fn test_migrate_dir_keeps_existing_target() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let legacy = temp_dir.path().join("legacy");
+        let target = temp_dir.path().join("new");
+        fs::create_dir_all(&legacy).unwrap();
+        fs::create_dir_all(&target).unwrap();
+
+        assert!(!migrate_dir(&legacy, &target).unwrap());
+        assert!(legacy.exists());
+    }
+
     #[test]
     fn test_config_deserialization() {
         let toml_str = r#"
@@ -0,0 +1,114 @@
+//! Report delivery for `dev-recap daemon`
+//!
+//! Besides the output file, a finished report can be posted to a Slack
+//! incoming webhook and emailed through the local `sendmail`.
+
+use crate::chunk;
+use crate::error::{DevRecapError, Result};
+use serde_json::json;
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+/// Slack rejects messages longer than this many characters
+const SLACK_MAX_CHARS: usize = 40_000;
+
+/// Post a report to a Slack incoming webhook, as one message per part of at
+/// most `max_chunk_chars` characters, or as one (truncated) message
+pub async fn post_to_slack(webhook_url: &str, text: &str, max_chunk_chars: Option<usize>) -> Result<()> {
+    let client = reqwest::Client::new();
+    for message in slack_messages(text, max_chunk_chars) {
+        let response = client.post(webhook_url).json(&json!({ "text": message })).send().await?;
+
+        let status = response.status();
+        if !status.is_success() {
+            let body = response.text().await.unwrap_or_default();
+            return Err(DevRecapError::delivery(format!(
+                "Slack webhook returned {}: {}",
+                status,
+                body.trim()
+            )));
+        }
+    }
+    Ok(())
+}
+
+/// The messages a report is posted to Slack as
+pub fn slack_messages(text: &str, max_chunk_chars: Option<usize>) -> Vec<String> {
+    match max_chunk_chars {
+        Some(max) => chunk::split(text, max.min(SLACK_MAX_CHARS)),
+        None => vec![truncate(text, SLACK_MAX_CHARS)],
+    }
+}
+
+/// Email a plain-text report via `sendmail -t`
+pub fn send_email(recipients: &[String], subject: &str, body: &str) -> Result<()> {
+    let mut child = Command::new("sendmail")
+        .arg("-t")
+        .stdin(Stdio::piped())
+        .spawn()
+        .map_err(|e| DevRecapError::delivery(format!("Failed to run sendmail: {}", e)))?;
+
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin.write_all(email_message(recipients, subject, body).as_bytes())?;
+    }
+
+    let status = child.wait()?;
+    if !status.success() {
+        return Err(DevRecapError::delivery(format!("sendmail failed ({})", status)));
+    }
+    Ok(())
+}
+
+/// RFC 5322 message with the headers `sendmail -t` reads recipients from
+fn email_message(recipients: &[String], subject: &str, body: &str) -> String {
+    format!(
+        "To: {}\r\nSubject: {}\r\nMIME-Version: 1.0\r\nContent-Type: text/plain; charset=utf-8\r\n\r\n{}",
+        recipients.join(", "),
+        subject.replace(['\r', '\n'], " "),
+        body
+    )
+}
+
+/// Cut `text` to at most `max` characters, marking the cut
+fn truncate(text: &str, max: usize) -> String {
+    if text.chars().count() <= max {
+        return text.to_string();
+    }
+    let marker = "\n… (truncated)";
+    let kept: String = text.chars().take(max - marker.chars().count()).collect();
+    kept + marker
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_email_message() {
+        let message = email_message(
+            &["a@example.com".to_string(), "b@example.com".to_string()],
+            "Dev Recap\nBcc: evil@example.com",
+            "# Dev Recap\n",
+        );
+        assert!(message.starts_with("To: a@example.com, b@example.com\r\n"));
+        assert!(message.contains("Subject: Dev Recap Bcc: evil@example.com\r\n"));
+        assert!(message.ends_with("\r\n\r\n# Dev Recap\n"));
+    }
+
+    #[test]
+    fn test_slack_messages() {
+        let report = format!("# Dev Recap\n\n{}", "## api\n\nWork.\n\n".repeat(100));
+        assert_eq!(slack_messages(&report, None).len(), 1);
+        let messages = slack_messages(&report, Some(500));
+        assert!(messages.len() > 1);
+        assert!(messages.iter().all(|message| message.chars().count() <= 500));
+    }
+
+    #[test]
+    fn test_truncate() {
+        assert_eq!(truncate("short", 10), "short");
+        let cut = truncate(&"x".repeat(100), 50);
+        assert_eq!(cut.chars().count(), 50);
+        assert!(cut.ends_with("(truncated)"));
+    }
+}
@@ -0,0 +1,288 @@
+//! Recap comparison (`dev-recap diff`)
+//!
+//! Compares two saved recaps, e.g. the last demo's and this one's: which
+//! repositories were added or dropped, how their stats moved, and which
+//! achievements (items of any summary section but the tips) are new.
+
+use crate::history::{RepoRecap, StoredRecap};
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+/// Differences between an older and a newer recap
+#[derive(Debug, Clone)]
+pub struct RecapDiff {
+    /// Repositories only in the newer recap
+    pub added: Vec<RepoRecap>,
+    /// Repositories only in the older recap
+    pub removed: Vec<RepoRecap>,
+    /// Repositories in both, old and new side by side
+    pub common: Vec<RepoDiff>,
+}
+
+/// One repository in both recaps
+#[derive(Debug, Clone)]
+pub struct RepoDiff {
+    pub old: RepoRecap,
+    pub new: RepoRecap,
+}
+
+impl RepoDiff {
+    /// Achievements in the newer summary but not the older one
+    pub fn new_achievements(&self) -> Vec<&String> {
+        let old = achievements(&self.old);
+        achievements(&self.new)
+            .into_iter()
+            .filter(|a| !old.contains(a))
+            .collect()
+    }
+
+    /// Achievements in the older summary that the newer one no longer lists
+    pub fn dropped_achievements(&self) -> Vec<&String> {
+        let new = achievements(&self.new);
+        achievements(&self.old)
+            .into_iter()
+            .filter(|a| !new.contains(a))
+            .collect()
+    }
+
+    /// Whether the work summary text differs
+    pub fn summary_changed(&self) -> bool {
+        let text = |repo: &RepoRecap| repo.summary.as_ref().map(|s| s.work_summary.trim().to_string());
+        text(&self.old) != text(&self.new)
+    }
+}
+
+/// Items of every section of the summary but the presentation tips, so
+/// custom sections are compared too
+fn achievements(repo: &RepoRecap) -> Vec<&String> {
+    repo.summary
+        .as_ref()
+        .map(|s| s.content_sections().flat_map(|section| &section.items).collect())
+        .unwrap_or_default()
+}
+
+impl RecapDiff {
+    /// Compare two recaps, matching repositories by path
+    pub fn new(old: &StoredRecap, new: &StoredRecap) -> Self {
+        let mut old_repos: BTreeMap<&PathBuf, &RepoRecap> =
+            old.repositories.iter().map(|repo| (&repo.path, repo)).collect();
+
+        let mut added = Vec::new();
+        let mut common = Vec::new();
+        for repo in &new.repositories {
+            match old_repos.remove(&repo.path) {
+                Some(old_repo) => common.push(RepoDiff {
+                    old: old_repo.clone(),
+                    new: repo.clone(),
+                }),
+                None => added.push(repo.clone()),
+            }
+        }
+
+        // Keep the older recap's order for dropped repositories
+        let removed = old
+            .repositories
+            .iter()
+            .filter(|repo| old_repos.contains_key(&repo.path))
+            .cloned()
+            .collect();
+
+        Self {
+            added,
+            removed,
+            common,
+        }
+    }
+
+    /// Markdown report of the differences
+    pub fn to_markdown(&self, old: &StoredRecap, new: &StoredRecap) -> String {
+        let mut md = String::from("# Dev Recap Diff\n\n");
+        md.push_str(&format!("**Before:** {}\n", describe(old)));
+        md.push_str(&format!("**After:** {}\n\n", describe(new)));
+
+        let total = |recap: &StoredRecap| {
+            recap.repositories.iter().fold((0, 0, 0), |(c, i, d), repo| {
+                (c + repo.commits, i + repo.insertions, d + repo.deletions)
+            })
+        };
+        let (old_commits, old_ins, old_del) = total(old);
+        let (new_commits, new_ins, new_del) = total(new);
+        md.push_str("| | Before | After | Change |\n|---|---:|---:|---:|\n");
+        md.push_str(&stat_row("Repositories", old.repositories.len() as u32, new.repositories.len() as u32));
+        md.push_str(&stat_row("Commits", old_commits, new_commits));
+        md.push_str(&stat_row("Insertions", old_ins, new_ins));
+        md.push_str(&stat_row("Deletions", old_del, new_del));
+        md.push('\n');
+
+        if !self.added.is_empty() {
+            md.push_str("## New Repositories\n\n");
+            for repo in &self.added {
+                md.push_str(&format!(
+                    "- **{}**: {} commits, +{} / -{}\n",
+                    repo.name, repo.commits, repo.insertions, repo.deletions
+                ));
+            }
+            md.push('\n');
+        }
+
+        if !self.removed.is_empty() {
+            md.push_str("## No Longer Active\n\n");
+            for repo in &self.removed {
+                md.push_str(&format!("- **{}**\n", repo.name));
+            }
+            md.push('\n');
+        }
+
+        for diff in &self.common {
+            md.push_str(&format!("## Repository: {}\n\n", diff.new.name));
+            md.push_str(&format!(
+                "- Commits: {} → {} ({})\n",
+                diff.old.commits,
+                diff.new.commits,
+                signed(diff.new.commits, diff.old.commits)
+            ));
+            md.push_str(&format!(
+                "- Lines: +{} / -{} → +{} / -{}\n\n",
+                diff.old.insertions, diff.old.deletions, diff.new.insertions, diff.new.deletions
+            ));
+
+            let new_achievements = diff.new_achievements();
+            if !new_achievements.is_empty() {
+                md.push_str("**New achievements:**\n");
+                for achievement in new_achievements {
+                    md.push_str(&format!("- {}\n", achievement));
+                }
+                md.push('\n');
+            }
+
+            let dropped = diff.dropped_achievements();
+            if !dropped.is_empty() {
+                md.push_str("**Previously:**\n");
+                for achievement in dropped {
+                    md.push_str(&format!("- {}\n", achievement));
+                }
+                md.push('\n');
+            }
+
+            if diff.summary_changed() {
+                if let Some(ref summary) = diff.new.summary {
+                    md.push_str("**Now:**\n\n");
+                    md.push_str(summary.work_summary.trim());
+                    md.push_str("\n\n");
+                }
+            }
+        }
+
+        md
+    }
+}
+
+/// "#3, 2025-01-01 to 2025-01-14 (alice@example.com)"
+fn describe(recap: &StoredRecap) -> String {
+    let id = if recap.id > 0 {
+        format!("#{}, ", recap.id)
+    } else {
+        String::new()
+    };
+    format!(
+        "{}{} to {} ({})",
+        id,
+        recap.timespan_start.format("%Y-%m-%d"),
+        recap.timespan_end.format("%Y-%m-%d"),
+        recap.authors.join(", ")
+    )
+}
+
+fn stat_row(label: &str, old: u32, new: u32) -> String {
+    format!("| {} | {} | {} | {} |\n", label, old, new, signed(new, old))
+}
+
+/// `new - old` with an explicit sign
+fn signed(new: u32, old: u32) -> String {
+    let change = new as i64 - old as i64;
+    if change > 0 {
+        format!("+{}", change)
+    } else {
+        change.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ai::{Section, Summary};
+    use chrono::Utc;
+
+    fn repo(name: &str, commits: u32, achievements: &[&str]) -> RepoRecap {
+        RepoRecap {
+            name: name.to_string(),
+            path: PathBuf::from(format!("/code/{}", name)),
+            commits,
+            insertions: commits * 10,
+            deletions: commits,
+            summary: Some(Summary::new(
+                name.to_string(),
+                format!("{} commits of work", commits),
+                achievements.iter().map(|a| a.to_string()).collect(),
+                vec![],
+            )),
+            error: None,
+            commit_hashes: vec![],
+        }
+    }
+
+    fn recap(repositories: Vec<RepoRecap>) -> StoredRecap {
+        StoredRecap {
+            id: 1,
+            created_at: Utc::now(),
+            scan_path: PathBuf::from("/code"),
+            authors: vec!["alice@example.com".to_string()],
+            timespan_start: Utc::now(),
+            timespan_end: Utc::now(),
+            command: "dev-recap".to_string(),
+            anonymized: false,
+            overall: None,
+            repositories,
+            report: String::new(),
+        }
+    }
+
+    #[test]
+    fn test_diff_repositories() {
+        let old = recap(vec![repo("api", 5, &["Billing"]), repo("legacy", 2, &[])]);
+        let new = recap(vec![repo("web", 3, &[]), repo("api", 8, &["Billing", "Invoices"])]);
+
+        let diff = RecapDiff::new(&old, &new);
+        assert_eq!(diff.added.len(), 1);
+        assert_eq!(diff.added[0].name, "web");
+        assert_eq!(diff.removed.len(), 1);
+        assert_eq!(diff.removed[0].name, "legacy");
+        assert_eq!(diff.common.len(), 1);
+
+        let api = &diff.common[0];
+        assert_eq!(api.new_achievements(), vec!["Invoices"]);
+        assert!(api.dropped_achievements().is_empty());
+        assert!(api.summary_changed());
+
+        // Items of custom sections count too
+        let mut custom = repo("api", 8, &[]);
+        if let Some(ref mut summary) = custom.summary {
+            summary.sections = vec![Section::bullets("Metrics", vec!["p99 down 40%".to_string()])];
+        }
+        let metrics = RecapDiff::new(&old, &recap(vec![custom]));
+        assert_eq!(metrics.common[0].new_achievements(), vec!["p99 down 40%"]);
+        assert_eq!(metrics.common[0].dropped_achievements(), vec!["Billing"]);
+
+        let md = diff.to_markdown(&old, &new);
+        assert!(md.contains("| Commits | 7 | 11 | +4 |"));
+        assert!(md.contains("- Commits: 5 → 8 (+3)"));
+        assert!(md.contains("## No Longer Active\n\n- **legacy**"));
+    }
+
+    #[test]
+    fn test_signed() {
+        assert_eq!(signed(5, 3), "+2");
+        assert_eq!(signed(3, 5), "-2");
+        assert_eq!(signed(3, 3), "0");
+    }
+}
@@ -0,0 +1,204 @@
+//! Enrichment plugins (`[[enrichers]]` in config)
+//!
+//! An enricher is an external command that adds context dev-recap can't
+//! collect itself, such as ticket data or deployment logs. Before a
+//! repository is summarized, each configured command gets the repository's
+//! JSON on stdin; whatever it prints to stdout is added to the prompt.
+//!
+//! A failing, slow or silent enricher is logged and skipped, so a broken
+//! integration never blocks the recap.
+
+use crate::config::EnricherConfig;
+use crate::error::{DevRecapError, Result};
+use crate::export::RepoExport;
+use crate::git::Repository;
+use serde_json::{json, Value};
+use std::process::Stdio;
+use std::time::Duration;
+use tokio::io::AsyncWriteExt;
+use tokio::process::Command;
+
+/// Time an enricher gets unless `timeout_secs` is set
+pub const DEFAULT_TIMEOUT_SECS: u64 = 30;
+
+/// Longest context kept from one enricher, in characters
+const MAX_CONTEXT_CHARS: usize = 8_000;
+
+/// Context returned by one enricher
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Enrichment {
+    /// Enricher name from config
+    pub name: String,
+    /// Its trimmed stdout
+    pub context: String,
+}
+
+/// JSON an enricher receives: the repository's statistics and its commits
+pub fn repo_input(repo: &Repository) -> Value {
+    let commits: Vec<Value> = repo
+        .commits
+        .iter()
+        .map(|commit| {
+            json!({
+                "hash": commit.hash,
+                "short_hash": commit.short_hash,
+                "author": { "name": commit.author.name, "email": commit.author.email },
+                "timestamp": commit.timestamp,
+                "summary": commit.summary,
+                "message": commit.message,
+                "pr_numbers": commit.pr_numbers,
+                "files_changed": commit.files_changed,
+                "insertions": commit.insertions,
+                "deletions": commit.deletions,
+            })
+        })
+        .collect();
+
+    json!({
+        "repository": RepoExport::new(repo, None),
+        "commits": commits,
+    })
+}
+
+/// Run every enricher for a repository, in config order
+pub async fn enrich(enrichers: &[EnricherConfig], repo: &Repository) -> Vec<Enrichment> {
+    if enrichers.is_empty() {
+        return Vec::new();
+    }
+
+    let input = repo_input(repo).to_string();
+    let mut enrichments = Vec::new();
+    for enricher in enrichers {
+        match run_enricher(enricher, &input).await {
+            Ok(context) if context.is_empty() => {
+                tracing::debug!(enricher = %enricher.name, repo = %repo.name, "enricher printed nothing");
+            }
+            Ok(context) => enrichments.push(Enrichment {
+                name: enricher.name.clone(),
+                context,
+            }),
+            Err(e) => {
+                tracing::warn!(enricher = %enricher.name, repo = %repo.name, error = %e, "enricher skipped");
+            }
+        }
+    }
+    enrichments
+}
+
+/// Run one enricher through the shell, feeding `input` on stdin
+async fn run_enricher(enricher: &EnricherConfig, input: &str) -> Result<String> {
+    let mut command = if cfg!(target_os = "windows") {
+        let mut command = Command::new("cmd");
+        command.args(["/C", &enricher.command]);
+        command
+    } else {
+        let mut command = Command::new("sh");
+        command.args(["-c", &enricher.command]);
+        command
+    };
+    let mut child = command
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true)
+        .spawn()
+        .map_err(|e| DevRecapError::other(format!("failed to run `{}`: {}", enricher.command, e)))?;
+
+    let timeout = Duration::from_secs(enricher.timeout_secs.unwrap_or(DEFAULT_TIMEOUT_SECS));
+    let run = async {
+        if let Some(mut stdin) = child.stdin.take() {
+            // Commands that don't read their input close the pipe early
+            if let Err(e) = stdin.write_all(input.as_bytes()).await {
+                if e.kind() != std::io::ErrorKind::BrokenPipe {
+                    return Err(e);
+                }
+            }
+        }
+        child.wait_with_output().await
+    };
+    let output = tokio::time::timeout(timeout, run)
+        .await
+        .map_err(|_| DevRecapError::other(format!("timed out after {}s", timeout.as_secs())))??;
+
+    if !output.status.success() {
+        return Err(DevRecapError::other(format!(
+            "exited with {}: {}",
+            output.status,
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+
+    let context = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    if context.chars().count() > MAX_CONTEXT_CHARS {
+        let kept: String = context.chars().take(MAX_CONTEXT_CHARS).collect();
+        return Ok(format!("{}\n(truncated)", kept));
+    }
+    Ok(context)
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use crate::git::Commit;
+
+    fn enricher(name: &str, command: &str) -> EnricherConfig {
+        EnricherConfig {
+            name: name.to_string(),
+            command: command.to_string(),
+            timeout_secs: Some(5),
+        }
+    }
+
+    fn test_repo() -> Repository {
+        let commit = Commit {
+            insertions: 10,
+            deletions: 2,
+            ..Commit::test("abc1234def", "Fix login (PROJ-42)").with_files(&["src/login.rs"], 12)
+        };
+        Repository::test("app", vec![commit])
+    }
+
+    #[tokio::test]
+    async fn test_enrich() {
+        let enrichers = vec![
+            // Reads the repository JSON from stdin
+            enricher("tickets", "grep -o 'PROJ-[0-9]*' | head -1"),
+            enricher("silent", "true"),
+            enricher("broken", "echo oops >&2; exit 3"),
+            enricher("deploys", "echo 'Deployed to production on Friday'"),
+        ];
+
+        let enrichments = enrich(&enrichers, &test_repo()).await;
+        assert_eq!(
+            enrichments,
+            vec![
+                Enrichment {
+                    name: "tickets".to_string(),
+                    context: "PROJ-42".to_string(),
+                },
+                Enrichment {
+                    name: "deploys".to_string(),
+                    context: "Deployed to production on Friday".to_string(),
+                },
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_enricher_timeout() {
+        let slow = EnricherConfig {
+            timeout_secs: Some(1),
+            ..enricher("slow", "sleep 10")
+        };
+        let err = run_enricher(&slow, "{}").await.unwrap_err();
+        assert!(err.to_string().contains("timed out"));
+    }
+
+    #[test]
+    fn test_repo_input() {
+        let input = repo_input(&test_repo());
+        assert_eq!(input["repository"]["name"], "app");
+        assert_eq!(input["commits"][0]["short_hash"], "abc1234");
+        assert_eq!(input["commits"][0]["author"]["email"], "test@example.com");
+    }
+}
@@ -36,22 +36,69 @@ pub enum DevRecapError {
     #[error("Claude API error: {0}")]
     ClaudeApi(String),
 
+    /// Claude API request answered with an error status
+    #[error("Claude API error: {message}")]
+    ClaudeStatus { status: u16, message: String },
+
+    /// GitHub API errors
+    #[error("GitHub API error: {0}")]
+    GitHubApi(String),
+
+    /// Linear API errors
+    #[error("Linear API error: {0}")]
+    LinearApi(String),
+
+    /// Report delivery errors (Slack, email)
+    #[error("Delivery error: {0}")]
+    Delivery(String),
+
     /// Caching errors
     #[error("Cache error: {0}")]
     Cache(#[from] sled::Error),
 
+    /// Recap history database errors
+    #[error("History error: {0}")]
+    History(String),
+
+    /// OS keyring errors
+    #[error("Keyring error: {0}")]
+    Keyring(#[from] keyring::Error),
+
     /// Repository not found
     #[error("Repository not found at path: {0}")]
-    #[allow(dead_code)]
     RepositoryNotFound(PathBuf),
 
+    /// Scan path that doesn't exist or isn't a directory
+    #[error("Invalid scan path {}: {reason}", path.display())]
+    InvalidScanPath { path: PathBuf, reason: String },
+
     /// No commits found
     #[error("No commits found for author {author} in timespan")]
     NoCommitsFound { author: String },
 
+    /// Repository has no commits at all (HEAD is unborn)
+    #[error("No commits yet")]
+    NoCommitsYet,
+
+    /// Every commit repeats a change already reported for another repository
+    #[error("All commits are cherry-picks of changes already reported in other repositories")]
+    OnlyDuplicateCommits,
+
+    /// Missing or unreadable objects, as reported by the gitoxide backend
+    /// (libgit2 reports them as `Git` errors)
+    #[error("Corrupted repository: {0}")]
+    CorruptedRepository(String),
+
+    /// Repository exceeded its time budget
+    #[error("Timed out after {seconds}s; repository skipped")]
+    RepoTimeout { seconds: u64 },
+
+    /// `git log` text on stdin that couldn't be read
+    #[error("Invalid git log on line {line}: {reason}")]
+    InvalidGitLog { line: usize, reason: String },
+
     /// Invalid timespan
     #[error("Invalid timespan: {0}")]
-    #[allow(dead_code)]
     InvalidTimespan(String),
 
     /// Missing configuration
@@ -64,7 +111,6 @@ pub enum DevRecapError {
 
     /// Generic error
     #[error("{0}")]
-    #[allow(dead_code)]
     Other(String),
 }
 
@@ -82,9 +128,131 @@ impl DevRecapError {
         Self::ClaudeApi(msg.into())
     }
 
+    /// Create a new GitHub API error
+    pub fn github_api<S: Into<String>>(msg: S) -> Self {
+        Self::GitHubApi(msg.into())
+    }
+
+    /// Create a new Linear API error
+    pub fn linear_api<S: Into<String>>(msg: S) -> Self {
+        Self::LinearApi(msg.into())
+    }
+
+    /// Create a new report delivery error
+    pub fn delivery<S: Into<String>>(msg: S) -> Self {
+        Self::Delivery(msg.into())
+    }
+
+    /// Create a new recap history error
+    pub fn history<S: Into<String>>(msg: S) -> Self {
+        Self::History(msg.into())
+    }
+
+    /// Whether the operation may succeed if simply retried (network, API, timeout)
+    ///
+    /// Of the client errors only a request timeout (408) and rate limiting
+    /// (429) are; a bad key or request fails the same way again.
+    pub fn is_transient(&self) -> bool {
+        match self {
+            Self::ClaudeStatus { status, .. } => matches!(status, 408 | 429) || *status >= 500,
+            _ => matches!(self, Self::Http(_) | Self::ClaudeApi(_) | Self::RepoTimeout { .. }),
+        }
+    }
+
+    /// Whether a repository simply had nothing to recap, rather than failing
+    pub fn is_no_commits(&self) -> bool {
+        matches!(
+            self,
+            Self::NoCommitsFound { .. } | Self::NoCommitsYet | Self::OnlyDuplicateCommits
+        )
+    }
+
+    /// Short machine-readable name of the kind of error, e.g. `claude_api`
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Self::Git(_) if self.is_corruption() => "corrupted_repository",
+            Self::CorruptedRepository(_) => "corrupted_repository",
+            Self::Git(_) => "git",
+            Self::Io(_) => "io",
+            Self::Config(_) | Self::TomlParse(_) | Self::TomlSerialize(_) | Self::MissingConfig(_) => "config",
+            Self::Http(_) => "network",
+            Self::Json(_) => "json",
+            Self::ClaudeApi(_) | Self::ClaudeStatus { .. } => "claude_api",
+            Self::GitHubApi(_) => "github_api",
+            Self::LinearApi(_) => "linear_api",
+            Self::Delivery(_) => "delivery",
+            Self::Cache(_) => "cache",
+            Self::History(_) => "history",
+            Self::Keyring(_) => "keyring",
+            Self::RepositoryNotFound(_) | Self::InvalidScanPath { .. } => "not_found",
+            Self::NoCommitsFound { .. } | Self::NoCommitsYet | Self::OnlyDuplicateCommits => "no_commits",
+            Self::RepoTimeout { .. } => "timeout",
+            Self::InvalidGitLog { .. } => "invalid_git_log",
+            Self::InvalidTimespan(_) => "invalid_timespan",
+            Self::Regex(_) => "regex",
+            Self::Other(_) => "other",
+        }
+    }
+
+    /// What the user can do about the error, when there's a likely fix
+    pub fn suggested_fix(&self) -> Option<&'static str> {
+        let fix = match self {
+            Self::Git(_) | Self::CorruptedRepository(_) if self.is_corruption() => {
+                "Run `git fsck` in the repository; re-clone it if objects are missing"
+            }
+            Self::Git(_) => "Check that `git log` works in the repository",
+            Self::Io(_) => "Check that the repository still exists and is readable",
+            Self::Config(_) | Self::TomlParse(_) | Self::MissingConfig(_) => "Run `dev-recap config validate`",
+            Self::Http(_) => "Check the network connection and proxy, then run again (finished summaries are cached)",
+            Self::ClaudeApi(_) | Self::ClaudeStatus { .. } => {
+                "Check the API key, model and base URL with `dev-recap models`; rate limits clear on a later run"
+            }
+            Self::GitHubApi(_) => "Check that github_token can read the repository",
+            Self::Cache(_) => "Run `dev-recap clear-cache`",
+            Self::RepoTimeout { .. } => "Raise `repo_timeout_secs` (or `--repo-timeout`), or shorten the timespan",
+            Self::Regex(_) => "Fix the pattern in the config (`dev-recap config validate`)",
+            _ => return None,
+        };
+        Some(fix)
+    }
+
+    /// Whether the error comes from a damaged object database (missing or
+    /// unreadable objects), which no retry or other setting will fix
+    pub fn is_corruption(&self) -> bool {
+        match self {
+            Self::Git(e) => {
+                matches!(
+                    e.class(),
+                    git2::ErrorClass::Odb | git2::ErrorClass::Object | git2::ErrorClass::Zlib
+                ) || e.code() == git2::ErrorCode::HashsumMismatch
+            }
+            Self::CorruptedRepository(_) => true,
+            _ => false,
+        }
+    }
+
     /// Create a new generic error
-    #[allow(dead_code)]
     pub fn other<S: Into<String>>(msg: S) -> Self {
         Self::Other(msg.into())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_transient() {
+        let status = |status| DevRecapError::ClaudeStatus {
+            status,
+            message: format!("API request failed with status {}", status),
+        };
+        assert!(status(429).is_transient());
+        assert!(status(408).is_transient());
+        assert!(status(529).is_transient());
+        assert!(!status(400).is_transient());
+        assert!(!status(401).is_transient());
+        assert_eq!(status(401).kind(), "claude_api");
+        assert!(DevRecapError::claude_api("No content in Claude response").is_transient());
+    }
+}
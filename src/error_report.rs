@@ -0,0 +1,137 @@
+//! Report of the repositories a recap failed on (`<output>-errors.json`,
+//! e.g. `recap-errors.json` for `recap.md`)
+//!
+//! Written next to the output file instead of leaving error messages in the
+//! report, where they'd end up pasted into slides. The report only mentions
+//! that a repository wasn't summarized and points to this file.
+
+use crate::ai::Summary;
+use crate::error::{DevRecapError, Result};
+use crate::git::Repository;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+
+/// Appended to the output file's stem to name its error report
+pub const FILE_SUFFIX: &str = "-errors.json";
+
+/// Every repository that couldn't be recapped in a run
+#[derive(Debug, Serialize)]
+pub struct ErrorReport {
+    pub generated_at: DateTime<Utc>,
+    pub failures: Vec<RepoFailure>,
+}
+
+/// One repository that couldn't be recapped
+#[derive(Debug, PartialEq, Eq, Serialize)]
+pub struct RepoFailure {
+    pub name: String,
+    pub path: PathBuf,
+    /// Kind of error, e.g. `claude_api` or `timeout` (see [`DevRecapError::kind`])
+    pub kind: &'static str,
+    /// The error message
+    pub error: String,
+    /// What to do about it, when there's a likely fix
+    pub suggested_fix: Option<&'static str>,
+    /// Whether the report shows the repository's last cached summary instead
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub cached_summary_shown: bool,
+}
+
+impl ErrorReport {
+    /// The failed repositories among `results`, including those shown with
+    /// an older cached summary; repositories without commits in the timespan
+    /// didn't fail
+    pub fn new<'a>(results: impl IntoIterator<Item = (&'a Repository, &'a Result<Summary>)>) -> Self {
+        let failures = results
+            .into_iter()
+            .filter_map(|(repo, result)| {
+                let (e, cached_summary_shown) = match result {
+                    Err(e) if is_failure(e) => (e, false),
+                    Ok(summary) => (summary.fallback_error.as_deref()?, true),
+                    Err(_) => return None,
+                };
+                Some(RepoFailure {
+                    name: repo.name.clone(),
+                    path: repo.path.clone(),
+                    kind: e.kind(),
+                    error: e.to_string(),
+                    suggested_fix: e.suggested_fix(),
+                    cached_summary_shown,
+                })
+            })
+            .collect();
+        Self {
+            generated_at: Utc::now(),
+            failures,
+        }
+    }
+
+    /// Whether every repository was recapped
+    pub fn is_empty(&self) -> bool {
+        self.failures.is_empty()
+    }
+
+    /// Write the report as pretty-printed JSON
+    pub fn write(&self, path: &Path) -> Result<()> {
+        if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(path, serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+}
+
+/// Whether a repository's error is a failure to report, rather than it
+/// having nothing to recap
+pub fn is_failure(error: &DevRecapError) -> bool {
+    !error.is_no_commits()
+}
+
+/// Where the error report of an output file goes: beside it, named after
+/// it, so reports written to the same directory keep their own
+pub fn path_beside(output: &Path) -> PathBuf {
+    let stem = output.file_stem().map_or("recap".into(), |stem| stem.to_string_lossy());
+    output.with_file_name(format!("{}{}", stem, FILE_SUFFIX))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_error_report() {
+        let (api, docs, web) = (
+            Repository::test("api", vec![]),
+            Repository::test("docs", vec![]),
+            Repository::test("web", vec![]),
+        );
+        let results = [
+            (&api, Err(DevRecapError::RepoTimeout { seconds: 120 })),
+            (&docs, Err(DevRecapError::NoCommitsYet)),
+            (&web, Ok(Summary::new("web".to_string(), "Done".to_string(), vec![], vec![]))),
+        ];
+        let report = ErrorReport::new(results.iter().map(|(repo, result)| (*repo, result)));
+
+        assert_eq!(
+            report.failures,
+            vec![RepoFailure {
+                name: "api".to_string(),
+                path: PathBuf::from("/code/api"),
+                kind: "timeout",
+                error: "Timed out after 120s; repository skipped".to_string(),
+                suggested_fix: Some("Raise `repo_timeout_secs` (or `--repo-timeout`), or shorten the timespan"),
+                cached_summary_shown: false,
+            }]
+        );
+
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let path = path_beside(&temp_dir.path().join("recaps/2025-01-17.md"));
+        assert_eq!(path, temp_dir.path().join("recaps").join("2025-01-17-errors.json"));
+        report.write(&path).unwrap();
+        let json: serde_json::Value = serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+        assert_eq!(json["failures"][0]["kind"], "timeout");
+
+        assert_eq!(path_beside(Path::new("recap.md")), PathBuf::from("recap-errors.json"));
+    }
+}
@@ -0,0 +1,56 @@
+use crate::ai::Summary;
+use crate::error::DevRecapError;
+use crate::git::Repository;
+use std::path::{Path, PathBuf};
+
+/// A batch stage of a run, reported to an [`EventSink`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Stage {
+    /// Parsing git history (no API calls)
+    Analyzing,
+    /// Generating summaries (parsing may still overlap in pipeline mode)
+    Summarizing,
+    /// Retrying summaries that failed with a transient error
+    Retrying,
+}
+
+/// Receiver for progress events emitted by the `Orchestrator`
+///
+/// Every method has a no-op default, so frontends (progress bars, TUI, JSONL,
+/// GUI) only implement the events they care about. Events may be delivered
+/// from worker tasks, hence the `Send + Sync` bound.
+#[allow(unused_variables)]
+pub trait EventSink: Send + Sync {
+    /// Repository discovery started under `root`
+    fn on_scan_started(&self, root: &Path) {}
+
+    /// Repository discovery finished
+    fn on_scan_finished(&self, repos: &[PathBuf]) {}
+
+    /// A stage started; `total` repositories will complete it
+    fn on_stage_started(&self, stage: Stage, total: usize) {}
+
+    /// A stage finished
+    fn on_stage_finished(&self, stage: Stage) {}
+
+    /// Analysis of a repository started
+    fn on_repo_started(&self, repo_path: &Path) {}
+
+    /// A repository's git history was parsed
+    fn on_repo_analyzed(&self, repo: &Repository) {}
+
+    /// A repository's summary is available
+    fn on_summary_ready(&self, repo: &Repository, summary: &Summary) {}
+
+    /// Analyzing or summarizing a repository failed
+    fn on_error(&self, repo_path: &Path, error: &DevRecapError) {}
+
+    /// A repository was left out of the results, e.g. because it's corrupted
+    fn on_repo_skipped(&self, repo_path: &Path, reason: &DevRecapError) {}
+}
+
+/// Event sink that ignores every event
+#[derive(Debug, Default, Clone, Copy)]
+pub struct NoopEventSink;
+
+impl EventSink for NoopEventSink {}
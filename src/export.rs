@@ -0,0 +1,233 @@
+//! Machine-readable statistics export (`--stats-json`)
+//!
+//! The schema is versioned by `schema_version`; fields are only ever added
+//! within a version, so dashboards can rely on existing ones.
+
+use crate::error::Result;
+use crate::git::github::DeliveryMetrics;
+use crate::git::stats::directory_breakdown;
+use crate::git::{Repository, Timespan};
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+
+/// Current version of the export schema
+pub const SCHEMA_VERSION: u32 = 1;
+
+/// All statistics of one run
+#[derive(Debug, Serialize)]
+pub struct StatsExport {
+    pub schema_version: u32,
+    pub generated_at: DateTime<Utc>,
+    pub timespan_start: DateTime<Utc>,
+    pub timespan_end: DateTime<Utc>,
+    pub repositories: Vec<RepoExport>,
+}
+
+/// Statistics of one repository
+#[derive(Debug, Serialize)]
+pub struct RepoExport {
+    pub name: String,
+    pub path: PathBuf,
+    pub remote_url: Option<String>,
+    /// The repository a fork was made from (its `upstream` remote)
+    pub upstream_url: Option<String>,
+    pub commits: u32,
+    pub files_changed: u32,
+    pub insertions: u32,
+    pub deletions: u32,
+    pub net_lines: i64,
+    pub pull_requests: u32,
+    pub test_percent: f64,
+    /// Set when older commits were dropped to stay under this cap
+    pub commit_cap: Option<usize>,
+    /// Commits per day, keyed by YYYY-MM-DD
+    pub per_day: BTreeMap<String, u32>,
+    /// Percentage of file changes per top-level directory
+    pub per_directory: BTreeMap<String, f64>,
+    pub per_author: Vec<AuthorExport>,
+    pub per_file: Vec<FileExport>,
+    pub weeks: Vec<WeekExport>,
+    pub keywords: BTreeMap<String, u32>,
+    pub delivery: Option<DeliveryExport>,
+}
+
+/// Activity of one commit author
+#[derive(Debug, Serialize)]
+pub struct AuthorExport {
+    pub name: String,
+    pub email: String,
+    pub commits: u32,
+    pub insertions: u32,
+    pub deletions: u32,
+}
+
+/// Activity on one file
+#[derive(Debug, Serialize)]
+pub struct FileExport {
+    pub path: String,
+    /// Commits touching the file
+    pub changes: u32,
+    /// Lines inserted plus deleted
+    pub churn: u32,
+}
+
+/// Activity during one week of the timespan
+#[derive(Debug, Serialize)]
+pub struct WeekExport {
+    pub start: String,
+    pub commits: u32,
+    pub lines: u32,
+}
+
+/// Pull request delivery metrics
+#[derive(Debug, Serialize)]
+pub struct DeliveryExport {
+    pub merged_prs: u32,
+    pub avg_time_to_merge_secs: Option<i64>,
+    pub avg_review_turnaround_secs: Option<i64>,
+    /// Reviews submitted on the merged PRs, per reviewer login
+    pub reviews_by: BTreeMap<String, u32>,
+}
+
+impl StatsExport {
+    /// Collect the statistics of every analyzed repository
+    pub fn new<'a>(
+        repos: impl IntoIterator<Item = &'a Repository>,
+        timespan: &Timespan,
+        delivery: &HashMap<PathBuf, DeliveryMetrics>,
+    ) -> Self {
+        Self {
+            schema_version: SCHEMA_VERSION,
+            generated_at: Utc::now(),
+            timespan_start: timespan.start,
+            timespan_end: timespan.end,
+            repositories: repos
+                .into_iter()
+                .filter(|repo| !repo.commits.is_empty())
+                .map(|repo| RepoExport::new(repo, delivery.get(&repo.path)))
+                .collect(),
+        }
+    }
+
+    /// Write the export as pretty-printed JSON
+    pub fn write(&self, path: &Path) -> Result<()> {
+        if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(path, serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+}
+
+impl RepoExport {
+    /// Statistics of an analyzed repository
+    pub fn new(repo: &Repository, delivery: Option<&DeliveryMetrics>) -> Self {
+        let mut authors: BTreeMap<&str, AuthorExport> = BTreeMap::new();
+        let mut files: BTreeMap<&str, FileExport> = BTreeMap::new();
+
+        for commit in &repo.commits {
+            let author = authors
+                .entry(commit.author.email.as_str())
+                .or_insert_with(|| AuthorExport {
+                    name: commit.author.name.clone(),
+                    email: commit.author.email.clone(),
+                    commits: 0,
+                    insertions: 0,
+                    deletions: 0,
+                });
+            author.commits += 1;
+            author.insertions += commit.insertions;
+            author.deletions += commit.deletions;
+
+            for (i, path) in commit.files_changed.iter().enumerate() {
+                let file = files.entry(path.as_str()).or_insert_with(|| FileExport {
+                    path: path.clone(),
+                    changes: 0,
+                    churn: 0,
+                });
+                file.changes += 1;
+                file.churn += commit.file_churn.get(i).copied().unwrap_or(0);
+            }
+        }
+
+        Self {
+            name: repo.name.clone(),
+            path: repo.path.clone(),
+            remote_url: repo.remote_url.clone(),
+            upstream_url: repo.upstream_url.clone(),
+            commits: repo.stats.total_commits,
+            files_changed: repo.stats.total_files_changed,
+            insertions: repo.stats.total_insertions,
+            deletions: repo.stats.total_deletions,
+            net_lines: repo.stats.net_lines_changed(),
+            pull_requests: repo.stats.pr_count,
+            test_percent: repo.test_ratio.test_percent(),
+            commit_cap: repo.commit_cap,
+            per_day: repo.stats.commit_frequency.clone().into_iter().collect(),
+            per_directory: directory_breakdown(&repo.stats, usize::MAX)
+                .into_iter()
+                .collect(),
+            per_author: authors.into_values().collect(),
+            per_file: files.into_values().collect(),
+            weeks: repo
+                .velocity
+                .iter()
+                .map(|week| WeekExport {
+                    start: week.start.to_string(),
+                    commits: week.commits,
+                    lines: week.lines,
+                })
+                .collect(),
+            keywords: repo.keywords.iter().cloned().collect(),
+            delivery: delivery.map(|metrics| DeliveryExport {
+                merged_prs: metrics.merged_prs,
+                avg_time_to_merge_secs: metrics.avg_time_to_merge.map(|d| d.num_seconds()),
+                avg_review_turnaround_secs: metrics.avg_review_turnaround.map(|d| d.num_seconds()),
+                reviews_by: metrics.reviews_by.clone(),
+            }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::git::{Author, Commit};
+
+    fn commit(email: &str, files: &[&str], churn: u32) -> Commit {
+        Commit {
+            author: Author {
+                name: "Test".to_string(),
+                email: email.to_string(),
+            },
+            insertions: churn * files.len() as u32,
+            ..Commit::test("abc123", "Test").with_files(files, churn)
+        }
+    }
+
+    #[test]
+    fn test_stats_export() {
+        let commits = vec![
+            commit("a@example.com", &["src/lib.rs", "README.md"], 5),
+            commit("b@example.com", &["src/lib.rs"], 3),
+        ];
+        let repo = Repository::test("repo", commits);
+
+        let export = StatsExport::new([&repo], &Timespan::days_back(7), &HashMap::new());
+        assert_eq!(export.schema_version, SCHEMA_VERSION);
+        assert_eq!(export.repositories.len(), 1);
+
+        let repo_export = &export.repositories[0];
+        assert_eq!(repo_export.commits, 2);
+        assert_eq!(repo_export.per_author.len(), 2);
+        assert_eq!(repo_export.per_file[1].path, "src/lib.rs");
+        assert_eq!(repo_export.per_file[1].changes, 2);
+        assert_eq!(repo_export.per_file[1].churn, 8);
+        assert_eq!(repo_export.per_directory.get("src/"), Some(&(200.0 / 3.0)));
+
+        let json = serde_json::to_value(&export).unwrap();
+        assert_eq!(json["repositories"][0]["per_author"][0]["email"], "a@example.com");
+    }
+}
@@ -0,0 +1,446 @@
+// Project context for prompts
+// What a repository is, read from its README and package manifests
+
+use super::Commit;
+use serde::Deserialize;
+use std::collections::HashSet;
+use std::path::Path;
+
+/// Longest description or README paragraph kept, in characters
+const MAX_TEXT_CHARS: usize = 400;
+
+/// Topics kept per project
+const MAX_TOPICS: usize = 10;
+
+/// Languages and frameworks kept per project
+const MAX_STACK: usize = 12;
+
+/// Changed manifests read per project
+const MAX_MANIFESTS: usize = 20;
+
+/// Dependencies worth naming, per ecosystem: (package, display name).
+/// Go modules match without their `/vN` suffix.
+const CARGO_FRAMEWORKS: &[(&str, &str)] = &[
+    ("actix-web", "Actix Web"),
+    ("axum", "Axum"),
+    ("bevy", "Bevy"),
+    ("clap", "clap"),
+    ("diesel", "Diesel"),
+    ("leptos", "Leptos"),
+    ("rocket", "Rocket"),
+    ("sea-orm", "SeaORM"),
+    ("sqlx", "SQLx"),
+    ("tauri", "Tauri"),
+    ("tokio", "Tokio"),
+    ("tonic", "tonic"),
+    ("warp", "warp"),
+    ("yew", "Yew"),
+];
+const NPM_FRAMEWORKS: &[(&str, &str)] = &[
+    ("@angular/core", "Angular"),
+    ("@nestjs/core", "NestJS"),
+    ("@prisma/client", "Prisma"),
+    ("@sveltejs/kit", "SvelteKit"),
+    ("electron", "Electron"),
+    ("express", "Express"),
+    ("fastify", "Fastify"),
+    ("jest", "Jest"),
+    ("next", "Next.js"),
+    ("nuxt", "Nuxt"),
+    ("react", "React"),
+    ("react-native", "React Native"),
+    ("svelte", "Svelte"),
+    ("tailwindcss", "Tailwind CSS"),
+    ("vite", "Vite"),
+    ("vitest", "Vitest"),
+    ("vue", "Vue"),
+];
+const GO_FRAMEWORKS: &[(&str, &str)] = &[
+    ("github.com/gin-gonic/gin", "Gin"),
+    ("github.com/go-chi/chi", "chi"),
+    ("github.com/gofiber/fiber", "Fiber"),
+    ("github.com/gorilla/mux", "Gorilla mux"),
+    ("github.com/labstack/echo", "Echo"),
+    ("github.com/spf13/cobra", "Cobra"),
+    ("google.golang.org/grpc", "gRPC"),
+    ("gorm.io/gorm", "GORM"),
+    ("k8s.io/client-go", "Kubernetes client-go"),
+];
+
+/// README file names, in order of preference
+const README_NAMES: &[&str] = &["README.md", "README.markdown", "README", "README.txt", "readme.md"];
+
+/// What a project is, as described by its own files
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ProjectInfo {
+    /// First heading of the README
+    pub title: Option<String>,
+    /// Description from Cargo.toml or package.json
+    pub description: Option<String>,
+    /// First paragraph of the README
+    pub readme_intro: Option<String>,
+    /// Keywords from Cargo.toml or package.json
+    pub topics: Vec<String>,
+    /// Languages and frameworks from the manifests changed in the timespan
+    pub stack: Vec<String>,
+}
+
+impl ProjectInfo {
+    /// Read the README and manifests in `dir`; missing or malformed files are skipped
+    pub fn read(dir: &Path) -> Self {
+        let mut info = Self::default();
+
+        if let Some(readme) = README_NAMES
+            .iter()
+            .find_map(|name| std::fs::read_to_string(dir.join(name)).ok())
+        {
+            let (title, intro) = parse_readme(&readme);
+            info.title = title;
+            info.readme_intro = intro;
+        }
+
+        for manifest in [cargo_manifest(dir), package_json(dir)].into_iter().flatten() {
+            if info.description.is_none() {
+                info.description = manifest
+                    .description
+                    .filter(|description| !description.trim().is_empty())
+                    .map(|description| truncate(description.trim()));
+            }
+            for topic in manifest.keywords {
+                if !info.topics.contains(&topic) && info.topics.len() < MAX_TOPICS {
+                    info.topics.push(topic);
+                }
+            }
+        }
+
+        info
+    }
+
+    /// Detect the tech stack from the Cargo.toml, package.json and go.mod
+    /// files that `commits` changed, read as they are now under `git_root`
+    ///
+    /// Only manifests touched in the timespan count, so a monorepo's stack is
+    /// the part that was worked on. Deleted or malformed files are skipped.
+    pub fn with_stack(mut self, git_root: &Path, commits: &[Commit]) -> Self {
+        let mut seen = HashSet::new();
+        let manifests = commits
+            .iter()
+            .flat_map(|commit| &commit.files_changed)
+            .filter(|file| {
+                let name = file.rsplit('/').next().unwrap_or(file);
+                matches!(name, "Cargo.toml" | "package.json" | "go.mod")
+            })
+            .filter(|file| seen.insert(file.as_str()))
+            .take(MAX_MANIFESTS);
+
+        for file in manifests {
+            let Ok(text) = std::fs::read_to_string(git_root.join(file)) else {
+                continue;
+            };
+            let detected = if file.ends_with("Cargo.toml") {
+                cargo_stack(&text)
+            } else if file.ends_with("package.json") {
+                npm_stack(&text)
+            } else {
+                go_stack(&text)
+            };
+            for name in detected.into_iter().flatten() {
+                if !self.stack.contains(&name) && self.stack.len() < MAX_STACK {
+                    self.stack.push(name);
+                }
+            }
+        }
+
+        self
+    }
+
+    /// Whether nothing was found
+    pub fn is_empty(&self) -> bool {
+        self.title.is_none()
+            && self.description.is_none()
+            && self.readme_intro.is_none()
+            && self.topics.is_empty()
+            && self.stack.is_empty()
+    }
+}
+
+/// Description and keywords shared by Cargo.toml and package.json
+#[derive(Debug, Default, Deserialize)]
+struct Manifest {
+    // Workspace-inherited values (`description.workspace = true`) aren't strings
+    #[serde(default, deserialize_with = "string_or_none")]
+    description: Option<String>,
+    #[serde(default, deserialize_with = "strings_or_empty")]
+    keywords: Vec<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct CargoToml {
+    package: Option<Manifest>,
+}
+
+fn cargo_manifest(dir: &Path) -> Option<Manifest> {
+    let text = std::fs::read_to_string(dir.join("Cargo.toml")).ok()?;
+    toml::from_str::<CargoToml>(&text).ok()?.package
+}
+
+fn package_json(dir: &Path) -> Option<Manifest> {
+    let text = std::fs::read_to_string(dir.join("package.json")).ok()?;
+    serde_json::from_str(&text).ok()
+}
+
+/// "Rust" and the known frameworks among a Cargo.toml's dependencies
+fn cargo_stack(text: &str) -> Option<Vec<String>> {
+    let manifest: toml::Value = toml::from_str(text).ok()?;
+    let tables = [
+        manifest.get("dependencies"),
+        manifest.get("dev-dependencies"),
+        manifest.get("build-dependencies"),
+        manifest.get("workspace").and_then(|workspace| workspace.get("dependencies")),
+    ];
+    let dependencies: Vec<&str> = tables
+        .into_iter()
+        .flatten()
+        .filter_map(toml::Value::as_table)
+        .flat_map(|table| table.keys().map(String::as_str))
+        .collect();
+    Some(stack("Rust", &dependencies, CARGO_FRAMEWORKS))
+}
+
+/// JavaScript or TypeScript and the known frameworks among a package.json's dependencies
+fn npm_stack(text: &str) -> Option<Vec<String>> {
+    let manifest: serde_json::Value = serde_json::from_str(text).ok()?;
+    let dependencies: Vec<&str> = ["dependencies", "devDependencies", "peerDependencies"]
+        .iter()
+        .filter_map(|key| manifest.get(key)?.as_object())
+        .flat_map(|object| object.keys().map(String::as_str))
+        .collect();
+    let language = if dependencies.contains(&"typescript") {
+        "TypeScript"
+    } else {
+        "JavaScript"
+    };
+    Some(stack(language, &dependencies, NPM_FRAMEWORKS))
+}
+
+/// "Go" and the known frameworks among a go.mod's requirements
+fn go_stack(text: &str) -> Option<Vec<String>> {
+    let mut modules = Vec::new();
+    let mut in_block = false;
+    for line in text.lines() {
+        let line = line.split("//").next().unwrap_or_default().trim();
+        let requirement = if in_block {
+            if line == ")" {
+                in_block = false;
+                continue;
+            }
+            line
+        } else if line == "require (" {
+            in_block = true;
+            continue;
+        } else if let Some(requirement) = line.strip_prefix("require ") {
+            requirement
+        } else {
+            continue;
+        };
+        if let Some(module) = requirement.split_whitespace().next() {
+            modules.push(strip_major_version(module));
+        }
+    }
+    Some(stack("Go", &modules, GO_FRAMEWORKS))
+}
+
+/// A Go module path without its `/vN` major version suffix
+fn strip_major_version(module: &str) -> &str {
+    match module.rsplit_once("/v") {
+        Some((path, version)) if version.parse::<u32>().is_ok() => path,
+        _ => module,
+    }
+}
+
+/// `language` followed by the display names of the known `dependencies`, in table order
+fn stack(language: &str, dependencies: &[&str], known: &[(&str, &str)]) -> Vec<String> {
+    std::iter::once(language.to_string())
+        .chain(
+            known
+                .iter()
+                .filter(|(package, _)| dependencies.contains(package))
+                .map(|(_, name)| name.to_string()),
+        )
+        .collect()
+}
+
+fn string_or_none<'de, D: serde::Deserializer<'de>>(deserializer: D) -> Result<Option<String>, D::Error> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Value {
+        String(String),
+        Other(serde::de::IgnoredAny),
+    }
+    Ok(match Value::deserialize(deserializer)? {
+        Value::String(s) => Some(s),
+        Value::Other(_) => None,
+    })
+}
+
+fn strings_or_empty<'de, D: serde::Deserializer<'de>>(deserializer: D) -> Result<Vec<String>, D::Error> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Value {
+        Strings(Vec<String>),
+        Other(serde::de::IgnoredAny),
+    }
+    Ok(match Value::deserialize(deserializer)? {
+        Value::Strings(strings) => strings,
+        Value::Other(_) => Vec::new(),
+    })
+}
+
+/// The README's first heading and first paragraph of prose
+///
+/// Badges, HTML, code blocks and further headings are skipped.
+fn parse_readme(readme: &str) -> (Option<String>, Option<String>) {
+    let mut title = None;
+    let mut paragraph: Vec<&str> = Vec::new();
+    let mut in_code = false;
+
+    for line in readme.lines() {
+        let line = line.trim();
+        if line.starts_with("```") || line.starts_with("~~~") {
+            in_code = !in_code;
+            continue;
+        }
+        if in_code {
+            continue;
+        }
+
+        let is_prose = !(line.is_empty()
+            || line.starts_with('#')
+            || line.starts_with('<')
+            || line.starts_with("![")
+            || line.starts_with("[![")
+            || line.starts_with("---")
+            || line.starts_with("==="));
+        if is_prose {
+            paragraph.push(line);
+            continue;
+        }
+        if !paragraph.is_empty() {
+            break;
+        }
+        if title.is_none() {
+            if let Some(heading) = line.strip_prefix("# ") {
+                title = Some(heading.trim().to_string());
+            }
+        }
+    }
+
+    let intro = (!paragraph.is_empty()).then(|| truncate(&paragraph.join(" ")));
+    (title, intro)
+}
+
+/// `text` cut to `MAX_TEXT_CHARS`
+fn truncate(text: &str) -> String {
+    if text.chars().count() <= MAX_TEXT_CHARS {
+        return text.to_string();
+    }
+    let kept: String = text.chars().take(MAX_TEXT_CHARS).collect();
+    format!("{}...", kept.trim_end())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_read_project_info() {
+        let dir = TempDir::new().unwrap();
+        std::fs::write(
+            dir.path().join("README.md"),
+            "[![CI](https://ci.example.com/badge.svg)](https://ci.example.com)\n\n\
+             # Ledger\n\n\
+             <p align=\"center\"><img src=\"logo.png\"></p>\n\n\
+             A double-entry bookkeeping service\nfor small teams.\n\n\
+             ## Install\n\nRun `make`.\n",
+        )
+        .unwrap();
+        std::fs::write(
+            dir.path().join("Cargo.toml"),
+            "[package]\nname = \"ledger\"\ndescription = \"Bookkeeping API\"\nkeywords = [\"accounting\", \"api\"]\n",
+        )
+        .unwrap();
+        std::fs::write(
+            dir.path().join("package.json"),
+            r#"{"name": "ledger-ui", "description": "Web UI", "keywords": ["api", "react"]}"#,
+        )
+        .unwrap();
+
+        let info = ProjectInfo::read(dir.path());
+        assert_eq!(info.title.as_deref(), Some("Ledger"));
+        assert_eq!(
+            info.readme_intro.as_deref(),
+            Some("A double-entry bookkeeping service for small teams.")
+        );
+        // Cargo.toml wins; keywords from both are merged
+        assert_eq!(info.description.as_deref(), Some("Bookkeeping API"));
+        assert_eq!(info.topics, vec!["accounting", "api", "react"]);
+    }
+
+    #[test]
+    fn test_read_project_info_missing_files() {
+        let dir = TempDir::new().unwrap();
+        assert!(ProjectInfo::read(dir.path()).is_empty());
+
+        // Workspace-inherited fields and broken JSON are skipped
+        std::fs::write(
+            dir.path().join("Cargo.toml"),
+            "[package]\nname = \"member\"\ndescription.workspace = true\n",
+        )
+        .unwrap();
+        std::fs::write(dir.path().join("package.json"), "{ not json").unwrap();
+        assert!(ProjectInfo::read(dir.path()).is_empty());
+    }
+
+    fn touching(files: &[&str]) -> Commit {
+        Commit::test("abc1234def", "Bump deps").with_files(files, 1)
+    }
+
+    #[test]
+    fn test_with_stack() {
+        let dir = TempDir::new().unwrap();
+        std::fs::create_dir_all(dir.path().join("web")).unwrap();
+        std::fs::create_dir_all(dir.path().join("gateway")).unwrap();
+        std::fs::write(
+            dir.path().join("Cargo.toml"),
+            "[package]\nname = \"api\"\n\n[dependencies]\ntokio = { version = \"1\" }\naxum = \"0.7\"\nserde = \"1\"\n",
+        )
+        .unwrap();
+        std::fs::write(
+            dir.path().join("web/package.json"),
+            r#"{"dependencies": {"next": "14", "react": "18"}, "devDependencies": {"typescript": "5"}}"#,
+        )
+        .unwrap();
+        std::fs::write(
+            dir.path().join("gateway/go.mod"),
+            "module example.com/gateway\n\ngo 1.22\n\nrequire github.com/spf13/cobra v1.8.0\n\n\
+             require (\n\tgithub.com/labstack/echo/v4 v4.11.4 // indirect\n\tgolang.org/x/net v0.20.0\n)\n",
+        )
+        .unwrap();
+
+        let commits = [
+            touching(&["Cargo.toml", "src/main.rs"]),
+            touching(&["web/package.json", "gateway/go.mod", "Cargo.toml"]),
+        ];
+        let info = ProjectInfo::default().with_stack(dir.path(), &commits);
+        assert_eq!(
+            info.stack,
+            vec!["Rust", "Axum", "Tokio", "TypeScript", "Next.js", "React", "Go", "Echo", "Cobra"]
+        );
+        assert!(!info.is_empty());
+
+        // Manifests outside the timespan's changes, or deleted since, don't count
+        let commits = [touching(&["src/main.rs", "old/package.json"])];
+        assert!(ProjectInfo::default().with_stack(dir.path(), &commits).is_empty());
+    }
+}
@@ -0,0 +1,370 @@
+//! Commit index kept up to date by a post-commit hook (`dev-recap hook install`)
+//!
+//! The hook appends every new commit, with its diff stats, to
+//! `<git dir>/dev-recap/commits.jsonl`. When the first-parent chain from HEAD
+//! back to the start of the timespan is fully indexed, the parser reads the
+//! index instead of walking history. Anything the hook didn't see (a pull,
+//! a rebase, a merge of other work) breaks the chain, and the parser falls
+//! back to a normal walk.
+
+use crate::error::{DevRecapError, Result};
+use crate::git::diff_cache::DiffStats;
+use crate::git::parser::Parser;
+use crate::git::{Author, CommitDate, Timespan};
+use chrono::{DateTime, FixedOffset, TimeZone, Utc};
+use git2::Repository as Git2Repository;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+/// First lines of the hook script, identifying hooks dev-recap may replace
+const HOOK_MARKER: &str = "# Installed by dev-recap (dev-recap hook install)";
+
+/// One indexed commit
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IndexEntry {
+    pub hash: String,
+    pub parents: Vec<String>,
+    pub author: IndexAuthor,
+    /// Committer time (entries recorded by older versions are in UTC)
+    pub timestamp: DateTime<FixedOffset>,
+    /// Author time (missing in entries recorded by older versions)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub author_timestamp: Option<DateTime<FixedOffset>>,
+    pub message: String,
+    pub diff_stats: DiffStats,
+}
+
+/// Author of an indexed commit
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IndexAuthor {
+    pub name: String,
+    pub email: String,
+}
+
+impl IndexEntry {
+    /// Author in the form the parser filters on
+    pub fn author(&self) -> Author {
+        Author {
+            name: self.author.name.clone(),
+            email: self.author.email.clone(),
+        }
+    }
+}
+
+/// Path of the index inside a repository's git directory
+fn index_path(repo: &Git2Repository) -> PathBuf {
+    repo.path().join("dev-recap").join("commits.jsonl")
+}
+
+/// Path of the post-commit hook, honoring `core.hooksPath`
+fn hook_path(repo: &Git2Repository) -> Result<PathBuf> {
+    let hooks_dir = match repo.config()?.get_path("core.hooksPath") {
+        Ok(dir) if dir.is_absolute() => dir,
+        Ok(dir) => repo.workdir().unwrap_or_else(|| repo.path()).join(dir),
+        Err(_) => repo.path().join("hooks"),
+    };
+    Ok(hooks_dir.join("post-commit"))
+}
+
+/// Install the post-commit hook and create an empty index
+///
+/// An existing hook that dev-recap didn't install is only replaced with `force`.
+pub fn install_hook(repo_path: &Path, force: bool) -> Result<PathBuf> {
+    let repo = Git2Repository::open(repo_path)?;
+    let hook = hook_path(&repo)?;
+
+    if let Ok(existing) = fs::read_to_string(&hook) {
+        if !existing.contains(HOOK_MARKER) && !force {
+            return Err(DevRecapError::config(format!(
+                "{} already exists; pass --force to replace it, or add `dev-recap hook record` to it",
+                hook.display()
+            )));
+        }
+    }
+
+    let script = hook_script(&std::env::current_exe()?);
+    if let Some(parent) = hook.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(&hook, script)?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        fs::set_permissions(&hook, fs::Permissions::from_mode(0o755))?;
+    }
+
+    let index = index_path(&repo);
+    if let Some(parent) = index.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    OpenOptions::new().create(true).append(true).open(&index)?;
+
+    Ok(hook)
+}
+
+/// Post-commit hook script running `exe hook record`
+fn hook_script(exe: &Path) -> String {
+    format!(
+        "#!/bin/sh\n{}\n{} hook record >/dev/null 2>&1 || true\n",
+        HOOK_MARKER,
+        shell_quote(&exe.to_string_lossy())
+    )
+}
+
+/// `value` single-quoted for sh, so spaces and metacharacters stay literal
+fn shell_quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', "'\\''"))
+}
+
+/// Remove the hook (if dev-recap installed it) and the index
+///
+/// Returns whether a hook was removed.
+pub fn uninstall_hook(repo_path: &Path) -> Result<bool> {
+    let repo = Git2Repository::open(repo_path)?;
+    let hook = hook_path(&repo)?;
+
+    let removed = match fs::read_to_string(&hook) {
+        Ok(existing) if existing.contains(HOOK_MARKER) => {
+            fs::remove_file(&hook)?;
+            true
+        }
+        _ => false,
+    };
+
+    let index_dir = index_path(&repo).parent().map(Path::to_path_buf);
+    if let Some(dir) = index_dir.filter(|dir| dir.exists()) {
+        fs::remove_dir_all(dir)?;
+    }
+    Ok(removed)
+}
+
+/// Append HEAD to the index (run by the hook after each commit)
+pub fn record_head(repo_path: &Path) -> Result<()> {
+    let repo = Git2Repository::open(repo_path)?;
+    let index = index_path(&repo);
+    if !index.exists() {
+        // The hook is installed but the index was removed: stay out of the way
+        return Ok(());
+    }
+
+    let commit = repo.head()?.peel_to_commit()?;
+    let author = Parser::extract_author(&commit);
+    let entry = IndexEntry {
+        hash: commit.id().to_string(),
+        parents: commit.parent_ids().map(|oid| oid.to_string()).collect(),
+        author: IndexAuthor {
+            name: author.name,
+            email: author.email,
+        },
+        timestamp: Parser::convert_timestamp(&commit, CommitDate::Committer),
+        author_timestamp: Some(Parser::convert_timestamp(&commit, CommitDate::Author)),
+        message: Parser::extract_message(&commit),
+        diff_stats: Parser::get_diff_stats(&repo, &commit, None, true)?,
+    };
+
+    let mut file = OpenOptions::new().append(true).open(index)?;
+    writeln!(file, "{}", serde_json::to_string(&entry)?)?;
+    Ok(())
+}
+
+/// Indexed commits of the timespan, newest first, or None if the repository
+/// isn't hooked or its index doesn't cover the timespan
+pub fn covered_commits(repo_path: &Path, timespan: &Timespan) -> Result<Option<Vec<IndexEntry>>> {
+    let repo = Git2Repository::open(repo_path)?;
+    let contents = match fs::read_to_string(index_path(&repo)) {
+        Ok(contents) => contents,
+        Err(_) => return Ok(None),
+    };
+    let Ok(head) = repo.head().and_then(|head| head.peel_to_commit()) else {
+        return Ok(None);
+    };
+
+    // A line that doesn't parse (e.g. a write cut short) just isn't indexed
+    let entries: HashMap<String, IndexEntry> = contents
+        .lines()
+        .filter_map(|line| serde_json::from_str::<IndexEntry>(line).ok())
+        .map(|entry| (entry.hash.clone(), entry))
+        .collect();
+
+    // Where the index ends, git itself tells whether the timespan did too
+    let commit_time = |hash: &str| {
+        let oid = git2::Oid::from_str(hash).ok()?;
+        let commit = repo.find_commit(oid).ok()?;
+        Utc.timestamp_opt(commit.time().seconds(), 0).single()
+    };
+
+    Ok(first_parent_chain(&entries, &head.id().to_string(), timespan, commit_time))
+}
+
+/// Follow first parents from `head` until the timespan starts
+///
+/// None when the chain reaches a commit inside the timespan that isn't
+/// indexed (`commit_time` looks those up), or a merge, whose other parents
+/// the hook never saw.
+fn first_parent_chain(
+    entries: &HashMap<String, IndexEntry>,
+    head: &str,
+    timespan: &Timespan,
+    commit_time: impl Fn(&str) -> Option<DateTime<Utc>>,
+) -> Option<Vec<IndexEntry>> {
+    let mut chain = Vec::new();
+    let mut next = Some(head.to_string());
+
+    while let Some(hash) = next {
+        let Some(entry) = entries.get(&hash) else {
+            return match commit_time(&hash) {
+                Some(time) if time < timespan.start => Some(chain),
+                _ => None,
+            };
+        };
+        if entry.timestamp < timespan.start {
+            break;
+        }
+        if entry.parents.len() > 1 {
+            return None;
+        }
+        next = entry.parents.first().cloned();
+        chain.push(entry.clone());
+        if chain.len() > entries.len() {
+            // Defensive: a corrupt index with a parent cycle
+            return None;
+        }
+    }
+
+    Some(chain)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn commit(repo: &Git2Repository, file: &str, message: &str, days_ago: i64) {
+        let workdir = repo.workdir().unwrap();
+        fs::write(workdir.join(file), message).unwrap();
+        let mut index = repo.index().unwrap();
+        index.add_path(Path::new(file)).unwrap();
+        index.write().unwrap();
+        let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+        let time = git2::Time::new((Utc::now() - chrono::Duration::days(days_ago)).timestamp(), 0);
+        let signature = git2::Signature::new("Test User", "test@example.com", &time).unwrap();
+        let parent = repo.head().ok().and_then(|head| head.peel_to_commit().ok());
+        let parents: Vec<&git2::Commit> = parent.iter().collect();
+        repo.commit(Some("HEAD"), &signature, &signature, message, &tree, &parents)
+            .unwrap();
+    }
+
+    #[test]
+    fn test_index_covers_hooked_commits() {
+        let temp_dir = TempDir::new().unwrap();
+        let repo = Git2Repository::init(temp_dir.path()).unwrap();
+        let timespan = Timespan::days_back(1);
+
+        // Not hooked
+        commit(&repo, "a.txt", "Before the hook", 2);
+        assert!(covered_commits(temp_dir.path(), &timespan).unwrap().is_none());
+
+        let hook = install_hook(temp_dir.path(), false).unwrap();
+        assert!(fs::read_to_string(&hook).unwrap().contains("hook record"));
+
+        commit(&repo, "b.txt", "After the hook", 0);
+        record_head(temp_dir.path()).unwrap();
+
+        let covered = covered_commits(temp_dir.path(), &timespan).unwrap().unwrap();
+        assert_eq!(covered.len(), 1);
+        assert_eq!(covered[0].message, "After the hook");
+        assert_eq!(covered[0].diff_stats.files_changed, vec!["b.txt".to_string()]);
+
+        // The commit from before the hook isn't indexed
+        assert!(covered_commits(temp_dir.path(), &Timespan::days_back(7)).unwrap().is_none());
+
+        assert!(uninstall_hook(temp_dir.path()).unwrap());
+        assert!(!hook.exists());
+        assert!(covered_commits(temp_dir.path(), &timespan).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_install_keeps_foreign_hook() {
+        let temp_dir = TempDir::new().unwrap();
+        let repo = Git2Repository::init(temp_dir.path()).unwrap();
+        let hook = hook_path(&repo).unwrap();
+        fs::create_dir_all(hook.parent().unwrap()).unwrap();
+        fs::write(&hook, "#!/bin/sh\nmake lint\n").unwrap();
+
+        assert!(install_hook(temp_dir.path(), false).is_err());
+        assert!(!uninstall_hook(temp_dir.path()).unwrap());
+        assert!(hook.exists());
+        install_hook(temp_dir.path(), true).unwrap();
+    }
+
+    #[test]
+    fn test_hook_script_quotes_exe() {
+        let script = hook_script(Path::new("/Users/Jane Doe/it's $(bin)/dev-recap"));
+        assert!(script.contains("\n'/Users/Jane Doe/it'\\''s $(bin)/dev-recap' hook record "));
+
+        // The shell runs the program at exactly that path: `touch hook record`
+        #[cfg(unix)]
+        {
+            let temp_dir = TempDir::new().unwrap();
+            let dir = temp_dir.path().join("Jane Doe's bin");
+            fs::create_dir_all(&dir).unwrap();
+            let touch = ["/usr/bin/touch", "/bin/touch"].into_iter().find(|path| Path::new(path).exists()).unwrap();
+            let exe = dir.join("dev-recap");
+            std::os::unix::fs::symlink(touch, &exe).unwrap();
+
+            let status = std::process::Command::new("sh")
+                .args(["-c", &hook_script(&exe)])
+                .current_dir(temp_dir.path())
+                .status()
+                .unwrap();
+            assert!(status.success());
+            assert!(temp_dir.path().join("record").exists());
+        }
+    }
+
+    #[test]
+    fn test_first_parent_chain() {
+        let entry = |hash: &str, parents: &[&str], hours_ago: i64| IndexEntry {
+            hash: hash.to_string(),
+            parents: parents.iter().map(|p| p.to_string()).collect(),
+            author: IndexAuthor {
+                name: "Test".to_string(),
+                email: "test@example.com".to_string(),
+            },
+            timestamp: (Utc::now() - chrono::Duration::hours(hours_ago)).fixed_offset(),
+            author_timestamp: None,
+            message: hash.to_string(),
+            diff_stats: DiffStats::default(),
+        };
+        let timespan = Timespan::days_back(1);
+
+        let entries: HashMap<String, IndexEntry> = [
+            entry("c", &["b"], 1),
+            entry("b", &["a"], 2),
+            entry("a", &["root"], 48),
+            entry("m", &["c", "x"], 0),
+        ]
+        .into_iter()
+        .map(|e| (e.hash.clone(), e))
+        .collect();
+
+        // "root" isn't indexed and was committed four days ago
+        let commit_time = |hash: &str| (hash == "root").then(|| Utc::now() - chrono::Duration::days(4));
+
+        let chain = first_parent_chain(&entries, "c", &timespan, commit_time).unwrap();
+        let hashes: Vec<&str> = chain.iter().map(|e| e.hash.as_str()).collect();
+        assert_eq!(hashes, vec!["c", "b"]);
+        let chain = first_parent_chain(&entries, "c", &Timespan::days_back(3), commit_time).unwrap();
+        assert_eq!(chain.len(), 3);
+
+        // A merge brings in commits the hook never saw
+        assert!(first_parent_chain(&entries, "m", &timespan, commit_time).is_none());
+        // So does a HEAD that isn't indexed
+        assert!(first_parent_chain(&entries, "z", &timespan, commit_time).is_none());
+        // An older timespan reaches past the index
+        assert!(first_parent_chain(&entries, "c", &Timespan::days_back(7), commit_time).is_none());
+    }
+}
@@ -0,0 +1,100 @@
+use crate::error::Result;
+use git2::Oid;
+use serde::{Deserialize, Serialize};
+
+/// Diff statistics of one commit against its first parent
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DiffStats {
+    /// Paths of changed files
+    pub files_changed: Vec<String>,
+    /// Lines inserted plus deleted, per entry of `files_changed`
+    #[serde(default)]
+    pub file_churn: Vec<u32>,
+    /// Lines inserted
+    pub insertions: u32,
+    /// Lines deleted
+    pub deletions: u32,
+    /// Patch ID of the diff (as `git patch-id --stable`), the same for
+    /// cherry-picks of one change; not computed by the gix backend
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub patch_id: Option<String>,
+}
+
+/// Persistent per-commit diff stats, keyed by commit OID
+///
+/// A commit's diff never changes, so entries don't expire and are shared
+/// across repositories (and clones) containing the same commit.
+pub struct DiffStatCache {
+    tree: sled::Tree,
+}
+
+impl DiffStatCache {
+    /// Wrap a sled tree dedicated to diff stats
+    pub fn new(tree: sled::Tree) -> Self {
+        Self { tree }
+    }
+
+    /// Look up the stats of a commit; an unreadable entry is a miss (and is
+    /// overwritten once the stats are computed again)
+    pub fn get(&self, oid: Oid) -> Result<Option<DiffStats>> {
+        let data = match self.tree.get(oid.as_bytes())? {
+            Some(data) => data,
+            None => return Ok(None),
+        };
+        match serde_json::from_slice(&data) {
+            Ok(stats) => Ok(Some(stats)),
+            Err(e) => {
+                tracing::debug!(commit = %oid, error = %e, "ignoring corrupt diff cache entry");
+                Ok(None)
+            }
+        }
+    }
+
+    /// Store the stats of a commit
+    pub fn set(&self, oid: Oid, stats: &DiffStats) -> Result<()> {
+        self.tree.insert(oid.as_bytes(), serde_json::to_vec(stats)?)?;
+        Ok(())
+    }
+
+    /// Number of cached commits
+    pub fn len(&self) -> usize {
+        self.tree.len()
+    }
+
+    /// Whether no commits are cached
+    pub fn is_empty(&self) -> bool {
+        self.tree.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_diff_stat_cache_roundtrip() {
+        let temp_dir = TempDir::new().unwrap();
+        let db = sled::open(temp_dir.path().join("db")).unwrap();
+        let cache = DiffStatCache::new(db.open_tree("diff_stats").unwrap());
+
+        let oid = Oid::from_str("0123456789abcdef0123456789abcdef01234567").unwrap();
+        assert!(cache.get(oid).unwrap().is_none());
+
+        let stats = DiffStats {
+            files_changed: vec!["src/main.rs".to_string()],
+            file_churn: vec![12],
+            insertions: 10,
+            deletions: 2,
+            patch_id: Some("4d5b6a1c0e2f3a4b5c6d7e8f9a0b1c2d3e4f5a6b".to_string()),
+        };
+        cache.set(oid, &stats).unwrap();
+
+        assert_eq!(cache.get(oid).unwrap(), Some(stats));
+        assert_eq!(cache.len(), 1);
+
+        // A corrupt entry is a miss rather than an error
+        db.open_tree("diff_stats").unwrap().insert(oid.as_bytes(), b"{not json".to_vec()).unwrap();
+        assert!(cache.get(oid).unwrap().is_none());
+    }
+}
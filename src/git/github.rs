@@ -1,5 +1,26 @@
-use crate::git::GitHubRepo;
+use crate::error::{DevRecapError, Result};
+use crate::git::github_cache::{CachedResponse, GitHubResponseCache};
+use crate::git::{GitHubRepo, Timespan};
+use chrono::{DateTime, Duration, TimeZone, Utc};
 use regex::Regex;
+use reqwest::header::{HeaderMap, ETAG, IF_NONE_MATCH, LINK, RETRY_AFTER};
+use reqwest::{Client, StatusCode, Url};
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::sync::Mutex;
+
+const DEFAULT_API_URL: &str = "https://api.github.com";
+const USER_AGENT: &str = concat!("dev-recap/", env!("CARGO_PKG_VERSION"));
+/// Merged PRs whose reviews are fetched (one request each) for review turnaround
+const MAX_REVIEWED_PRS: usize = 30;
+/// Attempts per request when GitHub is rate limiting or failing
+const MAX_ATTEMPTS: u32 = 4;
+/// Wait before the first retry; doubled for each further one
+const BASE_BACKOFF: std::time::Duration = std::time::Duration::from_secs(1);
+/// Longest wait for an exhausted rate limit to reset; past it, cached
+/// responses are used as they are, and requests without one fail. Longer
+/// `Retry-After` delays are cut to it too
+const MAX_RATE_LIMIT_WAIT: std::time::Duration = std::time::Duration::from_secs(60);
 
 /// Extract PR numbers from a commit message
 pub fn extract_pr_numbers(message: &str) -> Vec<u32> {
@@ -81,10 +102,707 @@ pub fn parse_github_url(url: &str) -> Option<GitHubRepo> {
     None
 }
 
+/// Timing of one merged pull request
+#[derive(Debug, Clone)]
+pub struct PullRequestTiming {
+    /// PR number
+    pub number: u32,
+    /// When the PR was opened
+    pub created_at: DateTime<Utc>,
+    /// When the PR was merged
+    pub merged_at: DateTime<Utc>,
+    /// When the first review was submitted, if any
+    pub first_review_at: Option<DateTime<Utc>>,
+    /// GitHub logins of the reviewers, once per submitted review
+    pub reviewers: Vec<String>,
+}
+
+/// Delivery metrics of an author's pull requests in a repository
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct DeliveryMetrics {
+    /// PRs merged during the timespan
+    pub merged_prs: u32,
+    /// Average time from opening to merging a PR
+    pub avg_time_to_merge: Option<Duration>,
+    /// Average time from opening a PR to its first review
+    pub avg_review_turnaround: Option<Duration>,
+    /// Reviews submitted on those PRs, per reviewer login
+    pub reviews_by: BTreeMap<String, u32>,
+}
+
+impl DeliveryMetrics {
+    /// Aggregate metrics over merged pull requests
+    pub fn from_pull_requests(prs: &[PullRequestTiming]) -> Self {
+        let average = |durations: Vec<Duration>| -> Option<Duration> {
+            let count = durations.len() as i32;
+            (count > 0).then(|| durations.into_iter().sum::<Duration>() / count)
+        };
+
+        let mut reviews_by = BTreeMap::new();
+        for reviewer in prs.iter().flat_map(|pr| &pr.reviewers) {
+            *reviews_by.entry(reviewer.clone()).or_insert(0) += 1;
+        }
+
+        Self {
+            merged_prs: prs.len() as u32,
+            avg_time_to_merge: average(prs.iter().map(|pr| pr.merged_at - pr.created_at).collect()),
+            avg_review_turnaround: average(
+                prs.iter()
+                    .filter_map(|pr| pr.first_review_at.map(|at| at - pr.created_at))
+                    .collect(),
+            ),
+            reviews_by,
+        }
+    }
+}
+
+/// Format a duration compactly ("2d 4h", "3h 20m", "45m")
+pub fn format_duration(duration: Duration) -> String {
+    let minutes = duration.num_minutes().max(0);
+    let (days, hours, minutes) = (minutes / 1440, minutes / 60 % 24, minutes % 60);
+
+    if days > 0 {
+        format!("{}d {}h", days, hours)
+    } else if hours > 0 {
+        format!("{}h {}m", hours, minutes)
+    } else {
+        format!("{}m", minutes)
+    }
+}
+
+/// Requests left in the current rate-limit window, as GitHub last reported
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct RateLimit {
+    /// `X-RateLimit-Remaining`
+    remaining: u32,
+    /// `X-RateLimit-Reset`: when the window resets
+    reset: DateTime<Utc>,
+}
+
+impl RateLimit {
+    /// The rate limit reported in a response's headers
+    fn from_headers(headers: &HeaderMap) -> Option<Self> {
+        let header = |name: &str| headers.get(name)?.to_str().ok()?.parse::<i64>().ok();
+        Some(Self {
+            remaining: u32::try_from(header("x-ratelimit-remaining")?).ok()?,
+            reset: Utc.timestamp_opt(header("x-ratelimit-reset")?, 0).single()?,
+        })
+    }
+
+    /// How long until requests may be made again (none while some are left)
+    fn wait(&self, now: DateTime<Utc>) -> Option<std::time::Duration> {
+        if self.remaining > 0 {
+            return None;
+        }
+        (self.reset - now).to_std().ok()
+    }
+}
+
+/// Minimal GitHub REST API client
+///
+/// With a response cache, every GET is revalidated with the ETag of its last
+/// response, which GitHub answers with a `304 Not Modified` that doesn't
+/// count against the rate limit. Once `X-RateLimit-Remaining` reaches zero,
+/// requests wait for the window to reset (up to a minute) or fall back to
+/// the cached response; rate-limited and failed requests are retried with
+/// exponential backoff.
+pub struct GitHubClient {
+    token: String,
+    api_url: String,
+    client: Client,
+    cache: Option<GitHubResponseCache>,
+    rate_limit: Mutex<Option<RateLimit>>,
+}
+
+impl GitHubClient {
+    /// Create a client authenticating with `token`
+    pub fn new(token: String) -> Result<Self> {
+        Self::with_base_url(token, None)
+    }
+
+    /// Create a client against a custom API URL (e.g. GitHub Enterprise's `https://host/api/v3`)
+    pub fn with_base_url(token: String, base_url: Option<String>) -> Result<Self> {
+        let client = Client::builder()
+            .timeout(std::time::Duration::from_secs(30))
+            .user_agent(USER_AGENT)
+            .build()?;
+
+        let api_url = base_url
+            .unwrap_or_else(|| DEFAULT_API_URL.to_string())
+            .trim_end_matches('/')
+            .to_string();
+
+        Ok(Self {
+            token,
+            api_url,
+            client,
+            cache: None,
+            rate_limit: Mutex::new(None),
+        })
+    }
+
+    /// Revalidate responses stored in `cache` instead of fetching them again
+    pub fn with_cache(mut self, cache: GitHubResponseCache) -> Self {
+        self.cache = Some(cache);
+        self
+    }
+
+    /// Compute delivery metrics for PRs merged during the timespan
+    ///
+    /// `author` is a GitHub login (`@me` for the token's owner); `None` counts
+    /// every author's PRs.
+    pub async fn delivery_metrics(
+        &self,
+        repo: &GitHubRepo,
+        author: Option<&str>,
+        timespan: &Timespan,
+    ) -> Result<DeliveryMetrics> {
+        // Search results come a hundred to a page, each linking the next;
+        // the token is only ever sent to the API itself
+        let query = merged_prs_query(repo, author, timespan);
+        let mut items = Vec::new();
+        let mut page = Some(format!("{}/search/issues?q={}&per_page=100", self.api_url, urlencode(&query)));
+        while let Some(url) = page {
+            let (search, next): (SearchResponse, _) = self.get_page(&url).await?;
+            items.extend(search.items);
+            page = next.filter(|next| is_api_url(&self.api_url, next));
+        }
+
+        let mut prs = Vec::new();
+        for item in items {
+            // The search covers whole days; keep the PRs merged in the timespan
+            let merged_at = match item.pull_request.and_then(|pr| pr.merged_at) {
+                Some(merged_at) if timespan.contains(&merged_at) => merged_at,
+                _ => continue,
+            };
+
+            let reviews = if prs.len() < MAX_REVIEWED_PRS {
+                self.reviews(repo, item.number).await?
+            } else {
+                Vec::new()
+            };
+
+            prs.push(PullRequestTiming {
+                number: item.number,
+                created_at: item.created_at,
+                merged_at,
+                first_review_at: reviews.iter().filter_map(|review| review.submitted_at).min(),
+                reviewers: reviews
+                    .into_iter()
+                    .filter(|review| review.submitted_at.is_some())
+                    .filter_map(|review| review.user.map(|user| user.login))
+                    .collect(),
+            });
+        }
+
+        tracing::debug!(
+            repo = %format!("{}/{}", repo.owner, repo.repo),
+            merged = prs.len(),
+            "fetched merged pull requests"
+        );
+
+        Ok(DeliveryMetrics::from_pull_requests(&prs))
+    }
+
+    /// Topics set on the repository
+    pub async fn topics(&self, repo: &GitHubRepo) -> Result<Vec<String>> {
+        let topics: TopicsResponse = self
+            .get(&format!("/repos/{}/{}/topics", repo.owner, repo.repo))
+            .await?;
+        Ok(topics.names)
+    }
+
+    /// Reviews of a PR
+    async fn reviews(&self, repo: &GitHubRepo, number: u32) -> Result<Vec<Review>> {
+        self.get(&format!(
+            "/repos/{}/{}/pulls/{}/reviews?per_page=100",
+            repo.owner, repo.repo, number
+        ))
+        .await
+    }
+
+    /// GET a JSON resource, revalidating a cached response and backing off
+    /// while rate limited
+    async fn get<T: for<'de> Deserialize<'de>>(&self, path: &str) -> Result<T> {
+        let (value, _) = self.get_page(&format!("{}{}", self.api_url, path)).await?;
+        Ok(value)
+    }
+
+    /// GET one page of a JSON resource at `url`, like `get`, with the URL of
+    /// the next page if there is one
+    async fn get_page<T: for<'de> Deserialize<'de>>(&self, url: &str) -> Result<(T, Option<String>)> {
+        let cached = match self.cache {
+            Some(ref cache) => cache.get(url)?,
+            None => None,
+        };
+
+        let mut attempt = 1;
+        loop {
+            // Out of requests: wait for the window to reset if it's soon,
+            // otherwise make do with the cached response
+            let exhausted = *self.rate_limit.lock().unwrap();
+            if let Some((wait, reset)) = exhausted.and_then(|limit| Some((limit.wait(Utc::now())?, limit.reset))) {
+                if wait > MAX_RATE_LIMIT_WAIT {
+                    if let Some(cached) = cached {
+                        tracing::warn!(%url, %reset, "GitHub rate limit exhausted, using the cached response");
+                        return Ok((serde_json::from_str(&cached.body)?, cached.next));
+                    }
+                    return Err(DevRecapError::github_api(format!(
+                        "rate limit exhausted until {}",
+                        reset.with_timezone(&chrono::Local).format("%H:%M")
+                    )));
+                }
+                tracing::info!(seconds = wait.as_secs(), "GitHub rate limit exhausted, waiting for it to reset");
+                tokio::time::sleep(wait).await;
+            }
+
+            tracing::debug!(%url, attempt, "sending GitHub request");
+            let mut request = self
+                .client
+                .get(url)
+                .bearer_auth(&self.token)
+                .header("accept", "application/vnd.github+json");
+            if let Some(ref cached) = cached {
+                request = request.header(IF_NONE_MATCH, &cached.etag);
+            }
+            let response = request.send().await?;
+
+            let rate_limit = RateLimit::from_headers(response.headers());
+            if rate_limit.is_some() {
+                *self.rate_limit.lock().unwrap() = rate_limit;
+            }
+
+            let status = response.status();
+            if status == StatusCode::NOT_MODIFIED {
+                if let Some(cached) = cached {
+                    tracing::debug!(%url, "GitHub response not modified");
+                    return Ok((serde_json::from_str(&cached.body)?, cached.next));
+                }
+            }
+
+            if status.is_success() {
+                let etag = response
+                    .headers()
+                    .get(ETAG)
+                    .and_then(|etag| etag.to_str().ok())
+                    .map(str::to_string);
+                let next = next_link(response.headers());
+                let body = response.text().await?;
+                let value = serde_json::from_str(&body)?;
+                if let (Some(cache), Some(etag)) = (&self.cache, etag) {
+                    cache.set(url, &CachedResponse { etag, body, next: next.clone() })?;
+                }
+                return Ok((value, next));
+            }
+
+            // Rate limited (429, or 403 with a Retry-After or no requests
+            // left) or a server error: back off and try again
+            let retry_after = retry_after(response.headers());
+            let out_of_requests = rate_limit.is_some_and(|limit| limit.remaining == 0);
+            let retryable = status == StatusCode::TOO_MANY_REQUESTS
+                || status.is_server_error()
+                || (status == StatusCode::FORBIDDEN && (retry_after.is_some() || out_of_requests));
+            let error_text = response.text().await.unwrap_or_else(|_| "Unknown error".to_string());
+
+            if retryable && attempt < MAX_ATTEMPTS {
+                // An exhausted rate limit is waited out at the top of the loop
+                let delay = match retry_after {
+                    Some(delay) => delay,
+                    None if out_of_requests => std::time::Duration::ZERO,
+                    None => BASE_BACKOFF * 2u32.pow(attempt - 1),
+                };
+                tracing::warn!(%status, attempt, delay_ms = delay.as_millis() as u64, "GitHub request failed, retrying");
+                tokio::time::sleep(delay).await;
+                attempt += 1;
+                continue;
+            }
+
+            tracing::warn!(%status, body = %error_text, "GitHub request failed");
+            return Err(DevRecapError::github_api(format!(
+                "request failed with status {}: {}",
+                status, error_text
+            )));
+        }
+    }
+}
+
+/// Search query for PRs merged during the days of the timespan
+///
+/// Whole (UTC) days keep the URL, and so its cached response and ETag, the
+/// same across runs on the same day; a relative timespan's exact bounds
+/// change every second.
+fn merged_prs_query(repo: &GitHubRepo, author: Option<&str>, timespan: &Timespan) -> String {
+    let mut query = format!("repo:{}/{} is:pr is:merged", repo.owner, repo.repo);
+    if let Some(author) = author {
+        query.push_str(&format!(" author:{}", author));
+    }
+    query.push_str(&format!(
+        " merged:{}..{}",
+        timespan.start.date_naive(),
+        timespan.end.date_naive()
+    ));
+    query
+}
+
+/// The wait a `Retry-After` header (in seconds) asks for, at most
+/// `MAX_RATE_LIMIT_WAIT`
+fn retry_after(headers: &HeaderMap) -> Option<std::time::Duration> {
+    headers
+        .get(RETRY_AFTER)
+        .and_then(|value| value.to_str().ok()?.parse().ok())
+        .map(|seconds| std::time::Duration::from_secs(seconds).min(MAX_RATE_LIMIT_WAIT))
+}
+
+/// The `rel="next"` URL of a paginated response's `Link` header
+fn next_link(headers: &HeaderMap) -> Option<String> {
+    headers.get(LINK)?.to_str().ok()?.split(',').find_map(|link| {
+        let (url, params) = link.split_once(';')?;
+        params
+            .split(';')
+            .any(|param| param.trim() == "rel=\"next\"")
+            .then(|| url.trim().trim_start_matches('<').trim_end_matches('>').to_string())
+    })
+}
+
+/// Whether `url` points into the API at `api_url`: same scheme, host and
+/// port, no credentials, and under its path (so a link to
+/// `https://api.github.com.example` or `https://api.github.com@example` is not)
+fn is_api_url(api_url: &str, url: &str) -> bool {
+    let (Ok(api), Ok(url)) = (Url::parse(api_url), Url::parse(url)) else {
+        return false;
+    };
+    let base = api.path().trim_end_matches('/');
+    let under_base = url.path() == base || url.path().starts_with(&format!("{}/", base));
+    url.scheme() == api.scheme()
+        && url.host_str() == api.host_str()
+        && url.port_or_known_default() == api.port_or_known_default()
+        && url.username().is_empty()
+        && url.password().is_none()
+        && under_base
+}
+
+/// Percent-encode a query string value
+fn urlencode(value: &str) -> String {
+    value
+        .bytes()
+        .map(|byte| match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                (byte as char).to_string()
+            }
+            _ => format!("%{:02X}", byte),
+        })
+        .collect()
+}
+
+#[derive(Debug, Deserialize)]
+struct TopicsResponse {
+    names: Vec<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct SearchResponse {
+    items: Vec<SearchItem>,
+}
+
+#[derive(Debug, Deserialize)]
+struct SearchItem {
+    number: u32,
+    created_at: DateTime<Utc>,
+    pull_request: Option<SearchPullRequest>,
+}
+
+#[derive(Debug, Deserialize)]
+struct SearchPullRequest {
+    merged_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Review {
+    submitted_at: Option<DateTime<Utc>>,
+    user: Option<ReviewUser>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ReviewUser {
+    login: String,
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_delivery_metrics() {
+        let at = |day: u32, hour: u32| Utc.with_ymd_and_hms(2025, 1, day, hour, 0, 0).unwrap();
+        let prs = vec![
+            PullRequestTiming {
+                number: 1,
+                created_at: at(6, 10),
+                merged_at: at(7, 10),
+                first_review_at: Some(at(6, 12)),
+                reviewers: vec!["bob".to_string(), "carol".to_string()],
+            },
+            PullRequestTiming {
+                number: 2,
+                created_at: at(8, 10),
+                merged_at: at(8, 14),
+                first_review_at: None,
+                reviewers: vec![],
+            },
+        ];
+
+        let metrics = DeliveryMetrics::from_pull_requests(&prs);
+        assert_eq!(metrics.merged_prs, 2);
+        assert_eq!(metrics.avg_time_to_merge, Some(Duration::hours(14)));
+        assert_eq!(metrics.avg_review_turnaround, Some(Duration::hours(2)));
+        assert_eq!(metrics.reviews_by.get("bob"), Some(&1));
+        assert_eq!(metrics.reviews_by.len(), 2);
+
+        assert_eq!(DeliveryMetrics::from_pull_requests(&[]), DeliveryMetrics::default());
+    }
+
+    /// Client against a local server answering `GET /repos/owner/REPO/topics`
+    /// with `respond`, which gets the request count so far and the request headers
+    async fn topics_server(
+        respond: fn(usize, &axum::http::HeaderMap) -> axum::response::Response,
+    ) -> (GitHubClient, std::sync::Arc<std::sync::atomic::AtomicUsize>, tempfile::TempDir) {
+        use axum::extract::State;
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        use std::sync::Arc;
+
+        let requests = Arc::new(AtomicUsize::new(0));
+        let app = axum::Router::new()
+            .route(
+                "/repos/owner/:repo/topics",
+                axum::routing::get(
+                    move |State(requests): State<Arc<AtomicUsize>>, headers: axum::http::HeaderMap| async move {
+                        respond(requests.fetch_add(1, Ordering::SeqCst), &headers)
+                    },
+                ),
+            )
+            .with_state(Arc::clone(&requests));
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base_url = format!("http://{}", listener.local_addr().unwrap());
+        tokio::spawn(async move { axum::serve(listener, app).await });
+
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let db = sled::open(temp_dir.path().join("db")).unwrap();
+        let client = GitHubClient::with_base_url("token".to_string(), Some(base_url))
+            .unwrap()
+            .with_cache(GitHubResponseCache::new(db.open_tree("github_responses").unwrap()));
+        (client, requests, temp_dir)
+    }
+
+    fn repo(name: &str) -> GitHubRepo {
+        GitHubRepo {
+            owner: "owner".to_string(),
+            repo: name.to_string(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_get_revalidates_with_etag() {
+        use axum::response::IntoResponse;
+
+        let (client, requests, _temp_dir) = topics_server(|_, headers| {
+            if headers.get("if-none-match").is_some_and(|etag| etag == "\"v1\"") {
+                return axum::http::StatusCode::NOT_MODIFIED.into_response();
+            }
+            ([("etag", "\"v1\"")], axum::Json(serde_json::json!({ "names": ["rust"] }))).into_response()
+        })
+        .await;
+
+        assert_eq!(client.topics(&repo("api")).await.unwrap(), ["rust"]);
+        // Served from the cache after a 304
+        assert_eq!(client.topics(&repo("api")).await.unwrap(), ["rust"]);
+        assert_eq!(requests.load(std::sync::atomic::Ordering::SeqCst), 2);
+        assert_eq!(client.cache.as_ref().unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_get_backs_off_when_rate_limited() {
+        use axum::response::IntoResponse;
+
+        let (client, requests, _temp_dir) = topics_server(|count, _| {
+            if count == 0 {
+                return (axum::http::StatusCode::TOO_MANY_REQUESTS, [("retry-after", "0")], "slow down").into_response();
+            }
+            // The last request of the hour
+            let reset = (Utc::now() + Duration::hours(1)).timestamp().to_string();
+            let headers = [
+                ("etag", "\"v1\"".to_string()),
+                ("x-ratelimit-remaining", "0".to_string()),
+                ("x-ratelimit-reset", reset),
+            ];
+            (headers, axum::Json(serde_json::json!({ "names": ["rust"] }))).into_response()
+        })
+        .await;
+
+        // Retried after the 429
+        assert_eq!(client.topics(&repo("api")).await.unwrap(), ["rust"]);
+        assert_eq!(requests.load(std::sync::atomic::Ordering::SeqCst), 2);
+
+        // Out of requests until the reset: cached responses are used as they
+        // are, and anything else fails without a request
+        assert_eq!(client.topics(&repo("api")).await.unwrap(), ["rust"]);
+        let err = client.topics(&repo("web")).await.unwrap_err();
+        assert!(err.to_string().contains("rate limit exhausted"));
+        assert_eq!(requests.load(std::sync::atomic::Ordering::SeqCst), 2);
+    }
+
+    #[test]
+    fn test_rate_limit_wait() {
+        let now = Utc.with_ymd_and_hms(2025, 1, 1, 12, 0, 0).unwrap();
+        let limit = RateLimit {
+            remaining: 0,
+            reset: now + Duration::seconds(30),
+        };
+        assert_eq!(limit.wait(now), Some(std::time::Duration::from_secs(30)));
+        assert_eq!(limit.wait(now + Duration::minutes(1)), None);
+        assert_eq!(RateLimit { remaining: 5, ..limit }.wait(now), None);
+
+        let mut headers = HeaderMap::new();
+        headers.insert("x-ratelimit-remaining", "4999".parse().unwrap());
+        headers.insert("x-ratelimit-reset", "1735732800".parse().unwrap());
+        assert_eq!(
+            RateLimit::from_headers(&headers),
+            Some(RateLimit {
+                remaining: 4999,
+                reset: now,
+            })
+        );
+        assert_eq!(RateLimit::from_headers(&HeaderMap::new()), None);
+    }
+
+    #[test]
+    fn test_format_duration() {
+        assert_eq!(format_duration(Duration::minutes(45)), "45m");
+        assert_eq!(format_duration(Duration::minutes(200)), "3h 20m");
+        assert_eq!(format_duration(Duration::hours(52)), "2d 4h");
+    }
+
+    #[test]
+    fn test_merged_prs_query() {
+        let repo = GitHubRepo {
+            owner: "owner".to_string(),
+            repo: "repo".to_string(),
+        };
+        let timespan = Timespan::from_dates(
+            Utc.with_ymd_and_hms(2025, 1, 1, 0, 0, 0).unwrap(),
+            Utc.with_ymd_and_hms(2025, 1, 15, 0, 0, 0).unwrap(),
+        );
+
+        assert_eq!(
+            merged_prs_query(&repo, Some("@me"), &timespan),
+            "repo:owner/repo is:pr is:merged author:@me merged:2025-01-01..2025-01-15"
+        );
+        // The same query all day
+        let later = Timespan::from_dates(
+            Utc.with_ymd_and_hms(2025, 1, 1, 9, 30, 0).unwrap(),
+            Utc.with_ymd_and_hms(2025, 1, 15, 9, 30, 0).unwrap(),
+        );
+        assert_eq!(merged_prs_query(&repo, Some("@me"), &later), merged_prs_query(&repo, Some("@me"), &timespan));
+        assert!(!merged_prs_query(&repo, None, &timespan).contains("author:"));
+        assert_eq!(urlencode("is:pr @me"), "is%3Apr%20%40me");
+    }
+
+    #[test]
+    fn test_next_link() {
+        let mut headers = HeaderMap::new();
+        assert_eq!(next_link(&headers), None);
+        headers.insert(
+            LINK,
+            "<https://api.github.com/search/issues?q=x&page=2>; rel=\"next\", \
+             <https://api.github.com/search/issues?q=x&page=5>; rel=\"last\""
+                .parse()
+                .unwrap(),
+        );
+        assert_eq!(next_link(&headers).as_deref(), Some("https://api.github.com/search/issues?q=x&page=2"));
+        headers.insert(LINK, "<https://api.github.com/search/issues?q=x&page=1>; rel=\"prev\"".parse().unwrap());
+        assert_eq!(next_link(&headers), None);
+
+        // Only links into the API itself are followed with the token
+        let api = "https://api.github.com";
+        assert!(is_api_url(api, "https://api.github.com/search/issues?q=x&page=2"));
+        assert!(is_api_url(api, "https://api.github.com:443/search/issues?page=2"));
+        assert!(!is_api_url(api, "https://api.github.com.attacker.example/search/issues"));
+        assert!(!is_api_url(api, "https://api.github.com@attacker.example/search/issues"));
+        assert!(!is_api_url(api, "http://api.github.com/search/issues"));
+        assert!(!is_api_url(api, "not a url"));
+        let enterprise = "https://git.corp/api/v3";
+        assert!(is_api_url(enterprise, "https://git.corp/api/v3/search/issues?page=2"));
+        assert!(!is_api_url(enterprise, "https://git.corp/api/v3x/search/issues"));
+    }
+
+    #[tokio::test]
+    async fn test_merged_prs_follow_pages() {
+        use axum::extract::{Query, State};
+        use axum::response::IntoResponse;
+
+        // Two pages of one merged PR each, not modified once fetched
+        let app = axum::Router::new()
+            .route(
+                "/search/issues",
+                axum::routing::get(
+                    |State(base_url): State<String>,
+                     Query(query): Query<BTreeMap<String, String>>,
+                     headers: axum::http::HeaderMap| async move {
+                        let page: u32 = query.get("page").map_or(1, |page| page.parse().unwrap());
+                        if headers.get("if-none-match").is_some_and(|etag| etag == format!("\"p{}\"", page).as_str()) {
+                            return axum::http::StatusCode::NOT_MODIFIED.into_response();
+                        }
+                        let item = serde_json::json!({
+                            "number": page,
+                            "created_at": "2025-01-06T10:00:00Z",
+                            "pull_request": { "merged_at": "2025-01-07T10:00:00Z" },
+                        });
+                        let link = match page {
+                            1 => format!("<{}/search/issues?q=x&per_page=100&page=2>; rel=\"next\"", base_url),
+                            _ => format!("<{}/search/issues?q=x&per_page=100&page=1>; rel=\"first\"", base_url),
+                        };
+                        let headers = [("etag", format!("\"p{}\"", page)), ("link", link)];
+                        (headers, axum::Json(serde_json::json!({ "items": [item] }))).into_response()
+                    },
+                ),
+            )
+            .route(
+                "/repos/owner/:repo/pulls/:number/reviews",
+                axum::routing::get(|| async { axum::Json(serde_json::json!([])) }),
+            );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base_url = format!("http://{}", listener.local_addr().unwrap());
+        let app = app.with_state(base_url.clone());
+        tokio::spawn(async move { axum::serve(listener, app).await });
+
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let db = sled::open(temp_dir.path().join("db")).unwrap();
+        let client = GitHubClient::with_base_url("token".to_string(), Some(base_url))
+            .unwrap()
+            .with_cache(GitHubResponseCache::new(db.open_tree("github_responses").unwrap()));
+        let timespan = Timespan::from_dates(
+            Utc.with_ymd_and_hms(2025, 1, 1, 0, 0, 0).unwrap(),
+            Utc.with_ymd_and_hms(2025, 1, 15, 0, 0, 0).unwrap(),
+        );
+
+        let metrics = client.delivery_metrics(&repo("api"), None, &timespan).await.unwrap();
+        assert_eq!(metrics.merged_prs, 2);
+        assert_eq!(client.cache.as_ref().unwrap().len(), 2);
+
+        // A cached page still links the next one
+        let metrics = client.delivery_metrics(&repo("api"), None, &timespan).await.unwrap();
+        assert_eq!(metrics.merged_prs, 2);
+    }
+
+    #[test]
+    fn test_retry_after_capped() {
+        let mut headers = HeaderMap::new();
+        assert_eq!(retry_after(&headers), None);
+        headers.insert(RETRY_AFTER, "5".parse().unwrap());
+        assert_eq!(retry_after(&headers), Some(std::time::Duration::from_secs(5)));
+        headers.insert(RETRY_AFTER, "3600".parse().unwrap());
+        assert_eq!(retry_after(&headers), Some(MAX_RATE_LIMIT_WAIT));
+    }
+
     #[test]
     fn test_extract_pr_numbers() {
         // Test various formats
@@ -0,0 +1,53 @@
+use crate::error::Result;
+use serde::{Deserialize, Serialize};
+
+/// A GitHub API response body with the ETag it was served with
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CachedResponse {
+    /// `ETag` header of the response, sent back as `If-None-Match`
+    pub etag: String,
+    /// JSON body of the response
+    pub body: String,
+    /// URL of the next page, from the response's `Link` header
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub next: Option<String>,
+}
+
+/// Persistent GitHub API responses, keyed by request URL
+///
+/// Entries don't expire: each one is revalidated with its ETag, and a
+/// `304 Not Modified` answer doesn't count against the rate limit.
+pub struct GitHubResponseCache {
+    tree: sled::Tree,
+}
+
+impl GitHubResponseCache {
+    /// Wrap a sled tree dedicated to GitHub responses
+    pub fn new(tree: sled::Tree) -> Self {
+        Self { tree }
+    }
+
+    /// Look up the last response to a URL
+    pub fn get(&self, url: &str) -> Result<Option<CachedResponse>> {
+        match self.tree.get(url)? {
+            Some(data) => Ok(Some(serde_json::from_slice(&data)?)),
+            None => Ok(None),
+        }
+    }
+
+    /// Store the response to a URL
+    pub fn set(&self, url: &str, response: &CachedResponse) -> Result<()> {
+        self.tree.insert(url, serde_json::to_vec(response)?)?;
+        Ok(())
+    }
+
+    /// Number of cached responses
+    pub fn len(&self) -> usize {
+        self.tree.len()
+    }
+
+    /// Whether no responses are cached
+    pub fn is_empty(&self) -> bool {
+        self.tree.is_empty()
+    }
+}
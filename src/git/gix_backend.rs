@@ -0,0 +1,274 @@
+//! gitoxide-based commit walking, enabled with the `gix` feature
+//!
+//! Produces the same `Commit`s as the libgit2 walk in `Parser`, but revwalk
+//! and tree diffs are substantially faster on large repositories.
+
+use crate::error::{DevRecapError, Result};
+use crate::git::diff_cache::DiffStats;
+use crate::git::parser::{local_time, Parser};
+use crate::git::{Author, Commit, CommitDate};
+use gix::object::tree::diff::Action;
+use gix::revision::walk::Sorting;
+use gix::traverse::commit::simple::CommitTimeOrder;
+use std::convert::Infallible;
+use std::error::Error;
+use std::ops::ControlFlow;
+use std::path::Path;
+use std::time::Instant;
+
+/// Walk commits newest first with gitoxide (see `Parser::visit_commits`)
+pub(crate) fn visit_commits(
+    parser: &Parser,
+    repo_path: &Path,
+    visit: &mut dyn FnMut(Commit) -> ControlFlow<()>,
+) -> Result<bool> {
+    let started = Instant::now();
+    let repo = gix::open(repo_path).map_err(gix_error)?;
+    let mut head = repo.head().map_err(gix_error)?;
+    if head.is_unborn() {
+        return Err(DevRecapError::NoCommitsYet);
+    }
+    let head = head.peel_to_commit().map_err(gix_error)?;
+
+    let walk = repo
+        .rev_walk([head.id])
+        .sorting(Sorting::ByCommitTime(CommitTimeOrder::NewestFirst))
+        .all()
+        .map_err(gix_error)?;
+
+    let mut visited = 0;
+
+    for info in walk {
+        parser.check_time_budget(started)?;
+
+        let info = info.map_err(gix_error)?;
+        let commit = info.object().map_err(gix_error)?;
+
+        let signature = commit.author().map_err(gix_error)?;
+        let time = match parser.commit_date() {
+            CommitDate::Author => signature.time().map_err(gix_error)?,
+            CommitDate::Committer => commit.time().map_err(gix_error)?,
+        };
+        let timestamp = local_time(time.seconds, time.offset);
+        let author = Author {
+            name: signature.name.to_string(),
+            email: signature.email.to_string(),
+        };
+
+        if !parser.accepts(&timestamp, &author) {
+            continue;
+        }
+
+        let message = commit.message_raw_sloppy().to_string();
+        if parser.is_ignored(&message) {
+            continue;
+        }
+
+        if parser.cap_reached(visited) {
+            return Ok(true);
+        }
+
+        let oid = git2::Oid::from_bytes(commit.id.as_bytes())?;
+        let diff_stats = parser.cached_diff_stats(oid, || get_diff_stats(&repo, &commit))?;
+
+        visited += 1;
+        let commit = Parser::build_commit(commit.id.to_string(), author, timestamp, message, diff_stats);
+        if visit(commit).is_break() {
+            break;
+        }
+    }
+
+    Ok(false)
+}
+
+/// Diff a commit against its first parent (or the empty tree for root commits)
+fn get_diff_stats(repo: &gix::Repository, commit: &gix::Commit<'_>) -> Result<DiffStats> {
+    let tree = commit.tree().map_err(gix_error)?;
+    let parent_tree = match commit.parent_ids().next() {
+        Some(parent_id) => parent_id
+            .object()
+            .map_err(gix_error)?
+            .into_commit()
+            .tree()
+            .map_err(gix_error)?,
+        None => repo.empty_tree(),
+    };
+
+    let mut resource_cache = repo.diff_resource_cache_for_tree_diff().map_err(gix_error)?;
+    let mut files_changed = Vec::new();
+    let mut file_churn = Vec::new();
+    let (mut insertions, mut deletions) = (0u32, 0u32);
+
+    parent_tree
+        .changes()
+        .map_err(gix_error)?
+        .options(|options| {
+            // Match libgit2's defaults: full paths, no rename detection
+            options.track_path().track_rewrites(None);
+        })
+        .for_each_to_obtain_tree(&tree, |change| {
+            if change.entry_mode().is_no_tree() {
+                files_changed.push(change.location().to_string());
+
+                let counts = change
+                    .diff(&mut resource_cache)
+                    .ok()
+                    .and_then(|mut platform| platform.line_counts().ok())
+                    .flatten();
+                match counts {
+                    Some(counts) => {
+                        insertions += counts.insertions;
+                        deletions += counts.removals;
+                        file_churn.push(counts.insertions + counts.removals);
+                    }
+                    None => file_churn.push(0),
+                }
+                resource_cache.clear_resource_cache_keep_allocation();
+            }
+            Ok::<_, Infallible>(Action::Continue)
+        })
+        .map_err(gix_error)?;
+
+    Ok(DiffStats {
+        files_changed,
+        file_churn,
+        insertions,
+        deletions,
+        patch_id: None,
+    })
+}
+
+/// Wrap a gitoxide error, telling a damaged object database apart like
+/// `DevRecapError::is_corruption` does for libgit2
+fn gix_error(e: impl Error + 'static) -> DevRecapError {
+    if corrupted(&e) {
+        DevRecapError::CorruptedRepository(e.to_string())
+    } else {
+        DevRecapError::other(format!("gix error: {}", e))
+    }
+}
+
+/// Whether `e`, or an error it wraps, is a missing or unreadable object
+///
+/// Transparent wrappers don't report the error they wrap as a source, so
+/// missing objects are also looked for inside them.
+fn corrupted(e: &(dyn Error + 'static)) -> bool {
+    use gix::object::commit;
+    use gix::objs::find::{existing, existing_iter, existing_object};
+
+    let mut source = Some(e);
+    while let Some(e) = source {
+        let damaged = e.is::<gix::odb::loose::find::Error>()
+            || e.is::<gix::features::zlib::inflate::Error>()
+            || e.is::<gix::objs::decode::Error>()
+            || e.is::<gix::hash::verify::Error>()
+            || matches!(e.downcast_ref(), Some(existing::Error::NotFound { .. }))
+            || matches!(e.downcast_ref(), Some(existing_iter::Error::NotFound { .. }))
+            || matches!(e.downcast_ref(), Some(existing_object::Error::NotFound { .. }))
+            || matches!(
+                e.downcast_ref(),
+                Some(commit::Error::FindExistingObject(existing::Error::NotFound { .. }))
+            );
+        if damaged {
+            return true;
+        }
+        source = e.source();
+    }
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::git::{GitBackend, Timespan};
+    use std::fs;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_gix_matches_libgit2() {
+        let temp_dir = TempDir::new().unwrap();
+        let repo = git2::Repository::init(temp_dir.path()).unwrap();
+        let signature = git2::Signature::now("Test User", "test@example.com").unwrap();
+
+        let mut parent = None;
+        for (i, contents) in ["one\n", "one\ntwo\n", "two\n"].iter().enumerate() {
+            fs::write(temp_dir.path().join("file.txt"), contents).unwrap();
+            let mut index = repo.index().unwrap();
+            index.add_path(Path::new("file.txt")).unwrap();
+            index.write().unwrap();
+            let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+            let parents: Vec<git2::Commit> = parent.iter().map(|id| repo.find_commit(*id).unwrap()).collect();
+            let parent_refs: Vec<&git2::Commit> = parents.iter().collect();
+            let id = repo
+                .commit(Some("HEAD"), &signature, &signature, &format!("Commit {} #1{}", i, i), &tree, &parent_refs)
+                .unwrap();
+            parent = Some(id);
+        }
+
+        let mut libgit2 = Parser::new(None, Timespan::days_back(1))
+            .parse_commits(temp_dir.path())
+            .unwrap();
+        let mut gix = Parser::new(None, Timespan::days_back(1))
+            .with_backend(GitBackend::Gix)
+            .parse_commits(temp_dir.path())
+            .unwrap();
+
+        // All commits share a timestamp, so walk order may differ between backends
+        libgit2.sort_by(|a, b| a.hash.cmp(&b.hash));
+        gix.sort_by(|a, b| a.hash.cmp(&b.hash));
+
+        assert_eq!(gix.len(), 3);
+        for (a, b) in libgit2.iter().zip(&gix) {
+            assert_eq!(a.hash, b.hash);
+            assert_eq!(a.summary, b.summary);
+            assert_eq!(a.files_changed, b.files_changed);
+            assert_eq!((a.insertions, a.deletions), (b.insertions, b.deletions));
+        }
+    }
+
+    #[test]
+    fn test_gix_unborn_head() {
+        let temp_dir = TempDir::new().unwrap();
+        git2::Repository::init(temp_dir.path()).unwrap();
+
+        let result = Parser::new(None, Timespan::days_back(1))
+            .with_backend(GitBackend::Gix)
+            .parse_commits(temp_dir.path());
+        assert!(matches!(result, Err(DevRecapError::NoCommitsYet)));
+    }
+
+    #[test]
+    fn test_gix_corrupted_objects() {
+        let temp_dir = TempDir::new().unwrap();
+        let repo = git2::Repository::init(temp_dir.path()).unwrap();
+        let signature = git2::Signature::now("Test User", "test@example.com").unwrap();
+        fs::write(temp_dir.path().join("file.txt"), "one\n").unwrap();
+        let mut index = repo.index().unwrap();
+        index.add_path(Path::new("file.txt")).unwrap();
+        index.write().unwrap();
+        let tree = index.write_tree().unwrap();
+        let head = repo
+            .commit(Some("HEAD"), &signature, &signature, "Commit", &repo.find_tree(tree).unwrap(), &[])
+            .unwrap();
+        let object = |id: git2::Oid| {
+            let id = id.to_string();
+            temp_dir.path().join(".git/objects").join(&id[..2]).join(&id[2..])
+        };
+        let parse = || {
+            Parser::new(None, Timespan::days_back(1))
+                .with_backend(GitBackend::Gix)
+                .parse_commits(temp_dir.path())
+        };
+
+        // A missing tree
+        fs::remove_file(object(tree)).unwrap();
+        let e = parse().unwrap_err();
+        assert!(e.is_corruption(), "{}", e);
+
+        // An unreadable commit
+        fs::remove_file(object(head)).unwrap();
+        fs::write(object(head), b"not a zlib stream").unwrap();
+        let e = parse().unwrap_err();
+        assert!(e.is_corruption(), "{}", e);
+    }
+}
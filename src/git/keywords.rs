@@ -0,0 +1,118 @@
+// Keyword extraction from commit messages
+// Lightweight term counting that surfaces recurring topics ("auth", "migration")
+
+use crate::git::Commit;
+use std::collections::{HashMap, HashSet};
+
+/// Keywords kept per repository
+pub const KEYWORD_LIMIT: usize = 8;
+
+/// Words too common in commit messages to say anything about the topic
+const STOPWORDS: &[&str] = &[
+    "the", "and", "for", "with", "from", "into", "onto", "that", "this", "these", "those", "when",
+    "then", "than", "but", "not", "are", "was", "were", "has", "have", "had", "its", "all", "any",
+    "more", "less", "some", "via", "per", "out", "off", "over", "under", "also", "only", "now",
+    "new", "use", "uses", "using", "used", "make", "makes", "made", "add", "adds", "added",
+    "adding", "fix", "fixes", "fixed", "fixing", "update", "updates", "updated", "updating",
+    "remove", "removes", "removed", "removing", "change", "changes", "changed", "improve",
+    "improves", "improved", "refactor", "refactored", "cleanup", "clean", "bump", "merge",
+    "merged", "branch", "pull", "request", "commit", "revert", "wip", "minor", "small", "misc",
+    "feat", "chore", "docs", "style", "perf", "test", "tests", "build", "main", "master",
+    "should", "can", "will", "don", "doesn", "isn", "instead", "after", "before", "about",
+];
+
+/// Count how many commits mention each keyword, most frequent first
+///
+/// Words come from commit subjects and bodies, lowercased, ignoring
+/// stopwords, numbers, short words and hash-like tokens. Only keywords
+/// mentioned by at least two commits are kept.
+pub fn extract_keywords(commits: &[Commit], limit: usize) -> Vec<(String, u32)> {
+    let mut counts: HashMap<String, u32> = HashMap::new();
+
+    for commit in commits {
+        count_keywords(&mut counts, commit);
+    }
+
+    top_keywords(counts, limit)
+}
+
+/// Count the keywords of one commit into `counts`
+pub(crate) fn count_keywords(counts: &mut HashMap<String, u32>, commit: &Commit) {
+    for word in words(&commit.message) {
+        *counts.entry(word).or_insert(0) += 1;
+    }
+}
+
+/// The `limit` most counted keywords mentioned by at least two commits
+pub(crate) fn top_keywords(counts: HashMap<String, u32>, limit: usize) -> Vec<(String, u32)> {
+    let mut keywords: Vec<_> = counts.into_iter().filter(|(_, count)| *count >= 2).collect();
+    keywords.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    keywords.truncate(limit);
+
+    keywords
+}
+
+/// Format keywords as "auth (5), migration (3)"
+pub fn format_keywords(keywords: &[(String, u32)]) -> String {
+    keywords
+        .iter()
+        .map(|(word, count)| format!("{} ({})", word, count))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// The distinct keyword-like words of `text`, lowercased
+pub(crate) fn words(text: &str) -> HashSet<String> {
+    text.split(|c: char| !c.is_alphanumeric())
+        .map(str::to_lowercase)
+        .filter(|word| is_keyword(word))
+        .collect()
+}
+
+/// Whether a lowercased word can be a keyword
+fn is_keyword(word: &str) -> bool {
+    let looks_like_hash = word.len() >= 7 && word.chars().all(|c| c.is_ascii_hexdigit());
+
+    word.chars().count() >= 3
+        && !word.chars().all(|c| c.is_ascii_digit())
+        && !looks_like_hash
+        && !STOPWORDS.contains(&word)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn commit(message: &str) -> Commit {
+        Commit::test("abc123", message)
+    }
+
+    #[test]
+    fn test_extract_keywords() {
+        let commits = vec![
+            commit("feat(auth): add OAuth login"),
+            commit("Fix auth token refresh and caching"),
+            commit("Add caching layer for auth sessions (#42)"),
+            commit("Run database migration 2024"),
+            commit("Revert deadbeef0123 migration"),
+        ];
+
+        let keywords = extract_keywords(&commits, 10);
+        assert_eq!(keywords[0], ("auth".to_string(), 3));
+        assert_eq!(keywords[1], ("caching".to_string(), 2));
+        assert_eq!(keywords[2], ("migration".to_string(), 2));
+        assert_eq!(keywords.len(), 3);
+
+        assert_eq!(extract_keywords(&commits, 1).len(), 1);
+        assert_eq!(format_keywords(&keywords[..2]), "auth (3), caching (2)");
+    }
+
+    #[test]
+    fn test_is_keyword() {
+        assert!(is_keyword("oauth"));
+        assert!(!is_keyword("the"));
+        assert!(!is_keyword("2024"));
+        assert!(!is_keyword("deadbeef01"));
+        assert!(!is_keyword("ui"));
+    }
+}
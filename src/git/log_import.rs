@@ -0,0 +1,271 @@
+//! Commits from `git log` text piped in (`--stdin-format git-log`)
+//!
+//! Lets a recap be written where the repository isn't available locally,
+//! e.g. from `ssh server git -C /srv/app log --numstat --author=me --since=2.weeks`.
+//! The default `git log` layout is expected, with `--numstat` for the changed
+//! files and lines (without it, commits carry no diff stats). `--date=iso`,
+//! `iso-strict`, `rfc` and `raw` dates are understood too, as is
+//! `--format=fuller`, whose author date is used.
+
+use crate::error::{DevRecapError, Result};
+use crate::git::diff_cache::DiffStats;
+use crate::git::parser::{local_time, Parser};
+use crate::git::{Author, Commit};
+use chrono::{DateTime, FixedOffset};
+use std::fmt;
+
+/// Formats accepted on stdin
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StdinFormat {
+    /// `git log --numstat` output
+    GitLog,
+}
+
+impl fmt::Display for StdinFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::GitLog => write!(f, "git-log"),
+        }
+    }
+}
+
+impl std::str::FromStr for StdinFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s {
+            "git-log" => Ok(Self::GitLog),
+            _ => Err(format!("unknown stdin format '{}' (expected git-log)", s)),
+        }
+    }
+}
+
+/// A commit being read, before its message and stats are complete
+struct PendingCommit {
+    hash: String,
+    author: Option<Author>,
+    timestamp: Option<DateTime<FixedOffset>>,
+    message: Vec<String>,
+    stats: DiffStats,
+    /// Line of the `commit` header, for errors
+    line: usize,
+}
+
+impl PendingCommit {
+    fn finish(self) -> Result<Commit> {
+        let missing = |field: &str| DevRecapError::InvalidGitLog {
+            line: self.line,
+            reason: format!("commit {} has no {} line", self.hash, field),
+        };
+        let author = self.author.clone().ok_or_else(|| missing("Author"))?;
+        let timestamp = self.timestamp.ok_or_else(|| missing("Date"))?;
+        let message = self.message.join("\n").trim().to_string();
+        Ok(Parser::build_commit(self.hash, author, timestamp, message, self.stats))
+    }
+}
+
+/// Parse `git log` output into commits, in the order given (newest first for
+/// plain `git log`)
+pub fn parse_git_log(text: &str) -> Result<Vec<Commit>> {
+    let mut commits = Vec::new();
+    let mut current: Option<PendingCommit> = None;
+
+    for (index, line) in text.lines().enumerate() {
+        let number = index + 1;
+        let invalid = |reason: String| DevRecapError::InvalidGitLog { line: number, reason };
+
+        if let Some(hash) = commit_header(line) {
+            if let Some(pending) = current.take() {
+                commits.push(pending.finish()?);
+            }
+            current = Some(PendingCommit {
+                hash,
+                author: None,
+                timestamp: None,
+                message: Vec::new(),
+                stats: DiffStats::default(),
+                line: number,
+            });
+            continue;
+        }
+
+        let Some(ref mut pending) = current else {
+            if line.trim().is_empty() {
+                continue;
+            }
+            return Err(invalid("expected a `commit <hash>` line".to_string()));
+        };
+
+        if let Some(message_line) = line.strip_prefix("    ") {
+            pending.message.push(message_line.to_string());
+        } else if let Some(author) = line.strip_prefix("Author:") {
+            pending.author = Some(parse_author(author.trim()).ok_or_else(|| {
+                invalid(format!("`{}` is not `Name <email>`", author.trim()))
+            })?);
+        } else if let Some(date) = line.strip_prefix("AuthorDate:").or_else(|| line.strip_prefix("Date:")) {
+            pending.timestamp = Some(
+                parse_date(date.trim()).ok_or_else(|| invalid(format!("unrecognized date `{}`", date.trim())))?,
+            );
+        } else if let Some((insertions, deletions, path)) = numstat(line) {
+            pending.stats.insertions += insertions;
+            pending.stats.deletions += deletions;
+            pending.stats.files_changed.push(path);
+            pending.stats.file_churn.push(insertions + deletions);
+        }
+        // Other headers (Merge:, Commit:, CommitDate:) and blank lines carry nothing we use
+    }
+
+    if let Some(pending) = current {
+        commits.push(pending.finish()?);
+    }
+    Ok(commits)
+}
+
+/// The hash of a `commit <hash>` line, ignoring decorations like `(HEAD -> main)`
+fn commit_header(line: &str) -> Option<String> {
+    let hash = line.strip_prefix("commit ")?.split_whitespace().next()?;
+    (hash.len() >= 7 && hash.chars().all(|c| c.is_ascii_hexdigit())).then(|| hash.to_string())
+}
+
+/// `Name <email>`
+fn parse_author(text: &str) -> Option<Author> {
+    let (name, rest) = text.split_once('<')?;
+    let email = rest.strip_suffix('>')?;
+    Some(Author {
+        name: name.trim().to_string(),
+        email: email.trim().to_string(),
+    })
+}
+
+/// A date in one of the layouts `git log --date=...` prints
+fn parse_date(text: &str) -> Option<DateTime<FixedOffset>> {
+    const LAYOUTS: &[&str] = &[
+        // default: Mon Jan 15 10:00:00 2024 +0100
+        "%a %b %e %H:%M:%S %Y %z",
+        // iso: 2024-01-15 10:00:00 +0100
+        "%Y-%m-%d %H:%M:%S %z",
+    ];
+    if let Some(date) = LAYOUTS
+        .iter()
+        .find_map(|layout| DateTime::parse_from_str(text, layout).ok())
+    {
+        return Some(date);
+    }
+    if let Ok(date) = DateTime::parse_from_rfc3339(text).or_else(|_| DateTime::parse_from_rfc2822(text)) {
+        return Some(date);
+    }
+
+    // raw: 1705309200 +0100
+    let (seconds, offset) = text.split_once(' ')?;
+    let seconds: i64 = seconds.parse().ok()?;
+    let sign = match offset.get(..1)? {
+        "+" => 1,
+        "-" => -1,
+        _ => return None,
+    };
+    let hours: i32 = offset.get(1..3)?.parse().ok()?;
+    let minutes: i32 = offset.get(3..5)?.parse().ok()?;
+    Some(local_time(seconds, sign * (hours * 3600 + minutes * 60)))
+}
+
+/// Lines added, lines removed and path of a `--numstat` line (binary files,
+/// shown as `-`, count as no lines)
+fn numstat(line: &str) -> Option<(u32, u32, String)> {
+    let mut fields = line.splitn(3, '\t');
+    let count = |field: Option<&str>| -> Option<u32> {
+        match field? {
+            "-" => Some(0),
+            number => number.parse().ok(),
+        }
+    };
+    let insertions = count(fields.next())?;
+    let deletions = count(fields.next())?;
+    Some((insertions, deletions, renamed_path(fields.next()?)))
+}
+
+/// The new path of a rename: `old => new` or `src/{old => new}/lib.rs`
+fn renamed_path(path: &str) -> String {
+    if let (Some(open), Some(close)) = (path.find('{'), path.find('}')) {
+        if let Some((_, new)) = path[open + 1..close].split_once(" => ") {
+            let joined = format!("{}{}{}", &path[..open], new, &path[close + 1..]);
+            return joined.replace("//", "/");
+        }
+    }
+    match path.split_once(" => ") {
+        Some((_, new)) => new.to_string(),
+        None => path.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LOG: &str = "\
+commit 3f2a1b4c5d6e7f8091a2b3c4d5e6f708192a3b4c (HEAD -> main, origin/main)
+Author: Jane Doe <jane@example.com>
+Date:   Mon Jan 15 10:00:00 2024 +0100
+
+    Add retry queue for webhooks (#42)
+
+    Deliveries are retried with backoff.
+
+12\t3\tsrc/webhooks/queue.rs
+-\t-\tassets/diagram.png
+4\t4\tsrc/{old => webhooks}/mod.rs
+
+commit 0a1b2c3d4e5f60718293a4b5c6d7e8f901234567
+Merge: 1111111 2222222
+Author: Jane Doe <jane@example.com>
+Date:   Sun Jan 14 09:30:00 2024 +0100
+
+    Merge branch 'cleanup'
+";
+
+    #[test]
+    fn test_parse_git_log() {
+        let commits = parse_git_log(LOG).unwrap();
+        assert_eq!(commits.len(), 2);
+
+        let first = &commits[0];
+        assert_eq!(first.short_hash, "3f2a1b4");
+        assert_eq!(first.author.email, "jane@example.com");
+        assert_eq!(first.summary, "Add retry queue for webhooks (#42)");
+        assert_eq!(first.body.as_deref(), Some("Deliveries are retried with backoff."));
+        assert_eq!(first.pr_numbers, [42]);
+        assert_eq!(first.timestamp.to_rfc3339(), "2024-01-15T10:00:00+01:00");
+        assert_eq!(
+            first.files_changed,
+            ["src/webhooks/queue.rs", "assets/diagram.png", "src/webhooks/mod.rs"]
+        );
+        assert_eq!((first.insertions, first.deletions), (16, 7));
+
+        assert_eq!(commits[1].summary, "Merge branch 'cleanup'");
+        assert!(commits[1].files_changed.is_empty());
+    }
+
+    #[test]
+    fn test_parse_git_log_errors() {
+        let err = parse_git_log("not a log\n").unwrap_err();
+        assert!(err.to_string().contains("line 1"));
+
+        let err = parse_git_log("commit 3f2a1b4c\nAuthor: Jane <jane@example.com>\n\n    Subject\n").unwrap_err();
+        assert!(err.to_string().contains("has no Date line"));
+
+        assert!(parse_git_log("").unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_parse_date() {
+        for text in [
+            "2024-01-15 10:00:00 +0100",
+            "2024-01-15T10:00:00+01:00",
+            "Mon, 15 Jan 2024 10:00:00 +0100",
+            "1705309200 +0100",
+        ] {
+            assert_eq!(parse_date(text).map(|date| date.to_rfc3339()).as_deref(), Some("2024-01-15T10:00:00+01:00"));
+        }
+        assert_eq!(renamed_path("docs/a.md => guide/a.md"), "guide/a.md");
+        assert_eq!(renamed_path("src/{ => net}/http.rs"), "src/net/http.rs");
+    }
+}
@@ -1,9 +1,20 @@
+pub mod about;
+pub mod commit_index;
+pub mod diff_cache;
 pub mod github;
+pub mod github_cache;
+#[cfg(feature = "gix")]
+pub mod gix_backend;
+pub mod keywords;
+pub mod log_import;
 pub mod parser;
 pub mod scanner;
 pub mod stats;
 
-use chrono::{DateTime, Utc};
+use crate::error::{DevRecapError, Result};
+use chrono::{
+    DateTime, Datelike, FixedOffset, Local, NaiveDate, NaiveDateTime, NaiveTime, TimeZone, Utc, Weekday,
+};
 use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
 
@@ -14,17 +25,62 @@ pub struct Repository {
     pub path: PathBuf,
     /// Repository name (derived from directory name)
     pub name: String,
-    /// Remote URL (if available)
+    /// Remote URL (if available): `origin`, or the only other remote
     pub remote_url: Option<String>,
-    /// GitHub repository info (if applicable)
-    #[allow(dead_code)]
+    /// URL of the `upstream` remote when the repository is a fork of it
+    /// (`remote_url` is then the fork's)
+    pub upstream_url: Option<String>,
+    /// GitHub repository info of the canonical repository (if applicable),
+    /// for PR and commit links and delivery metrics
     pub github_info: Option<GitHubRepo>,
+    /// What the project is, from its README and manifests
+    pub about: about::ProjectInfo,
     /// Filtered commits
     pub commits: Vec<Commit>,
+    /// Files with the most repeated churn, highest first
+    pub hotspots: Vec<stats::Hotspot>,
+    /// Commits and changed lines per week of the timespan, oldest first
+    pub velocity: Vec<stats::WeekBucket>,
+    /// Split of the changes between test and source code
+    pub test_ratio: stats::TestRatio,
+    /// Recurring words in commit messages with the number of commits using them
+    pub keywords: Vec<(String, u32)>,
+    /// Set when older commits were dropped to stay under this cap
+    pub commit_cap: Option<usize>,
     /// Repository statistics
     pub stats: RepoStats,
 }
 
+impl Repository {
+    /// URL of the canonical repository: the upstream of a fork, otherwise the remote
+    pub fn canonical_url(&self) -> Option<&str> {
+        self.upstream_url.as_deref().or(self.remote_url.as_deref())
+    }
+}
+
+#[cfg(test)]
+impl Repository {
+    /// Repository `name` at `/code/<name>` with `commits` and their stats,
+    /// and nothing else known; tests adjust the rest with struct update syntax
+    pub(crate) fn test(name: &str, commits: Vec<Commit>) -> Self {
+        Self {
+            path: PathBuf::from("/code").join(name),
+            name: name.to_string(),
+            remote_url: None,
+            upstream_url: None,
+            github_info: None,
+            about: about::ProjectInfo::default(),
+            stats: RepoStats::from_commits(&commits),
+            commits,
+            hotspots: vec![],
+            velocity: vec![],
+            test_ratio: stats::TestRatio::default(),
+            keywords: vec![],
+            commit_cap: None,
+        }
+    }
+}
+
 /// GitHub repository information
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct GitHubRepo {
@@ -36,13 +92,11 @@ pub struct GitHubRepo {
 
 impl GitHubRepo {
     /// Create a GitHub PR URL
-    #[allow(dead_code)]
     pub fn pr_url(&self, pr_number: u32) -> String {
         format!("https://github.com/{}/{}/pull/{}", self.owner, self.repo, pr_number)
     }
 
     /// Create a GitHub commit URL
-    #[allow(dead_code)]
     pub fn commit_url(&self, hash: &str) -> String {
         format!("https://github.com/{}/{}/commit/{}", self.owner, self.repo, hash)
     }
@@ -56,41 +110,90 @@ pub struct Commit {
     /// Short commit hash (7 characters)
     pub short_hash: String,
     /// Commit author
-    #[allow(dead_code)]
     pub author: Author,
-    /// Commit timestamp
-    pub timestamp: DateTime<Utc>,
+    /// Commit timestamp, with the UTC offset it was recorded with
+    pub timestamp: DateTime<FixedOffset>,
     /// Full commit message
-    #[allow(dead_code)]
     pub message: String,
     /// First line of commit message
     pub summary: String,
     /// Rest of commit message (if any)
-    #[allow(dead_code)]
     pub body: Option<String>,
     /// List of files changed
     pub files_changed: Vec<String>,
+    /// Lines inserted plus deleted, per entry of `files_changed`
+    pub file_churn: Vec<u32>,
     /// Number of insertions
     pub insertions: u32,
     /// Number of deletions
     pub deletions: u32,
     /// PR numbers mentioned in commit message
     pub pr_numbers: Vec<u32>,
+    /// Patch ID of the diff, equal for cherry-picks of the same change
+    /// (unknown without diff stats or with the gix backend)
+    pub patch_id: Option<String>,
 }
 
 impl Commit {
     /// Create a short representation of the commit
-    #[allow(dead_code)]
     pub fn short_desc(&self) -> String {
         format!("{} - {}", self.short_hash, self.summary)
     }
+
+    /// Whether the commit is marked as breaking, Conventional Commits style:
+    /// `feat(api)!: ...` or a `BREAKING CHANGE:` footer
+    pub fn is_breaking(&self) -> bool {
+        let bang = self
+            .summary
+            .split_once(':')
+            .is_some_and(|(prefix, _)| prefix.ends_with('!') && !prefix.contains(char::is_whitespace));
+        bang || self.message.lines().any(|line| {
+            let line = line.trim_start();
+            line.starts_with("BREAKING CHANGE:") || line.starts_with("BREAKING-CHANGE:")
+        })
+    }
+}
+
+#[cfg(test)]
+impl Commit {
+    /// Commit `hash` by Test User <test@example.com>, made now, changing
+    /// nothing; tests adjust the rest with struct update syntax
+    pub(crate) fn test(hash: &str, message: &str) -> Self {
+        Self {
+            hash: hash.to_string(),
+            short_hash: hash.chars().take(7).collect(),
+            author: Author {
+                name: "Test User".to_string(),
+                email: "test@example.com".to_string(),
+            },
+            timestamp: Utc::now().fixed_offset(),
+            message: message.to_string(),
+            summary: message.lines().next().unwrap_or_default().to_string(),
+            body: None,
+            files_changed: vec![],
+            file_churn: vec![],
+            insertions: 0,
+            deletions: 0,
+            pr_numbers: vec![],
+            patch_id: None,
+        }
+    }
+
+    /// The commit changing each of `files` by `lines` lines, `lines` insertions in all
+    pub(crate) fn with_files(self, files: &[&str], lines: u32) -> Self {
+        Self {
+            files_changed: files.iter().map(|file| file.to_string()).collect(),
+            file_churn: vec![lines; files.len()],
+            insertions: lines,
+            ..self
+        }
+    }
 }
 
 /// Commit author information
 #[derive(Debug, Clone)]
 pub struct Author {
     /// Author name
-    #[allow(dead_code)]
     pub name: String,
     /// Author email
     pub email: String,
@@ -111,6 +214,8 @@ pub struct RepoStats {
     pub pr_count: u32,
     /// Commits per day (date string -> count)
     pub commit_frequency: std::collections::HashMap<String, u32>,
+    /// File changes per top-level directory ("api/", or "(root)" for top-level files)
+    pub directory_changes: std::collections::HashMap<String, u32>,
 }
 
 impl RepoStats {
@@ -120,31 +225,78 @@ impl RepoStats {
         let mut pr_set = std::collections::HashSet::new();
 
         for commit in commits {
-            stats.total_commits += 1;
-            stats.total_files_changed += commit.files_changed.len() as u32;
-            stats.total_insertions += commit.insertions;
-            stats.total_deletions += commit.deletions;
-
-            // Track PRs
-            for pr in &commit.pr_numbers {
-                pr_set.insert(*pr);
-            }
-
-            // Track commit frequency by date
-            let date = commit.timestamp.format("%Y-%m-%d").to_string();
-            *stats.commit_frequency.entry(date).or_insert(0) += 1;
+            stats.add_commit(commit, &mut pr_set);
         }
 
         stats.pr_count = pr_set.len() as u32;
         stats
     }
 
+    /// Count one commit, collecting its PR numbers into `pr_set` (from which
+    /// `pr_count` is set once all commits are counted)
+    pub(crate) fn add_commit(&mut self, commit: &Commit, pr_set: &mut std::collections::HashSet<u32>) {
+        self.total_commits += 1;
+        self.total_files_changed += commit.files_changed.len() as u32;
+        self.total_insertions += commit.insertions;
+        self.total_deletions += commit.deletions;
+
+        for file in &commit.files_changed {
+            *self
+                .directory_changes
+                .entry(stats::top_level_directory(file))
+                .or_insert(0) += 1;
+        }
+
+        // Track PRs
+        for pr in &commit.pr_numbers {
+            pr_set.insert(*pr);
+        }
+
+        // Track commit frequency by date
+        let date = commit.timestamp.format("%Y-%m-%d").to_string();
+        *self.commit_frequency.entry(date).or_insert(0) += 1;
+    }
+
     /// Get net lines changed (insertions - deletions)
     pub fn net_lines_changed(&self) -> i64 {
         self.total_insertions as i64 - self.total_deletions as i64
     }
 }
 
+/// Library used to read commit history and diffs
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum GitBackend {
+    /// libgit2 via the git2 crate
+    #[default]
+    Libgit2,
+    /// gitoxide, faster on large repositories (needs the `gix` feature)
+    Gix,
+}
+
+/// Which of a commit's two timestamps places it in time
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CommitDate {
+    /// When the change was written; survives rebases and cherry-picks
+    #[default]
+    Author,
+    /// When the commit was last created or rewritten
+    Committer,
+}
+
+impl std::str::FromStr for CommitDate {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s {
+            "author" => Ok(Self::Author),
+            "committer" => Ok(Self::Committer),
+            other => Err(format!("unknown date '{}' (expected author or committer)", other)),
+        }
+    }
+}
+
 /// Timespan for filtering commits
 #[derive(Debug, Clone)]
 pub struct Timespan {
@@ -162,15 +314,98 @@ impl Timespan {
         Self { start, end }
     }
 
+    /// Since the start of the previous weekday in the local timezone, like
+    /// git-standup: Friday on a Monday (and over the weekend), else yesterday
+    pub fn since_last_weekday() -> Self {
+        let start_day = Self::last_weekday(Local::now().date_naive());
+        let start = Local
+            .from_local_datetime(&start_day.and_time(NaiveTime::MIN))
+            .earliest()
+            .map(|local| local.with_timezone(&Utc))
+            .unwrap_or_else(|| Utc::now() - chrono::Duration::days(1));
+        Self { start, end: Utc::now() }
+    }
+
+    /// The weekday before `today`
+    pub fn last_weekday(today: NaiveDate) -> NaiveDate {
+        let mut day = today.pred_opt().unwrap_or(today);
+        while matches!(day.weekday(), Weekday::Sat | Weekday::Sun) {
+            day = day.pred_opt().unwrap_or(day);
+        }
+        day
+    }
+
     /// Create a timespan from specific dates
-    #[allow(dead_code)]
     pub fn from_dates(start: DateTime<Utc>, end: DateTime<Utc>) -> Self {
         Self { start, end }
     }
 
     /// Check if a date is within this timespan
-    pub fn contains(&self, date: &DateTime<Utc>) -> bool {
-        date >= &self.start && date <= &self.end
+    pub fn contains<Tz: TimeZone>(&self, date: &DateTime<Tz>) -> bool {
+        *date >= self.start && *date <= self.end
+    }
+
+    /// Parse a `--since`/`--until` bound into a UTC instant
+    ///
+    /// Accepts RFC3339 timestamps (`2025-01-15T09:30:00+02:00`), date-times without
+    /// an offset (`2025-01-15T09:30`, `2025-01-15 09:30:00`) and bare dates (`2025-01-15`).
+    /// Values without an offset are interpreted in the local timezone unless `utc` is set.
+    /// Bare dates resolve to the start of the day, or its last second if `end_of_day` is set.
+    pub fn parse_bound(input: &str, end_of_day: bool, utc: bool) -> Result<DateTime<Utc>> {
+        let input = input.trim();
+
+        if let Ok(datetime) = DateTime::parse_from_rfc3339(input) {
+            return Ok(datetime.with_timezone(&Utc));
+        }
+
+        let naive = Self::parse_naive_datetime(input)
+            .or_else(|| {
+                NaiveDate::parse_from_str(input, "%Y-%m-%d").ok().map(|date| {
+                    let time = if end_of_day {
+                        NaiveTime::from_hms_opt(23, 59, 59)
+                    } else {
+                        NaiveTime::from_hms_opt(0, 0, 0)
+                    };
+                    date.and_time(time.unwrap_or_default())
+                })
+            })
+            .ok_or_else(|| {
+                DevRecapError::InvalidTimespan(format!(
+                    "'{}' is not a date (YYYY-MM-DD) or RFC3339 timestamp",
+                    input
+                ))
+            })?;
+
+        if utc {
+            return Ok(naive.and_utc());
+        }
+
+        // `earliest` picks the first instant when a DST transition makes the
+        // local time ambiguous; a time skipped by DST has no mapping at all
+        Local
+            .from_local_datetime(&naive)
+            .earliest()
+            .map(|local| local.with_timezone(&Utc))
+            .ok_or_else(|| {
+                DevRecapError::InvalidTimespan(format!(
+                    "'{}' does not exist in the local timezone",
+                    input
+                ))
+            })
+    }
+
+    /// Parse a date-time without an offset
+    fn parse_naive_datetime(input: &str) -> Option<NaiveDateTime> {
+        const FORMATS: &[&str] = &[
+            "%Y-%m-%dT%H:%M:%S",
+            "%Y-%m-%dT%H:%M",
+            "%Y-%m-%d %H:%M:%S",
+            "%Y-%m-%d %H:%M",
+        ];
+
+        FORMATS
+            .iter()
+            .find_map(|format| NaiveDateTime::parse_from_str(input, format).ok())
     }
 }
 
@@ -202,6 +437,76 @@ mod tests {
         assert!(!timespan.contains(&old_date));
     }
 
+    #[test]
+    fn test_last_weekday() {
+        let date = |day| NaiveDate::from_ymd_opt(2025, 1, day).unwrap();
+        // 2025-01-06 is a Monday
+        assert_eq!(Timespan::last_weekday(date(6)), date(3));
+        assert_eq!(Timespan::last_weekday(date(7)), date(6));
+        assert_eq!(Timespan::last_weekday(date(5)), date(3));
+        assert_eq!(Timespan::last_weekday(date(4)), date(3));
+        assert_eq!(Timespan::last_weekday(date(10)), date(9));
+
+        let timespan = Timespan::since_last_weekday();
+        assert!(timespan.start < timespan.end);
+        assert!(timespan.end - timespan.start <= chrono::Duration::days(4));
+    }
+
+    #[test]
+    fn test_parse_bound_rfc3339() {
+        let bound = Timespan::parse_bound("2025-01-15T09:30:00+02:00", false, false).unwrap();
+        assert_eq!(bound.to_rfc3339(), "2025-01-15T07:30:00+00:00");
+
+        // Offset in the input wins over --utc
+        let bound = Timespan::parse_bound("2025-01-15T09:30:00Z", true, true).unwrap();
+        assert_eq!(bound.to_rfc3339(), "2025-01-15T09:30:00+00:00");
+    }
+
+    #[test]
+    fn test_parse_bound_bare_date_utc() {
+        let start = Timespan::parse_bound("2025-01-15", false, true).unwrap();
+        let end = Timespan::parse_bound("2025-01-15", true, true).unwrap();
+        assert_eq!(start.to_rfc3339(), "2025-01-15T00:00:00+00:00");
+        assert_eq!(end.to_rfc3339(), "2025-01-15T23:59:59+00:00");
+    }
+
+    #[test]
+    fn test_parse_bound_bare_date_local() {
+        let start = Timespan::parse_bound("2025-01-15", false, false).unwrap();
+        let expected = Local
+            .with_ymd_and_hms(2025, 1, 15, 0, 0, 0)
+            .unwrap()
+            .with_timezone(&Utc);
+        assert_eq!(start, expected);
+    }
+
+    #[test]
+    fn test_parse_bound_naive_datetime() {
+        let bound = Timespan::parse_bound("2025-01-15 18:45", true, true).unwrap();
+        assert_eq!(bound.to_rfc3339(), "2025-01-15T18:45:00+00:00");
+
+        let bound = Timespan::parse_bound("2025-01-15T18:45:10", false, true).unwrap();
+        assert_eq!(bound.to_rfc3339(), "2025-01-15T18:45:10+00:00");
+    }
+
+    #[test]
+    fn test_parse_bound_invalid() {
+        assert!(Timespan::parse_bound("yesterday", false, false).is_err());
+        assert!(Timespan::parse_bound("2025-13-01", false, true).is_err());
+    }
+
+    #[test]
+    fn test_commit_is_breaking() {
+        let commit = |message: &str| Commit::test("abc123", message);
+
+        assert!(commit("feat(api)!: drop the v1 endpoints").is_breaking());
+        assert!(commit("refactor!: rename config keys").is_breaking());
+        assert!(commit("feat: new auth flow\n\nBREAKING CHANGE: tokens must be reissued").is_breaking());
+        assert!(!commit("feat: add export").is_breaking());
+        assert!(!commit("Fix crash on startup! Again: see #12").is_breaking());
+        assert!(!commit("docs: explain what a BREAKING CHANGE: footer is").is_breaking());
+    }
+
     #[test]
     fn test_repo_stats() {
         let commits = vec![
@@ -212,14 +517,16 @@ mod tests {
                     name: "Test".to_string(),
                     email: "test@example.com".to_string(),
                 },
-                timestamp: Utc::now(),
+                timestamp: Utc::now().fixed_offset(),
                 message: "Test commit #123".to_string(),
                 summary: "Test commit".to_string(),
                 body: None,
                 files_changed: vec!["file1.rs".to_string(), "file2.rs".to_string()],
+                file_churn: vec![10, 5],
                 insertions: 10,
                 deletions: 5,
                 pr_numbers: vec![123],
+                patch_id: None,
             },
         ];
 
@@ -229,6 +536,7 @@ mod tests {
         assert_eq!(stats.total_insertions, 10);
         assert_eq!(stats.total_deletions, 5);
         assert_eq!(stats.pr_count, 1);
+        assert_eq!(stats.directory_changes.get("(root)"), Some(&2));
         assert_eq!(stats.net_lines_changed(), 5);
     }
 }
@@ -1,15 +1,44 @@
-use crate::error::Result;
-use crate::git::{Author, Commit, Timespan};
-use chrono::{DateTime, TimeZone, Utc};
+use crate::error::{DevRecapError, Result};
+use crate::git::commit_index::{self, IndexEntry};
+use crate::git::diff_cache::{DiffStatCache, DiffStats};
+use crate::git::{Author, Commit, CommitDate, GitBackend, Timespan};
+use crate::timings::{Phase, Timings};
+use chrono::{DateTime, FixedOffset, Offset, TimeZone, Utc};
 use git2::Repository as Git2Repository;
-use std::path::Path;
+use regex::{Regex, RegexBuilder};
+use std::ops::ControlFlow;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
 
 /// Parser for extracting commits from a git repository
 pub struct Parser {
     /// Author email filter
     author_email: Option<String>,
+    /// Author email filters of a team, any of which a commit may match
+    authors: Vec<String>,
     /// Timespan filter
     timespan: Timespan,
+    /// Maximum time to spend parsing one repository
+    time_budget: Option<Duration>,
+    /// Maximum number of commits to collect
+    max_commits: Option<usize>,
+    /// Where to record diff-stat time, if measuring
+    timings: Option<Arc<Timings>>,
+    /// Persistent diff stats from previous runs
+    diff_cache: Option<Arc<DiffStatCache>>,
+    /// Library used to read the repository
+    backend: GitBackend,
+    /// Timestamp used for filtering and stats
+    commit_date: CommitDate,
+    /// Whether to compute per-commit files/insertions/deletions
+    diff_stats: bool,
+    /// Whether to compute per-file line counts (for hotspots)
+    file_churn: bool,
+    /// Commits whose message matches any of these are skipped
+    ignore_patterns: Vec<Regex>,
+    /// Only commits touching this directory (relative to the repository root)
+    subdir: Option<PathBuf>,
 }
 
 impl Parser {
@@ -17,93 +46,422 @@ impl Parser {
     pub fn new(author_email: Option<String>, timespan: Timespan) -> Self {
         Self {
             author_email,
+            authors: Vec::new(),
             timespan,
+            time_budget: None,
+            max_commits: None,
+            timings: None,
+            diff_cache: None,
+            backend: GitBackend::default(),
+            commit_date: CommitDate::default(),
+            diff_stats: true,
+            file_churn: true,
+            ignore_patterns: Vec::new(),
+            subdir: None,
         }
     }
 
+    /// Compile `ignore_commit_patterns` (case-insensitive regexes)
+    pub fn compile_ignore_patterns(patterns: &[String]) -> Result<Vec<Regex>> {
+        patterns
+            .iter()
+            .map(|pattern| {
+                RegexBuilder::new(pattern)
+                    .case_insensitive(true)
+                    .build()
+                    .map_err(DevRecapError::from)
+            })
+            .collect()
+    }
+
+    /// Only collect commits by one of `authors` (matched like the author
+    /// email filter), e.g. the members of a configured team
+    pub fn with_authors(mut self, authors: Vec<String>) -> Self {
+        self.authors = authors;
+        self
+    }
+
+    /// Skip commits whose message matches any of `patterns`
+    pub fn with_ignore_patterns(mut self, patterns: Vec<Regex>) -> Self {
+        self.ignore_patterns = patterns;
+        self
+    }
+
+    /// Only collect commits that touch `subdir` (relative to the repository
+    /// root), with diff stats limited to it and paths relative to it
+    ///
+    /// Used for monorepo sub-projects; always walks with libgit2 and skips
+    /// the hook index and diff cache, which hold whole-commit stats.
+    pub fn with_subdir(mut self, subdir: PathBuf) -> Self {
+        self.subdir = Some(subdir);
+        self
+    }
+
+    /// Skip per-commit diffs, leaving files and line counts empty (much faster)
+    pub fn without_diff_stats(mut self) -> Self {
+        self.diff_stats = false;
+        self
+    }
+
+    /// Skip per-file line counts, which libgit2 can only get by building a
+    /// patch per file; hotspots are then empty and the test ratio counts files
+    pub fn without_file_churn(mut self) -> Self {
+        self.file_churn = false;
+        self
+    }
+
+    /// Read repositories with `backend` instead of libgit2
+    pub fn with_backend(mut self, backend: GitBackend) -> Self {
+        self.backend = backend;
+        self
+    }
+
+    /// Place commits by their `date` timestamp (author by default)
+    pub fn with_commit_date(mut self, date: CommitDate) -> Self {
+        self.commit_date = date;
+        self
+    }
+
+    /// Reuse (and store) per-commit diff stats in `cache`
+    pub fn with_diff_cache(mut self, cache: Arc<DiffStatCache>) -> Self {
+        self.diff_cache = Some(cache);
+        self
+    }
+
+    /// Record time spent computing diff stats into `timings`
+    pub fn with_timings(mut self, timings: Arc<Timings>) -> Self {
+        self.timings = Some(timings);
+        self
+    }
+
+    /// Collect at most `max` commits (the most recent ones)
+    pub fn with_max_commits(mut self, max: usize) -> Self {
+        self.max_commits = Some(max);
+        self
+    }
+
+    /// Give up on a repository (with `RepoTimeout`) once parsing takes longer than `budget`
+    pub fn with_time_budget(mut self, budget: Duration) -> Self {
+        self.time_budget = Some(budget);
+        self
+    }
+
     /// Parse commits from a repository
     pub fn parse_commits(&self, repo_path: &Path) -> Result<Vec<Commit>> {
+        self.parse_commits_capped(repo_path).map(|(commits, _)| commits)
+    }
+
+    /// Parse commits from a repository, also reporting whether matching
+    /// commits were left out because of the commit cap
+    pub fn parse_commits_capped(&self, repo_path: &Path) -> Result<(Vec<Commit>, bool)> {
+        let mut commits = Vec::new();
+        let capped = self.visit_commits(repo_path, |commit| {
+            commits.push(commit);
+            ControlFlow::Continue(())
+        })?;
+        Ok((commits, capped))
+    }
+
+    /// Read exactly the commits `hashes` names, in that order, without
+    /// walking the history or applying any filter (`dev-recap regen`)
+    pub fn commits_by_hash(&self, repo_path: &Path, hashes: &[String]) -> Result<Vec<Commit>> {
+        let repo = Git2Repository::open(repo_path)?;
+        hashes
+            .iter()
+            .map(|hash| {
+                let oid = git2::Oid::from_str(hash)?;
+                let git_commit = repo.find_commit(oid)?;
+                let diff_stats = self.cached_diff_stats(oid, || {
+                    Self::get_diff_stats(&repo, &git_commit, self.subdir.as_deref(), self.file_churn)
+                })?;
+                Ok(Self::build_commit(
+                    hash.clone(),
+                    Self::extract_author(&git_commit),
+                    Self::convert_timestamp(&git_commit, self.commit_date),
+                    Self::extract_message(&git_commit),
+                    diff_stats,
+                ))
+            })
+            .collect()
+    }
+
+    /// Stream matching commits, newest first, to `visit` without collecting them
+    ///
+    /// Lets callers aggregate over huge histories in constant memory; return
+    /// `ControlFlow::Break(())` from `visit` to stop early. Returns true if the
+    /// walk stopped at the commit cap while more matching commits remained.
+    pub fn visit_commits(
+        &self,
+        repo_path: &Path,
+        mut visit: impl FnMut(Commit) -> ControlFlow<()>,
+    ) -> Result<bool> {
+        // Only the libgit2 walk filters by path
+        if self.subdir.is_some() {
+            return self.visit_commits_libgit2(repo_path, &mut visit);
+        }
+
+        // Hooked repositories may not need a walk at all; any problem with
+        // the index just means walking as usual
+        if let Ok(Some(entries)) = commit_index::covered_commits(repo_path, &self.timespan) {
+            // Entries recorded by older versions lack the author date
+            let dated = self.commit_date == CommitDate::Committer
+                || entries.iter().all(|entry| entry.author_timestamp.is_some());
+            if dated {
+                tracing::debug!(repo = %repo_path.display(), "reading commits from the hook index");
+                return Ok(self.visit_indexed(entries, &mut visit));
+            }
+        }
+
+        match self.backend {
+            GitBackend::Libgit2 => self.visit_commits_libgit2(repo_path, &mut visit),
+            #[cfg(feature = "gix")]
+            GitBackend::Gix => crate::git::gix_backend::visit_commits(self, repo_path, &mut visit),
+            #[cfg(not(feature = "gix"))]
+            GitBackend::Gix => Err(DevRecapError::config(
+                "git_backend = \"gix\" requires dev-recap built with the `gix` feature",
+            )),
+        }
+    }
+
+    /// Walk commits with libgit2
+    fn visit_commits_libgit2(
+        &self,
+        repo_path: &Path,
+        visit: &mut dyn FnMut(Commit) -> ControlFlow<()>,
+    ) -> Result<bool> {
+        let started = Instant::now();
         let repo = Git2Repository::open(repo_path)?;
         let mut revwalk = repo.revwalk()?;
 
-        // Start from HEAD
-        revwalk.push_head()?;
+        // Start from HEAD, which may also be detached; unlike peeling,
+        // `push_head` reports a damaged HEAD commit as an object database error
+        match repo.head() {
+            Ok(_) => revwalk.push_head()?,
+            // Freshly initialized: the branch HEAD names has no commits yet
+            Err(e) if e.code() == git2::ErrorCode::UnbornBranch => return Err(DevRecapError::NoCommitsYet),
+            Err(e) => return Err(e.into()),
+        }
 
         // Set sorting to chronological order
         revwalk.set_sorting(git2::Sort::TIME)?;
 
-        let mut commits = Vec::new();
+        let mut visited = 0;
 
         for oid in revwalk {
+            self.check_time_budget(started)?;
+
             let oid = oid?;
             let git_commit = repo.find_commit(oid)?;
 
             // Convert timestamp
-            let timestamp = Self::convert_timestamp(&git_commit);
+            let timestamp = Self::convert_timestamp(&git_commit, self.commit_date);
 
-            // Filter by timespan
-            if !self.timespan.contains(&timestamp) {
+            // Get author info
+            let author = Self::extract_author(&git_commit);
+
+            // Filter by timespan and author
+            if !self.accepts(&timestamp, &author) {
                 continue;
             }
 
-            // Get author info
-            let author = Self::extract_author(&git_commit);
+            let message = Self::extract_message(&git_commit);
+            if self.is_ignored(&message) {
+                continue;
+            }
 
-            // Filter by author email if specified
-            if let Some(ref filter_email) = self.author_email {
-                if !author.email.to_lowercase().contains(&filter_email.to_lowercase()) {
+            if let Some(ref subdir) = self.subdir {
+                if !Self::touches_subdir(&git_commit, subdir)? {
                     continue;
                 }
             }
 
-            // Extract commit data
-            let hash = oid.to_string();
-            let short_hash = format!("{:.7}", hash);
-            let message = git_commit.message().unwrap_or("").to_string();
-            let (summary, body) = Self::split_message(&message);
+            // Commits are walked newest first, so the cap keeps the most recent
+            if self.cap_reached(visited) {
+                return Ok(true);
+            }
 
             // Get diff stats
-            let (files_changed, insertions, deletions) =
-                Self::get_diff_stats(&repo, &git_commit)?;
-
-            // Detect PR numbers
-            let pr_numbers = crate::git::github::extract_pr_numbers(&message);
-
-            commits.push(Commit {
-                hash,
-                short_hash,
-                author,
-                timestamp,
-                message,
-                summary,
-                body,
-                files_changed,
-                insertions,
-                deletions,
-                pr_numbers,
-            });
+            let diff_stats = self.cached_diff_stats(oid, || {
+                Self::get_diff_stats(&repo, &git_commit, self.subdir.as_deref(), self.file_churn)
+            })?;
+
+            visited += 1;
+            let commit = Self::build_commit(oid.to_string(), author, timestamp, message, diff_stats);
+            if visit(commit).is_break() {
+                break;
+            }
+        }
+
+        Ok(false)
+    }
+
+    /// Visit commits recorded by the post-commit hook, newest first
+    fn visit_indexed(
+        &self,
+        entries: Vec<IndexEntry>,
+        visit: &mut dyn FnMut(Commit) -> ControlFlow<()>,
+    ) -> bool {
+        let mut visited = 0;
+
+        for entry in entries {
+            let author = entry.author();
+            let timestamp = match self.commit_date {
+                CommitDate::Author => entry.author_timestamp.unwrap_or(entry.timestamp),
+                CommitDate::Committer => entry.timestamp,
+            };
+            if !self.accepts(&timestamp, &author) || self.is_ignored(&entry.message) {
+                continue;
+            }
+            if self.cap_reached(visited) {
+                return true;
+            }
+
+            visited += 1;
+            let diff_stats = if self.diff_stats { entry.diff_stats } else { DiffStats::default() };
+            let commit = Self::build_commit(entry.hash, author, timestamp, entry.message, diff_stats);
+            if visit(commit).is_break() {
+                break;
+            }
+        }
+
+        false
+    }
+
+    /// Fail with `RepoTimeout` once the walk has exceeded its time budget
+    pub(crate) fn check_time_budget(&self, started: Instant) -> Result<()> {
+        match self.time_budget {
+            Some(budget) if started.elapsed() > budget => Err(DevRecapError::RepoTimeout {
+                seconds: budget.as_secs(),
+            }),
+            _ => Ok(()),
+        }
+    }
+
+    /// Whether a commit passes the timespan and author filters
+    pub(crate) fn accepts(&self, timestamp: &DateTime<FixedOffset>, author: &Author) -> bool {
+        if !self.timespan.contains(timestamp) {
+            return false;
+        }
+
+        // Filter by author email if specified
+        let email = author.email.to_lowercase();
+        let matches = |filter: &String| email.contains(&filter.to_lowercase());
+        if !self.authors.is_empty() && !self.authors.iter().any(matches) {
+            return false;
+        }
+        self.author_email.as_ref().is_none_or(matches)
+    }
+
+    /// Timestamp used for filtering and stats
+    #[cfg(feature = "gix")]
+    pub(crate) fn commit_date(&self) -> CommitDate {
+        self.commit_date
+    }
+
+    /// Whether a commit message matches one of the ignore patterns
+    pub(crate) fn is_ignored(&self, message: &str) -> bool {
+        self.ignore_patterns.iter().any(|pattern| pattern.is_match(message))
+    }
+
+    /// Whether `visited` commits already fill the commit cap
+    pub(crate) fn cap_reached(&self, visited: usize) -> bool {
+        self.max_commits.is_some_and(|max| visited >= max)
+    }
+
+    /// Diff stats for a commit: from the diff cache when possible, otherwise
+    /// computed by `compute` (and timed for `--timings`)
+    pub(crate) fn cached_diff_stats(
+        &self,
+        oid: git2::Oid,
+        compute: impl FnOnce() -> Result<DiffStats>,
+    ) -> Result<DiffStats> {
+        if !self.diff_stats {
+            return Ok(DiffStats::default());
+        }
+
+        let lookup = || -> Result<DiffStats> {
+            let cache = match self.diff_cache {
+                // Sub-project stats cover part of a commit; don't mix them in
+                Some(ref cache) if self.subdir.is_none() => cache,
+                _ => return compute(),
+            };
+
+            // Entries written before per-file churn or patch IDs were tracked
+            // (or without churn) are recomputed (gix never computes patch IDs)
+            if let Some(stats) = cache.get(oid)? {
+                let has_patch_id = stats.patch_id.is_some() || self.backend == GitBackend::Gix;
+                let has_churn = !self.file_churn || stats.file_churn.len() == stats.files_changed.len();
+                if has_churn && has_patch_id {
+                    return Ok(stats);
+                }
+            }
+
+            let stats = compute()?;
+            cache.set(oid, &stats)?;
+            Ok(stats)
+        };
+
+        match self.timings {
+            Some(ref timings) => timings.time(Phase::DiffStats, lookup),
+            None => lookup(),
         }
+    }
 
-        Ok(commits)
+    /// Assemble a `Commit` from backend-independent parts
+    pub(crate) fn build_commit(
+        hash: String,
+        author: Author,
+        timestamp: DateTime<FixedOffset>,
+        message: String,
+        diff_stats: DiffStats,
+    ) -> Commit {
+        let short_hash = format!("{:.7}", hash);
+        let (summary, body) = Self::split_message(&message);
+
+        // Detect PR numbers
+        let pr_numbers = crate::git::github::extract_pr_numbers(&message);
+
+        Commit {
+            hash,
+            short_hash,
+            author,
+            timestamp,
+            message,
+            summary,
+            body,
+            files_changed: diff_stats.files_changed,
+            file_churn: diff_stats.file_churn,
+            insertions: diff_stats.insertions,
+            deletions: diff_stats.deletions,
+            pr_numbers,
+            patch_id: diff_stats.patch_id,
+        }
     }
 
-    /// Convert git2 Time to DateTime<Utc>
-    fn convert_timestamp(commit: &git2::Commit) -> DateTime<Utc> {
-        let time = commit.time();
-        Utc.timestamp_opt(time.seconds(), 0)
-            .single()
-            .unwrap_or_else(Utc::now)
+    /// The commit's author or committer time, in the UTC offset it was recorded with
+    pub(crate) fn convert_timestamp(commit: &git2::Commit, date: CommitDate) -> DateTime<FixedOffset> {
+        let time = match date {
+            CommitDate::Author => commit.author().when(),
+            CommitDate::Committer => commit.time(),
+        };
+        local_time(time.seconds(), time.offset_minutes() * 60)
     }
 
     /// Extract author information
-    fn extract_author(commit: &git2::Commit) -> Author {
+    pub(crate) fn extract_author(commit: &git2::Commit) -> Author {
         let author = commit.author();
+        let encoding = commit.message_encoding();
         Author {
-            name: author.name().unwrap_or("Unknown").to_string(),
-            email: author.email().unwrap_or("unknown@example.com").to_string(),
+            name: decode_text(author.name_bytes(), encoding),
+            email: decode_text(author.email_bytes(), encoding),
         }
     }
 
+    /// Extract the full commit message, even if it isn't valid UTF-8
+    pub(crate) fn extract_message(commit: &git2::Commit) -> String {
+        decode_text(commit.message_bytes(), commit.message_encoding())
+    }
+
     /// Split commit message into summary and body
     fn split_message(message: &str) -> (String, Option<String>) {
         let mut lines = message.lines();
@@ -119,14 +477,27 @@ impl Parser {
         }
     }
 
-    /// Get diff statistics for a commit
-    fn get_diff_stats(
+    /// Whether a commit changed anything under `subdir`, compared to its first parent
+    fn touches_subdir(commit: &git2::Commit, subdir: &Path) -> Result<bool> {
+        let entry_id = |tree: &git2::Tree| tree.get_path(subdir).ok().map(|entry| entry.id());
+        let current = entry_id(&commit.tree()?);
+        let previous = match commit.parent_count() {
+            0 => None,
+            _ => entry_id(&commit.parent(0)?.tree()?),
+        };
+        Ok(current != previous)
+    }
+
+    /// Get diff statistics for a commit, optionally limited to `subdir`;
+    /// per-file line counts are left empty unless `with_churn`
+    pub(crate) fn get_diff_stats(
         repo: &Git2Repository,
         commit: &git2::Commit,
-    ) -> Result<(Vec<String>, u32, u32)> {
+        subdir: Option<&Path>,
+        with_churn: bool,
+    ) -> Result<DiffStats> {
         let mut files_changed = Vec::new();
-        let insertions;
-        let deletions;
+        let mut file_churn = Vec::new();
 
         // Get the tree for this commit
         let tree = commit.tree()?;
@@ -138,37 +509,90 @@ impl Parser {
         };
 
         // Create diff
-        let diff = if let Some(parent_tree) = parent_tree {
-            repo.diff_tree_to_tree(Some(&parent_tree), Some(&tree), None)?
-        } else {
-            repo.diff_tree_to_tree(None, Some(&tree), None)?
-        };
+        let mut options = git2::DiffOptions::new();
+        if let Some(subdir) = subdir {
+            options.pathspec(subdir);
+        }
+        let diff = repo.diff_tree_to_tree(parent_tree.as_ref(), Some(&tree), Some(&mut options))?;
 
         // Get stats
         let stats = diff.stats()?;
-        insertions = stats.insertions() as u32;
-        deletions = stats.deletions() as u32;
-
-        // Collect file names
-        diff.foreach(
-            &mut |delta, _| {
-                if let Some(path) = delta.new_file().path() {
-                    files_changed.push(path.to_string_lossy().to_string());
+        let insertions = stats.insertions() as u32;
+        let deletions = stats.deletions() as u32;
+
+        // Collect file names and per-file line counts
+        for (idx, delta) in diff.deltas().enumerate() {
+            if let Some(path) = delta.new_file().path() {
+                let path = subdir
+                    .and_then(|subdir| path.strip_prefix(subdir).ok())
+                    .unwrap_or(path);
+                files_changed.push(path.to_string_lossy().to_string());
+                if !with_churn {
+                    continue;
                 }
-                true
-            },
-            None,
-            None,
-            None,
-        )?;
 
-        Ok((files_changed, insertions, deletions))
+                let churn = match git2::Patch::from_diff(&diff, idx)? {
+                    Some(patch) => {
+                        let (_, added, removed) = patch.line_stats()?;
+                        (added + removed) as u32
+                    }
+                    None => 0, // binary
+                };
+                file_churn.push(churn);
+            }
+        }
+
+        // Identifies the change itself, so cherry-picks can be collapsed
+        let patch_id = diff.patchid(None)?.to_string();
+
+        Ok(DiffStats {
+            files_changed,
+            file_churn,
+            insertions,
+            deletions,
+            patch_id: Some(patch_id),
+        })
+    }
+}
+
+/// Seconds since the epoch in the committer's own timezone
+///
+/// Keeping the offset lets "most active day" and displayed times reflect the
+/// committer's calendar rather than UTC's.
+pub(crate) fn local_time(seconds: i64, offset_seconds: i32) -> DateTime<FixedOffset> {
+    let offset = FixedOffset::east_opt(offset_seconds).unwrap_or_else(|| Utc.fix());
+    offset
+        .timestamp_opt(seconds, 0)
+        .single()
+        .unwrap_or_else(|| Utc::now().fixed_offset())
+}
+
+/// Decode commit text that libgit2 won't hand out as `&str`
+///
+/// Old commits are often latin-1; when the commit's `encoding` header says so
+/// the bytes are decoded exactly, otherwise invalid sequences become U+FFFD
+/// rather than dropping the whole text.
+fn decode_text(bytes: &[u8], encoding: Option<&str>) -> String {
+    if let Ok(text) = std::str::from_utf8(bytes) {
+        return text.to_string();
+    }
+    let latin1 = encoding.is_some_and(|encoding| {
+        matches!(
+            encoding.to_ascii_lowercase().as_str(),
+            "iso-8859-1" | "iso8859-1" | "latin1" | "latin-1"
+        )
+    });
+    if latin1 {
+        bytes.iter().map(|&b| char::from(b)).collect()
+    } else {
+        String::from_utf8_lossy(bytes).into_owned()
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use chrono::Timelike;
     use std::fs;
     use std::io::Write;
     use tempfile::TempDir;
@@ -222,6 +646,247 @@ mod tests {
         assert_eq!(commits[0].author.email, "test@example.com");
     }
 
+    #[test]
+    fn test_commits_by_hash() {
+        let temp_dir = TempDir::new().unwrap();
+        create_test_repo_with_commits(temp_dir.path()).unwrap();
+        let head = Git2Repository::open(temp_dir.path())
+            .unwrap()
+            .head()
+            .unwrap()
+            .target()
+            .unwrap()
+            .to_string();
+
+        // Named commits are read whatever the filters say
+        let parser = Parser::new(Some("someone@example.com".to_string()), Timespan::days_back(0));
+        let commits = parser.commits_by_hash(temp_dir.path(), std::slice::from_ref(&head)).unwrap();
+        assert_eq!(commits.len(), 1);
+        assert_eq!((commits[0].hash.as_str(), commits[0].pr_numbers.as_slice()), (head.as_str(), &[123][..]));
+        assert_eq!(commits[0].files_changed, ["test.txt"]);
+
+        assert!(parser.commits_by_hash(temp_dir.path(), &["0".repeat(40)]).is_err());
+    }
+
+    #[test]
+    fn test_unborn_head() {
+        let temp_dir = TempDir::new().unwrap();
+        Git2Repository::init(temp_dir.path()).unwrap();
+
+        let result = Parser::new(None, Timespan::days_back(1)).parse_commits(temp_dir.path());
+        assert!(matches!(result, Err(DevRecapError::NoCommitsYet)));
+    }
+
+    #[test]
+    fn test_detached_head() {
+        let temp_dir = TempDir::new().unwrap();
+        create_test_repo_with_commits(temp_dir.path()).unwrap();
+        let repo = Git2Repository::open(temp_dir.path()).unwrap();
+        let head = repo.head().unwrap().target().unwrap();
+        repo.set_head_detached(head).unwrap();
+
+        let commits = Parser::new(None, Timespan::days_back(1))
+            .parse_commits(temp_dir.path())
+            .unwrap();
+        assert_eq!(commits.len(), 1);
+        assert_eq!(commits[0].hash, head.to_string());
+    }
+
+    #[test]
+    fn test_commit_date() {
+        let temp_dir = TempDir::new().unwrap();
+        create_test_repo_with_commits(temp_dir.path()).unwrap();
+        let repo = Git2Repository::open(temp_dir.path()).unwrap();
+
+        // Written ten days ago, rebased just now
+        let head = repo.head().unwrap().peel_to_commit().unwrap();
+        let written = git2::Time::new(Utc::now().timestamp() - 10 * 86_400, 0);
+        let author = git2::Signature::new("Test User", "test@example.com", &written).unwrap();
+        let committer = git2::Signature::now("Test User", "test@example.com").unwrap();
+        repo.commit(
+            Some("HEAD"),
+            &author,
+            &committer,
+            "Rebased work",
+            &head.tree().unwrap(),
+            &[&head],
+        )
+        .unwrap();
+
+        let summaries = |date: CommitDate| -> Vec<String> {
+            Parser::new(None, Timespan::days_back(1))
+                .with_commit_date(date)
+                .parse_commits(temp_dir.path())
+                .unwrap()
+                .into_iter()
+                .map(|commit| commit.summary)
+                .collect()
+        };
+        assert_eq!(summaries(CommitDate::Author), vec!["Initial commit #123"]);
+        assert_eq!(summaries(CommitDate::Committer).len(), 2);
+    }
+
+    #[test]
+    fn test_timezone_offset_kept() {
+        let temp_dir = TempDir::new().unwrap();
+        let repo = Git2Repository::init(temp_dir.path()).unwrap();
+
+        // Late evening in California is already the next day in UTC
+        let late = Utc::now() - chrono::Duration::hours(2);
+        let time = git2::Time::new(late.timestamp(), -8 * 60);
+        let signature = git2::Signature::new("Test User", "test@example.com", &time).unwrap();
+        let tree = repo.find_tree(repo.index().unwrap().write_tree().unwrap()).unwrap();
+        repo.commit(Some("HEAD"), &signature, &signature, "Evening fix", &tree, &[])
+            .unwrap();
+
+        let commits = Parser::new(None, Timespan::days_back(1))
+            .parse_commits(temp_dir.path())
+            .unwrap();
+        let pacific = FixedOffset::west_opt(8 * 3600).unwrap();
+        assert_eq!(commits[0].timestamp.offset(), &pacific);
+        assert_eq!(commits[0].timestamp, late.with_timezone(&pacific).with_nanosecond(0).unwrap());
+
+        let stats = crate::git::RepoStats::from_commits(&commits);
+        let local_day = late.with_timezone(&pacific).format("%Y-%m-%d").to_string();
+        assert_eq!(stats.commit_frequency.get(&local_day), Some(&1));
+    }
+
+    #[test]
+    fn test_hook_index_used() {
+        let temp_dir = TempDir::new().unwrap();
+        create_test_repo_with_commits(temp_dir.path()).unwrap();
+        commit_index::install_hook(temp_dir.path(), false).unwrap();
+        commit_index::record_head(temp_dir.path()).unwrap();
+
+        // Rewrite the recorded message to tell the index and the history apart
+        let index = temp_dir.path().join(".git/dev-recap/commits.jsonl");
+        let recorded = fs::read_to_string(&index).unwrap();
+        fs::write(&index, recorded.replace("Initial commit", "Indexed commit")).unwrap();
+
+        let commits = Parser::new(None, Timespan::days_back(1))
+            .parse_commits(temp_dir.path())
+            .unwrap();
+        assert_eq!(commits.len(), 1);
+        assert_eq!(commits[0].summary, "Indexed commit #123");
+        assert_eq!(commits[0].pr_numbers, vec![123]);
+    }
+
+    #[test]
+    fn test_time_budget_exceeded() {
+        let temp_dir = TempDir::new().unwrap();
+        create_test_repo_with_commits(temp_dir.path()).unwrap();
+
+        let parser = Parser::new(None, Timespan::days_back(1)).with_time_budget(Duration::ZERO);
+        std::thread::sleep(Duration::from_millis(1));
+
+        let result = parser.parse_commits(temp_dir.path());
+        assert!(matches!(result, Err(DevRecapError::RepoTimeout { seconds: 0 })));
+    }
+
+    #[test]
+    fn test_diff_cache_reused() {
+        let temp_dir = TempDir::new().unwrap();
+        create_test_repo_with_commits(temp_dir.path()).unwrap();
+
+        let db = sled::open(temp_dir.path().join("cache.sled")).unwrap();
+        let cache = Arc::new(DiffStatCache::new(db.open_tree("diff_stats").unwrap()));
+
+        let parser = Parser::new(None, Timespan::days_back(1)).with_diff_cache(cache.clone());
+        let commits = parser.parse_commits(temp_dir.path()).unwrap();
+        assert_eq!(cache.len(), 1);
+
+        // A second run reads the stored stats instead of diffing again
+        let oid = git2::Oid::from_str(&commits[0].hash).unwrap();
+        let stats = DiffStats {
+            files_changed: vec!["cached.txt".to_string()],
+            file_churn: vec![7],
+            insertions: 7,
+            deletions: 0,
+            patch_id: Some("4d5b6a1c0e2f3a4b5c6d7e8f9a0b1c2d3e4f5a6b".to_string()),
+        };
+        cache.set(oid, &stats).unwrap();
+
+        let commits = parser.parse_commits(temp_dir.path()).unwrap();
+        assert_eq!(commits[0].files_changed, vec!["cached.txt"]);
+        assert_eq!(commits[0].insertions, 7);
+    }
+
+    #[test]
+    fn test_without_diff_stats() {
+        let temp_dir = TempDir::new().unwrap();
+        create_test_repo_with_commits(temp_dir.path()).unwrap();
+
+        let parser = Parser::new(None, Timespan::days_back(1)).without_diff_stats();
+        let commits = parser.parse_commits(temp_dir.path()).unwrap();
+
+        assert_eq!(commits.len(), 1);
+        assert!(commits[0].files_changed.is_empty());
+        assert_eq!((commits[0].insertions, commits[0].deletions), (0, 0));
+    }
+
+    #[test]
+    fn test_without_file_churn() {
+        let temp_dir = TempDir::new().unwrap();
+        create_test_repo_with_commits(temp_dir.path()).unwrap();
+
+        let parser = Parser::new(None, Timespan::days_back(1)).without_file_churn();
+        let commits = parser.parse_commits(temp_dir.path()).unwrap();
+
+        assert!(!commits[0].files_changed.is_empty());
+        assert!(commits[0].file_churn.is_empty());
+        assert!(commits[0].insertions > 0);
+    }
+
+    #[test]
+    fn test_visit_commits() {
+        let temp_dir = TempDir::new().unwrap();
+        create_test_repo_with_commits(temp_dir.path()).unwrap();
+
+        let parser = Parser::new(None, Timespan::days_back(1));
+        let mut summaries = Vec::new();
+        let capped = parser
+            .visit_commits(temp_dir.path(), |commit| {
+                summaries.push(commit.summary);
+                ControlFlow::Break(())
+            })
+            .unwrap();
+
+        assert_eq!(summaries, vec!["Initial commit #123"]);
+        assert!(!capped);
+    }
+
+    #[test]
+    fn test_ignore_patterns() {
+        let temp_dir = TempDir::new().unwrap();
+        create_test_repo_with_commits(temp_dir.path()).unwrap();
+
+        let patterns = Parser::compile_ignore_patterns(&["^initial".to_string()]).unwrap();
+        let parser = Parser::new(None, Timespan::days_back(1)).with_ignore_patterns(patterns);
+        assert!(parser.parse_commits(temp_dir.path()).unwrap().is_empty());
+
+        let patterns = Parser::compile_ignore_patterns(&["^wip".to_string()]).unwrap();
+        let parser = Parser::new(None, Timespan::days_back(1)).with_ignore_patterns(patterns);
+        assert_eq!(parser.parse_commits(temp_dir.path()).unwrap().len(), 1);
+
+        assert!(Parser::compile_ignore_patterns(&["(".to_string()]).is_err());
+    }
+
+    #[test]
+    fn test_max_commits() {
+        let temp_dir = TempDir::new().unwrap();
+        create_test_repo_with_commits(temp_dir.path()).unwrap();
+
+        let parser = Parser::new(None, Timespan::days_back(1)).with_max_commits(1);
+        let (commits, capped) = parser.parse_commits_capped(temp_dir.path()).unwrap();
+        assert_eq!(commits.len(), 1);
+        assert!(!capped);
+
+        let parser = Parser::new(None, Timespan::days_back(1)).with_max_commits(0);
+        let (commits, capped) = parser.parse_commits_capped(temp_dir.path()).unwrap();
+        assert!(commits.is_empty());
+        assert!(capped);
+    }
+
     #[test]
     fn test_author_filter() {
         let temp_dir = TempDir::new().unwrap();
@@ -238,6 +903,48 @@ mod tests {
         let parser = Parser::new(Some("wrong@example.com".to_string()), timespan);
         let commits = parser.parse_commits(temp_dir.path()).unwrap();
         assert_eq!(commits.len(), 0);
+
+        // A team's authors, any of which matches
+        let team = vec!["wrong@example.com".to_string(), "TEST@example.com".to_string()];
+        let parser = Parser::new(None, Timespan::days_back(1)).with_authors(team);
+        assert_eq!(parser.parse_commits(temp_dir.path()).unwrap().len(), 1);
+
+        let parser = Parser::new(None, Timespan::days_back(1)).with_authors(vec!["wrong@example.com".to_string()]);
+        assert_eq!(parser.parse_commits(temp_dir.path()).unwrap().len(), 0);
+    }
+
+    #[test]
+    fn test_non_utf8_message() {
+        let temp_dir = TempDir::new().unwrap();
+        create_test_repo_with_commits(temp_dir.path()).unwrap();
+        let repo = Git2Repository::open(temp_dir.path()).unwrap();
+
+        // Commit objects written by hand, as libgit2 only accepts UTF-8 messages
+        let tree = repo.head().unwrap().peel_to_tree().unwrap().id();
+        let parent = repo.head().unwrap().target().unwrap();
+        let time = Utc::now().timestamp();
+        let odb = repo.odb().unwrap();
+        let write_commit = |parent: git2::Oid, headers: &str, message: &[u8]| {
+            let mut raw = format!(
+                "tree {}\nparent {}\nauthor Test User <test@example.com> {} +0000\n\
+                 committer Test User <test@example.com> {} +0000\n{}\n",
+                tree, parent, time, time, headers
+            )
+            .into_bytes();
+            raw.extend_from_slice(message);
+            odb.write(git2::ObjectType::Commit, &raw).unwrap()
+        };
+        let latin1 = write_commit(parent, "encoding ISO-8859-1\n", b"Caf\xe9 menu\n");
+        let unknown = write_commit(latin1, "", b"Fix \xff parser\n");
+        repo.reference("refs/heads/master", unknown, true, "test").unwrap();
+        repo.set_head("refs/heads/master").unwrap();
+
+        let commits = Parser::new(None, Timespan::days_back(1))
+            .parse_commits(temp_dir.path())
+            .unwrap();
+        let summaries: Vec<&str> = commits.iter().map(|c| c.summary.as_str()).collect();
+        assert!(summaries.contains(&"Café menu"));
+        assert!(summaries.contains(&"Fix \u{fffd} parser"));
     }
 
     #[test]
@@ -1,8 +1,22 @@
-use crate::error::Result;
+use crate::error::{DevRecapError, Result};
 use git2::Repository as Git2Repository;
+use std::collections::HashSet;
 use std::fs;
 use std::path::{Path, PathBuf};
 
+/// Whether directory names compare case-insensitively, as on Windows
+const IGNORE_CASE: bool = cfg!(windows);
+
+/// URLs of the remotes of a repository that matter for links and attribution
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Remotes {
+    /// Where work is pushed: `origin`, or the first other remote besides `upstream`
+    pub origin: Option<String>,
+    /// The canonical repository of a fork: the `upstream` remote (unless it's
+    /// the same URL as `origin`)
+    pub upstream: Option<String>,
+}
+
 /// Scanner for discovering git repositories
 pub struct Scanner {
     /// Patterns to exclude from scanning
@@ -22,13 +36,83 @@ impl Scanner {
 
     /// Scan a directory for git repositories
     pub fn scan(&self, path: &Path) -> Result<Vec<PathBuf>> {
+        let path = Self::normalize_path(path);
+        Self::validate_root(&path)?;
         let mut repos = Vec::new();
-        self.scan_recursive(path, 0, &mut repos)?;
+        let mut visited = HashSet::new();
+        self.scan_recursive(&path, 0, &mut repos, &mut visited)?;
         Ok(repos)
     }
 
+    /// `path` as the user would write it, so the same directory always gets
+    /// the same repository paths (and cache and history keys)
+    ///
+    /// On Windows this drops the `\\?\` prefix of verbatim paths (the standard
+    /// library adds it back itself for paths over 260 characters) and turns a
+    /// bare drive like `D:`, which means "the current directory on D:", into
+    /// the drive root `D:\`. Other platforms get `path` unchanged.
+    pub fn normalize_path(path: &Path) -> PathBuf {
+        if !cfg!(windows) {
+            return path.to_path_buf();
+        }
+        match path.to_str() {
+            Some(text) => PathBuf::from(normalize_windows_path(text)),
+            None => path.to_path_buf(),
+        }
+    }
+
+    /// Fail with `InvalidScanPath` unless `path` is an existing directory
+    ///
+    /// Without this a typo in `--path` would just find zero repositories.
+    pub fn validate_root(path: &Path) -> Result<()> {
+        let reason = match fs::metadata(path) {
+            Ok(metadata) if metadata.is_dir() => return Ok(()),
+            Ok(_) => "not a directory".to_string(),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => "no such directory".to_string(),
+            Err(e) => e.to_string(),
+        };
+        Err(DevRecapError::InvalidScanPath {
+            path: path.to_path_buf(),
+            reason,
+        })
+    }
+
+    /// The repository rooted at `path`, for `--repo`: unlike a scan, a path
+    /// that isn't a repository's root is an error rather than zero results
+    pub fn open_repository(path: &Path) -> Result<PathBuf> {
+        let path = Self::normalize_path(path);
+        match Git2Repository::open(&path) {
+            Ok(repo) if repo.workdir().is_some_and(|workdir| same_dir(workdir, &path)) => Ok(path),
+            _ => Err(DevRecapError::RepositoryNotFound(path)),
+        }
+    }
+
+    /// Deepest directory containing every path in `paths` (`/` at worst)
+    pub fn common_root(paths: &[PathBuf]) -> Option<PathBuf> {
+        let (first, rest) = paths.split_first()?;
+        first
+            .ancestors()
+            .find(|dir| rest.iter().all(|path| path.starts_with(dir)))
+            .map(Path::to_path_buf)
+    }
+
     /// Recursively scan directories
-    fn scan_recursive(&self, path: &Path, depth: u32, repos: &mut Vec<PathBuf>) -> Result<()> {
+    ///
+    /// A repository's own tree isn't walked: vendored code and build output
+    /// inside large repositories would take most of the scan, and only its
+    /// checked-out submodules are looked at for further repositories. The
+    /// scan root is the exception: a `~/code` that is itself a repository
+    /// (e.g. for dotfiles) still holds the repositories asked for.
+    /// `visited` holds the canonical path of every directory scanned so far:
+    /// symlinks and Windows junctions are followed, but one leading back to a
+    /// parent (a cycle) or to an already scanned directory is not.
+    fn scan_recursive(
+        &self,
+        path: &Path,
+        depth: u32,
+        repos: &mut Vec<PathBuf>,
+        visited: &mut HashSet<PathBuf>,
+    ) -> Result<()> {
         // Check depth limit
         if let Some(max_depth) = self.max_depth {
             if depth >= max_depth {
@@ -36,18 +120,35 @@ impl Scanner {
             }
         }
 
+        // Skip directories already reached through another link
+        if let Ok(canonical) = fs::canonicalize(path) {
+            if !visited.insert(canonical) {
+                tracing::debug!(path = %path.display(), "skipping already scanned directory");
+                return Ok(());
+            }
+        }
+
         // Check if this is a git repository
-        let is_repo = self.is_git_repository(path);
-        if is_repo {
+        if self.is_git_repository(path) {
+            tracing::debug!(path = %path.display(), "found repository");
             repos.push(path.to_path_buf());
-            // Continue scanning inside to find submodules
+
+            // Only submodules can hold further repositories, except under the root
+            if depth > 0 {
+                for submodule in self.submodule_paths(path) {
+                    let levels = submodule.strip_prefix(path).map_or(1, |relative| relative.components().count());
+                    self.scan_recursive(&submodule, depth + levels as u32, repos, visited)?;
+                }
+                return Ok(());
+            }
         }
 
         // Read directory entries
         let entries = match fs::read_dir(path) {
             Ok(entries) => entries,
-            Err(_) => {
+            Err(e) => {
                 // Skip directories we can't read (permission denied, etc.)
+                tracing::debug!(path = %path.display(), error = %e, "skipping unreadable directory");
                 return Ok(());
             }
         };
@@ -77,13 +178,19 @@ impl Scanner {
                 continue;
             }
 
-            // Skip hidden directories (except .git when checking for repos)
-            if dir_name.starts_with('.') && dir_name != ".git" {
+            // Skip hidden directories, including the `.git` of a root that is a repository
+            if dir_name.starts_with('.') {
+                continue;
+            }
+
+            // Skip Windows system folders such as `$Recycle.Bin` and
+            // `System Volume Information` when scanning a drive root
+            if is_hidden_system(&entry) {
                 continue;
             }
 
             // Recursively scan subdirectory
-            self.scan_recursive(&path, depth + 1, repos)?;
+            self.scan_recursive(&path, depth + 1, repos, visited)?;
         }
 
         Ok(())
@@ -101,32 +208,189 @@ impl Scanner {
         Git2Repository::open(path).is_ok()
     }
 
+    /// Checked-out submodules of the repository at `path`, as declared in its
+    /// `.gitmodules`, skipping excluded ones
+    fn submodule_paths(&self, path: &Path) -> Vec<PathBuf> {
+        let submodules = Git2Repository::open(path).and_then(|repo| {
+            Ok(repo
+                .submodules()?
+                .iter()
+                .map(|submodule| path.join(submodule.path()))
+                .collect::<Vec<_>>())
+        });
+        let submodules = match submodules {
+            Ok(submodules) => submodules,
+            Err(e) => {
+                tracing::debug!(path = %path.display(), error = %e, "skipping unreadable submodules");
+                return Vec::new();
+            }
+        };
+        submodules
+            .into_iter()
+            .filter(|submodule| submodule.is_dir())
+            .filter(|submodule| {
+                !submodule
+                    .file_name()
+                    .is_some_and(|name| self.should_exclude(&name.to_string_lossy()))
+            })
+            .collect()
+    }
+
     /// Check if a directory name should be excluded
+    ///
+    /// Case-insensitive on Windows, where `Node_Modules` is `node_modules`.
     fn should_exclude(&self, name: &str) -> bool {
-        for pattern in &self.exclude_patterns {
-            if name == pattern || name.contains(pattern) {
-                return true;
-            }
+        self.exclude_patterns
+            .iter()
+            .any(|pattern| exclude_matches(name, pattern, IGNORE_CASE))
+    }
+
+    /// Subdirectories of a repository matching `pattern` (e.g. `services/*`),
+    /// each recapped as its own sub-project
+    pub fn subprojects(repo_root: &Path, pattern: &str) -> Result<Vec<PathBuf>> {
+        let full = format!(
+            "{}/{}",
+            glob::Pattern::escape(&repo_root.to_string_lossy()),
+            pattern.trim_matches('/')
+        );
+        let paths = glob::glob(&full)
+            .map_err(|e| DevRecapError::config(format!("Invalid --subdirs pattern '{}': {}", pattern, e)))?;
+
+        let mut subprojects: Vec<PathBuf> = paths
+            .filter_map(|path| path.ok())
+            .filter(|path| path.is_dir() && path.as_path() != repo_root)
+            .collect();
+        subprojects.sort();
+        Ok(subprojects)
+    }
+
+    /// Split a sub-project path into its repository root and its path inside it
+    ///
+    /// Returns `None` for a repository root (or a nested repository), which
+    /// is analyzed whole.
+    pub fn split_subproject(path: &Path) -> Option<(PathBuf, PathBuf)> {
+        if path.join(".git").exists() {
+            return None;
         }
-        false
+        let root = path.ancestors().skip(1).find(|dir| dir.join(".git").exists())?;
+        let subdir = path.strip_prefix(root).ok()?;
+        Some((root.to_path_buf(), subdir.to_path_buf()))
+    }
+
+    /// Root of the repository `path` is in (or is), if any
+    pub fn enclosing_repository(path: &Path) -> Option<PathBuf> {
+        path.ancestors()
+            .find(|dir| dir.join(".git").exists())
+            .map(Path::to_path_buf)
     }
 
     /// Get repository name from path
+    ///
+    /// A repository at a drive root (`D:\`) is named after the drive.
     pub fn get_repo_name(path: &Path) -> String {
-        path.file_name()
-            .and_then(|n| n.to_str())
-            .unwrap_or("unknown")
-            .to_string()
+        if let Some(name) = path.file_name().and_then(|n| n.to_str()) {
+            return name.to_string();
+        }
+        let drive = path
+            .to_string_lossy()
+            .trim_end_matches(['\\', '/'])
+            .trim_end_matches(':')
+            .trim_start_matches(r"\\?\")
+            .to_string();
+        if drive.is_empty() {
+            "unknown".to_string()
+        } else {
+            drive
+        }
     }
 
     /// Get remote URL from a git repository
     pub fn get_remote_url(path: &Path) -> Option<String> {
-        let repo = Git2Repository::open(path).ok()?;
-        let remote = repo.find_remote("origin").ok()?;
-        remote.url().map(String::from)
+        Self::get_remotes(path).origin
+    }
+
+    /// Get the origin and upstream remote URLs of a git repository
+    pub fn get_remotes(path: &Path) -> Remotes {
+        let Ok(repo) = Git2Repository::open(path) else {
+            return Remotes::default();
+        };
+        let url = |name: &str| repo.find_remote(name).ok()?.url().map(String::from);
+
+        let origin = url("origin").or_else(|| {
+            let names = repo.remotes().ok()?;
+            names
+                .iter()
+                .flatten()
+                .filter(|name| *name != "upstream")
+                .find_map(url)
+        });
+        let upstream = url("upstream").filter(|upstream| origin.as_ref() != Some(upstream));
+        Remotes { origin, upstream }
+    }
+}
+
+/// Whether `a` and `b` name the same directory (after resolving symlinks)
+fn same_dir(a: &Path, b: &Path) -> bool {
+    match (fs::canonicalize(a), fs::canonicalize(b)) {
+        (Ok(a), Ok(b)) => a == b,
+        _ => false,
+    }
+}
+
+/// Whether directory `name` matches exclude `pattern` (a substring)
+fn exclude_matches(name: &str, pattern: &str, ignore_case: bool) -> bool {
+    if ignore_case {
+        name.to_lowercase().contains(&pattern.to_lowercase())
+    } else {
+        name.contains(pattern)
+    }
+}
+
+/// The Windows spelling of `path` without the verbatim prefix, as a drive
+/// root when it is a bare drive letter
+///
+/// `\\?\C:\code` becomes `C:\code` and `\\?\UNC\server\share` becomes
+/// `\\server\share`; other verbatim paths (e.g. `\\?\Volume{..}`) have no
+/// shorter spelling and are kept.
+fn normalize_windows_path(path: &str) -> String {
+    let path = if let Some(share) = path.strip_prefix(r"\\?\UNC\") {
+        format!(r"\\{}", share)
+    } else {
+        match path.strip_prefix(r"\\?\") {
+            Some(rest) if is_drive(rest) => rest.to_string(),
+            _ => path.to_string(),
+        }
+    };
+    if path.len() == 2 && is_drive(&path) {
+        format!("{}\\", path)
+    } else {
+        path
     }
 }
 
+/// Whether `path` starts with a drive letter and colon
+fn is_drive(path: &str) -> bool {
+    let bytes = path.as_bytes();
+    bytes.len() >= 2 && bytes[0].is_ascii_alphabetic() && bytes[1] == b':'
+}
+
+/// Whether a directory entry has the Windows hidden and system attributes
+#[cfg(windows)]
+fn is_hidden_system(entry: &fs::DirEntry) -> bool {
+    use std::os::windows::fs::MetadataExt;
+    const FILE_ATTRIBUTE_HIDDEN: u32 = 0x2;
+    const FILE_ATTRIBUTE_SYSTEM: u32 = 0x4;
+    let hidden_system = FILE_ATTRIBUTE_HIDDEN | FILE_ATTRIBUTE_SYSTEM;
+    entry
+        .metadata()
+        .is_ok_and(|metadata| metadata.file_attributes() & hidden_system == hidden_system)
+}
+
+#[cfg(not(windows))]
+fn is_hidden_system(_entry: &fs::DirEntry) -> bool {
+    false
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -193,6 +457,92 @@ mod tests {
         assert!(repos[0].ends_with("shallow"));
     }
 
+    #[test]
+    fn test_scan_invalid_root() {
+        let temp_dir = TempDir::new().unwrap();
+        let scanner = Scanner::new(vec![], None);
+
+        let missing = temp_dir.path().join("missing");
+        let err = scanner.scan(&missing).unwrap_err();
+        assert!(matches!(err, DevRecapError::InvalidScanPath { ref path, .. } if *path == missing));
+        assert!(err.to_string().contains("no such directory"));
+
+        let file = temp_dir.path().join("file.txt");
+        fs::write(&file, "not a directory").unwrap();
+        let err = scanner.scan(&file).unwrap_err();
+        assert!(err.to_string().contains("not a directory"));
+    }
+
+    #[test]
+    fn test_open_repository() {
+        let temp_dir = TempDir::new().unwrap();
+        let repo_path = temp_dir.path().join("api");
+        fs::create_dir_all(repo_path.join("src")).unwrap();
+        create_test_git_repo(&repo_path).unwrap();
+
+        assert_eq!(Scanner::open_repository(&repo_path).unwrap(), repo_path);
+        // A directory inside a repository, or holding repositories, isn't one
+        for path in [repo_path.join("src"), temp_dir.path().to_path_buf(), temp_dir.path().join("missing")] {
+            let err = Scanner::open_repository(&path).unwrap_err();
+            assert!(matches!(err, DevRecapError::RepositoryNotFound(ref p) if *p == path));
+        }
+
+        let paths = [PathBuf::from("/code/api"), PathBuf::from("/code/web/app")];
+        assert_eq!(Scanner::common_root(&paths), Some(PathBuf::from("/code")));
+        assert_eq!(Scanner::common_root(&paths[..1]), Some(PathBuf::from("/code/api")));
+        assert_eq!(Scanner::common_root(&[]), None);
+    }
+
+    #[test]
+    fn test_subprojects() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path().join("mono");
+        for dir in ["services/api", "services/web", "libs/core"] {
+            fs::create_dir_all(root.join(dir)).unwrap();
+        }
+        fs::write(root.join("services/README.md"), "services").unwrap();
+        create_test_git_repo(&root).unwrap();
+
+        let subprojects = Scanner::subprojects(&root, "services/*").unwrap();
+        assert_eq!(subprojects, vec![root.join("services/api"), root.join("services/web")]);
+        assert!(Scanner::subprojects(&root, "apps/*").unwrap().is_empty());
+
+        assert_eq!(
+            Scanner::split_subproject(&root.join("services/api")),
+            Some((root.clone(), PathBuf::from("services/api")))
+        );
+        assert_eq!(Scanner::split_subproject(&root), None);
+
+        assert_eq!(Scanner::enclosing_repository(&root.join("services/api")), Some(root.clone()));
+        assert_eq!(Scanner::enclosing_repository(&root), Some(root.clone()));
+    }
+
+    #[test]
+    fn test_get_remotes() {
+        let temp_dir = TempDir::new().unwrap();
+        let repo = Git2Repository::init(temp_dir.path()).unwrap();
+        assert_eq!(Scanner::get_remotes(temp_dir.path()), Remotes::default());
+
+        // A fork: pushed to `origin`, made from `upstream`
+        repo.remote("upstream", "https://github.com/acme/api.git").unwrap();
+        repo.remote("origin", "git@github.com:me/api.git").unwrap();
+        assert_eq!(
+            Scanner::get_remotes(temp_dir.path()),
+            Remotes {
+                origin: Some("git@github.com:me/api.git".to_string()),
+                upstream: Some("https://github.com/acme/api.git".to_string()),
+            }
+        );
+
+        // Without `origin`, another remote stands in for it
+        repo.remote_delete("origin").unwrap();
+        repo.remote("github", "https://github.com/me/api.git").unwrap();
+        assert_eq!(
+            Scanner::get_remote_url(temp_dir.path()).as_deref(),
+            Some("https://github.com/me/api.git")
+        );
+    }
+
     #[test]
     fn test_get_repo_name() {
         let path = PathBuf::from("/path/to/my-repo");
@@ -209,6 +559,44 @@ mod tests {
         assert!(scanner.should_exclude("node_modules"));
         assert!(scanner.should_exclude("target"));
         assert!(!scanner.should_exclude("src"));
+
+        assert!(exclude_matches("Node_Modules", "node_modules", true));
+        assert!(!exclude_matches("Node_Modules", "node_modules", false));
+    }
+
+    #[test]
+    fn test_normalize_windows_path() {
+        assert_eq!(normalize_windows_path(r"\\?\C:\code\api"), r"C:\code\api");
+        assert_eq!(normalize_windows_path(r"\\?\UNC\nas\code"), r"\\nas\code");
+        assert_eq!(normalize_windows_path(r"\\?\Volume{1234}\code"), r"\\?\Volume{1234}\code");
+        assert_eq!(normalize_windows_path("D:"), r"D:\");
+        assert_eq!(normalize_windows_path(r"\\?\D:"), r"D:\");
+        assert_eq!(normalize_windows_path(r"D:\code"), r"D:\code");
+    }
+
+    #[test]
+    fn test_get_repo_name_drive_root() {
+        assert_eq!(Scanner::get_repo_name(Path::new("/")), "unknown");
+        if cfg!(windows) {
+            assert_eq!(Scanner::get_repo_name(Path::new(r"D:\")), "D");
+        }
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_scanner_symlink_cycles() {
+        let temp_dir = TempDir::new().unwrap();
+        let repo = temp_dir.path().join("code").join("api");
+        fs::create_dir_all(&repo).unwrap();
+        create_test_git_repo(&repo).unwrap();
+
+        // A link back to a parent, and a second route to the same repository
+        std::os::unix::fs::symlink(temp_dir.path(), repo.join("loop")).unwrap();
+        std::os::unix::fs::symlink(&repo, temp_dir.path().join("api-link")).unwrap();
+
+        let scanner = Scanner::new(vec![], None);
+        let repos = scanner.scan(temp_dir.path()).unwrap();
+        assert_eq!(repos.len(), 1);
     }
 
     #[test]
@@ -222,6 +610,11 @@ mod tests {
         fs::create_dir_all(&submodule).unwrap();
         create_test_git_repo(&main_repo).unwrap();
         create_test_git_repo(&submodule).unwrap();
+        fs::write(
+            main_repo.join(".gitmodules"),
+            "[submodule \"sub-repo\"]\n\tpath = submodules/sub-repo\n\turl = https://example.com/sub-repo.git\n",
+        )
+        .unwrap();
 
         let scanner = Scanner::new(vec![], None);
         let repos = scanner.scan(temp_dir.path()).unwrap();
@@ -231,4 +624,38 @@ mod tests {
         assert!(repos.contains(&main_repo));
         assert!(repos.contains(&submodule));
     }
+
+    #[test]
+    fn test_scanner_skips_repository_trees() {
+        let temp_dir = TempDir::new().unwrap();
+
+        // A vendored checkout inside a repository isn't a submodule
+        let main_repo = temp_dir.path().join("main-repo");
+        let vendored = main_repo.join("vendor").join("dep");
+        fs::create_dir_all(&vendored).unwrap();
+        create_test_git_repo(&main_repo).unwrap();
+        create_test_git_repo(&vendored).unwrap();
+
+        let scanner = Scanner::new(vec![], None);
+        assert_eq!(scanner.scan(temp_dir.path()).unwrap(), vec![main_repo]);
+    }
+
+    #[test]
+    fn test_scanner_descends_into_repository_root() {
+        let temp_dir = TempDir::new().unwrap();
+
+        // A code directory that is itself a repository, holding others
+        let root = temp_dir.path().to_path_buf();
+        let project = root.join("project");
+        let vendored = project.join("vendor").join("dep");
+        fs::create_dir_all(&vendored).unwrap();
+        create_test_git_repo(&root).unwrap();
+        create_test_git_repo(&project).unwrap();
+        create_test_git_repo(&vendored).unwrap();
+
+        let scanner = Scanner::new(vec![], None);
+        let mut repos = scanner.scan(&root).unwrap();
+        repos.sort();
+        assert_eq!(repos, vec![root, project]);
+    }
 }
@@ -2,11 +2,16 @@
 // The main RepoStats struct is defined in git/mod.rs
 // This module can contain additional statistics utilities
 
-use crate::git::{Commit, RepoStats};
-use std::collections::HashMap;
+use crate::error::Result;
+use crate::git::keywords::{count_keywords, top_keywords, KEYWORD_LIMIT};
+use crate::git::{Author, Commit, RepoStats, Repository, Timespan};
+use chrono::{Datelike, NaiveDate, Timelike, Weekday};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fmt;
 
 /// Calculate commit frequency over time
-#[allow(dead_code)]
 pub fn calculate_commit_frequency(commits: &[Commit]) -> HashMap<String, u32> {
     let mut frequency = HashMap::new();
 
@@ -19,7 +24,6 @@ pub fn calculate_commit_frequency(commits: &[Commit]) -> HashMap<String, u32> {
 }
 
 /// Find the most active day
-#[allow(dead_code)]
 pub fn find_most_active_day(stats: &RepoStats) -> Option<(String, u32)> {
     stats
         .commit_frequency
@@ -29,7 +33,6 @@ pub fn find_most_active_day(stats: &RepoStats) -> Option<(String, u32)> {
 }
 
 /// Calculate average commits per day
-#[allow(dead_code)]
 pub fn average_commits_per_day(stats: &RepoStats) -> f64 {
     if stats.commit_frequency.is_empty() {
         return 0.0;
@@ -39,7 +42,6 @@ pub fn average_commits_per_day(stats: &RepoStats) -> f64 {
 }
 
 /// Get a summary of file changes
-#[allow(dead_code)]
 pub fn summarize_file_changes(commits: &[Commit]) -> HashMap<String, u32> {
     let mut file_changes: HashMap<String, u32> = HashMap::new();
 
@@ -52,43 +54,641 @@ pub fn summarize_file_changes(commits: &[Commit]) -> HashMap<String, u32> {
     file_changes
 }
 
+/// A file changed repeatedly with a high line churn
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Hotspot {
+    /// File path
+    pub path: String,
+    /// Number of commits touching the file
+    pub changes: u32,
+    /// Lines inserted plus deleted across those commits
+    pub churn: u32,
+}
+
+/// Find churn hotspots: files changed in at least two commits, ranked by
+/// total lines inserted plus deleted
+pub fn find_hotspots(commits: &[Commit], limit: usize) -> Vec<Hotspot> {
+    let mut files = HashMap::new();
+
+    for commit in commits {
+        count_file_churn(&mut files, commit);
+    }
+
+    rank_hotspots(files, limit)
+}
+
+/// Count the changes and churn of one commit's files into `files`
+fn count_file_churn(files: &mut HashMap<String, (u32, u32)>, commit: &Commit) {
+    for (i, file) in commit.files_changed.iter().enumerate() {
+        let entry = files.entry(file.clone()).or_insert((0, 0));
+        entry.0 += 1;
+        entry.1 += commit.file_churn.get(i).copied().unwrap_or(0);
+    }
+}
+
+/// The `limit` top hotspots among files' (changes, churn) counts
+fn rank_hotspots(files: HashMap<String, (u32, u32)>, limit: usize) -> Vec<Hotspot> {
+    let mut hotspots: Vec<Hotspot> = files
+        .into_iter()
+        .filter(|(_, (changes, churn))| *changes >= 2 && *churn > 0)
+        .map(|(path, (changes, churn))| Hotspot { path, changes, churn })
+        .collect();
+    hotspots.sort_by(|a, b| {
+        b.churn
+            .cmp(&a.churn)
+            .then(b.changes.cmp(&a.changes))
+            .then(a.path.cmp(&b.path))
+    });
+    hotspots.truncate(limit);
+
+    hotspots
+}
+
+/// Activity during one week of the timespan
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WeekBucket {
+    /// First day of the week (weeks start at the beginning of the timespan)
+    pub start: NaiveDate,
+    /// Commits made during the week
+    pub commits: u32,
+    /// Lines inserted plus deleted during the week
+    pub lines: u32,
+}
+
+/// Split the timespan into 7-day buckets, oldest first, and count the
+/// commits and changed lines falling into each
+pub fn weekly_velocity(commits: &[Commit], timespan: &Timespan) -> Vec<WeekBucket> {
+    let mut buckets = empty_weeks(timespan);
+    for commit in commits {
+        count_in_week(&mut buckets, commit, timespan);
+    }
+    buckets
+}
+
+/// One empty bucket per week of the timespan
+fn empty_weeks(timespan: &Timespan) -> Vec<WeekBucket> {
+    let days = (timespan.end - timespan.start).num_days().max(0);
+    let weeks = ((days + 6) / 7).max(1);
+
+    (0..weeks)
+        .map(|week| WeekBucket {
+            start: (timespan.start + chrono::Duration::weeks(week)).date_naive(),
+            commits: 0,
+            lines: 0,
+        })
+        .collect()
+}
+
+/// Count one commit into the bucket of its week, if it's in the timespan
+fn count_in_week(buckets: &mut [WeekBucket], commit: &Commit, timespan: &Timespan) {
+    if !timespan.contains(&commit.timestamp) {
+        return;
+    }
+    let offset = commit.timestamp.signed_duration_since(timespan.start).num_days();
+    // The final partial day belongs to the last week
+    let last = buckets.len() - 1;
+    let bucket = &mut buckets[(offset as usize / 7).min(last)];
+    bucket.commits += 1;
+    bucket.lines += commit.insertions + commit.deletions;
+}
+
+/// Most weeks of velocity shown in reports and prompts
+pub const VELOCITY_WEEKS_SHOWN: usize = 12;
+
+/// The most recent `VELOCITY_WEEKS_SHOWN` weeks, so long timespans don't
+/// list dozens of weeks
+pub fn recent_weeks(velocity: &[WeekBucket]) -> &[WeekBucket] {
+    &velocity[velocity.len().saturating_sub(VELOCITY_WEEKS_SHOWN)..]
+}
+
+/// Render values as a unicode sparkline (one bar per value, scaled to the maximum)
+pub fn sparkline(values: &[u32]) -> String {
+    const BARS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+
+    let max = values.iter().copied().max().unwrap_or(0);
+    values
+        .iter()
+        .map(|&value| {
+            if max == 0 {
+                BARS[0]
+            } else {
+                BARS[(value as usize * (BARS.len() - 1) + max as usize / 2) / max as usize]
+            }
+        })
+        .collect()
+}
+
+/// Hour (the committer's local time) from which a commit counts as after hours
+const WORKDAY_END_HOUR: u32 = 18;
+/// Hour (the committer's local time) before which a commit counts as after hours
+const WORKDAY_START_HOUR: u32 = 9;
+
+/// When commits were made: streaks, weekends and late nights
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct WorkPatterns {
+    /// Longest run of consecutive days with at least one commit
+    pub longest_streak_days: u32,
+    /// Percentage of commits made on Saturday or Sunday
+    pub weekend_percent: f64,
+    /// Percentage of all commits made on a weekday before 9:00 or from 18:00
+    pub after_hours_percent: f64,
+}
+
+/// Compute work patterns, reading each commit's time at its own offset, so
+/// a commit made at 22:00 in another timezone still counts as after hours
+pub fn work_patterns(commits: &[Commit]) -> WorkPatterns {
+    if commits.is_empty() {
+        return WorkPatterns::default();
+    }
+
+    let mut days = Vec::with_capacity(commits.len());
+    let (mut weekend, mut after_hours) = (0u32, 0u32);
+
+    for commit in commits {
+        let local = commit.timestamp;
+        days.push(local.date_naive());
+
+        if matches!(local.weekday(), Weekday::Sat | Weekday::Sun) {
+            weekend += 1;
+        } else if local.hour() < WORKDAY_START_HOUR || local.hour() >= WORKDAY_END_HOUR {
+            after_hours += 1;
+        }
+    }
+
+    days.sort_unstable();
+    days.dedup();
+
+    let (mut longest, mut current) = (1u32, 1u32);
+    for pair in days.windows(2) {
+        if pair[0].succ_opt() == Some(pair[1]) {
+            current += 1;
+            longest = longest.max(current);
+        } else {
+            current = 1;
+        }
+    }
+
+    let percent = |count: u32| count as f64 * 100.0 / commits.len() as f64;
+    WorkPatterns {
+        longest_streak_days: longest,
+        weekend_percent: percent(weekend),
+        after_hours_percent: percent(after_hours),
+    }
+}
+
+/// How the commit listing of the report is organized (`--group-by`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum GroupBy {
+    /// Day by day, oldest first, with a "Timeline" section in the summary
+    Day,
+}
+
+impl fmt::Display for GroupBy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Day => write!(f, "day"),
+        }
+    }
+}
+
+impl std::str::FromStr for GroupBy {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s {
+            "day" => Ok(Self::Day),
+            _ => Err(format!("unknown grouping '{}' (expected day)", s)),
+        }
+    }
+}
+
+/// Order of the repositories in the report (`--order`)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RepoOrder {
+    /// Alphabetically by name
+    #[default]
+    Name,
+    /// Most commits first
+    Commits,
+    /// Most lines changed (inserted + deleted) first
+    Lines,
+    /// Most recent commit first
+    Recent,
+}
+
+impl RepoOrder {
+    /// Every order
+    pub const ALL: [RepoOrder; 4] = [RepoOrder::Name, RepoOrder::Commits, RepoOrder::Lines, RepoOrder::Recent];
+}
+
+impl fmt::Display for RepoOrder {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Name => write!(f, "name"),
+            Self::Commits => write!(f, "commits"),
+            Self::Lines => write!(f, "lines"),
+            Self::Recent => write!(f, "recent"),
+        }
+    }
+}
+
+impl std::str::FromStr for RepoOrder {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|order| order.to_string() == s)
+            .ok_or_else(|| format!("unknown order '{}' (expected name, commits, lines or recent)", s))
+    }
+}
+
+/// Sort `items` by the repository each holds, in `order`
+///
+/// Ties (and repositories without commits under `recent`) fall back to the
+/// name, then the path, so the order doesn't depend on scan or completion order.
+pub fn sort_repositories<T>(items: &mut [T], order: RepoOrder, repo: impl Fn(&T) -> &Repository) {
+    items.sort_by(|a, b| {
+        let (a, b) = (repo(a), repo(b));
+        let by_order = match order {
+            RepoOrder::Name => std::cmp::Ordering::Equal,
+            RepoOrder::Commits => b.stats.total_commits.cmp(&a.stats.total_commits),
+            RepoOrder::Lines => {
+                let lines = |repo: &Repository| u64::from(repo.stats.total_insertions) + u64::from(repo.stats.total_deletions);
+                lines(b).cmp(&lines(a))
+            }
+            RepoOrder::Recent => {
+                let latest = |repo: &Repository| repo.commits.iter().map(|commit| commit.timestamp).max();
+                latest(b).cmp(&latest(a))
+            }
+        };
+        by_order
+            .then_with(|| a.name.to_lowercase().cmp(&b.name.to_lowercase()))
+            .then_with(|| a.path.cmp(&b.path))
+    });
+}
+
+/// Commits grouped by the day they were made in their author's own
+/// timezone, oldest day first and oldest commit first within a day
+pub fn commits_by_day<'a>(commits: impl IntoIterator<Item = &'a Commit>) -> Vec<(NaiveDate, Vec<&'a Commit>)> {
+    let mut days: BTreeMap<NaiveDate, Vec<&Commit>> = BTreeMap::new();
+    for commit in commits {
+        days.entry(commit.timestamp.date_naive()).or_default().push(commit);
+    }
+    days.into_iter()
+        .map(|(day, mut commits)| {
+            commits.sort_by_key(|commit| commit.timestamp);
+            (day, commits)
+        })
+        .collect()
+}
+
+/// Top-level directories listed in an activity breakdown
+pub const DIRECTORY_BREAKDOWN_LIMIT: usize = 5;
+
+/// Top-level directory of a changed path ("src/git/mod.rs" -> "src/"),
+/// or "(root)" for files at the repository root
+pub fn top_level_directory(path: &str) -> String {
+    match path.split_once('/') {
+        Some((dir, _)) => format!("{}/", dir),
+        None => "(root)".to_string(),
+    }
+}
+
+/// Share of file changes per top-level directory, largest first, as
+/// (directory, percent) pairs
+pub fn directory_breakdown(stats: &RepoStats, limit: usize) -> Vec<(String, f64)> {
+    let total: u32 = stats.directory_changes.values().sum();
+    if total == 0 {
+        return Vec::new();
+    }
+
+    let mut breakdown: Vec<_> = stats
+        .directory_changes
+        .iter()
+        .map(|(dir, count)| (dir.clone(), *count as f64 * 100.0 / total as f64))
+        .collect();
+    breakdown.sort_by(|a, b| b.1.total_cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    breakdown.truncate(limit);
+
+    breakdown
+}
+
+/// Format a directory breakdown as "api/ 45%, ui/ 30%"
+pub fn format_directory_breakdown(breakdown: &[(String, f64)]) -> String {
+    breakdown
+        .iter()
+        .map(|(dir, percent)| format!("{} {:.0}%", dir, percent))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// How much of the change went into test code versus source code
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TestRatio {
+    /// Changes to test files
+    pub test_files: u32,
+    /// Changes to all other files
+    pub source_files: u32,
+    /// Lines inserted plus deleted in test files
+    pub test_lines: u32,
+    /// Lines inserted plus deleted in all other files
+    pub source_lines: u32,
+}
+
+impl TestRatio {
+    /// Percentage of changed lines in test code, or of changed files when
+    /// line counts weren't collected
+    pub fn test_percent(&self) -> f64 {
+        let (test, total) = if self.test_lines + self.source_lines > 0 {
+            (self.test_lines, self.test_lines + self.source_lines)
+        } else {
+            (self.test_files, self.test_files + self.source_files)
+        };
+
+        if total == 0 {
+            0.0
+        } else {
+            test as f64 * 100.0 / total as f64
+        }
+    }
+}
+
+/// Classifies changed paths as test or source code using regex patterns
+#[derive(Debug, Clone)]
+pub struct TestClassifier {
+    patterns: Vec<Regex>,
+}
+
+impl TestClassifier {
+    /// Compile the patterns; a path matching any of them is a test
+    pub fn new(patterns: &[String]) -> Result<Self> {
+        let patterns = patterns
+            .iter()
+            .map(|pattern| Regex::new(pattern))
+            .collect::<std::result::Result<_, _>>()?;
+        Ok(Self { patterns })
+    }
+
+    /// Whether a path is test code
+    pub fn is_test(&self, path: &str) -> bool {
+        self.patterns.iter().any(|pattern| pattern.is_match(path))
+    }
+
+    /// Split the changes of the commits between test and source code
+    pub fn ratio(&self, commits: &[Commit]) -> TestRatio {
+        let mut ratio = TestRatio::default();
+        for commit in commits {
+            self.count_changes(&mut ratio, commit);
+        }
+        ratio
+    }
+
+    /// Split the changes of one commit into `ratio`
+    fn count_changes(&self, ratio: &mut TestRatio, commit: &Commit) {
+        for (i, file) in commit.files_changed.iter().enumerate() {
+            let churn = commit.file_churn.get(i).copied().unwrap_or(0);
+            if self.is_test(file) {
+                ratio.test_files += 1;
+                ratio.test_lines += churn;
+            } else {
+                ratio.source_files += 1;
+                ratio.source_lines += churn;
+            }
+        }
+    }
+}
+
+/// A repository's statistics, hotspots, velocity, test ratio and keywords,
+/// added up one commit at a time so a walk needn't keep every commit it counts
+pub struct CommitTally<'a> {
+    classifier: &'a TestClassifier,
+    timespan: Timespan,
+    stats: RepoStats,
+    pr_numbers: HashSet<u32>,
+    files: HashMap<String, (u32, u32)>,
+    velocity: Vec<WeekBucket>,
+    test_ratio: TestRatio,
+    keywords: HashMap<String, u32>,
+}
+
+impl<'a> CommitTally<'a> {
+    /// An empty tally over `timespan`, classifying tests with `classifier`
+    pub fn new(classifier: &'a TestClassifier, timespan: &Timespan) -> Self {
+        Self {
+            classifier,
+            timespan: timespan.clone(),
+            stats: RepoStats::default(),
+            pr_numbers: HashSet::new(),
+            files: HashMap::new(),
+            velocity: empty_weeks(timespan),
+            test_ratio: TestRatio::default(),
+            keywords: HashMap::new(),
+        }
+    }
+
+    /// Count one commit
+    pub fn add(&mut self, commit: &Commit) {
+        self.stats.add_commit(commit, &mut self.pr_numbers);
+        count_file_churn(&mut self.files, commit);
+        count_in_week(&mut self.velocity, commit, &self.timespan);
+        self.classifier.count_changes(&mut self.test_ratio, commit);
+        count_keywords(&mut self.keywords, commit);
+    }
+
+    /// Set the repository's statistics, keeping `hotspot_limit` hotspots
+    pub fn apply(mut self, repo: &mut Repository, hotspot_limit: usize) {
+        self.stats.pr_count = self.pr_numbers.len() as u32;
+        repo.stats = self.stats;
+        repo.hotspots = rank_hotspots(self.files, hotspot_limit);
+        repo.velocity = self.velocity;
+        repo.test_ratio = self.test_ratio;
+        repo.keywords = top_keywords(self.keywords, KEYWORD_LIMIT);
+    }
+}
+
+/// One author's line in the team leaderboard
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LeaderboardRow {
+    /// Author name (or `@login` for reviewers without commits)
+    pub name: String,
+    /// Author email (empty for reviewers without commits)
+    pub email: String,
+    /// Commits across all repositories
+    pub commits: u32,
+    /// Lines inserted
+    pub insertions: u32,
+    /// Lines deleted
+    pub deletions: u32,
+    /// Distinct PRs referenced by the author's commits
+    pub prs: u32,
+    /// Reviews submitted on merged PRs (needs GitHub delivery metrics)
+    pub reviews: u32,
+}
+
+/// Per-author totals across repositories, most commits first
+///
+/// `reviews` maps GitHub logins to review counts. Logins are matched to
+/// authors by GitHub noreply email, email user name or author name; reviewers
+/// that match nobody get their own rows.
+pub fn leaderboard(repos: &[&Repository], reviews: &BTreeMap<String, u32>) -> Vec<LeaderboardRow> {
+    let mut rows: Vec<LeaderboardRow> = Vec::new();
+    let mut prs: Vec<HashSet<(String, u32)>> = Vec::new();
+
+    for repo in repos {
+        for commit in &repo.commits {
+            let email = commit.author.email.to_lowercase();
+            let index = match rows.iter().position(|row| row.email == email) {
+                Some(index) => index,
+                None => {
+                    rows.push(LeaderboardRow {
+                        name: commit.author.name.clone(),
+                        email,
+                        ..Default::default()
+                    });
+                    prs.push(HashSet::new());
+                    rows.len() - 1
+                }
+            };
+
+            let row = &mut rows[index];
+            row.commits += 1;
+            row.insertions += commit.insertions;
+            row.deletions += commit.deletions;
+            for pr in &commit.pr_numbers {
+                prs[index].insert((repo.name.clone(), *pr));
+            }
+        }
+    }
+
+    for (row, prs) in rows.iter_mut().zip(&prs) {
+        row.prs = prs.len() as u32;
+    }
+
+    for (login, count) in reviews {
+        let author = |row: &LeaderboardRow| Author {
+            name: row.name.clone(),
+            email: row.email.clone(),
+        };
+        match rows.iter_mut().find(|row| login_matches(login, &author(row))) {
+            Some(row) => row.reviews += count,
+            None => rows.push(LeaderboardRow {
+                name: format!("@{}", login),
+                reviews: *count,
+                ..Default::default()
+            }),
+        }
+    }
+
+    rows.sort_by(|a, b| {
+        b.commits
+            .cmp(&a.commits)
+            .then(b.reviews.cmp(&a.reviews))
+            .then_with(|| a.name.cmp(&b.name))
+    });
+    rows
+}
+
+/// Whether a GitHub login plausibly belongs to a commit author
+pub(crate) fn login_matches(login: &str, author: &Author) -> bool {
+    let login = login.to_lowercase();
+    let email = author.email.to_lowercase();
+    let user = email.split('@').next().unwrap_or_default();
+
+    // GitHub noreply emails look like 12345+login@users.noreply.github.com
+    let noreply_login = user.rsplit('+').next().unwrap_or_default();
+
+    user == login
+        || (email.ends_with("@users.noreply.github.com") && noreply_login == login)
+        || author.name.to_lowercase().replace(' ', "") == login
+}
+
 /// Find the most frequently changed files
-#[allow(dead_code)]
 pub fn most_changed_files(commits: &[Commit], limit: usize) -> Vec<(String, u32)> {
     let file_changes = summarize_file_changes(commits);
 
     let mut changes: Vec<_> = file_changes.into_iter().collect();
-    changes.sort_by(|a, b| b.1.cmp(&a.1));
+    changes.sort_by_key(|b| std::cmp::Reverse(b.1));
     changes.truncate(limit);
 
     changes
 }
 
+/// Drop commits whose change was already seen, by patch ID, and record the
+/// rest in `seen`; returns how many were dropped
+///
+/// Commits are expected newest first, as parsed, so within one list the
+/// oldest instance of a change (usually the original rather than a later
+/// cherry-pick or backport) is kept. Commits without a patch ID or without
+/// changed files are always kept.
+pub fn drop_duplicate_patches(commits: &mut Vec<Commit>, seen: &mut HashSet<String>) -> usize {
+    let mut keep = vec![true; commits.len()];
+    for (i, commit) in commits.iter().enumerate().rev() {
+        if commit.files_changed.is_empty() {
+            continue;
+        }
+        if let Some(ref patch_id) = commit.patch_id {
+            keep[i] = seen.insert(patch_id.clone());
+        }
+    }
+
+    let before = commits.len();
+    let mut keep = keep.into_iter();
+    commits.retain(|_| keep.next().unwrap_or(true));
+    before - commits.len()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::git::Author;
-    use chrono::Utc;
+    use chrono::{FixedOffset, TimeZone, Utc};
 
     fn create_test_commit(files: Vec<String>, insertions: u32, deletions: u32) -> Commit {
+        let files: Vec<&str> = files.iter().map(String::as_str).collect();
         Commit {
-            hash: "abc123".to_string(),
-            short_hash: "abc123".to_string(),
-            author: Author {
-                name: "Test".to_string(),
-                email: "test@example.com".to_string(),
-            },
-            timestamp: Utc::now(),
-            message: "Test".to_string(),
-            summary: "Test".to_string(),
-            body: None,
-            files_changed: files,
             insertions,
             deletions,
-            pr_numbers: vec![],
+            ..Commit::test("abc123", "Test").with_files(&files, insertions + deletions)
         }
     }
 
+    #[test]
+    fn test_drop_duplicate_patches() {
+        let commit = |hash: &str, patch_id: Option<&str>| Commit {
+            hash: hash.to_string(),
+            patch_id: patch_id.map(String::from),
+            ..create_test_commit(vec!["src/lib.rs".to_string()], 3, 1)
+        };
+
+        // Newest first: the backport and the re-applied change are dropped
+        let mut commits = vec![
+            commit("backport", Some("p1")),
+            commit("unknown", None),
+            commit("feature", Some("p2")),
+            commit("original", Some("p1")),
+        ];
+        let mut seen = HashSet::new();
+        assert_eq!(drop_duplicate_patches(&mut commits, &mut seen), 1);
+        let hashes: Vec<&str> = commits.iter().map(|c| c.hash.as_str()).collect();
+        assert_eq!(hashes, vec!["unknown", "feature", "original"]);
+
+        // Changes already seen in another repository are dropped too
+        let mut other = vec![commit("cherry-pick", Some("p2")), commit("new", Some("p3"))];
+        assert_eq!(drop_duplicate_patches(&mut other, &mut seen), 1);
+        assert_eq!(other[0].hash, "new");
+
+        // Empty commits share a patch ID but aren't the same work
+        let empty = |hash: &str| Commit {
+            files_changed: vec![],
+            ..commit(hash, Some("empty"))
+        };
+        let mut commits = vec![empty("a"), empty("b")];
+        assert_eq!(drop_duplicate_patches(&mut commits, &mut seen), 0);
+    }
+
     #[test]
     fn test_calculate_commit_frequency() {
         let commits = vec![
@@ -134,6 +734,228 @@ mod tests {
         assert_eq!(top_files[0].1, 3); // Changed 3 times
     }
 
+    #[test]
+    fn test_find_hotspots() {
+        let commits = vec![
+            create_test_commit(vec!["a.rs".to_string(), "b.rs".to_string()], 10, 5),
+            create_test_commit(vec!["a.rs".to_string(), "c.rs".to_string()], 40, 40),
+            create_test_commit(vec!["b.rs".to_string()], 1, 0),
+            create_test_commit(vec!["d.rs".to_string()], 500, 0),
+        ];
+
+        let hotspots = find_hotspots(&commits, 5);
+        // d.rs and c.rs were only changed once
+        assert_eq!(hotspots.len(), 2);
+        assert_eq!(hotspots[0].path, "a.rs");
+        assert_eq!(hotspots[0].changes, 2);
+        assert_eq!(hotspots[0].churn, 95);
+        assert_eq!(hotspots[1].path, "b.rs");
+
+        assert_eq!(find_hotspots(&commits, 1).len(), 1);
+        assert!(find_hotspots(&commits, 0).is_empty());
+    }
+
+    #[test]
+    fn test_weekly_velocity() {
+        let end = Utc::now();
+        let timespan = Timespan::from_dates(end - chrono::Duration::days(20), end);
+
+        let mut old = create_test_commit(vec![], 10, 5);
+        old.timestamp = (end - chrono::Duration::days(18)).fixed_offset();
+        let mut recent = create_test_commit(vec![], 20, 10);
+        recent.timestamp = (end - chrono::Duration::hours(1)).fixed_offset();
+        let mut outside = create_test_commit(vec![], 1, 1);
+        outside.timestamp = (end - chrono::Duration::days(30)).fixed_offset();
+
+        let buckets = weekly_velocity(&[old, recent, outside], &timespan);
+        assert_eq!(buckets.len(), 3);
+        assert_eq!(buckets[0].commits, 1);
+        assert_eq!(buckets[0].lines, 15);
+        assert_eq!(buckets[1].commits, 0);
+        assert_eq!(buckets[2].commits, 1);
+        assert_eq!(buckets[2].lines, 30);
+        assert_eq!(buckets[0].start, timespan.start.date_naive());
+
+        // A two-week timespan has exactly two buckets
+        let timespan = Timespan::from_dates(end - chrono::Duration::days(14), end);
+        assert_eq!(weekly_velocity(&[], &timespan).len(), 2);
+
+        // A year lists only the most recent weeks
+        let timespan = Timespan::from_dates(end - chrono::Duration::days(365), end);
+        let buckets = weekly_velocity(&[], &timespan);
+        let recent = recent_weeks(&buckets);
+        assert_eq!(recent.len(), VELOCITY_WEEKS_SHOWN);
+        assert_eq!(recent.last(), buckets.last());
+        assert_eq!(recent_weeks(&buckets[..2]).len(), 2);
+    }
+
+    #[test]
+    fn test_work_patterns() {
+        let at = |day: u32, hour: u32| {
+            let mut commit = create_test_commit(vec![], 1, 0);
+            // 2025-01-06 is a Monday
+            commit.timestamp = Utc.with_ymd_and_hms(2025, 1, day, hour, 0, 0).unwrap().fixed_offset();
+            commit
+        };
+        let commits = vec![
+            at(6, 10),
+            at(7, 22), // late night
+            at(7, 11),
+            at(8, 14),
+            at(11, 12), // Saturday
+            at(13, 7),  // early morning
+        ];
+
+        let patterns = work_patterns(&commits);
+        assert_eq!(patterns.longest_streak_days, 3);
+        assert!((patterns.weekend_percent - 100.0 / 6.0).abs() < 1e-9);
+        assert!((patterns.after_hours_percent - 200.0 / 6.0).abs() < 1e-9);
+
+        // 02:00 UTC is 11:00 in Tokyo (+09:00)
+        let tokyo = FixedOffset::east_opt(9 * 3600).unwrap();
+        let mut commit = at(7, 2);
+        commit.timestamp = commit.timestamp.with_timezone(&tokyo);
+        assert_eq!(work_patterns(&[commit]).after_hours_percent, 0.0);
+
+        assert_eq!(work_patterns(&[]), WorkPatterns::default());
+    }
+
+    #[test]
+    fn test_commits_by_day() {
+        let at = |day: u32, hour: u32, hash: &str| {
+            let mut commit = create_test_commit(vec![], 1, 0);
+            commit.short_hash = hash.to_string();
+            commit.timestamp = Utc.with_ymd_and_hms(2025, 1, day, hour, 0, 0).unwrap().fixed_offset();
+            commit
+        };
+        // Newest first, as parsed
+        let commits = vec![at(8, 9, "c"), at(6, 15, "b"), at(6, 10, "a")];
+
+        let days = commits_by_day(&commits);
+        let listed: Vec<(String, Vec<&str>)> = days
+            .iter()
+            .map(|(day, commits)| (day.to_string(), commits.iter().map(|c| c.short_hash.as_str()).collect()))
+            .collect();
+        assert_eq!(
+            listed,
+            [("2025-01-06".to_string(), vec!["a", "b"]), ("2025-01-08".to_string(), vec!["c"])]
+        );
+
+        // 23:30 in India is still that day there, wherever the recap is run
+        let india = FixedOffset::east_opt(5 * 3600 + 1800).unwrap();
+        let mut late = at(8, 18, "d");
+        late.timestamp = india.with_ymd_and_hms(2025, 1, 9, 23, 30, 0).unwrap();
+        let days = commits_by_day([&late]);
+        assert_eq!(days[0].0.to_string(), "2025-01-09");
+
+        assert_eq!("day".parse(), Ok(GroupBy::Day));
+        assert!("week".parse::<GroupBy>().is_err());
+    }
+
+    #[test]
+    fn test_directory_breakdown() {
+        let commits = vec![
+            create_test_commit(
+                vec!["api/routes.rs".to_string(), "api/db/mod.rs".to_string()],
+                10,
+                5,
+            ),
+            create_test_commit(vec!["ui/app.tsx".to_string(), "README.md".to_string()], 5, 2),
+        ];
+
+        let stats = RepoStats::from_commits(&commits);
+        let breakdown = directory_breakdown(&stats, 2);
+        assert_eq!(breakdown.len(), 2);
+        assert_eq!(breakdown[0], ("api/".to_string(), 50.0));
+        assert_eq!(breakdown[1], ("(root)".to_string(), 25.0));
+        assert_eq!(format_directory_breakdown(&breakdown), "api/ 50%, (root) 25%");
+
+        assert!(directory_breakdown(&RepoStats::default(), 5).is_empty());
+    }
+
+    #[test]
+    fn test_test_classifier() {
+        let classifier = TestClassifier::new(&crate::config::default_test_patterns()).unwrap();
+        assert!(classifier.is_test("tests/integration.rs"));
+        assert!(classifier.is_test("src/api/__tests__/client.ts"));
+        assert!(classifier.is_test("web/button.test.tsx"));
+        assert!(classifier.is_test("pkg/server_test.go"));
+        assert!(classifier.is_test("app/test_models.py"));
+        assert!(!classifier.is_test("src/main.rs"));
+        assert!(!classifier.is_test("src/contest.rs"));
+
+        let commits = vec![
+            create_test_commit(vec!["tests/a.rs".to_string(), "src/a.rs".to_string()], 30, 0),
+            create_test_commit(vec!["src/b.rs".to_string()], 10, 0),
+        ];
+        let ratio = classifier.ratio(&commits);
+        assert_eq!((ratio.test_files, ratio.source_files), (1, 2));
+        assert_eq!((ratio.test_lines, ratio.source_lines), (30, 40));
+        assert!((ratio.test_percent() - 300.0 / 7.0).abs() < 1e-9);
+
+        assert!(TestClassifier::new(&["(".to_string()]).is_err());
+    }
+
+    #[test]
+    fn test_sort_repositories() {
+        let mut old = create_test_commit(vec![], 500, 0);
+        old.timestamp = Utc.with_ymd_and_hms(2025, 1, 6, 12, 0, 0).unwrap().fixed_offset();
+        let small = create_test_commit(vec![], 1, 0);
+        let mut repos = vec![
+            Repository::test("web", vec![small.clone(), small.clone()]),
+            Repository::test("Api", vec![old]),
+            Repository::test("docs", vec![]),
+        ];
+        let names = |repos: &[Repository]| repos.iter().map(|repo| repo.name.clone()).collect::<Vec<_>>();
+
+        sort_repositories(&mut repos, RepoOrder::Name, |repo| repo);
+        assert_eq!(names(&repos), ["Api", "docs", "web"]);
+        sort_repositories(&mut repos, RepoOrder::Commits, |repo| repo);
+        assert_eq!(names(&repos), ["web", "Api", "docs"]);
+        sort_repositories(&mut repos, RepoOrder::Lines, |repo| repo);
+        assert_eq!(names(&repos), ["Api", "web", "docs"]);
+        sort_repositories(&mut repos, RepoOrder::Recent, |repo| repo);
+        assert_eq!(names(&repos), ["web", "Api", "docs"]);
+        assert_eq!("lines".parse(), Ok(RepoOrder::Lines));
+        assert!("stars".parse::<RepoOrder>().is_err());
+    }
+
+    #[test]
+    fn test_leaderboard() {
+        let mut alice = create_test_commit(vec![], 10, 2);
+        alice.author = Author {
+            name: "Alice".to_string(),
+            email: "alice@example.com".to_string(),
+        };
+        alice.pr_numbers = vec![1, 2];
+        let mut bob = create_test_commit(vec![], 5, 0);
+        bob.author = Author {
+            name: "Bob".to_string(),
+            email: "99+bobby@users.noreply.github.com".to_string(),
+        };
+
+        let repo = Repository::test("repo", vec![alice.clone(), alice, bob]);
+        let reviews = BTreeMap::from([
+            ("alice".to_string(), 1),
+            ("bobby".to_string(), 4),
+            ("carol".to_string(), 2),
+        ]);
+
+        let rows = leaderboard(&[&repo], &reviews);
+        assert_eq!(rows.len(), 3);
+        assert_eq!(rows[0].name, "Alice");
+        assert_eq!((rows[0].commits, rows[0].insertions, rows[0].prs, rows[0].reviews), (2, 20, 2, 1));
+        assert_eq!((rows[1].name.as_str(), rows[1].reviews), ("Bob", 4));
+        assert_eq!((rows[2].name.as_str(), rows[2].commits, rows[2].reviews), ("@carol", 0, 2));
+    }
+
+    #[test]
+    fn test_sparkline() {
+        assert_eq!(sparkline(&[0, 4, 8]), "▁▅█");
+        assert_eq!(sparkline(&[0, 0]), "▁▁");
+        assert_eq!(sparkline(&[]), "");
+    }
+
     #[test]
     fn test_average_commits_per_day() {
         let commits = vec![
@@ -0,0 +1,219 @@
+//! Goals and OKRs the recap maps work against (`--goals`)
+//!
+//! A goals file is either markdown, where each top-level list item is a goal
+//! and the items nested under it are its key results:
+//!
+//! ```markdown
+//! ## Q3
+//! - Faster checkout
+//!   - p95 latency under 200ms
+//! - Self-serve invoices
+//! ```
+//!
+//! or TOML (by its `.toml` extension), with one `[[goals]]` table per goal:
+//!
+//! ```toml
+//! [[goals]]
+//! title = "Faster checkout"
+//! key_results = ["p95 latency under 200ms"]
+//! ```
+
+use crate::error::{DevRecapError, Result};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+/// A stated goal and its key results
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Goal {
+    /// What the goal is, e.g. "Faster checkout"
+    pub title: String,
+    /// Measurable results that mean the goal is met
+    #[serde(default)]
+    pub key_results: Vec<String>,
+}
+
+impl Goal {
+    /// The goal on one line, with its key results
+    pub fn describe(&self) -> String {
+        if self.key_results.is_empty() {
+            self.title.clone()
+        } else {
+            format!("{} (key results: {})", self.title, self.key_results.join("; "))
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct GoalsFile {
+    #[serde(default)]
+    goals: Vec<Goal>,
+}
+
+/// Read the goals in a markdown or TOML file
+///
+/// A file that can't be read or lists no goals is a config error, so a typo
+/// in `--goals` stops the run before any API call.
+pub fn load(path: &Path) -> Result<Vec<Goal>> {
+    let content = std::fs::read_to_string(path).map_err(|e| {
+        DevRecapError::config(format!("Failed to read goals file {}: {}", path.display(), e))
+    })?;
+    let goals = if path.extension().is_some_and(|ext| ext.eq_ignore_ascii_case("toml")) {
+        let file: GoalsFile = toml::from_str(&content).map_err(|e| {
+            DevRecapError::config(format!("Failed to parse goals file {}: {}", path.display(), e))
+        })?;
+        file.goals
+    } else {
+        parse_markdown(&content)
+    };
+
+    if goals.is_empty() {
+        return Err(DevRecapError::config(format!(
+            "goals file {} lists no goals (use list items in markdown, or [[goals]] tables in TOML)",
+            path.display()
+        )));
+    }
+    Ok(goals)
+}
+
+/// Goals in a markdown list: top-level items are goals, nested items their
+/// key results; headings and other text are ignored
+pub fn parse_markdown(content: &str) -> Vec<Goal> {
+    let mut goals: Vec<Goal> = Vec::new();
+    let mut goal_indent = None;
+
+    for line in content.lines() {
+        let trimmed = line.trim_start();
+        let Some(item) = list_item(trimmed) else {
+            continue;
+        };
+        let indent = line.len() - trimmed.len();
+        match (goals.last_mut(), goal_indent) {
+            (Some(goal), Some(goal_indent)) if indent > goal_indent => goal.key_results.push(item),
+            _ => {
+                goal_indent = Some(indent);
+                goals.push(Goal {
+                    title: item,
+                    key_results: Vec::new(),
+                });
+            }
+        }
+    }
+
+    goals
+}
+
+/// Goals no repository's Goal Alignment section says it advanced
+///
+/// Each summary has one bullet per goal, in order, starting with the goal;
+/// a bullet is found by the goal's title, or else by position. A goal
+/// counts as addressed once any repository's bullet for it isn't "Not
+/// addressed", so a goal one repository skipped but another moved isn't
+/// flagged in the overall recap.
+pub fn unaddressed<'a>(goals: &'a [Goal], alignments: &[&[String]]) -> Vec<&'a Goal> {
+    goals
+        .iter()
+        .enumerate()
+        .filter(|(i, goal)| {
+            let title = goal.title.to_lowercase();
+            !alignments.iter().any(|items| {
+                let bullet = items
+                    .iter()
+                    .find(|item| item.trim_start_matches(['*', '_', ' ']).to_lowercase().starts_with(&title))
+                    .or_else(|| items.get(*i));
+                bullet.is_some_and(|bullet| !bullet.to_lowercase().contains("not addressed"))
+            })
+        })
+        .map(|(_, goal)| goal)
+        .collect()
+}
+
+/// The text of a bulleted or numbered list item, without a task checkbox
+fn list_item(line: &str) -> Option<String> {
+    let text = line
+        .strip_prefix("- ")
+        .or_else(|| line.strip_prefix("* "))
+        .or_else(|| line.strip_prefix("+ "))
+        .or_else(|| {
+            let (number, rest) = line.split_once(['.', ')'])?;
+            let numbered = !number.is_empty() && number.chars().all(|c| c.is_ascii_digit());
+            rest.strip_prefix(' ').filter(|_| numbered)
+        })?;
+    let text = ["[ ] ", "[x] ", "[X] "]
+        .iter()
+        .find_map(|checkbox| text.strip_prefix(checkbox))
+        .unwrap_or(text)
+        .trim();
+    (!text.is_empty()).then(|| text.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_markdown() {
+        let goals = parse_markdown(
+            "# Q3 goals\n\nSome context.\n\n- Faster checkout\n  - p95 latency under 200ms\n  \
+             * [x] Cache the cart\n1. Self-serve invoices\n- [ ] Fewer pages\n",
+        );
+        assert_eq!(
+            goals,
+            vec![
+                Goal {
+                    title: "Faster checkout".to_string(),
+                    key_results: vec!["p95 latency under 200ms".to_string(), "Cache the cart".to_string()],
+                },
+                Goal {
+                    title: "Self-serve invoices".to_string(),
+                    key_results: vec![],
+                },
+                Goal {
+                    title: "Fewer pages".to_string(),
+                    key_results: vec![],
+                },
+            ]
+        );
+        assert_eq!(goals[0].describe(), "Faster checkout (key results: p95 latency under 200ms; Cache the cart)");
+    }
+
+    #[test]
+    fn test_unaddressed() {
+        let goals = parse_markdown("- Faster checkout\n- Self-serve invoices\n- Fewer pages\n");
+        let api = vec![
+            "**Faster checkout**: cached the cart".to_string(),
+            "Self-serve invoices: Not addressed".to_string(),
+            "Fewer pages: Not addressed".to_string(),
+        ];
+        // Titles reworded, so found by position
+        let billing = vec![
+            "Checkout: Not addressed".to_string(),
+            "Invoices: added the download page".to_string(),
+        ];
+
+        let titles = |alignments: &[&[String]]| -> Vec<String> {
+            unaddressed(&goals, alignments).iter().map(|goal| goal.title.clone()).collect()
+        };
+        assert_eq!(titles(&[&api]), ["Self-serve invoices", "Fewer pages"]);
+        assert_eq!(titles(&[&api, &billing]), ["Fewer pages"]);
+        assert_eq!(titles(&[]).len(), 3);
+    }
+
+    #[test]
+    fn test_load() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let toml_path = temp_dir.path().join("goals.toml");
+        std::fs::write(
+            &toml_path,
+            "[[goals]]\ntitle = \"Faster checkout\"\nkey_results = [\"p95 under 200ms\"]\n\n[[goals]]\ntitle = \"Docs\"\n",
+        )
+        .unwrap();
+        let goals = load(&toml_path).unwrap();
+        assert_eq!(goals.len(), 2);
+        assert_eq!(goals[0].key_results, ["p95 under 200ms"]);
+
+        let empty = temp_dir.path().join("goals.md");
+        std::fs::write(&empty, "# Nothing planned\n").unwrap();
+        assert!(load(&empty).unwrap_err().to_string().contains("lists no goals"));
+        assert!(load(&temp_dir.path().join("missing.md")).is_err());
+    }
+}
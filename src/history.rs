@@ -0,0 +1,576 @@
+//! Recap history (`dev-recap history`)
+//!
+//! Every generated recap is saved, with its parameters, summaries and the
+//! full report, to a SQLite database in the platform data directory, so past
+//! recaps can be listed and shown again without regenerating them.
+
+use crate::ai::{OverallRecap, Summary};
+use crate::error::{DevRecapError, Result};
+use crate::git::Repository;
+use chrono::{DateTime, Utc};
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+/// Database file name in the data directory ([`crate::Config::default_data_dir`])
+pub const HISTORY_FILE: &str = "history.sqlite";
+
+/// Schema of a new database; `IF NOT EXISTS` keeps opening idempotent
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS recaps (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    created_at TEXT NOT NULL,
+    scan_path TEXT NOT NULL,
+    authors TEXT NOT NULL,
+    timespan_start TEXT NOT NULL,
+    timespan_end TEXT NOT NULL,
+    command TEXT NOT NULL,
+    overall TEXT,
+    report TEXT NOT NULL,
+    anonymized INTEGER NOT NULL DEFAULT 0
+);
+CREATE TABLE IF NOT EXISTS repositories (
+    recap_id INTEGER NOT NULL REFERENCES recaps(id) ON DELETE CASCADE,
+    position INTEGER NOT NULL,
+    name TEXT NOT NULL,
+    path TEXT NOT NULL,
+    commits INTEGER NOT NULL,
+    insertions INTEGER NOT NULL,
+    deletions INTEGER NOT NULL,
+    summary TEXT,
+    error TEXT,
+    commit_hashes TEXT,
+    PRIMARY KEY (recap_id, position)
+);
+";
+
+/// Columns added to the schema since the first release; databases created
+/// before get them on open
+const ADDED_COLUMNS: &[(&str, &str, &str)] = &[
+    ("recaps", "anonymized", "INTEGER NOT NULL DEFAULT 0"),
+    ("repositories", "commit_hashes", "TEXT"),
+];
+
+/// Recap of one repository
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RepoRecap {
+    pub name: String,
+    pub path: PathBuf,
+    pub commits: u32,
+    pub insertions: u32,
+    pub deletions: u32,
+    pub summary: Option<Summary>,
+    pub error: Option<String>,
+    /// The commits the summary covers, newest first (empty for recaps saved
+    /// before they were recorded)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub commit_hashes: Vec<String>,
+}
+
+impl RepoRecap {
+    /// Recap of an analyzed repository and its summary result
+    pub fn new(repo: &Repository, summary: &Result<Summary>) -> Self {
+        let (summary, error) = match summary {
+            Ok(summary) => (Some(summary.clone()), None),
+            Err(e) => (None, Some(e.to_string())),
+        };
+        Self {
+            name: repo.name.clone(),
+            path: repo.path.clone(),
+            commits: repo.stats.total_commits,
+            insertions: repo.stats.total_insertions,
+            deletions: repo.stats.total_deletions,
+            summary,
+            error,
+            commit_hashes: repo.commits.iter().map(|commit| commit.hash.clone()).collect(),
+        }
+    }
+}
+
+/// A recap as saved in the history
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StoredRecap {
+    /// Assigned when saved
+    pub id: i64,
+    pub created_at: DateTime<Utc>,
+    pub scan_path: PathBuf,
+    pub authors: Vec<String>,
+    pub timespan_start: DateTime<Utc>,
+    pub timespan_end: DateTime<Utc>,
+    /// Command line the recap was generated with
+    pub command: String,
+    /// Whether authors were shown as pseudonyms (`--anonymize`)
+    #[serde(default)]
+    pub anonymized: bool,
+    pub overall: Option<OverallRecap>,
+    pub repositories: Vec<RepoRecap>,
+    /// The full markdown report
+    pub report: String,
+}
+
+impl StoredRecap {
+    /// Position of the repository with this name (or path)
+    pub fn find_repository(&self, name: &str) -> Option<usize> {
+        self.repositories
+            .iter()
+            .position(|repo| repo.name == name || repo.path == Path::new(name))
+    }
+
+    /// Replace a repository's recap, updating its summary in the report
+    pub fn replace_repository(&mut self, position: usize, repo: RepoRecap) {
+        let old = &self.repositories[position];
+        let old_section = report_section(old);
+        let header = format!("## Repository: {}\n\n", old.name);
+        let found = self.report.find(&header).and_then(|start| {
+            self.report[start..]
+                .find(&old_section)
+                .map(|offset| start + offset)
+        });
+
+        match found {
+            Some(start) if !old_section.is_empty() => {
+                self.report
+                    .replace_range(start..start + old_section.len(), &report_section(&repo));
+            }
+            _ => tracing::warn!(repo = %repo.name, "summary not found in the stored report"),
+        }
+        self.repositories[position] = repo;
+    }
+
+    /// Replace the overall recap, in the report too
+    pub fn replace_overall(&mut self, overall: OverallRecap) {
+        if let Some(ref old) = self.overall {
+            self.report = self.report.replacen(&old.to_markdown(), &overall.to_markdown(), 1);
+        }
+        self.overall = Some(overall);
+    }
+}
+
+/// A repository's summary (or error) as written into the report
+fn report_section(repo: &RepoRecap) -> String {
+    match (&repo.summary, &repo.error) {
+        (Some(summary), _) => format!("{}\n\n", summary.to_markdown()),
+        (None, Some(error)) => format!("**Error:** {}\n\n", error),
+        (None, None) => String::new(),
+    }
+}
+
+/// One line of `history list`
+#[derive(Debug, Clone)]
+pub struct RecapListing {
+    pub id: i64,
+    pub created_at: DateTime<Utc>,
+    pub scan_path: PathBuf,
+    pub authors: Vec<String>,
+    pub timespan_start: DateTime<Utc>,
+    pub timespan_end: DateTime<Utc>,
+    pub repositories: u32,
+}
+
+/// SQLite-backed recap history
+pub struct HistoryStore {
+    conn: Connection,
+}
+
+impl HistoryStore {
+    /// Open (or create) the history database at `path`
+    pub fn open(path: &Path) -> Result<Self> {
+        if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            std::fs::create_dir_all(parent)?;
+        }
+        let conn = Connection::open(path).map_err(history_error)?;
+        conn.execute_batch(SCHEMA).map_err(history_error)?;
+        for (table, column, definition) in ADDED_COLUMNS {
+            let exists: bool = conn
+                .query_row(
+                    "SELECT COUNT(*) > 0 FROM pragma_table_info(?1) WHERE name = ?2",
+                    params![table, column],
+                    |row| row.get(0),
+                )
+                .map_err(history_error)?;
+            if !exists {
+                conn.execute_batch(&format!("ALTER TABLE {} ADD COLUMN {} {};", table, column, definition))
+                    .map_err(history_error)?;
+            }
+        }
+        conn.execute_batch("PRAGMA foreign_keys = ON;").map_err(history_error)?;
+        Ok(Self { conn })
+    }
+
+    /// Save a recap, returning its id (`recap.id` is ignored)
+    pub fn save(&mut self, recap: &StoredRecap) -> Result<i64> {
+        let tx = self.conn.transaction().map_err(history_error)?;
+        tx.execute(
+            "INSERT INTO recaps (created_at, scan_path, authors, timespan_start, timespan_end, command, overall, report, anonymized)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+            params![
+                recap.created_at.to_rfc3339(),
+                recap.scan_path.to_string_lossy(),
+                serde_json::to_string(&recap.authors)?,
+                recap.timespan_start.to_rfc3339(),
+                recap.timespan_end.to_rfc3339(),
+                recap.command,
+                recap.overall.as_ref().map(serde_json::to_string).transpose()?,
+                recap.report,
+                recap.anonymized,
+            ],
+        )
+        .map_err(history_error)?;
+        let id = tx.last_insert_rowid();
+
+        for (position, repo) in recap.repositories.iter().enumerate() {
+            tx.execute(
+                "INSERT INTO repositories (recap_id, position, name, path, commits, insertions, deletions, summary, error, commit_hashes)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
+                params![
+                    id,
+                    position as i64,
+                    repo.name,
+                    repo.path.to_string_lossy(),
+                    repo.commits,
+                    repo.insertions,
+                    repo.deletions,
+                    repo.summary.as_ref().map(serde_json::to_string).transpose()?,
+                    repo.error,
+                    serde_json::to_string(&repo.commit_hashes)?,
+                ],
+            )
+            .map_err(history_error)?;
+        }
+
+        tx.commit().map_err(history_error)?;
+        Ok(id)
+    }
+
+    /// Save changes to a stored recap's overall recap, report and repositories
+    pub fn update(&mut self, recap: &StoredRecap) -> Result<()> {
+        let tx = self.conn.transaction().map_err(history_error)?;
+        tx.execute(
+            "UPDATE recaps SET overall = ?1, report = ?2 WHERE id = ?3",
+            params![
+                recap.overall.as_ref().map(serde_json::to_string).transpose()?,
+                recap.report,
+                recap.id,
+            ],
+        )
+        .map_err(history_error)?;
+
+        for (position, repo) in recap.repositories.iter().enumerate() {
+            tx.execute(
+                "UPDATE repositories SET name = ?1, path = ?2, commits = ?3, insertions = ?4, deletions = ?5,
+                        summary = ?6, error = ?7, commit_hashes = ?8
+                 WHERE recap_id = ?9 AND position = ?10",
+                params![
+                    repo.name,
+                    repo.path.to_string_lossy(),
+                    repo.commits,
+                    repo.insertions,
+                    repo.deletions,
+                    repo.summary.as_ref().map(serde_json::to_string).transpose()?,
+                    repo.error,
+                    serde_json::to_string(&repo.commit_hashes)?,
+                    recap.id,
+                    position as i64,
+                ],
+            )
+            .map_err(history_error)?;
+        }
+
+        tx.commit().map_err(history_error)
+    }
+
+    /// The most recent `limit` recaps, newest first
+    pub fn list(&self, limit: usize) -> Result<Vec<RecapListing>> {
+        let mut statement = self
+            .conn
+            .prepare(
+                "SELECT r.id, r.created_at, r.scan_path, r.authors, r.timespan_start, r.timespan_end,
+                        (SELECT COUNT(*) FROM repositories WHERE recap_id = r.id)
+                 FROM recaps r ORDER BY r.id DESC LIMIT ?1",
+            )
+            .map_err(history_error)?;
+
+        let rows = statement
+            .query_map(params![limit as i64], |row| {
+                Ok((
+                    row.get::<_, i64>(0)?,
+                    row.get::<_, String>(1)?,
+                    row.get::<_, String>(2)?,
+                    row.get::<_, String>(3)?,
+                    row.get::<_, String>(4)?,
+                    row.get::<_, String>(5)?,
+                    row.get::<_, u32>(6)?,
+                ))
+            })
+            .map_err(history_error)?;
+
+        rows.map(|row| {
+            let (id, created_at, scan_path, authors, start, end, repositories) =
+                row.map_err(history_error)?;
+            Ok(RecapListing {
+                id,
+                created_at: parse_time(&created_at)?,
+                scan_path: PathBuf::from(scan_path),
+                authors: serde_json::from_str(&authors)?,
+                timespan_start: parse_time(&start)?,
+                timespan_end: parse_time(&end)?,
+                repositories,
+            })
+        })
+        .collect()
+    }
+
+    /// A saved recap by id
+    pub fn get(&self, id: i64) -> Result<Option<StoredRecap>> {
+        let recap = self
+            .conn
+            .query_row(
+                "SELECT created_at, scan_path, authors, timespan_start, timespan_end, command, overall, report, anonymized
+                 FROM recaps WHERE id = ?1",
+                params![id],
+                |row| {
+                    Ok((
+                        row.get::<_, String>(0)?,
+                        row.get::<_, String>(1)?,
+                        row.get::<_, String>(2)?,
+                        row.get::<_, String>(3)?,
+                        row.get::<_, String>(4)?,
+                        row.get::<_, String>(5)?,
+                        row.get::<_, Option<String>>(6)?,
+                        row.get::<_, String>(7)?,
+                        row.get::<_, bool>(8)?,
+                    ))
+                },
+            )
+            .optional()
+            .map_err(history_error)?;
+
+        let Some((created_at, scan_path, authors, start, end, command, overall, report, anonymized)) = recap else {
+            return Ok(None);
+        };
+
+        let mut statement = self
+            .conn
+            .prepare(
+                "SELECT name, path, commits, insertions, deletions, summary, error, commit_hashes
+                 FROM repositories WHERE recap_id = ?1 ORDER BY position",
+            )
+            .map_err(history_error)?;
+        let repositories = statement
+            .query_map(params![id], |row| {
+                Ok((
+                    row.get::<_, String>(0)?,
+                    row.get::<_, String>(1)?,
+                    row.get::<_, u32>(2)?,
+                    row.get::<_, u32>(3)?,
+                    row.get::<_, u32>(4)?,
+                    row.get::<_, Option<String>>(5)?,
+                    row.get::<_, Option<String>>(6)?,
+                    row.get::<_, Option<String>>(7)?,
+                ))
+            })
+            .map_err(history_error)?
+            .map(|row| {
+                let (name, path, commits, insertions, deletions, summary, error, commit_hashes) =
+                    row.map_err(history_error)?;
+                Ok(RepoRecap {
+                    name,
+                    path: PathBuf::from(path),
+                    commits,
+                    insertions,
+                    deletions,
+                    summary: summary.map(|s| serde_json::from_str(&s)).transpose()?,
+                    error,
+                    commit_hashes: commit_hashes.map(|h| serde_json::from_str(&h)).transpose()?.unwrap_or_default(),
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(Some(StoredRecap {
+            id,
+            created_at: parse_time(&created_at)?,
+            scan_path: PathBuf::from(scan_path),
+            authors: serde_json::from_str(&authors)?,
+            timespan_start: parse_time(&start)?,
+            timespan_end: parse_time(&end)?,
+            command,
+            anonymized,
+            overall: overall.map(|o| serde_json::from_str(&o)).transpose()?,
+            repositories,
+            report,
+        }))
+    }
+}
+
+fn history_error(e: rusqlite::Error) -> DevRecapError {
+    DevRecapError::history(e.to_string())
+}
+
+fn parse_time(value: &str) -> Result<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(value)
+        .map(|time| time.with_timezone(&Utc))
+        .map_err(|e| DevRecapError::history(format!("invalid timestamp `{}`: {}", value, e)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn recap(scan_path: &str) -> StoredRecap {
+        StoredRecap {
+            id: 0,
+            created_at: Utc::now(),
+            scan_path: PathBuf::from(scan_path),
+            authors: vec!["alice@example.com".to_string()],
+            timespan_start: Utc::now() - chrono::Duration::days(14),
+            timespan_end: Utc::now(),
+            command: "dev-recap --days 14".to_string(),
+            anonymized: false,
+            overall: None,
+            repositories: vec![
+                RepoRecap {
+                    name: "api".to_string(),
+                    path: PathBuf::from("/code/api"),
+                    commits: 12,
+                    insertions: 340,
+                    deletions: 80,
+                    summary: Some(Summary::new(
+                        "api".to_string(),
+                        "Shipped the billing endpoints".to_string(),
+                        vec!["Invoices".to_string()],
+                        vec![],
+                    )),
+                    error: None,
+                    commit_hashes: vec!["b".repeat(40), "a".repeat(40)],
+                },
+                RepoRecap {
+                    name: "web".to_string(),
+                    path: PathBuf::from("/code/web"),
+                    commits: 3,
+                    insertions: 20,
+                    deletions: 5,
+                    summary: None,
+                    error: Some("Claude API error: overloaded".to_string()),
+                    commit_hashes: vec![],
+                },
+            ],
+            report: "# Dev Recap\n".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_save_and_get() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut store = HistoryStore::open(&temp_dir.path().join("history.sqlite")).unwrap();
+
+        let id = store.save(&recap("/code")).unwrap();
+        let stored = store.get(id).unwrap().unwrap();
+        assert_eq!(stored.id, id);
+        assert_eq!(stored.scan_path, PathBuf::from("/code"));
+        assert_eq!(stored.authors, vec!["alice@example.com".to_string()]);
+        assert_eq!(stored.report, "# Dev Recap\n");
+        assert_eq!(stored.repositories.len(), 2);
+        let api = &stored.repositories[0];
+        assert_eq!((api.commits, api.insertions, api.deletions), (12, 340, 80));
+        assert_eq!(
+            api.summary.as_ref().unwrap().work_summary,
+            "Shipped the billing endpoints"
+        );
+        assert_eq!(
+            stored.repositories[1].error.as_deref(),
+            Some("Claude API error: overloaded")
+        );
+
+        assert!(store.get(id + 1).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_replace_repository() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut store = HistoryStore::open(&temp_dir.path().join("history.sqlite")).unwrap();
+
+        let mut recap = recap("/code");
+        recap.report = String::from("# Dev Recap\n\n---\n\n");
+        for repo in &recap.repositories {
+            recap.report.push_str(&format!("## Repository: {}\n\n**Path:** {}\n\n", repo.name, repo.path.display()));
+            recap.report.push_str(&report_section(repo));
+            recap.report.push_str("---\n\n");
+        }
+        recap.id = store.save(&recap).unwrap();
+        assert!(recap.report.contains("**Error:** Claude API error: overloaded"));
+
+        let position = recap.find_repository("web").unwrap();
+        let summary = Summary::new("web".to_string(), "Rebuilt the checkout page".to_string(), vec![], vec![]);
+        let web = RepoRecap {
+            summary: Some(summary.clone()),
+            error: None,
+            ..recap.repositories[position].clone()
+        };
+        recap.replace_repository(position, web);
+        store.update(&recap).unwrap();
+
+        let stored = store.get(recap.id).unwrap().unwrap();
+        assert!(!stored.report.contains("**Error:**"));
+        assert!(stored.report.contains(&format!("## Repository: web\n\n**Path:** /code/web\n\n{}\n\n---", summary.to_markdown())));
+        assert!(stored.report.contains("Shipped the billing endpoints"));
+        assert_eq!(stored.repositories[1].summary.as_ref().unwrap().work_summary, "Rebuilt the checkout page");
+        assert_eq!(stored.repositories[1].error, None);
+
+        assert_eq!(stored.find_repository("/code/api"), Some(0));
+        assert_eq!(stored.find_repository("mobile"), None);
+    }
+
+    #[test]
+    fn test_replace_overall() {
+        let old = OverallRecap::new(vec!["Billing".to_string()], vec![], vec![]);
+        let mut recap = recap("/code");
+        recap.report = format!("# Dev Recap\n\n---\n\n{}---\n\n## Repository: api\n", old.to_markdown());
+        recap.overall = Some(old);
+
+        let new = OverallRecap::new(vec!["Performance".to_string()], vec![], vec![]);
+        recap.replace_overall(new.clone());
+        assert_eq!(recap.report, format!("# Dev Recap\n\n---\n\n{}---\n\n## Repository: api\n", new.to_markdown()));
+        assert_eq!(recap.overall.unwrap().themes, vec!["Performance"]);
+    }
+
+    #[test]
+    fn test_columns_added_to_old_database() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("history.sqlite");
+        let old_schema = SCHEMA
+            .replace(",\n    anonymized INTEGER NOT NULL DEFAULT 0", "")
+            .replace("    commit_hashes TEXT,\n", "");
+        assert!(!old_schema.contains("anonymized") && !old_schema.contains("commit_hashes"));
+        Connection::open(&path).unwrap().execute_batch(&old_schema).unwrap();
+
+        let mut store = HistoryStore::open(&path).unwrap();
+        let mut recap = recap("/code");
+        recap.anonymized = true;
+        let id = store.save(&recap).unwrap();
+        let stored = store.get(id).unwrap().unwrap();
+        assert!(stored.anonymized);
+        assert_eq!(stored.repositories[0].commit_hashes, ["b".repeat(40), "a".repeat(40)]);
+        assert!(stored.repositories[1].commit_hashes.is_empty());
+
+        // Opening again finds the columns there
+        HistoryStore::open(&path).unwrap();
+    }
+
+    #[test]
+    fn test_list_newest_first() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("history.sqlite");
+        let mut store = HistoryStore::open(&path).unwrap();
+        store.save(&recap("/first")).unwrap();
+        store.save(&recap("/second")).unwrap();
+
+        // Reopening keeps the history
+        let store = HistoryStore::open(&path).unwrap();
+        let listing = store.list(10).unwrap();
+        assert_eq!(listing.len(), 2);
+        assert_eq!(listing[0].scan_path, PathBuf::from("/second"));
+        assert_eq!(listing[0].repositories, 2);
+        assert_eq!(store.list(1).unwrap().len(), 1);
+    }
+}
@@ -0,0 +1,80 @@
+//! dev-recap: AI-powered git commit summarizer
+//!
+//! The `dev-recap` binary is a thin CLI over this library. Other tools
+//! (editor plugins, bots) can embed the same workflow directly:
+//!
+//! ```no_run
+//! use dev_recap::{Config, EventSink, Orchestrator, Repository, Summary, Timespan};
+//! use std::path::Path;
+//! use std::sync::Arc;
+//!
+//! // Progress events, called as each repository is summarized
+//! struct PrintProgress;
+//!
+//! impl EventSink for PrintProgress {
+//!     fn on_summary_ready(&self, repo: &Repository, _summary: &Summary) {
+//!         println!("done: {}", repo.name);
+//!     }
+//! }
+//!
+//! # async fn run() -> dev_recap::Result<()> {
+//! let config = Config::load()?;
+//! let orchestrator = Arc::new(Orchestrator::new(config)?.with_event_sink(Arc::new(PrintProgress)));
+//!
+//! let repos = orchestrator.scan_repositories(Path::new("."))?;
+//! let results = orchestrator
+//!     .analyze_repositories(repos, None, &Timespan::days_back(14), 4, false)
+//!     .await;
+//!
+//! for (repo, summary) in results {
+//!     if let Ok(summary) = summary {
+//!         println!("## {}\n{}", repo.name, summary.work_summary);
+//!     }
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+pub mod ai;
+pub mod anonymize;
+pub mod chunk;
+pub mod config;
+pub mod deliver;
+pub mod diff;
+pub mod enrich;
+pub mod error;
+pub mod error_report;
+pub mod events;
+pub mod export;
+pub mod git;
+pub mod goals;
+pub mod history;
+pub mod linear;
+pub mod links;
+pub mod mcp;
+pub mod metrics;
+pub mod orchestrator;
+pub mod preview;
+pub mod provenance;
+pub mod redact;
+pub mod schedule;
+pub mod secrets;
+pub mod server;
+pub mod slack;
+pub mod slides;
+pub mod sprint;
+pub mod theme;
+pub mod timings;
+pub mod watch;
+pub mod wsl;
+pub mod year;
+
+pub use ai::Summary;
+pub use config::Config;
+pub use error::{DevRecapError, Result};
+pub use events::{EventSink, Stage};
+pub use git::parser::Parser;
+pub use git::scanner::Scanner;
+pub use git::{Commit, RepoStats, Repository, Timespan};
+pub use orchestrator::Orchestrator;
+pub use timings::{Phase, Timings};
@@ -0,0 +1,379 @@
+//! Linear issue enrichment
+//!
+//! Commits often name the Linear issue they work on (`ENG-123: Fix login`).
+//! With `linear_api_key` set, those identifiers are resolved through
+//! Linear's GraphQL API, and each issue's title and workflow state is shown
+//! with the commits referencing it: in the summary prompt, so the model can
+//! describe the work in the issue's own terms, and in the report.
+
+use crate::error::{DevRecapError, Result};
+use crate::git::{Commit, Repository};
+use regex::Regex;
+use reqwest::Client;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::sync::{LazyLock, Mutex};
+
+/// Linear's GraphQL endpoint
+pub const DEFAULT_API_URL: &str = "https://api.linear.app/graphql";
+
+/// Issues looked up per GraphQL request
+const BATCH_SIZE: usize = 50;
+
+/// Issues resolved per repository, most referenced first
+const MAX_ISSUES: usize = 50;
+
+/// Name the issues are added to the prompt under, like an enricher's
+pub const ENRICHMENT_NAME: &str = "Linear issues";
+
+/// A Linear issue
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LinearIssue {
+    /// Team key and number, e.g. `ENG-123`
+    pub identifier: String,
+    /// Issue title
+    pub title: String,
+    /// Workflow state, e.g. "In Progress" or "Done"
+    pub state: String,
+    /// Link to the issue
+    pub url: String,
+}
+
+/// An issue and the commits referencing it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IssueRef {
+    /// The resolved issue
+    pub issue: LinearIssue,
+    /// Short hashes of the commits naming it, newest first
+    pub commits: Vec<String>,
+}
+
+impl IssueRef {
+    /// One line for the prompt or the terminal:
+    /// `ENG-123 Fix login on Safari (Done): abc1234, def5678`
+    pub fn describe(&self) -> String {
+        format!(
+            "{} {} ({}): {}",
+            self.issue.identifier,
+            self.issue.title,
+            self.issue.state,
+            self.commits.join(", ")
+        )
+    }
+}
+
+/// Issue identifiers (`ENG-123`) in `text`, in order of appearance
+///
+/// With `teams`, only identifiers of those team keys count, which keeps
+/// lookalikes such as `UTF-8` or `SHA-256` out.
+pub fn issue_identifiers(text: &str, teams: &[String]) -> Vec<String> {
+    static PATTERN: LazyLock<Regex> =
+        LazyLock::new(|| Regex::new(r"\b([A-Z][A-Z0-9]{0,9})-([1-9][0-9]{0,6})\b").expect("valid issue pattern"));
+
+    let mut identifiers: Vec<String> = Vec::new();
+    for captures in PATTERN.captures_iter(text) {
+        let team = &captures[1];
+        if !teams.is_empty() && !teams.iter().any(|key| key.eq_ignore_ascii_case(team)) {
+            continue;
+        }
+        let identifier = captures[0].to_string();
+        if !identifiers.contains(&identifier) {
+            identifiers.push(identifier);
+        }
+    }
+    identifiers
+}
+
+/// Identifiers named in `commits` with the short hashes of the commits naming
+/// them, most referenced first (ties in order of first appearance)
+pub fn referenced_issues(commits: &[Commit], teams: &[String]) -> Vec<(String, Vec<String>)> {
+    let mut references: Vec<(String, Vec<String>)> = Vec::new();
+    for commit in commits {
+        for identifier in issue_identifiers(&commit.message, teams) {
+            match references.iter_mut().find(|(known, _)| *known == identifier) {
+                Some((_, hashes)) => hashes.push(commit.short_hash.clone()),
+                None => references.push((identifier, vec![commit.short_hash.clone()])),
+            }
+        }
+    }
+    references.sort_by_key(|(_, hashes)| std::cmp::Reverse(hashes.len()));
+    references
+}
+
+/// Prompt context for a repository's issues, one line per issue
+pub fn format_issues(issues: &[IssueRef]) -> String {
+    issues
+        .iter()
+        .map(IssueRef::describe)
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Client for Linear's GraphQL API
+///
+/// Lookups are remembered for the client's lifetime, so the report reuses
+/// what the summaries already fetched.
+pub struct LinearClient {
+    api_key: String,
+    api_url: String,
+    teams: Vec<String>,
+    client: Client,
+    known: Mutex<HashMap<String, Option<LinearIssue>>>,
+}
+
+impl LinearClient {
+    /// Create a client authenticating with a personal API key
+    pub fn new(api_key: String) -> Result<Self> {
+        Self::with_base_url(api_key, None)
+    }
+
+    /// Create a client against a custom GraphQL URL
+    pub fn with_base_url(api_key: String, base_url: Option<String>) -> Result<Self> {
+        let client = Client::builder()
+            .timeout(std::time::Duration::from_secs(30))
+            .build()?;
+
+        Ok(Self {
+            api_key,
+            api_url: base_url.unwrap_or_else(|| DEFAULT_API_URL.to_string()),
+            teams: Vec::new(),
+            client,
+            known: Mutex::new(HashMap::new()),
+        })
+    }
+
+    /// Only look up identifiers of these team keys (e.g. `["ENG", "OPS"]`)
+    pub fn with_teams(mut self, teams: Vec<String>) -> Self {
+        self.teams = teams;
+        self
+    }
+
+    /// Whether a repository's commits name any issue this client looks up
+    pub fn references_issues(&self, repo: &Repository) -> bool {
+        !referenced_issues(&repo.commits, &self.teams).is_empty()
+    }
+
+    /// Issues referenced by a repository's commits; identifiers Linear
+    /// doesn't know are left out
+    pub async fn repo_issues(&self, repo: &Repository) -> Result<Vec<IssueRef>> {
+        let mut references = referenced_issues(&repo.commits, &self.teams);
+        references.truncate(MAX_ISSUES);
+
+        let identifiers: Vec<String> = references.iter().map(|(id, _)| id.clone()).collect();
+        let mut issues = self.issues(&identifiers).await?;
+        Ok(references
+            .into_iter()
+            .filter_map(|(identifier, commits)| {
+                issues
+                    .remove(&identifier)
+                    .map(|issue| IssueRef { issue, commits })
+            })
+            .collect())
+    }
+
+    /// Look up issues by identifier
+    pub async fn issues(&self, identifiers: &[String]) -> Result<HashMap<String, LinearIssue>> {
+        let missing: Vec<String> = {
+            let known = self.known.lock().expect("linear lookups poisoned");
+            identifiers
+                .iter()
+                .filter(|identifier| !known.contains_key(*identifier))
+                .cloned()
+                .collect()
+        };
+
+        for batch in missing.chunks(BATCH_SIZE) {
+            let found = self.fetch(batch).await?;
+            let mut known = self.known.lock().expect("linear lookups poisoned");
+            for (i, identifier) in batch.iter().enumerate() {
+                known.insert(identifier.clone(), found.get(&i).cloned());
+            }
+        }
+
+        let known = self.known.lock().expect("linear lookups poisoned");
+        Ok(identifiers
+            .iter()
+            .filter_map(|identifier| {
+                let issue = known.get(identifier)?.clone()?;
+                Some((identifier.clone(), issue))
+            })
+            .collect())
+    }
+
+    /// One GraphQL request for `identifiers`, one aliased field each;
+    /// returns the found issues by position
+    async fn fetch(&self, identifiers: &[String]) -> Result<HashMap<usize, LinearIssue>> {
+        let fields: String = identifiers
+            .iter()
+            .enumerate()
+            .map(|(i, identifier)| {
+                format!(
+                    "i{}: issue(id: \"{}\") {{ identifier title url state {{ name }} }} ",
+                    i, identifier
+                )
+            })
+            .collect();
+        let body = json!({ "query": format!("query {{ {}}}", fields) });
+        tracing::debug!(issues = identifiers.len(), "sending Linear request");
+
+        let response = self
+            .client
+            .post(&self.api_url)
+            .header("authorization", &self.api_key)
+            .json(&body)
+            .send()
+            .await?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let error_text = response.text().await.unwrap_or_else(|_| "Unknown error".to_string());
+            tracing::warn!(%status, body = %error_text, "Linear request failed");
+            return Err(DevRecapError::linear_api(format!(
+                "request failed with status {}: {}",
+                status, error_text
+            )));
+        }
+
+        // Unknown identifiers come back as null fields with an error each;
+        // only a response without data at all is a failure
+        let response: GraphQlResponse = response.json().await?;
+        let Some(data) = response.data else {
+            let message = response
+                .errors
+                .first()
+                .map(|error| error.message.clone())
+                .unwrap_or_else(|| "response had no data".to_string());
+            return Err(DevRecapError::linear_api(message));
+        };
+
+        Ok((0..identifiers.len())
+            .filter_map(|i| {
+                let node = data.get(format!("i{}", i))?.clone();
+                let node: IssueNode = serde_json::from_value(node).ok()?;
+                Some((i, node.into()))
+            })
+            .collect())
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct GraphQlResponse {
+    data: Option<Value>,
+    #[serde(default)]
+    errors: Vec<GraphQlError>,
+}
+
+#[derive(Debug, Deserialize)]
+struct GraphQlError {
+    message: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct IssueNode {
+    identifier: String,
+    title: String,
+    url: String,
+    state: Option<StateNode>,
+}
+
+#[derive(Debug, Deserialize)]
+struct StateNode {
+    name: String,
+}
+
+impl From<IssueNode> for LinearIssue {
+    fn from(node: IssueNode) -> Self {
+        Self {
+            identifier: node.identifier,
+            title: node.title,
+            state: node.state.map_or_else(|| "Unknown".to_string(), |state| state.name),
+            url: node.url,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::routing::post;
+
+    fn commit(short_hash: &str, message: &str) -> Commit {
+        Commit {
+            insertions: 1,
+            ..Commit::test(&format!("{}000", short_hash), message)
+        }
+    }
+
+    #[test]
+    fn test_issue_identifiers() {
+        assert_eq!(
+            issue_identifiers("ENG-123: Fix login (see ENG-123, OPS-7 and UTF-8)", &[]),
+            vec!["ENG-123", "OPS-7", "UTF-8"]
+        );
+        assert_eq!(
+            issue_identifiers("ENG-123: Fix login (OPS-7, UTF-8)", &["eng".to_string(), "OPS".to_string()]),
+            vec!["ENG-123", "OPS-7"]
+        );
+        // Not identifiers: lowercase, zero, part of a longer word
+        assert!(issue_identifiers("eng-12, ENG-0, XENG-12a", &[]).is_empty());
+    }
+
+    #[test]
+    fn test_referenced_issues() {
+        let commits = [
+            commit("aaa1111", "ENG-2: Add retry"),
+            commit("bbb2222", "ENG-1: Fix login"),
+            commit("ccc3333", "Follow-up for ENG-2"),
+        ];
+        assert_eq!(
+            referenced_issues(&commits, &[]),
+            vec![
+                ("ENG-2".to_string(), vec!["aaa1111".to_string(), "ccc3333".to_string()]),
+                ("ENG-1".to_string(), vec!["bbb2222".to_string()]),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_issues_lookup() {
+        async fn graphql(axum::Json(body): axum::Json<Value>) -> axum::Json<Value> {
+            let query = body["query"].as_str().unwrap_or_default().to_string();
+            assert!(query.contains("i0: issue(id: \"ENG-1\")"));
+            axum::Json(json!({
+                "data": {
+                    "i0": {
+                        "identifier": "ENG-1",
+                        "title": "Login fails on Safari",
+                        "url": "https://linear.app/acme/issue/ENG-1",
+                        "state": { "name": "Done" }
+                    },
+                    "i1": null
+                },
+                "errors": [{ "message": "Entity not found: Issue" }]
+            }))
+        }
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base_url = format!("http://{}/graphql", listener.local_addr().unwrap());
+        let app = axum::Router::new().route("/graphql", post(graphql));
+        tokio::spawn(async move { axum::serve(listener, app).await });
+
+        let client = LinearClient::with_base_url("lin_api_test".to_string(), Some(base_url)).unwrap();
+        let identifiers = vec!["ENG-1".to_string(), "UTF-8".to_string()];
+        let issues = client.issues(&identifiers).await.unwrap();
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues["ENG-1"].state, "Done");
+
+        // Remembered, including the unknown identifier
+        assert_eq!(client.known.lock().unwrap().len(), 2);
+
+        let issue = IssueRef {
+            issue: issues["ENG-1"].clone(),
+            commits: vec!["aaa1111".to_string(), "bbb2222".to_string()],
+        };
+        assert_eq!(
+            format_issues(&[issue]),
+            "ENG-1 Login fails on Safari (Done): aaa1111, bbb2222"
+        );
+    }
+}
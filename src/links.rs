@@ -0,0 +1,155 @@
+//! Links in the report: `file://` links to local folders (`--file-links`)
+//! and commit links of self-hosted remotes (`[[commit_links]]`)
+//!
+//! Repository paths in the report can be rendered as links that open the
+//! folder: markdown links in files and the preview, and OSC 8 hyperlinks in
+//! terminals that support them (most current ones; others print the text).
+
+use crate::config::CommitLinkConfig;
+use std::path::{Path, PathBuf};
+
+/// `file://` URL of an absolute `path`, percent-encoded
+///
+/// Windows paths get forward slashes: `C:\code\my api` is
+/// `file:///C:/code/my%20api` and `\\nas\code` is `file://nas/code`.
+pub fn file_url(path: &Path) -> String {
+    let path = path.to_string_lossy().replace('\\', "/");
+    let (host, path) = match path.strip_prefix("//") {
+        Some(unc) => match unc.split_once('/') {
+            Some((host, rest)) => (host.to_string(), format!("/{}", rest)),
+            None => (unc.to_string(), String::new()),
+        },
+        None if path.starts_with('/') => (String::new(), path),
+        None => (String::new(), format!("/{}", path)),
+    };
+    format!("file://{}{}", host, encode_path(&path))
+}
+
+/// `text` as a markdown link to `path` (relative to the working directory
+/// unless absolute)
+pub fn markdown_link(text: &str, path: &Path) -> String {
+    format!("[{}]({})", text.replace('[', "\\[").replace(']', "\\]"), file_url(&absolute(path)))
+}
+
+/// `text` as an OSC 8 terminal hyperlink to `path` (relative to the working
+/// directory unless absolute)
+pub fn terminal_link(text: &str, path: &Path) -> String {
+    format!("\x1b]8;;{}\x1b\\{}\x1b]8;;\x1b\\", file_url(&absolute(path)), text)
+}
+
+/// URL of commit `hash` of the repository at `remote_url`, from the first
+/// `[[commit_links]]` entry whose host pattern matches the remote's host
+pub fn commit_url(remote_url: &str, hash: &str, links: &[CommitLinkConfig]) -> Option<String> {
+    let (host, repo) = remote_host_and_path(remote_url)?;
+    let link = links.iter().find(|link| host_matches(&link.host, &host))?;
+    Some(
+        link.commit_url
+            .replace("{host}", &host)
+            .replace("{repo}", &repo)
+            .replace("{hash}", hash),
+    )
+}
+
+/// Host and repository path of a remote URL: `https://git.corp/team/api.git`,
+/// `ssh://git@git.corp:2222/team/api` and `git@git.corp:team/api.git` are all
+/// (`git.corp`, `team/api`)
+fn remote_host_and_path(url: &str) -> Option<(String, String)> {
+    let url = url.trim();
+    let (authority, path) = match url.split_once("://") {
+        Some((_, rest)) => rest.split_once('/')?,
+        // scp-like syntax: [user@]host:path
+        None => url.split_once(':')?,
+    };
+    let host = authority.rsplit('@').next()?;
+    let host = host.split(':').next()?.to_lowercase();
+    let path = path.trim_matches('/');
+    let path = path.strip_suffix(".git").unwrap_or(path);
+    if host.is_empty() || path.is_empty() {
+        return None;
+    }
+    Some((host, path.to_string()))
+}
+
+/// Whether `host` matches `pattern`: the same host, or a subdomain of the
+/// rest of a `*.` pattern
+fn host_matches(pattern: &str, host: &str) -> bool {
+    let pattern = pattern.to_lowercase();
+    match pattern.strip_prefix("*.") {
+        Some(domain) => host.strip_suffix(domain).is_some_and(|sub| sub.ends_with('.') && sub.len() > 1),
+        None => pattern == host,
+    }
+}
+
+fn absolute(path: &Path) -> PathBuf {
+    std::path::absolute(path).unwrap_or_else(|_| path.to_path_buf())
+}
+
+/// Percent-encode everything but unreserved characters, `/` and a drive's `:`
+fn encode_path(path: &str) -> String {
+    path.bytes()
+        .map(|byte| match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' | b'/' | b':' => {
+                (byte as char).to_string()
+            }
+            _ => format!("%{:02X}", byte),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_file_url() {
+        assert_eq!(file_url(Path::new("/home/me/code/api")), "file:///home/me/code/api");
+        assert_eq!(file_url(Path::new("/code/my api#2")), "file:///code/my%20api%232");
+        assert_eq!(file_url(Path::new(r"C:\code\my api")), "file:///C:/code/my%20api");
+        assert_eq!(file_url(Path::new(r"\\nas\code")), "file://nas/code");
+    }
+
+    #[test]
+    fn test_links() {
+        assert_eq!(
+            markdown_link("/code/api", Path::new("/code/api")),
+            "[/code/api](file:///code/api)"
+        );
+        assert_eq!(
+            terminal_link("api", Path::new("/code/api")),
+            "\x1b]8;;file:///code/api\x1b\\api\x1b]8;;\x1b\\"
+        );
+    }
+
+    #[test]
+    fn test_commit_url() {
+        let links = [
+            CommitLinkConfig {
+                host: "git.corp".to_string(),
+                commit_url: "https://git.corp/{repo}/-/commit/{hash}".to_string(),
+            },
+            CommitLinkConfig {
+                host: "*.example.org".to_string(),
+                commit_url: "https://{host}/cgit/{repo}/commit/?id={hash}".to_string(),
+            },
+        ];
+        for remote in [
+            "https://git.corp/team/api.git",
+            "ssh://git@git.corp:2222/team/api",
+            "git@GIT.CORP:team/api.git",
+        ] {
+            assert_eq!(
+                commit_url(remote, "abc123", &links).as_deref(),
+                Some("https://git.corp/team/api/-/commit/abc123"),
+                "{}",
+                remote
+            );
+        }
+        assert_eq!(
+            commit_url("https://src.example.org/tools.git", "abc123", &links).as_deref(),
+            Some("https://src.example.org/cgit/tools/commit/?id=abc123")
+        );
+        assert_eq!(commit_url("https://example.org/tools.git", "abc123", &links), None);
+        assert_eq!(commit_url("https://github.com/acme/api.git", "abc123", &links), None);
+        assert_eq!(commit_url("/srv/git/api.git", "abc123", &links), None);
+    }
+}
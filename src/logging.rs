@@ -0,0 +1,58 @@
+use dev_recap::{DevRecapError, Result};
+use std::fs::{self, OpenOptions};
+use std::io;
+use std::path::Path;
+use std::sync::Mutex;
+use tracing::level_filters::LevelFilter;
+use tracing_subscriber::filter::Targets;
+use tracing_subscriber::fmt;
+use tracing_subscriber::prelude::*;
+
+/// Initialize the global tracing subscriber
+///
+/// Events at or above `level` go to stderr, and are additionally appended
+/// (without ANSI colors) to `log_file` if one is configured. Dependencies
+/// (sled, reqwest, ...) are capped at warn so debug output stays readable.
+pub fn init(level: LevelFilter, log_file: Option<&Path>) -> Result<()> {
+    let filter = Targets::new()
+        .with_target(env!("CARGO_CRATE_NAME"), level)
+        .with_default(level.min(LevelFilter::WARN));
+
+    let stderr_layer = fmt::layer()
+        .with_writer(io::stderr)
+        .with_target(false)
+        .with_filter(filter.clone());
+
+    let file_layer = match log_file {
+        Some(path) => {
+            if let Some(parent) = path.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            let file = OpenOptions::new().create(true).append(true).open(path)?;
+
+            Some(
+                fmt::layer()
+                    .with_writer(Mutex::new(file))
+                    .with_ansi(false)
+                    .with_filter(filter),
+            )
+        }
+        None => None,
+    };
+
+    tracing_subscriber::registry()
+        .with(stderr_layer)
+        .with(file_layer)
+        .try_init()
+        .map_err(|e| DevRecapError::other(format!("Failed to initialize logging: {}", e)))
+}
+
+/// Run `f` with warnings going to stderr, for work done before `init`
+pub fn with_stderr<T>(f: impl FnOnce() -> T) -> T {
+    let subscriber = fmt::Subscriber::builder()
+        .with_writer(io::stderr)
+        .with_target(false)
+        .with_max_level(LevelFilter::WARN)
+        .finish();
+    tracing::subscriber::with_default(subscriber, f)
+}
@@ -1,19 +1,50 @@
-mod ai;
 mod cli;
-mod config;
-mod error;
-mod git;
-mod orchestrator;
-
-use clap::Parser;
-use cli::{Cli, Commands};
-use config::Config;
-use error::Result;
-use git::Timespan;
-use indicatif::{ProgressBar, ProgressStyle};
-use orchestrator::Orchestrator;
+mod logging;
+mod progress;
+
+use clap::{CommandFactory, Parser};
+use cli::{AuthAction, CacheAction, Cli, Commands, ConfigAction, HistoryAction, HookAction};
+use dev_recap::ai::chat::Chat;
+use dev_recap::ai::claude::{is_model_available, ClaudeClient};
+use dev_recap::ai::cost::CostEstimate;
+use dev_recap::ai::prompt::{PromptVersion, RecapFormat};
+use dev_recap::ai::provider::Provider;
+use dev_recap::anonymize::Anonymizer;
+use dev_recap::diff::RecapDiff;
+use dev_recap::error_report::{self, ErrorReport};
+use dev_recap::export::StatsExport;
+use dev_recap::git::github::format_duration;
+use dev_recap::git::log_import::{self, StdinFormat};
+use dev_recap::git::keywords::format_keywords;
+use dev_recap::git::stats::{
+    commits_by_day, directory_breakdown, format_directory_breakdown, leaderboard, recent_weeks, sparkline,
+    sort_repositories, work_patterns, GroupBy, DIRECTORY_BREAKDOWN_LIMIT,
+};
+use dev_recap::history::{self, HistoryStore, RepoRecap, StoredRecap};
+use dev_recap::mcp::{self, McpServer};
+use dev_recap::metrics::{self, Metrics};
+use dev_recap::preview::{self, PreviewState};
+use dev_recap::provenance::Provenance;
+use dev_recap::schedule::Schedule;
+use dev_recap::server::{self, ServerState};
+use dev_recap::watch::{Heads, Recapped};
+use dev_recap::{ai, chunk, config, deliver, git, links, secrets, slides, wsl, year};
+use dev_recap::{Config, DevRecapError, Orchestrator, Result, Summary, Timespan};
+use futures::StreamExt;
+use progress::ProgressReporter;
+use std::collections::BTreeMap;
 use std::env;
-use std::io::{self, Write};
+use std::io::{self, IsTerminal, Read, Write};
+use std::net::SocketAddr;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Longest single sleep while the daemon waits for its next run
+const DAEMON_POLL_INTERVAL: Duration = Duration::from_secs(60);
+
+/// How often `--preview` checks the config file for changes
+const PREVIEW_POLL_INTERVAL: Duration = Duration::from_secs(1);
 
 #[tokio::main]
 async fn main() -> Result<()> {
@@ -26,9 +57,27 @@ async fn main() -> Result<()> {
         std::process::exit(1);
     }
 
-    // Handle subcommands
-    if let Some(command) = &cli.command {
-        return handle_command(command);
+    // Move config/cache written by older versions to the platform directories;
+    // a failed move leaves them where they were, which shouldn't stop the run.
+    // Logging isn't set up before the config is loaded, so say what moved on stderr
+    if let Err(e) = logging::with_stderr(Config::migrate_legacy_dirs) {
+        eprintln!("⚠ Moving config/cache to the platform directories failed: {}", e);
+    }
+
+    // Handle subcommands (`serve`, `daemon`, `watch`, `mcp`, `models`, `regen`, `year` and `cache` need the full config and API key below)
+    match &cli.command {
+        Some(
+            Commands::Serve { .. }
+            | Commands::Daemon { .. }
+            | Commands::Watch { .. }
+            | Commands::Mcp
+            | Commands::Models
+            | Commands::Regen { .. }
+            | Commands::Year { .. }
+            | Commands::Cache { .. },
+        )
+        | None => {}
+        Some(command) => return handle_command(command, cli.config.as_deref()),
     }
 
     // Load or create config
@@ -39,60 +88,752 @@ async fn main() -> Result<()> {
     };
 
     // Apply CLI overrides to config
-    let config = apply_cli_overrides(config, &cli);
+    let mut config = apply_cli_overrides(config, &cli);
 
-    // Verify API key is available (from env or config)
-    if let Err(e) = config.get_api_key() {
-        eprintln!("Error: {}", e);
-        eprintln!("\nPlease either:");
-        eprintln!("  1. Set the ANTHROPIC_AUTH_TOKEN environment variable");
-        eprintln!("  2. Add claude_api_key to your config file at: {}",
-            Config::default_config_path()?.display());
-        std::process::exit(1);
+    logging::init(cli.log_level.into(), config.log_file.as_deref())?;
+
+    // Verify API key is available, resolving it once so a credential
+    // command or keyring lookup doesn't run again later
+    match config.get_api_key() {
+        Ok(api_key) => config.set_api_key(api_key),
+        Err(e) if config.provider != Provider::Anthropic => return Err(e),
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            eprintln!("\nPlease either:");
+            eprintln!("  1. Set the ANTHROPIC_AUTH_TOKEN environment variable");
+            eprintln!("  2. Add claude_api_key to your config file at: {}",
+                Config::default_config_path()?.display());
+            eprintln!("  3. Run `dev-recap auth set-key` to store it in the system keyring");
+            std::process::exit(1);
+        }
+    }
+
+    if let Some(Commands::Serve { port, bind }) = cli.command {
+        let addr = SocketAddr::new(bind, port);
+        let slack_secret = config.slack_signing_secret.clone();
+        let token = config.serve_token.clone();
+        let mut state = ServerState::new(Arc::new(new_orchestrator(config, &cli)?), cli.jobs);
+        match token {
+            Some(token) => state = state.with_token(token),
+            None if !bind.is_loopback() => {
+                eprintln!("Warning: no serve_token is set, so anyone reaching {} can request recaps", addr);
+            }
+            None => {}
+        }
+        if let Some(secret) = slack_secret {
+            // Slash commands recap --path (or the working directory)
+            let scan_path = match cli.path {
+                Some(ref path) => git::scanner::Scanner::normalize_path(path),
+                None => env::current_dir()?,
+            };
+            git::scanner::Scanner::validate_root(&scan_path)?;
+            check_scan_fs(&scan_path, cli.allow_slow_fs)?;
+            println!("Answering Slack /recap for {}", scan_path.display());
+            state = state.with_slack(secret, scan_path);
+        }
+        println!("dev-recap v{} serving recaps on http://{}", env!("CARGO_PKG_VERSION"), addr);
+        return server::serve(Arc::new(state), addr).await;
+    }
+
+    if let Some(Commands::Mcp) = cli.command {
+        // stdout carries the protocol; everything else goes to stderr
+        eprintln!("dev-recap v{} MCP server on stdio", env!("CARGO_PKG_VERSION"));
+        let server = McpServer::new(Arc::new(new_orchestrator(config, &cli)?), cli.jobs);
+        return mcp::serve_stdio(Arc::new(server)).await;
+    }
+
+    if let Some(Commands::Models) = cli.command {
+        return check_models(&config).await;
+    }
+
+    if let Some(Commands::Regen { ref repo, id }) = cli.command {
+        return regen_summary(config, &cli, repo, id).await;
+    }
+
+    if let Some(Commands::Year { year }) = cli.command {
+        let year = year.unwrap_or_else(|| chrono::Datelike::year(&chrono::Local::now()));
+        return run_year(config, &cli, year).await;
+    }
+
+    if let Some(Commands::Cache {
+        action: CacheAction::Warm { ref path, ref author, days },
+    }) = cli.command
+    {
+        return run_cache_warm(config, &cli, path.as_deref(), author.as_deref(), days).await;
+    }
+
+    if let Some(Commands::Daemon { now, metrics_addr }) = cli.command {
+        return run_daemon(config, &cli, now, metrics_addr).await;
+    }
+
+    if let Some(Commands::Watch { interval }) = cli.command {
+        return run_watch(config, &cli, Duration::from_secs(interval)).await;
+    }
+
+    if let Some(format) = cli.stdin_format {
+        return run_stdin(config, &cli, format).await;
+    }
+
+    if cli.preview {
+        return run_preview(config, &cli).await;
+    }
+
+    if let Some(ref instruction) = cli.refine {
+        return run_refine(config, &cli, instruction).await;
     }
 
     // Run main analysis
-    run_analysis(config, &cli).await
+    let result = run_analysis(config, &cli, None, None).await.map(|_| ());
+    if let Err(ref e) = result {
+        tracing::error!(error = %e, "run failed");
+    }
+    result
+}
+
+/// `dev-recap models`: list the endpoint's models and validate the configured model
+async fn check_models(config: &Config) -> Result<()> {
+    let client =
+        ClaudeClient::for_provider(config.provider, config.get_api_key()?, config.get_base_url(), config.get_model())?;
+    let models = client.list_models().await?;
+
+    println!(
+        "Models available at {}:\n",
+        config.get_base_url().as_deref().unwrap_or(config.provider.default_base_url())
+    );
+    for model in &models {
+        let marker = if model.id == client.model() { "*" } else { " " };
+        match model.display_name {
+            Some(ref name) => println!("{} {:<36} {}", marker, model.id, name),
+            None => println!("{} {}", marker, model.id),
+        }
+    }
+    println!();
+
+    if !is_model_available(&models, client.model()) {
+        return Err(DevRecapError::config(format!(
+            "model `{}` is not offered by this endpoint; set claude_model to one of the models above",
+            client.model()
+        )));
+    }
+    println!("✓ model `{}` is available", client.model());
+    Ok(())
+}
+
+/// `--refine`: revise the summaries of the most recent recap and save the
+/// result as a new recap
+///
+/// Only the summarization step runs again, from the stored summaries; the
+/// rest of the report (stats, velocity, hotspots) is kept as it was.
+async fn run_refine(config: Config, cli: &Cli, instruction: &str) -> Result<()> {
+    // Without the commits, the authors named in the summaries aren't known
+    if config.private_prompts {
+        return Err(DevRecapError::config(
+            "--refine can't be used with private_prompts: the summaries would be sent with author names",
+        ));
+    }
+
+    let mut store = HistoryStore::open(&Config::default_data_dir()?.join(history::HISTORY_FILE))?;
+    let id = store
+        .list(1)?
+        .first()
+        .map(|recap| recap.id)
+        .ok_or_else(|| DevRecapError::history("no recaps saved yet; generate one before refining it"))?;
+    let mut recap = store
+        .get(id)?
+        .ok_or_else(|| DevRecapError::history(format!("no recap with id {}", id)))?;
+
+    let output_path = resolve_output_path(&config, cli)?;
+    let history_enabled = config.history_enabled;
+    let orchestrator = new_orchestrator(config, cli)?;
+
+    let summarized: Vec<(usize, Summary)> = recap
+        .repositories
+        .iter()
+        .enumerate()
+        .filter_map(|(position, repo)| repo.summary.clone().map(|summary| (position, summary)))
+        .collect();
+    println!("Refining {} summaries of recap {}: {}", summarized.len(), id, instruction);
+
+    let refined: Vec<(usize, Result<Summary>)> = futures::stream::iter(summarized)
+        .map(|(position, summary)| {
+            let orchestrator = &orchestrator;
+            async move { (position, orchestrator.refine_summary(&summary, instruction).await) }
+        })
+        .buffered(cli.jobs.max(1))
+        .collect()
+        .await;
+
+    for (position, result) in refined {
+        match result {
+            Ok(summary) => {
+                println!("✓ {}", summary.repository);
+                let repo = RepoRecap {
+                    summary: Some(summary),
+                    ..recap.repositories[position].clone()
+                };
+                recap.replace_repository(position, repo);
+            }
+            Err(e) => {
+                tracing::warn!(repo = %recap.repositories[position].name, error = %e, "refining summary failed");
+                println!("⚠ {}: {} (kept the previous summary)", recap.repositories[position].name, e);
+            }
+        }
+    }
+
+    // The overall recap is synthesized from the summaries, so it follows them
+    if recap.overall.is_some() {
+        let summaries: Vec<&Summary> = recap.repositories.iter().filter_map(|repo| repo.summary.as_ref()).collect();
+        match orchestrator.generate_overall_recap(&summaries).await {
+            Ok(overall) => recap.replace_overall(overall),
+            Err(e) => {
+                tracing::warn!(error = %e, "overall recap failed");
+                println!("⚠ Overall recap failed: {} (kept the previous one)", e);
+            }
+        }
+    }
+
+    recap.created_at = chrono::Utc::now();
+    if history_enabled {
+        let new_id = store.save(&recap)?;
+        println!("✓ Saved as recap {} (compare with `dev-recap diff {} {}`)", new_id, id, new_id);
+    }
+
+    match output_path {
+        Some(ref path) => write_report(path, &recap.report, cli.open)?,
+        None => println!("\n{}", recap.report),
+    }
+    Ok(())
+}
+
+/// `dev-recap regen`: a fresh summary for one repository of a saved recap
+///
+/// The commits the recap listed for the repository are read again by hash
+/// (reusing the diff-stat cache), without walking its history, and only its
+/// summary is regenerated; the stored report is updated in place.
+async fn regen_summary(config: Config, cli: &Cli, name: &str, id: Option<i64>) -> Result<()> {
+    let mut store = HistoryStore::open(&Config::default_data_dir()?.join(history::HISTORY_FILE))?;
+    let id = match id {
+        Some(id) => id,
+        None => store
+            .list(1)?
+            .first()
+            .map(|recap| recap.id)
+            .ok_or_else(|| DevRecapError::history("no recaps saved yet"))?,
+    };
+    let mut recap = store
+        .get(id)?
+        .ok_or_else(|| DevRecapError::history(format!("no recap with id {}", id)))?;
+
+    let position = recap.find_repository(name).ok_or_else(|| {
+        let names: Vec<&str> = recap.repositories.iter().map(|repo| repo.name.as_str()).collect();
+        DevRecapError::history(format!(
+            "recap {} has no repository `{}` (it has: {})",
+            id,
+            name,
+            names.join(", ")
+        ))
+    })?;
+    if recap.anonymized {
+        return Err(DevRecapError::history(format!(
+            "recap {} shows authors as pseudonyms, so a summary can't be regenerated; run the recap again instead",
+            id
+        )));
+    }
+    let stored = &recap.repositories[position];
+    if stored.commit_hashes.is_empty() {
+        return Err(DevRecapError::history(format!(
+            "recap {} doesn't list the commits of `{}` (saved by an older version or without a summary); run the recap again instead",
+            id, stored.name
+        )));
+    }
+
+    let timespan = Timespan::from_dates(recap.timespan_start, recap.timespan_end);
+    println!("Regenerating the summary of {} in recap {}...", stored.name, id);
+    let orchestrator = Arc::new(new_orchestrator(config, cli)?);
+    let repo = orchestrator
+        .reanalyze_repository_blocking(stored.path.clone(), stored.commit_hashes.clone(), timespan)
+        .await?;
+    let summary = orchestrator.regenerate_summary(&repo).await?;
+
+    // The stored totals also count commits past the cap or dropped as
+    // duplicates, so only the summary is replaced
+    let updated = RepoRecap {
+        summary: Some(summary.clone()),
+        error: None,
+        ..stored.clone()
+    };
+    recap.replace_repository(position, updated);
+    store.update(&recap)?;
+
+    println!("\n{}", summary.to_markdown());
+    println!("✓ Recap {} updated (see `dev-recap history show {}`)", id, id);
+    Ok(())
+}
+
+/// `--stdin-format`: recap commits piped in as text, without a local repository
+///
+/// The commits are summarized as one repository (`--stdin-name`, "stdin" by
+/// default) over the time they span.
+async fn run_stdin(config: Config, cli: &Cli, format: StdinFormat) -> Result<()> {
+    if io::stdin().is_terminal() {
+        return Err(DevRecapError::other(format!(
+            "--stdin-format {} reads commits from stdin; pipe them in, e.g. `git log --numstat | dev-recap --stdin-format git-log`",
+            format
+        )));
+    }
+    let mut text = String::new();
+    io::stdin().read_to_string(&mut text)?;
+    let commits = match format {
+        StdinFormat::GitLog => log_import::parse_git_log(&text)?,
+    };
+
+    let output_path = resolve_output_path(&config, cli)?;
+
+    let orchestrator = new_orchestrator(config, cli)?;
+    let repo = orchestrator.analyze_commits(cli.stdin_name.as_deref().unwrap_or("stdin"), commits)?;
+    println!("Read {} commits from stdin ({})", repo.commits.len(), format);
+
+    // Commits come in on stdin, so there's no way to ask; the request needs --yes
+    if !confirm_cost(cli, &orchestrator.estimate_cost(&[&repo], false)?, false)? {
+        return Ok(());
+    }
+    let summary = if cli.dry_run {
+        None
+    } else {
+        Some(orchestrator.generate_summary(&repo).await)
+    };
+
+    let first = repo.commits.iter().map(|commit| commit.timestamp).min();
+    let last = repo.commits.iter().map(|commit| commit.timestamp).max();
+    let mut report = String::from("# Dev Recap\n\n");
+    report.push_str(&format!("**Source:** `{}` on stdin\n", format));
+    if let (Some(first), Some(last)) = (first, last) {
+        report.push_str(&format!(
+            "**Timespan:** {} to {}\n",
+            first.format("%Y-%m-%d"),
+            last.format("%Y-%m-%d")
+        ));
+    }
+    report.push_str("\n---\n\n");
+    report.push_str(&format!("## Repository: {}\n\n", repo.name));
+    if !repo.keywords.is_empty() {
+        report.push_str(&format!("**Topics:** {}\n\n", format_keywords(&repo.keywords)));
+    }
+    if !repo.hotspots.is_empty() {
+        report.push_str("**Hotspots:**\n");
+        for hotspot in &repo.hotspots {
+            report.push_str(&format!(
+                "- `{}` ({} commits, {} lines churned)\n",
+                hotspot.path, hotspot.changes, hotspot.churn
+            ));
+        }
+        report.push('\n');
+    }
+    match summary {
+        Some(Ok(ref summary)) => {
+            report.push_str(&summary.to_markdown());
+            report.push('\n');
+        }
+        Some(Err(ref e)) => report.push_str(&format!("**Error:** {}\n\n", e)),
+        None => {}
+    }
+    if let (Some(first), Some(last)) = (first, last) {
+        let model = summary
+            .as_ref()
+            .and_then(|result| result.as_ref().ok())
+            .and_then(|summary| summary.model.as_deref());
+        let timespan = Timespan::from_dates(first.to_utc(), last.to_utc());
+        let provenance = Provenance::new(&repo, &[], &timespan, orchestrator.config(), model)
+            .with_filter(format!("stdin_format={}", format));
+        report.push_str(&provenance.to_markdown());
+    }
+
+    match output_path {
+        Some(ref path) => write_report(path, &report, cli.open)?,
+        None => println!("\n{}", report),
+    }
+    Ok(())
+}
+
+/// `dev-recap year`: a year in review
+///
+/// The year is analyzed once and split into calendar months; each month
+/// with commits is summarized (cached by its commits), then the months are
+/// synthesized into trends and milestones. Never prompts: the author comes
+/// from --author, default_author_email or git's user.email, and --team
+/// reviews everyone's commits.
+async fn run_year(config: Config, cli: &Cli, year: i32) -> Result<()> {
+    let given_repos = given_repositories(cli)?;
+    let scan_path = scan_root(cli.path.as_deref(), &given_repos)?;
+    git::scanner::Scanner::validate_root(&scan_path)?;
+    check_scan_fs(&scan_path, cli.allow_slow_fs)?;
+    let timespan = year::year_timespan(year)?;
+
+    let author = if cli.team_mode() {
+        None
+    } else {
+        let author = cli
+            .author
+            .clone()
+            .or_else(|| config.default_author_email.clone())
+            .or_else(get_git_user_email)
+            .ok_or_else(|| {
+                DevRecapError::MissingConfig(
+                    "author email (pass --author, set default_author_email or git user.email)".to_string(),
+                )
+            })?;
+        Some(author)
+    };
+
+    let output_path = resolve_output_path(&config, cli)?;
+
+    println!("Reviewing {} in {}", year, scan_path.display());
+    let orchestrator = Arc::new(new_orchestrator(config, cli)?);
+    let repos = repositories(&orchestrator, &scan_path, given_repos)?;
+    if repos.is_empty() {
+        println!("No git repositories found.");
+        return Ok(());
+    }
+
+    let jobs = cli.jobs.max(1);
+    let analyzed: Vec<git::Repository> = orchestrator
+        .analyze_all(repos, author, &timespan, jobs)
+        .await
+        .into_iter()
+        .filter_map(|(path, result)| match result {
+            Ok(repo) => Some(repo),
+            Err(e) => {
+                println!("⚠ {}: {}", path.display(), e);
+                None
+            }
+        })
+        .collect();
+    let mut recap = year::YearRecap {
+        year,
+        months: year::split_by_month(year, &analyzed),
+        synthesis: None,
+    };
+    if recap.commits() == 0 {
+        println!("No commits in {}.", year);
+        return Ok(());
+    }
+
+    // `year` is a subcommand, so `can_prompt` is always false; it still asks
+    // at a terminal when the report isn't going to a file
+    let estimate = orchestrator.estimate_year_cost(&recap.months)?;
+    let can_ask = !cli.non_interactive && cli.output.is_none() && io::stdin().is_terminal();
+    if !confirm_cost(cli, &estimate, can_ask)? {
+        return Ok(());
+    }
+
+    if !cli.dry_run {
+        let months = &recap.months;
+        let summaries: Vec<Option<Summary>> = futures::stream::iter(months)
+            .map(|month| {
+                let orchestrator = &orchestrator;
+                async move {
+                    if month.repositories.is_empty() {
+                        return None;
+                    }
+                    match orchestrator.month_summary(month).await {
+                        Ok(summary) => {
+                            println!("✓ {}: {}", month.label(), month.describe());
+                            Some(summary)
+                        }
+                        Err(e) => {
+                            tracing::warn!(month = %month.label(), error = %e, "month summary failed");
+                            println!("⚠ {}: {}", month.label(), e);
+                            None
+                        }
+                    }
+                }
+            })
+            .buffered(jobs)
+            .collect()
+            .await;
+        for (month, summary) in recap.months.iter_mut().zip(summaries) {
+            month.summary = summary;
+        }
+
+        match orchestrator.year_synthesis(year, &recap.months).await {
+            Ok(synthesis) => recap.synthesis = Some(synthesis),
+            Err(e) => {
+                tracing::warn!(error = %e, "year synthesis failed");
+                println!("⚠ Yearly synthesis failed: {}", e);
+            }
+        }
+    }
+
+    let report = recap.to_markdown();
+    match output_path {
+        Some(ref path) => write_report(path, &report, cli.open)?,
+        None => println!("\n{}", report),
+    }
+    Ok(())
+}
+
+/// `dev-recap cache warm`: summarize every repository with commits in the
+/// timespan into the cache, so a later run over the same commits is served
+/// from it
+///
+/// Meant for a nightly CI job; fails when any repository couldn't be
+/// summarized, so the job shows it.
+async fn run_cache_warm(
+    config: Config,
+    cli: &Cli,
+    path: Option<&Path>,
+    author: Option<&str>,
+    days: Option<u32>,
+) -> Result<()> {
+    if !config.cache_enabled {
+        return Err(DevRecapError::config(
+            "cache warm needs the cache (cache_enabled is false or --no-cache was passed)",
+        ));
+    }
+
+    if path.is_some() && !cli.repos.is_empty() {
+        return Err(DevRecapError::config("cache warm --path can't be combined with --repo"));
+    }
+    let given_repos = given_repositories(cli)?;
+    let scan_path = scan_root(path.or(cli.path.as_deref()), &given_repos)?;
+    git::scanner::Scanner::validate_root(&scan_path)?;
+    check_scan_fs(&scan_path, cli.allow_slow_fs)?;
+
+    let author = if cli.team_mode() {
+        None
+    } else {
+        let author = author
+            .map(str::to_string)
+            .or_else(|| cli.author.clone())
+            .or_else(|| config.default_author_email.clone())
+            .or_else(get_git_user_email)
+            .ok_or_else(|| {
+                DevRecapError::MissingConfig(
+                    "author email (pass --author, set default_author_email or git user.email)".to_string(),
+                )
+            })?;
+        Some(author)
+    };
+    let days = days
+        .or(cli.days)
+        .unwrap_or_else(|| config.format.default_days().unwrap_or(config.default_timespan_days));
+    let timespan = Timespan::days_back(days);
+
+    println!("Warming the summary cache for {} ({} days back)", scan_path.display(), days);
+    let orchestrator = Arc::new(new_orchestrator(config, cli)?);
+    let repos = repositories(&orchestrator, &scan_path, given_repos)?;
+    let jobs = cli.jobs.max(1);
+
+    // Repositories without commits in the timespan have nothing to cache
+    let mut failed = 0;
+    let analyzed: Vec<_> = orchestrator
+        .analyze_all(repos, author, &timespan, jobs)
+        .await
+        .into_iter()
+        .filter(|(path, result)| match result {
+            Ok(_) => true,
+            Err(e) if e.is_no_commits() => false,
+            Err(e) => {
+                println!("⚠ {}: {}", path.display(), e);
+                failed += 1;
+                false
+            }
+        })
+        .collect();
+
+    let to_summarize: Vec<&git::Repository> = analyzed
+        .iter()
+        .filter_map(|(_, result)| result.as_ref().ok())
+        .collect();
+    let estimate = orchestrator.estimate_cost(&to_summarize, false)?;
+    if cli.dry_run {
+        println!("Would make {}", estimate);
+        return Ok(());
+    }
+
+    let mut generated = 0;
+    for (repo, result) in orchestrator.summarize_all(analyzed, jobs, false).await {
+        match result {
+            Ok(_) => {
+                println!("✓ {}", repo.name);
+                generated += 1;
+            }
+            Err(e) => {
+                println!("⚠ {}: {}", repo.name, e);
+                failed += 1;
+            }
+        }
+    }
+    println!(
+        "\n✓ {} summaries cached ({} of them already were)",
+        generated, estimate.cached
+    );
+
+    if failed > 0 {
+        return Err(DevRecapError::other(format!("{} repositories could not be summarized", failed)));
+    }
+    Ok(())
+}
+
+/// Orchestrator with the flags shared by every mode (CLI, serve, mcp)
+fn new_orchestrator(config: Config, cli: &Cli) -> Result<Orchestrator> {
+    let team_authors = named_team_authors(&config, cli)?;
+    let mut orchestrator = Orchestrator::new(config)?;
+    if let Some(authors) = team_authors {
+        orchestrator = orchestrator.with_team_authors(authors);
+    }
+    Ok(match cli.subdirs {
+        Some(ref pattern) => orchestrator.with_subdirs(pattern.clone()),
+        None => orchestrator,
+    })
+}
+
+/// Authors of the team given to `--team NAME`, plus any `--authors`
+fn named_team_authors(config: &Config, cli: &Cli) -> Result<Option<Vec<String>>> {
+    let Some(name) = cli.team_name() else {
+        return Ok(None);
+    };
+    let mut authors = config.team_authors(name)?;
+    for author in cli.authors.iter().flatten() {
+        if !authors.contains(author) {
+            authors.push(author.clone());
+        }
+    }
+    Ok(Some(authors))
+}
+
+/// Refuse to scan a Windows drive from WSL unless `--allow-slow-fs`, and
+/// warn when it is allowed
+fn check_scan_fs(scan_path: &Path, allow_slow_fs: bool) -> Result<()> {
+    wsl::check_scan_path(scan_path, allow_slow_fs)?;
+    if wsl::is_slow_scan_path(scan_path) {
+        println!(
+            "⚠ Scanning a Windows drive from WSL; this can take minutes (the Windows build of dev-recap is much faster here)"
+        );
+    }
+    Ok(())
+}
+
+/// Repositories passed with `--repo`, each checked to be a repository's root
+/// and listed once
+fn given_repositories(cli: &Cli) -> Result<Vec<PathBuf>> {
+    let mut repos = Vec::new();
+    for path in &cli.repos {
+        let repo = git::scanner::Scanner::open_repository(path)?;
+        if !repos.contains(&repo) {
+            repos.push(repo);
+        }
+    }
+    Ok(repos)
+}
+
+/// Where the repositories are: the directory the `--repo` repositories share,
+/// else `path` or the current directory
+fn scan_root(path: Option<&Path>, given_repos: &[PathBuf]) -> Result<PathBuf> {
+    if let Some(root) = git::scanner::Scanner::common_root(given_repos) {
+        return Ok(root);
+    }
+    Ok(match path {
+        Some(path) => git::scanner::Scanner::normalize_path(path),
+        None => env::current_dir()?,
+    })
 }
 
-async fn run_analysis(config: Config, cli: &Cli) -> Result<()> {
+/// Repositories to recap: the ones given with `--repo`, else every
+/// repository found under `scan_path`
+fn repositories(orchestrator: &Orchestrator, scan_path: &Path, given_repos: Vec<PathBuf>) -> Result<Vec<PathBuf>> {
+    if given_repos.is_empty() {
+        orchestrator.scan_repositories(scan_path)
+    } else {
+        Ok(given_repos)
+    }
+}
+
+/// Run the recap once, returning the markdown report (None if nothing ran)
+///
+/// `metrics` collects counters across runs, for `daemon --metrics-addr`.
+/// `recapped` holds the repositories `watch` recapped before: those kept are
+/// reused rather than analyzed and summarized again, and it's updated with
+/// this run's.
+async fn run_analysis(
+    config: Config,
+    cli: &Cli,
+    metrics: Option<Arc<Metrics>>,
+    mut recapped: Option<&mut Recapped>,
+) -> Result<Option<String>> {
     println!("dev-recap v{}", env!("CARGO_PKG_VERSION"));
     println!("AI-powered git commit summarizer for Demo Day presentations\n");
 
-    // Interactive mode: prompt for missing values
-    let scan_path = if let Some(ref path) = cli.path {
-        path.clone()
+    // With no arguments at all inside a repository, recap that repository
+    // since the last weekday without prompting, like git-standup
+    let standup_repo = if env::args_os().len() == 1 {
+        env::current_dir()
+            .ok()
+            .and_then(|dir| git::scanner::Scanner::enclosing_repository(&dir))
     } else {
-        let default_path = env::current_dir().expect("Failed to get current directory");
-        prompt_with_default("Scan path", &default_path.display().to_string())?
-            .parse()
-            .unwrap_or(default_path)
+        None
+    };
+
+    // Prompt for missing values only when interactive; otherwise use defaults or fail
+    let interactive = standup_repo.is_none() && cli.can_prompt();
+
+    // --repo analyzes exactly the given repositories; the scan path is where they meet
+    let given_repos = given_repositories(cli)?;
+
+    let scan_path = if let Some(ref root) = standup_repo {
+        root.clone()
+    } else if let Some(root) = git::scanner::Scanner::common_root(&given_repos) {
+        root
+    } else if let Some(ref path) = cli.path {
+        git::scanner::Scanner::normalize_path(path)
+    } else {
+        let default_path = env::current_dir()?;
+        if interactive {
+            config::expand_home(&prompt_with_default("Scan path", &default_path.display().to_string())?)?
+        } else {
+            default_path
+        }
     };
+    git::scanner::Scanner::validate_root(&scan_path)?;
+    check_scan_fs(&scan_path, cli.allow_slow_fs)?;
 
     // Prompt for author email(s)
-    let author_emails = if cli.team {
-        // Team mode: get multiple authors
-        if let Some(ref authors) = cli.authors {
+    let author_emails = if cli.team_mode() {
+        // Team mode: get multiple authors, from a configured team and/or --authors
+        if let Some(authors) = named_team_authors(&config, cli)? {
+            authors
+        } else if let Some(ref authors) = cli.authors {
             authors.clone()
-        } else {
-            // Interactive mode: prompt for authors
+        } else if interactive {
             let input = prompt_required("Author emails (comma-separated)")?;
             input.split(',').map(|s| s.trim().to_string()).collect()
+        } else {
+            return Err(DevRecapError::MissingConfig(
+                "--authors is required for team mode when not running interactively".to_string(),
+            ));
         }
     } else {
-        // Single author mode
+        // Single author mode: config default first, then git config (the
+        // repository's own user.email first in standup mode)
+        let default_email = if standup_repo.is_some() {
+            get_git_user_email().or_else(|| config.default_author_email.clone())
+        } else {
+            config.default_author_email.clone().or_else(get_git_user_email)
+        };
+
         let author_email = if let Some(ref email) = cli.author {
             email.clone()
-        } else if let Some(ref default_email) = config.default_author_email {
-            prompt_with_default("Author email", default_email)?
+        } else if !interactive {
+            default_email.ok_or_else(|| {
+                DevRecapError::MissingConfig(
+                    "author email (pass --author, set default_author_email or git user.email)"
+                        .to_string(),
+                )
+            })?
+        } else if let Some(ref email) = default_email {
+            prompt_with_default("Author email", email)?
         } else {
-            // Try to get from git config
-            let git_email = get_git_user_email();
-            if let Some(ref email) = git_email {
-                prompt_with_default("Author email", email)?
-            } else {
-                prompt_required("Author email")?
-            }
+            prompt_required("Author email")?
         };
         vec![author_email]
     };
@@ -101,32 +842,43 @@ async fn run_analysis(config: Config, cli: &Cli) -> Result<()> {
     let (timespan, timespan_desc) = if cli.since.is_some() || cli.until.is_some() {
         // Use --since/--until for date range
         let since_str = cli.since.as_deref().unwrap_or("1970-01-01");
-        let until_str = cli.until.as_deref().unwrap_or_else(|| {
-            // Default to today
-            chrono::Utc::now().format("%Y-%m-%d").to_string().leak()
-        });
-
-        let start = chrono::NaiveDate::parse_from_str(since_str, "%Y-%m-%d")
-            .map_err(|_| error::DevRecapError::Other(format!("Invalid date format for --since: {}", since_str)))?
-            .and_hms_opt(0, 0, 0)
-            .ok_or_else(|| error::DevRecapError::Other("Invalid time".to_string()))?
-            .and_utc();
-
-        let end = chrono::NaiveDate::parse_from_str(until_str, "%Y-%m-%d")
-            .map_err(|_| error::DevRecapError::Other(format!("Invalid date format for --until: {}", until_str)))?
-            .and_hms_opt(23, 59, 59)
-            .ok_or_else(|| error::DevRecapError::Other("Invalid time".to_string()))?
-            .and_utc();
+        let until_str = cli.until.as_deref().unwrap_or("now");
+
+        let start = Timespan::parse_bound(since_str, false, cli.utc)?;
+        let end = match cli.until {
+            Some(ref until) => Timespan::parse_bound(until, true, cli.utc)?,
+            None => chrono::Utc::now(),
+        };
+
+        if start > end {
+            return Err(DevRecapError::InvalidTimespan(format!(
+                "--since ({}) is after --until ({})",
+                since_str, until_str
+            )));
+        }
 
         let timespan = Timespan::from_dates(start, end);
         let desc = format!("{} to {}", since_str, until_str);
         (timespan, desc)
+    } else if let Some(selector) = cli.sprint {
+        let cadence = config.sprint.ok_or_else(|| {
+            DevRecapError::config("--sprint needs a [sprint] table with start and length_days in the config")
+        })?;
+        let sprint = cadence.sprint(selector, chrono::Local::now().date_naive())?;
+        (sprint.timespan(chrono::Utc::now())?, sprint.describe())
+    } else if standup_repo.is_some() {
+        let timespan = Timespan::since_last_weekday();
+        let desc = format!("since {}", timespan.start.with_timezone(&chrono::Local).format("%A %Y-%m-%d"));
+        (timespan, desc)
     } else {
         // Use --days for days back
+        // A brag document looks back over a review cycle unless --days says otherwise
+        let default_days = config.format.default_days().unwrap_or(config.default_timespan_days);
         let days = if let Some(d) = cli.days {
             d
+        } else if !interactive {
+            default_days
         } else {
-            let default_days = config.default_timespan_days;
             let input = prompt_with_default("Days back", &default_days.to_string())?;
             input.parse().unwrap_or(default_days)
         };
@@ -136,203 +888,1104 @@ async fn run_analysis(config: Config, cli: &Cli) -> Result<()> {
         (timespan, desc)
     };
 
-    println!("\n{}", "=".repeat(60));
-    println!("Scanning: {}", scan_path.display());
-    if author_emails.len() == 1 {
-        println!("Author: {}", author_emails[0]);
-    } else {
-        println!("Authors: {}", author_emails.join(", "));
+    // With --anonymize, only pseudonyms are shown from here on
+    let anonymizer = cli.anonymize.then(|| Anonymizer::new(&author_emails));
+    let shown_authors: Vec<String> = match anonymizer {
+        Some(ref anonymizer) => author_emails.iter().map(|email| anonymizer.name(email)).collect(),
+        None => author_emails.clone(),
+    };
+
+    println!("\n{}", "=".repeat(60));
+    if given_repos.is_empty() {
+        println!("Scanning: {}", scan_path.display());
+    } else {
+        let shown: Vec<String> = given_repos.iter().map(|path| path.display().to_string()).collect();
+        println!("Repositories: {}", shown.join(", "));
+    }
+    if shown_authors.len() == 1 {
+        println!("Author: {}", shown_authors[0]);
+    } else {
+        println!("Authors: {}", shown_authors.join(", "));
+    }
+    println!("Timespan: {}", timespan_desc);
+    println!("{}\n", "=".repeat(60));
+
+    let output_path = resolve_output_path(&config, cli)?;
+
+    if cli.open && output_path.is_none() {
+        return Err(DevRecapError::MissingConfig(
+            "--open needs an output file (pass --output or set output_path)".to_string(),
+        ));
+    }
+
+    if cli.chat && !io::stdin().is_terminal() {
+        return Err(DevRecapError::other("--chat needs an interactive terminal"));
+    }
+
+    let history_enabled = config.history_enabled && !cli.dry_run;
+
+    // Progress goes to stderr, the report to stdout: each is colored only on a terminal
+    let progress = ProgressReporter::new(cli.dry_run).with_palette(config.theme.palette(io::stderr().is_terminal()));
+    let palette = config.theme.palette(io::stdout().is_terminal());
+
+    // Create orchestrator
+    let mut orchestrator = new_orchestrator(config, cli)?.with_event_sink(Arc::new(progress));
+    if let Some(anonymizer) = anonymizer {
+        orchestrator = orchestrator.with_anonymizer(anonymizer);
+    }
+    if let Some(metrics) = metrics {
+        orchestrator = orchestrator.with_metrics(metrics);
+    }
+
+    let orchestrator = Arc::new(orchestrator);
+
+    // Scan for repositories (just the current one in standup mode, or those
+    // given with --repo)
+    let repos = match standup_repo {
+        Some(ref root) => vec![root.clone()],
+        None if !given_repos.is_empty() => given_repos,
+        None => orchestrator.scan_repositories(&scan_path)?,
+    };
+
+    if repos.is_empty() {
+        println!("No git repositories found.");
+        return Ok(None);
+    }
+
+    println!();
+
+    // In team mode, analyze all commits (a named team's are filtered by the
+    // orchestrator); in single mode, filter by author
+    let author_filter = if cli.team_mode() {
+        None // Team mode: get all commits
+    } else {
+        Some(author_emails[0].clone()) // Single author mode
+    };
+    let jobs = cli.jobs.max(1);
+
+    // Unless --yes, every repository has to be parsed and the cost confirmed
+    // before the first API call; otherwise parsing and summarizing overlap.
+    // Standup mode asks too, since it can spend as much as any other run.
+    let confirm_first = !cli.dry_run && !cli.yes;
+
+    let order = orchestrator.config().order;
+
+    // Repositories recapped by an earlier watch update whose HEAD hasn't moved
+    // since; pseudonyms are assigned anew each run, so --anonymize recaps all
+    let mut reused = Vec::new();
+    let repos = match recapped {
+        Some(ref mut recapped) if !cli.anonymize && cli.compare_prompts.is_none() => repos
+            .into_iter()
+            .filter(|path| match recapped.take(path) {
+                Some((repo, summary)) => {
+                    orchestrator.remember_authors(&repo);
+                    reused.push((repo, Ok(summary)));
+                    false
+                }
+                None => true,
+            })
+            .collect(),
+        _ => repos,
+    };
+    if !reused.is_empty() {
+        println!("Reusing the recaps of {} unchanged repositories", reused.len());
+    }
+
+    // Prompt comparison: each repository summarized with every version instead of the report
+    if let Some(ref versions) = cli.compare_prompts {
+        let mut repos: Vec<git::Repository> = orchestrator
+            .analyze_all(repos, author_filter, &timespan, jobs)
+            .await
+            .into_iter()
+            .filter_map(|(_, result)| result.ok())
+            .collect();
+        sort_repositories(&mut repos, order, |repo| repo);
+
+        if confirm_first {
+            let to_summarize: Vec<&git::Repository> = repos.iter().collect();
+            let estimate = orchestrator.estimate_cost_with(&to_summarize, versions, false)?;
+            if !confirm_cost(cli, &estimate, cli.can_prompt())? {
+                return Ok(None);
+            }
+        }
+
+        let report = prompt_comparison(&orchestrator, &repos, versions, jobs).await;
+        match output_path {
+            Some(ref path) => write_report(path, &report, cli.open)?,
+            None => println!("\n{}", report),
+        }
+        return Ok(Some(report));
+    }
+
+    let mut results = if confirm_first {
+        // Analyze repositories (git only, no API calls yet)
+        let analyzed = orchestrator
+            .analyze_all(repos, author_filter, &timespan, jobs)
+            .await;
+
+        // Show the estimated cost and confirm before issuing any API calls
+        let to_summarize: Vec<&git::Repository> = analyzed
+            .iter()
+            .filter_map(|(_, result)| result.as_ref().ok())
+            .collect();
+        let estimate = orchestrator.estimate_cost(&to_summarize, !cli.no_overall)?;
+        if !confirm_cost(cli, &estimate, cli.can_prompt())? {
+            return Ok(None);
+        }
+
+        orchestrator.summarize_all(analyzed, jobs, false).await
+    } else {
+        // Parse and summarize as a pipeline
+        orchestrator
+            .analyze_repositories(repos, author_filter, &timespan, jobs, cli.dry_run)
+            .await
+    };
+    results.extend(reused);
+    if let Some(recapped) = recapped {
+        recapped.keep(&results);
+    }
+    // Repositories finish in any order; report them in the configured one
+    sort_repositories(&mut results, order, |(repo, _)| repo);
+
+    // Fetch PR delivery metrics (needs a GitHub token) and the Linear issues
+    // commits reference (needs a Linear API key)
+    let (delivery, issues) = if cli.dry_run {
+        Default::default()
+    } else {
+        let analyzed: Vec<&git::Repository> = results
+            .iter()
+            .map(|(repo, _)| repo)
+            .filter(|repo| !repo.commits.is_empty())
+            .collect();
+        let author = if cli.team_mode() { None } else { Some("@me") };
+        let delivery = orchestrator
+            .delivery_metrics(&analyzed, author, &timespan, jobs)
+            .await;
+        (delivery, orchestrator.linear_issues(&analyzed, jobs).await)
+    };
+
+    if let Some(ref path) = cli.stats_json {
+        let mut export = StatsExport::new(results.iter().map(|(repo, _)| repo), &timespan, &delivery);
+        if orchestrator.anonymizer().is_some() {
+            for repo in &mut export.repositories {
+                repo.path = PathBuf::from(&repo.name);
+                repo.remote_url = None;
+                repo.upstream_url = None;
+            }
+        }
+        export.write(path)?;
+        println!("✓ Statistics written to: {}", wsl::display_path(path));
+    }
+
+    // Synthesize an overall recap across repositories
+    // A brag document has no demo to plan, so no overall recap
+    let brag = orchestrator.config().format == RecapFormat::Brag;
+
+    // A dry run estimates what the real run would send and cost, to trim the
+    // timespan before spending anything
+    let dry_run_estimate = if cli.dry_run {
+        let analyzed: Vec<&git::Repository> = results.iter().map(|(repo, _)| repo).collect();
+        let estimate = orchestrator.estimate_cost(&analyzed, !cli.no_overall && !brag)?;
+        println!("\nA real run would make {}", estimate);
+        Some(estimate)
+    } else {
+        None
+    };
+
+    let overall = if cli.dry_run || cli.no_overall || brag {
+        None
+    } else {
+        let summaries: Vec<&Summary> = results
+            .iter()
+            .filter_map(|(_, result)| result.as_ref().ok())
+            .collect();
+
+        if summaries.len() > 1 {
+            println!("\nSynthesizing overall recap...");
+            match orchestrator.generate_overall_recap(&summaries).await {
+                Ok(recap) => Some(recap),
+                Err(e) => {
+                    tracing::warn!(error = %e, "overall recap failed");
+                    println!("⚠ Overall recap failed: {}", e);
+                    None
+                }
+            }
+        } else {
+            None
+        }
+    };
+
+    // Per-author totals for --leaderboard, with reviews from the delivery metrics
+    let leaderboard_rows = cli.leaderboard.then(|| {
+        let mut reviews = BTreeMap::new();
+        for metrics in delivery.values() {
+            for (login, count) in &metrics.reviews_by {
+                *reviews.entry(login.clone()).or_insert(0) += count;
+            }
+        }
+        // Logins are real names too; sorted, so pseudonyms are stable
+        if let Some(anonymizer) = orchestrator.anonymizer() {
+            let mut anonymized = BTreeMap::new();
+            for (login, count) in reviews {
+                *anonymized.entry(anonymizer.github_login(&login)).or_insert(0) += count;
+            }
+            reviews = anonymized;
+        }
+        let repos: Vec<&git::Repository> = results.iter().map(|(repo, _)| repo).collect();
+        leaderboard(&repos, &reviews)
+    });
+
+    if let Some(ref path) = cli.slides {
+        let summaries: Vec<&Summary> = results
+            .iter()
+            .filter_map(|(_, result)| result.as_ref().ok())
+            .collect();
+        let subtitle = format!("{}\n\n{}", shown_authors.join(", "), timespan_desc);
+        if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(path, slides::render_deck(&subtitle, &summaries, cli.slot))?;
+
+        let total = slides::total_seconds(&summaries);
+        println!(
+            "✓ Slides written to: {} (about {} to present)",
+            wsl::display_path(path),
+            slides::format_duration(total)
+        );
+        if let Some(slot) = cli.slot.filter(|slot| total > slot * 60) {
+            println!(
+                "⚠ That's {} over the {}-minute slot; trim the deck before presenting",
+                slides::format_duration(total - slot * 60),
+                slot
+            );
+        }
+    }
+
+    // Paths as file:// links with --file-links; OSC 8 only when printing to a terminal
+    let file_links = orchestrator.config().file_links;
+    let by_day = orchestrator.config().group_by == Some(GroupBy::Day);
+    let markdown_path = |path: &Path| {
+        let text = path.display().to_string();
+        if file_links {
+            links::markdown_link(&text, path)
+        } else {
+            text
+        }
+    };
+    let terminal_path = |path: &Path| {
+        let text = path.display().to_string();
+        if file_links && io::stdout().is_terminal() {
+            links::terminal_link(&text, path)
+        } else {
+            text
+        }
+    };
+
+    // Footer of each repository: its commit range, filters, model and version
+    let provenance = |repo: &git::Repository, summary_result: &Result<Summary>| {
+        let model = summary_result.as_ref().ok().and_then(|summary| summary.model.as_deref());
+        Provenance::new(repo, &shown_authors, &timespan, orchestrator.config(), model)
+    };
+
+    // With an output file, failures go to <output>-errors.json beside it
+    // rather than into the report
+    let error_report = output_path.as_ref().map(|path| {
+        let mut report = ErrorReport::new(results.iter().map(|(repo, result)| (repo, result)));
+        if let Some(anonymizer) = orchestrator.anonymizer() {
+            for failure in &mut report.failures {
+                failure.path = PathBuf::from(&failure.name);
+                failure.error = anonymizer.scrub_report(&failure.error);
+            }
+        }
+        (error_report::path_beside(path), report)
+    });
+    let error_file_name = error_report
+        .as_ref()
+        .and_then(|(path, _)| path.file_name())
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let failures_note = |repo_error: &DevRecapError| match error_report {
+        Some(_) if error_report::is_failure(repo_error) => {
+            Some(format!("*Not summarized; see {}.*", error_file_name))
+        }
+        _ => None,
+    };
+
+    // Build markdown output
+    let mut markdown_output = String::new();
+    markdown_output.push_str(if brag { "# Brag Document\n\n" } else { "# Dev Recap\n\n" });
+    // With --anonymize, paths and remotes would give the repositories away
+    let anonymizer = orchestrator.anonymizer();
+    let shown_error = |e: &DevRecapError| match anonymizer {
+        Some(anonymizer) => anonymizer.scrub_report(&e.to_string()),
+        None => e.to_string(),
+    };
+    if anonymizer.is_none() {
+        markdown_output.push_str(&format!("**Scan Path:** {}\n", markdown_path(&scan_path)));
+    }
+    if shown_authors.len() == 1 {
+        markdown_output.push_str(&format!("**Author:** {}\n", shown_authors[0]));
+    } else {
+        markdown_output.push_str(&format!("**Authors:** {}\n", shown_authors.join(", ")));
+    }
+    markdown_output.push_str(&format!("**Timespan:** {}\n", timespan_desc));
+    if let Some(ref estimate) = dry_run_estimate {
+        markdown_output.push_str(&format!("**Estimated cost:** {}\n", estimate));
+    }
+    if let Some((_, ref report)) = error_report.as_ref().filter(|(_, report)| !report.is_empty()) {
+        markdown_output.push_str(&format!(
+            "**Failed:** {} of {} repositories (see {})\n",
+            report.failures.len(),
+            results.len(),
+            error_file_name
+        ));
+    }
+    markdown_output.push('\n');
+    markdown_output.push_str("---\n\n");
+
+    if let Some(ref recap) = overall {
+        markdown_output.push_str(&recap.to_markdown());
+        markdown_output.push_str("---\n\n");
+    }
+
+    if let Some(ref rows) = leaderboard_rows {
+        markdown_output.push_str("## Leaderboard\n\n");
+        markdown_output.push_str("| Author | Commits | Lines | PRs | Reviews |\n");
+        markdown_output.push_str("|---|---:|---:|---:|---:|\n");
+        for row in rows {
+            markdown_output.push_str(&format!(
+                "| {} | {} | +{} / -{} | {} | {} |\n",
+                row.name, row.commits, row.insertions, row.deletions, row.prs, row.reviews
+            ));
+        }
+        markdown_output.push_str("\n---\n\n");
+    }
+
+    for (repo, summary_result) in &results {
+        markdown_output.push_str(&format!("## Repository: {}\n\n", repo.name));
+        if anonymizer.is_none() {
+            markdown_output.push_str(&format!("**Path:** {}\n\n", markdown_path(&repo.path)));
+            if let (Some(ref upstream), Some(ref fork)) = (&repo.upstream_url, &repo.remote_url) {
+                markdown_output.push_str(&format!("**Upstream:** {} (fork: {})\n\n", upstream, fork));
+            }
+        }
+
+        if let Some(cap) = repo.commit_cap {
+            markdown_output.push_str(&format!(
+                "*Note: only the {} most recent of {} commits were summarized.*\n\n",
+                cap, repo.stats.total_commits
+            ));
+        }
+
+        if !repo.keywords.is_empty() {
+            markdown_output.push_str(&format!("**Topics:** {}\n\n", format_keywords(&repo.keywords)));
+        }
+
+        if let Some(metrics) = delivery.get(&repo.path) {
+            markdown_output.push_str("**Delivery:**\n");
+            markdown_output.push_str(&format!("- Merged PRs: {}\n", metrics.merged_prs));
+            if let Some(time) = metrics.avg_time_to_merge {
+                markdown_output.push_str(&format!("- Avg time to merge: {}\n", format_duration(time)));
+            }
+            if let Some(time) = metrics.avg_review_turnaround {
+                markdown_output.push_str(&format!(
+                    "- Avg time to first review: {}\n",
+                    format_duration(time)
+                ));
+            }
+            markdown_output.push('\n');
+        }
+
+        if let Some(repo_issues) = issues.get(&repo.path) {
+            markdown_output.push_str("**Issues:**\n");
+            for issue_ref in repo_issues {
+                markdown_output.push_str(&format!(
+                    "- [{}]({}) {} ({}): {}\n",
+                    issue_ref.issue.identifier,
+                    issue_ref.issue.url,
+                    issue_ref.issue.title,
+                    issue_ref.issue.state,
+                    issue_ref.commits.join(", ")
+                ));
+            }
+            markdown_output.push('\n');
+        }
+
+        if repo.velocity.len() > 1 {
+            let weeks = recent_weeks(&repo.velocity);
+            let commits: Vec<u32> = weeks.iter().map(|week| week.commits).collect();
+            markdown_output.push_str(&format!(
+                "**Weekly velocity:** `{}` (commits per week, last {} weeks)\n",
+                sparkline(&commits),
+                weeks.len()
+            ));
+            for week in weeks {
+                markdown_output.push_str(&format!(
+                    "- Week of {}: {} commits, {} lines\n",
+                    week.start, week.commits, week.lines
+                ));
+            }
+            markdown_output.push('\n');
+        }
+
+        if cli.work_patterns && !repo.commits.is_empty() {
+            let patterns = work_patterns(&repo.commits);
+            markdown_output.push_str("**Work Patterns:**\n");
+            markdown_output.push_str(&format!(
+                "- Longest streak: {} days\n",
+                patterns.longest_streak_days
+            ));
+            markdown_output.push_str(&format!(
+                "- Weekend commits: {:.0}%\n",
+                patterns.weekend_percent
+            ));
+            markdown_output.push_str(&format!(
+                "- After-hours commits: {:.0}%\n\n",
+                patterns.after_hours_percent
+            ));
+        }
+
+        if !repo.hotspots.is_empty() {
+            markdown_output.push_str("**Hotspots:**\n");
+            for hotspot in &repo.hotspots {
+                markdown_output.push_str(&format!(
+                    "- `{}` ({} commits, {} lines churned)\n",
+                    hotspot.path, hotspot.changes, hotspot.churn
+                ));
+            }
+            markdown_output.push('\n');
+        }
+
+        // Add verbose information if requested
+        if cli.verbose >= 1 && !repo.commits.is_empty() {
+            markdown_output.push_str("**Stats:**\n");
+            markdown_output.push_str(&format!("- Total commits: {}\n", repo.stats.total_commits));
+            markdown_output.push_str(&format!("- Files changed: {}\n", repo.stats.total_files_changed));
+            markdown_output.push_str(&format!("- Insertions: +{}\n", repo.stats.total_insertions));
+            markdown_output.push_str(&format!("- Deletions: -{}\n", repo.stats.total_deletions));
+            markdown_output.push_str(&format!("- Net change: {}\n", repo.stats.net_lines_changed()));
+            if repo.test_ratio.test_files > 0 {
+                markdown_output.push_str(&format!(
+                    "- Test code: {:.0}% of changes\n",
+                    repo.test_ratio.test_percent()
+                ));
+            }
+            let breakdown = directory_breakdown(&repo.stats, DIRECTORY_BREAKDOWN_LIMIT);
+            if !breakdown.is_empty() {
+                markdown_output.push_str(&format!(
+                    "- Directories: {}\n",
+                    format_directory_breakdown(&breakdown)
+                ));
+            }
+            markdown_output.push('\n');
+        }
+
+        // Add commit list if verbose >= 2, hashes linking to the canonical repository
+        if cli.verbose >= 2 && !repo.commits.is_empty() {
+            // Configured links come first, so they can override the GitHub ones too
+            let commit_url = |commit: &git::Commit| {
+                if anonymizer.is_some() {
+                    return None;
+                }
+                repo.canonical_url()
+                    .and_then(|url| links::commit_url(url, &commit.hash, &orchestrator.config().commit_links))
+                    .or_else(|| repo.github_info.as_ref().map(|github| github.commit_url(&commit.hash)))
+            };
+            let commit_line = |commit: &git::Commit| match commit_url(commit) {
+                Some(url) => format!("- [`{}`]({}) {}\n", commit.short_hash, url, commit.summary),
+                None => format!("- `{}` {}\n", commit.short_hash, commit.summary),
+            };
+            markdown_output.push_str("**Commits:**\n");
+            if by_day {
+                for (day, commits) in commits_by_day(&repo.commits) {
+                    markdown_output.push_str(&format!("\n*{}*\n", day.format("%A, %Y-%m-%d")));
+                    for commit in commits {
+                        markdown_output.push_str(&commit_line(commit));
+                    }
+                }
+            } else {
+                for commit in &repo.commits {
+                    markdown_output.push_str(&commit_line(commit));
+                }
+            }
+            markdown_output.push('\n');
+        }
+
+        match summary_result {
+            Ok(summary) => {
+                markdown_output.push_str(&summary.to_markdown());
+                markdown_output.push_str("\n\n");
+            }
+            Err(e) => match failures_note(e) {
+                Some(note) => markdown_output.push_str(&format!("{}\n\n", note)),
+                None => markdown_output.push_str(&format!("**Error:** {}\n\n", shown_error(e))),
+            },
+        }
+        markdown_output.push_str(&provenance(repo, summary_result).to_markdown());
+        markdown_output.push('\n');
+
+        markdown_output.push_str("---\n\n");
+    }
+
+    // Follow-up questions see the whole report and every repository's context
+    let chat = cli.chat.then(|| {
+        let repos: Vec<&git::Repository> = results.iter().map(|(repo, _)| repo).collect();
+        orchestrator.start_chat(&repos, &markdown_output)
+    });
+
+    // Save the recap for `dev-recap history`; failing to doesn't lose the report
+    if history_enabled {
+        let recap = StoredRecap {
+            id: 0,
+            created_at: chrono::Utc::now(),
+            scan_path: scan_path.clone(),
+            authors: shown_authors.clone(),
+            timespan_start: timespan.start,
+            timespan_end: timespan.end,
+            command: env::args().collect::<Vec<_>>().join(" "),
+            anonymized: cli.anonymize,
+            overall: overall.clone(),
+            repositories: results
+                .iter()
+                .map(|(repo, result)| RepoRecap::new(repo, result))
+                .collect(),
+            report: markdown_output.clone(),
+        };
+        match save_to_history(&recap) {
+            Ok(id) => tracing::info!(id, "recap saved to history"),
+            Err(e) => {
+                tracing::warn!(error = %e, "saving recap to history failed");
+                eprintln!("⚠ Saving recap to history failed: {}", e);
+            }
+        }
+    }
+
+    // Write to file if --output is specified
+    if let Some(ref output_path) = output_path {
+        // Opened last, once the parts and the error report are written too
+        write_report(output_path, &markdown_output, false)?;
+
+        let mut part_count = 0;
+        if let Some(max_chars) = orchestrator.config().max_chunk_chars {
+            let parts = chunk::split(&markdown_output, max_chars);
+            for (index, part) in parts.iter().enumerate() {
+                std::fs::write(chunk::part_path(output_path, index + 1), part)?;
+            }
+            part_count = parts.len();
+            println!(
+                "✓ Split into {} parts of at most {} characters: {} to {}",
+                parts.len(),
+                max_chars,
+                wsl::display_path(&chunk::part_path(output_path, 1)),
+                wsl::display_path(&chunk::part_path(output_path, parts.len()))
+            );
+        }
+        // Parts of an earlier, longer report would read as part of this one
+        if let Err(e) = chunk::remove_stale_parts(output_path, part_count) {
+            eprintln!("⚠ Removing parts of an earlier report failed: {}", e);
+        }
+
+        // A report left over from an earlier run would blame repos that are fine now
+        if let Some((ref path, ref report)) = error_report {
+            if report.is_empty() {
+                // Only cleanup: the recap itself is already written
+                match std::fs::remove_file(path) {
+                    Err(e) if e.kind() != io::ErrorKind::NotFound => {
+                        eprintln!("⚠ Removing the stale {} failed: {}", wsl::display_path(path), e);
+                    }
+                    _ => {}
+                }
+            } else {
+                report.write(path)?;
+                println!(
+                    "⚠ {} repositories failed; details in {}",
+                    report.failures.len(),
+                    wsl::display_path(path)
+                );
+            }
+        }
+
+        if cli.open {
+            open_output(output_path)?;
+        }
+    } else if let Some(max_chars) = orchestrator.config().max_chunk_chars {
+        // Parts of the markdown report, ready to paste into chat one by one
+        let parts = chunk::split(&markdown_output, max_chars);
+        for (index, part) in parts.iter().enumerate() {
+            println!("\n{}\n", palette.heading(&format!("── Part {} of {} ──", index + 1, parts.len())));
+            println!("{}", part);
+        }
+    } else {
+        // Display results to stdout
+        println!("\n{}\n", "=".repeat(60));
+
+        if let Some(ref recap) = overall {
+            println!("{}", palette.markdown(&recap.to_markdown()));
+            println!("{}\n", "-".repeat(60));
+        }
+        if let Some(ref rows) = leaderboard_rows {
+            println!("{}", palette.heading("Leaderboard:"));
+            println!(
+                "  {:<24} {:>7} {:>17} {:>5} {:>7}",
+                "Author", "Commits", "Lines", "PRs", "Reviews"
+            );
+            for row in rows {
+                println!(
+                    "  {:<24} {:>7} {:>17} {:>5} {:>7}",
+                    row.name,
+                    row.commits,
+                    format!("+{} / -{}", row.insertions, row.deletions),
+                    row.prs,
+                    row.reviews
+                );
+            }
+            println!("{}\n", "-".repeat(60));
+        }
+
+        for (repo, summary_result) in results {
+            println!("{}", palette.heading(&format!("Repository: {}", repo.name)));
+            if anonymizer.is_none() {
+                println!("Path: {}", terminal_path(&repo.path));
+                if let (Some(ref upstream), Some(ref fork)) = (&repo.upstream_url, &repo.remote_url) {
+                    println!("Upstream: {} (fork: {})", upstream, fork);
+                }
+            }
+
+            if let Some(cap) = repo.commit_cap {
+                println!("Note: only the {} most recent of {} commits were summarized.", cap, repo.stats.total_commits);
+            }
+
+            if !repo.keywords.is_empty() {
+                println!("\nTopics: {}", format_keywords(&repo.keywords));
+            }
+
+            if let Some(metrics) = delivery.get(&repo.path) {
+                println!("\n{}", palette.heading("Delivery:"));
+                println!("  Merged PRs: {}", palette.stat(&metrics.merged_prs.to_string()));
+                if let Some(time) = metrics.avg_time_to_merge {
+                    println!("  Avg time to merge: {}", format_duration(time));
+                }
+                if let Some(time) = metrics.avg_review_turnaround {
+                    println!("  Avg time to first review: {}", format_duration(time));
+                }
+            }
+
+            if let Some(repo_issues) = issues.get(&repo.path) {
+                println!("\n{}", palette.heading("Issues:"));
+                for issue_ref in repo_issues {
+                    println!("  {}", issue_ref.describe());
+                }
+            }
+
+            if repo.velocity.len() > 1 {
+                let weeks = recent_weeks(&repo.velocity);
+                let commits: Vec<u32> = weeks.iter().map(|week| week.commits).collect();
+                println!(
+                    "\n{} {} (commits per week, last {} weeks)",
+                    palette.heading("Weekly velocity:"),
+                    palette.stat(&sparkline(&commits)),
+                    weeks.len()
+                );
+                for week in weeks {
+                    println!(
+                        "  - Week of {}: {} commits, {} lines",
+                        week.start, week.commits, week.lines
+                    );
+                }
+            }
+
+            if cli.work_patterns && !repo.commits.is_empty() {
+                let patterns = work_patterns(&repo.commits);
+                println!("\n{}", palette.heading("Work Patterns:"));
+                println!("  Longest streak: {} days", patterns.longest_streak_days);
+                println!("  Weekend commits: {:.0}%", patterns.weekend_percent);
+                println!("  After-hours commits: {:.0}%", patterns.after_hours_percent);
+            }
+
+            if !repo.hotspots.is_empty() {
+                println!("\n{}", palette.heading("Hotspots:"));
+                for hotspot in &repo.hotspots {
+                    println!(
+                        "  - {} ({} commits, {} lines churned)",
+                        hotspot.path, hotspot.changes, hotspot.churn
+                    );
+                }
+            }
+
+            // Add verbose information if requested
+            if cli.verbose >= 1 && !repo.commits.is_empty() {
+                println!("\n{}", palette.heading("Stats:"));
+                println!("  Total commits: {}", palette.stat(&repo.stats.total_commits.to_string()));
+                println!("  Files changed: {}", palette.stat(&repo.stats.total_files_changed.to_string()));
+                println!("  Insertions: {}", palette.stat(&format!("+{}", repo.stats.total_insertions)));
+                println!("  Deletions: {}", palette.stat(&format!("-{}", repo.stats.total_deletions)));
+                println!("  Net change: {}", palette.stat(&repo.stats.net_lines_changed().to_string()));
+                if repo.test_ratio.test_files > 0 {
+                    println!("  Test code: {:.0}% of changes", repo.test_ratio.test_percent());
+                }
+                let breakdown = directory_breakdown(&repo.stats, DIRECTORY_BREAKDOWN_LIMIT);
+                if !breakdown.is_empty() {
+                    println!("  Directories: {}", format_directory_breakdown(&breakdown));
+                }
+            }
+
+            // Add commit list if verbose >= 2
+            if cli.verbose >= 2 && !repo.commits.is_empty() {
+                println!("\n{}", palette.heading("Commits:"));
+                if by_day {
+                    for (day, commits) in commits_by_day(&repo.commits) {
+                        println!("  {}", day.format("%A, %Y-%m-%d"));
+                        for commit in commits {
+                            println!("    - {} {}", commit.short_hash, commit.summary);
+                        }
+                    }
+                } else {
+                    for commit in &repo.commits {
+                        println!("  - {} {}", commit.short_hash, commit.summary);
+                    }
+                }
+            }
+
+            let footer = provenance(&repo, &summary_result).to_line();
+            match summary_result {
+                Ok(summary) => {
+                    println!("\n{}", palette.markdown(&summary.to_markdown()));
+                }
+                Err(e) => {
+                    println!("\n{}", palette.error(&format!("❌ Error: {}", shown_error(&e))));
+                }
+            }
+            println!("\n{}", footer);
+
+            println!("\n{}\n", "-".repeat(60));
+        }
+    }
+
+    if let Some(chat) = chat {
+        run_chat(&orchestrator, chat).await?;
+    }
+
+    if cli.timings {
+        eprintln!("\n{}", orchestrator.timings());
+    }
+
+    Ok(Some(markdown_output))
+}
+
+/// `--compare-prompts`: every repository's summary from each prompt version,
+/// one after another
+async fn prompt_comparison(
+    orchestrator: &Orchestrator,
+    repos: &[git::Repository],
+    versions: &[PromptVersion],
+    jobs: usize,
+) -> String {
+    let summaries: Vec<Vec<Result<Summary>>> = futures::stream::iter(repos)
+        .map(|repo| {
+            futures::future::join_all(
+                versions
+                    .iter()
+                    .map(|&version| orchestrator.generate_summary_with(repo, version)),
+            )
+        })
+        .buffered(jobs.max(1))
+        .collect()
+        .await;
+
+    let names: Vec<String> = versions.iter().map(ToString::to_string).collect();
+    let mut report = format!("# Prompt Comparison: {}\n\n---\n\n", names.join(" vs "));
+    for (repo, results) in repos.iter().zip(summaries) {
+        report.push_str(&format!("## Repository: {}\n\n", repo.name));
+        for (version, result) in versions.iter().zip(results) {
+            report.push_str(&format!("### Prompt {}\n\n", version));
+            match result {
+                Ok(summary) => {
+                    report.push_str(&format!("{}\n\n", summary.work_summary));
+                    for section in summary.sections.iter().filter(|section| !section.items.is_empty()) {
+                        report.push_str(&format!("**{}:**\n", section.title));
+                        for (i, item) in section.items.iter().enumerate() {
+                            if section.numbered {
+                                report.push_str(&format!("{}. {}\n", i + 1, item));
+                            } else {
+                                report.push_str(&format!("- {}\n", item));
+                            }
+                        }
+                        report.push('\n');
+                    }
+                }
+                Err(e) => report.push_str(&format!("**Error:** {}\n\n", e)),
+            }
+        }
+        report.push_str("---\n\n");
+    }
+    report
+}
+
+/// `--chat`: answer follow-up questions about the recap until an empty line or EOF
+async fn run_chat(orchestrator: &Orchestrator, mut chat: Chat) -> Result<()> {
+    println!("\nAsk follow-up questions about the recap (empty line or Ctrl-D to finish).");
+
+    loop {
+        print!("\n> ");
+        io::stdout().flush()?;
+
+        let mut question = String::new();
+        if io::stdin().read_line(&mut question)? == 0 {
+            break;
+        }
+        let question = question.trim();
+        if question.is_empty() || matches!(question, "exit" | "quit") {
+            break;
+        }
+
+        match orchestrator.ask(&mut chat, question).await {
+            Ok(answer) => println!("\n{}", answer.trim()),
+            Err(e) => {
+                tracing::warn!(error = %e, "chat request failed");
+                println!("⚠ {}", e);
+            }
+        }
     }
-    println!("Timespan: {}", timespan_desc);
-    println!("{}\n", "=".repeat(60));
 
-    // Create orchestrator
-    let orchestrator = Orchestrator::new(config)?;
-
-    // Scan for repositories
-    let scan_spinner = ProgressBar::new_spinner();
-    scan_spinner.set_style(
-        ProgressStyle::default_spinner()
-            .template("{spinner:.cyan} {msg}")
-            .unwrap(),
-    );
-    scan_spinner.set_message("Scanning for git repositories...");
-    scan_spinner.enable_steady_tick(std::time::Duration::from_millis(100));
+    Ok(())
+}
 
-    let repos = orchestrator.scan_repositories(&scan_path)?;
+/// Run the recap on the configured schedule until stopped, delivering each report
+async fn run_daemon(config: Config, cli: &Cli, run_now: bool, metrics_addr: Option<SocketAddr>) -> Result<()> {
+    let expression = config.schedule.clone().ok_or_else(|| {
+        DevRecapError::MissingConfig("schedule in config (e.g. schedule = \"0 17 * * FRI\")".to_string())
+    })?;
+    let schedule = Schedule::parse(&expression)?;
 
-    scan_spinner.finish_with_message(format!("Found {} repositories", repos.len()));
+    if cli.output.is_none()
+        && config.output_path.is_none()
+        && config.slack_webhook_url.is_none()
+        && config.email_to.is_empty()
+    {
+        eprintln!("Warning: no output_path, slack_webhook_url or email_to configured; reports only go to stdout");
+    }
+    println!("dev-recap daemon running on schedule `{}`", schedule);
 
-    if repos.is_empty() {
-        println!("No git repositories found.");
-        return Ok(());
+    let metrics = Arc::new(Metrics::new());
+    if let Some(addr) = metrics_addr {
+        // Bind up front so a taken port fails the daemon instead of a background task
+        let listener = tokio::net::TcpListener::bind(addr).await?;
+        println!("Serving metrics on http://{}/metrics", listener.local_addr()?);
+        tokio::spawn(metrics::serve(Arc::clone(&metrics), listener));
     }
 
-    println!();
+    let mut run_now = run_now;
+    loop {
+        if !run_now {
+            let next = schedule.next_after(&chrono::Local::now()).ok_or_else(|| {
+                DevRecapError::config(format!("schedule `{}` never fires", schedule))
+            })?;
+            println!("Next run: {}", next.format("%Y-%m-%d %H:%M"));
 
-    // Analyze repositories
-    let progress = ProgressBar::new(repos.len() as u64);
-    progress.set_style(
-        ProgressStyle::default_bar()
-            .template("{spinner:.green} [{bar:40.cyan/blue}] {pos}/{len} {msg}")
-            .unwrap()
-            .progress_chars("=>-"),
-    );
-    progress.set_message("Analyzing repositories...");
-
-    let mut results = Vec::new();
-    for repo_path in &repos {
-        // Update progress message with current repo
-        let repo_name = repo_path
-            .file_name()
-            .and_then(|n| n.to_str())
-            .unwrap_or("unknown");
-        progress.set_message(format!("Analyzing {}", repo_name));
-
-        // Analyze single repository
-        // In team mode, analyze all commits; in single mode, filter by author
-        let author_filter = if cli.team {
-            None // Team mode: get all commits
-        } else {
-            Some(author_emails[0].as_str()) // Single author mode
-        };
-        let repo_result = orchestrator.analyze_repository(repo_path, author_filter, &timespan);
-
-        match repo_result {
-            Ok(repo) => {
-                if cli.dry_run {
-                    // Dry run: skip API call, create dummy success result
-                    use crate::ai::Summary;
-                    let summary = Summary::new(
-                        repo.name.clone(),
-                        format!("[Dry run] Would analyze {} commits", repo.stats.total_commits),
-                        vec![format!("{} files changed", repo.stats.total_files_changed)],
-                        vec![],
-                    );
-                    results.push((repo, Ok(summary)));
-                } else {
-                    // Generate summary
-                    let summary_result = orchestrator.generate_summary(&repo).await;
-                    results.push((repo, summary_result));
+            // Sleep in short steps against the wall clock, so a suspended
+            // machine catches up as soon as it wakes
+            loop {
+                let remaining = next - chrono::Local::now();
+                if remaining <= chrono::Duration::zero() {
+                    break;
                 }
+                let step = remaining.to_std().unwrap_or_default().min(DAEMON_POLL_INTERVAL);
+                tokio::time::sleep(step).await;
             }
+        }
+        run_now = false;
+
+        let result = run_analysis(config.clone(), cli, Some(Arc::clone(&metrics)), None).await;
+        metrics.record_run(result.is_ok());
+        match result {
+            Ok(Some(report)) => deliver_report(&config, &report).await,
+            Ok(None) => {}
             Err(e) => {
-                // Create a minimal repository for error reporting
-                let repo = git::Repository {
-                    path: repo_path.clone(),
-                    name: git::scanner::Scanner::get_repo_name(repo_path),
-                    remote_url: None,
-                    github_info: None,
-                    commits: vec![],
-                    stats: git::RepoStats::default(),
-                };
-                results.push((repo, Err(e)));
+                tracing::error!(error = %e, "scheduled run failed");
+                eprintln!("Scheduled run failed: {}", e);
             }
         }
-
-        progress.inc(1);
     }
+}
 
-    progress.finish_with_message(if cli.dry_run {
-        "Dry run complete"
-    } else {
-        "Analysis complete"
-    });
+/// Generate the report and serve it as a live-reloading HTML preview
+///
+/// If a preview is already running on the port, the report replaces the one
+/// shown there instead, so re-running with different flags refreshes the
+/// open page. Otherwise the report is regenerated whenever the config file
+/// changes, until interrupted.
+async fn run_preview(config: Config, cli: &Cli) -> Result<()> {
+    let url = format!("http://127.0.0.1:{}/", cli.preview_port);
+    let Some(report) = run_analysis(config, cli, None, None).await? else {
+        return Ok(());
+    };
 
-    // Build markdown output
-    let mut markdown_output = String::new();
-    markdown_output.push_str(&format!("# Dev Recap\n\n"));
-    markdown_output.push_str(&format!("**Scan Path:** {}\n", scan_path.display()));
-    if author_emails.len() == 1 {
-        markdown_output.push_str(&format!("**Author:** {}\n", author_emails[0]));
-    } else {
-        markdown_output.push_str(&format!("**Authors:** {}\n", author_emails.join(", ")));
+    if preview::push_report(cli.preview_port, &report).await? {
+        println!("\n✓ Updated the preview at {}", url);
+        return Ok(());
     }
-    markdown_output.push_str(&format!("**Timespan:** {}\n\n", timespan_desc));
-    markdown_output.push_str(&format!("---\n\n"));
 
-    for (repo, summary_result) in &results {
-        markdown_output.push_str(&format!("## Repository: {}\n\n", repo.name));
-        markdown_output.push_str(&format!("**Path:** {}\n\n", repo.path.display()));
+    let state = Arc::new(PreviewState::new(report));
+    let listener = tokio::net::TcpListener::bind(("127.0.0.1", cli.preview_port)).await?;
+    let server = tokio::spawn(preview::serve(Arc::clone(&state), listener));
+    println!("\n✓ Previewing at {} (Ctrl-C to stop)", url);
+    println!("  Edit the config file, or re-run with --preview and other flags, to refresh it.");
+    if let Err(e) = open_with_default_handler(url.as_ref()) {
+        tracing::warn!(error = %e, "couldn't open the browser");
+    }
 
-        // Add verbose information if requested
-        if cli.verbose >= 1 && !repo.commits.is_empty() {
-            markdown_output.push_str(&format!("**Stats:**\n"));
-            markdown_output.push_str(&format!("- Total commits: {}\n", repo.stats.total_commits));
-            markdown_output.push_str(&format!("- Files changed: {}\n", repo.stats.total_files_changed));
-            markdown_output.push_str(&format!("- Insertions: +{}\n", repo.stats.total_insertions));
-            markdown_output.push_str(&format!("- Deletions: -{}\n", repo.stats.total_deletions));
-            markdown_output.push_str(&format!("- Net change: {}\n\n", repo.stats.net_lines_changed()));
+    let config_path = match cli.config {
+        Some(ref path) => path.clone(),
+        None => Config::default_config_path()?,
+    };
+    let modified = |path: &Path| std::fs::metadata(path).and_then(|meta| meta.modified()).ok();
+    let mut last_modified = modified(&config_path);
+
+    loop {
+        tokio::time::sleep(PREVIEW_POLL_INTERVAL).await;
+        if server.is_finished() {
+            return server
+                .await
+                .map_err(|e| DevRecapError::other(format!("preview server failed: {}", e)))?;
         }
 
-        // Add commit list if verbose >= 2
-        if cli.verbose >= 2 && !repo.commits.is_empty() {
-            markdown_output.push_str(&format!("**Commits:**\n"));
-            for commit in &repo.commits {
-                markdown_output.push_str(&format!("- `{}` {}\n", commit.short_hash, commit.summary));
-            }
-            markdown_output.push_str("\n");
+        let current = modified(&config_path);
+        if current == last_modified {
+            continue;
         }
+        last_modified = current;
 
-        match summary_result {
-            Ok(summary) => {
-                markdown_output.push_str(&summary.to_markdown());
-                markdown_output.push_str("\n\n");
-            }
+        println!("\nConfig changed, regenerating the preview...");
+        let report = match load_preview_config(&config_path, cli) {
+            Ok(config) => run_analysis(config, cli, None, None).await,
+            Err(e) => Err(e),
+        };
+        match report {
+            Ok(Some(report)) => state.update(report),
+            Ok(None) => {}
             Err(e) => {
-                markdown_output.push_str(&format!("**Error:** {}\n\n", e));
+                tracing::warn!(error = %e, "preview regeneration failed");
+                println!("⚠ Regenerating failed: {}", e);
             }
         }
-
-        markdown_output.push_str("---\n\n");
     }
+}
 
-    // Write to file if --output is specified
-    if let Some(output_path) = &cli.output {
-        std::fs::write(output_path, &markdown_output)?;
-        println!("\n✓ Results written to: {}", output_path.display());
-    } else {
-        // Display results to stdout
-        println!("\n{}\n", "=".repeat(60));
-        for (repo, summary_result) in results {
-            println!("Repository: {}", repo.name);
-            println!("Path: {}", repo.path.display());
+/// `dev-recap watch`: regenerate the recap whenever a repository's HEAD moves
+///
+/// Only repositories whose HEAD moved are analyzed and summarized again; the
+/// others keep their recap from the previous update.
+async fn run_watch(config: Config, cli: &Cli, interval: Duration) -> Result<()> {
+    let given_repos = given_repositories(cli)?;
+    let scan_path = scan_root(cli.path.as_deref(), &given_repos)?;
+    git::scanner::Scanner::validate_root(&scan_path)?;
+    check_scan_fs(&scan_path, cli.allow_slow_fs)?;
+    let scanner = git::scanner::Scanner::new(config.exclude_patterns.clone(), config.max_scan_depth);
+    let read_heads = || {
+        if given_repos.is_empty() {
+            scanner.scan(&scan_path).map(|repos| Heads::read(&repos))
+        } else {
+            Ok(Heads::read(&given_repos))
+        }
+    };
 
-            // Add verbose information if requested
-            if cli.verbose >= 1 && !repo.commits.is_empty() {
-                println!("\nStats:");
-                println!("  Total commits: {}", repo.stats.total_commits);
-                println!("  Files changed: {}", repo.stats.total_files_changed);
-                println!("  Insertions: +{}", repo.stats.total_insertions);
-                println!("  Deletions: -{}", repo.stats.total_deletions);
-                println!("  Net change: {}", repo.stats.net_lines_changed());
-            }
+    if cli.output.is_none() && config.output_path.is_none() && !cli.preview {
+        eprintln!("Warning: no --output, output_path or --preview; updated reports only go to stdout");
+    }
+    let url = format!("http://127.0.0.1:{}/", cli.preview_port);
+    // Served here, or by another --preview run already listening on the port
+    let mut served_preview: Option<Arc<PreviewState>> = None;
+    let mut heads = read_heads()?;
+    let mut recapped = Recapped::default();
 
-            // Add commit list if verbose >= 2
-            if cli.verbose >= 2 && !repo.commits.is_empty() {
-                println!("\nCommits:");
-                for commit in &repo.commits {
-                    println!("  - {} {}", commit.short_hash, commit.summary);
+    loop {
+        match run_analysis(config.clone(), cli, None, Some(&mut recapped)).await {
+            Ok(Some(report)) if cli.preview => match served_preview {
+                Some(ref state) => state.update(report),
+                None if preview::push_report(cli.preview_port, &report).await? => {
+                    println!("\n✓ Updated the preview at {}", url);
                 }
+                None => {
+                    let state = Arc::new(PreviewState::new(report));
+                    let listener = tokio::net::TcpListener::bind(("127.0.0.1", cli.preview_port)).await?;
+                    tokio::spawn(preview::serve(Arc::clone(&state), listener));
+                    println!("\n✓ Previewing at {}", url);
+                    if let Err(e) = open_with_default_handler(url.as_ref()) {
+                        tracing::warn!(error = %e, "couldn't open the browser");
+                    }
+                    served_preview = Some(state);
+                }
+            },
+            Ok(_) => {}
+            Err(e) => {
+                tracing::warn!(error = %e, "watch update failed");
+                println!("⚠ Updating the recap failed: {}", e);
             }
+        }
+        println!(
+            "\nWatching {} repositories for new commits every {}s (Ctrl-C to stop)",
+            heads.len(),
+            interval.as_secs()
+        );
 
-            match summary_result {
-                Ok(summary) => {
-                    println!("\n{}", summary.to_markdown());
-                }
+        loop {
+            tokio::time::sleep(interval).await;
+            let current = match read_heads() {
+                Ok(current) => current,
                 Err(e) => {
-                    println!("\n❌ Error: {}", e);
+                    tracing::warn!(error = %e, "rescanning for watch failed");
+                    continue;
                 }
+            };
+            let changed = current.changed(&heads);
+            if changed.is_empty() {
+                continue;
             }
+            heads = current;
+            recapped.invalidate(&changed);
+            let names: Vec<String> = changed
+                .iter()
+                .map(|path| git::scanner::Scanner::get_repo_name(path))
+                .collect();
+            println!("\nHEAD moved in {}; updating the recap...", names.join(", "));
+            break;
+        }
+    }
+}
 
-            println!("\n{}\n", "-".repeat(60));
+/// Reload the config for a preview rerun, with the same CLI overrides
+fn load_preview_config(path: &Path, cli: &Cli) -> Result<Config> {
+    let mut config = apply_cli_overrides(Config::load_from(path)?, cli);
+    let api_key = config.get_api_key()?;
+    config.set_api_key(api_key);
+    Ok(config)
+}
+
+/// Send a report to every configured channel, reporting failures without stopping
+async fn deliver_report(config: &Config, report: &str) {
+    if let Some(ref url) = config.slack_webhook_url {
+        match deliver::post_to_slack(url, report, config.max_chunk_chars).await {
+            Ok(()) => println!("✓ Report posted to Slack"),
+            Err(e) => {
+                tracing::warn!(error = %e, "Slack delivery failed");
+                eprintln!("⚠ Slack delivery failed: {}", e);
+            }
         }
     }
 
-    Ok(())
+    if !config.email_to.is_empty() {
+        let subject = format!("Dev Recap {}", chrono::Local::now().format("%Y-%m-%d"));
+        match deliver::send_email(&config.email_to, &subject, report) {
+            Ok(()) => println!("✓ Report emailed to {}", config.email_to.join(", ")),
+            Err(e) => {
+                tracing::warn!(error = %e, "email delivery failed");
+                eprintln!("⚠ Email delivery failed: {}", e);
+            }
+        }
+    }
 }
 
-fn handle_command(command: &Commands) -> Result<()> {
+fn handle_command(command: &Commands, config_path: Option<&Path>) -> Result<()> {
     match command {
         Commands::Init { force } => {
             let config_path = Config::default_config_path()?;
@@ -353,11 +2006,45 @@ fn handle_command(command: &Commands) -> Result<()> {
             println!("  2. Add claude_api_key to the config file:");
             println!("     claude_api_key = \"sk-ant-YOUR_KEY_HERE\"");
         }
-        Commands::Config => {
-            let config = Config::load_or_create_default()?;
-            let toml_str = toml::to_string_pretty(&config)?;
-            println!("Current configuration:\n");
-            println!("{}", toml_str);
+        Commands::Config { action } => match action {
+            None | Some(ConfigAction::Show) => {
+                let config = match config_path {
+                    Some(path) => Config::load_from(path)?,
+                    None => Config::load_or_create_default()?,
+                };
+                let toml_str = toml::to_string_pretty(&config)?;
+                println!("Current configuration:\n");
+                println!("{}", toml_str);
+            }
+            Some(ConfigAction::Validate) => {
+                let path = match config_path {
+                    Some(path) => path.to_path_buf(),
+                    None => Config::default_config_path()?,
+                };
+                if !path.exists() {
+                    eprintln!("Config file not found at: {}", path.display());
+                    std::process::exit(1);
+                }
+
+                let issues = Config::validate_file(&path)?;
+                println!("Validating {}", path.display());
+                for issue in &issues {
+                    println!("  {}", issue);
+                }
+
+                if issues.iter().any(|issue| issue.is_error) {
+                    std::process::exit(1);
+                }
+                println!("✓ Config is valid");
+            }
+        },
+        Commands::Auth { action } => handle_auth(action, config_path)?,
+        Commands::Hook { action } => handle_hook(action)?,
+        Commands::History { action } => handle_history(action)?,
+        Commands::Diff { old, new } => {
+            let old = load_recap(old)?;
+            let new = load_recap(new)?;
+            print!("{}", RecapDiff::new(&old, &new).to_markdown(&old, &new));
         }
         Commands::ClearCache => {
             let cache_dir = Config::default_cache_dir()?;
@@ -379,13 +2066,225 @@ fn handle_command(command: &Commands) -> Result<()> {
                 if let Ok(cache) = ai::cache::SummaryCache::new(&cache_dir, 0) {
                     let stats = cache.stats();
                     println!("Total entries: {}", stats.total_entries);
+                    println!("Cached commit diff stats: {}", stats.diff_stat_entries);
+                    println!("Cached GitHub responses: {}", stats.github_entries);
                     println!("Database size: {}", stats.format_size());
                 } else {
                     println!("Could not open cache database");
                 }
             }
         }
+        Commands::Man { out_dir } => {
+            let command = Cli::command();
+            match out_dir {
+                Some(dir) => {
+                    std::fs::create_dir_all(dir)?;
+                    write_man_pages(&command, dir)?;
+                    println!("✓ Man pages written to: {}", wsl::display_path(dir));
+                }
+                None => clap_mangen::Man::new(command).render(&mut io::stdout())?,
+            }
+        }
+        Commands::Serve { .. }
+        | Commands::Daemon { .. }
+        | Commands::Watch { .. }
+        | Commands::Mcp
+        | Commands::Models
+        | Commands::Regen { .. }
+        | Commands::Year { .. }
+        | Commands::Cache { .. } => {
+            unreachable!("serve, daemon, watch, mcp, models, regen, year and cache are handled in main")
+        }
+    }
+    Ok(())
+}
+
+/// Handle `hook` subcommands
+fn handle_hook(action: &HookAction) -> Result<()> {
+    match action {
+        HookAction::Install { repo, force } => {
+            let hook = git::commit_index::install_hook(repo, *force)?;
+            println!("✓ Installed {}", hook.display());
+            println!("  Commits from now on are recorded; recaps covering only those skip the history walk.");
+        }
+        HookAction::Uninstall { repo } => {
+            if git::commit_index::uninstall_hook(repo)? {
+                println!("✓ Hook removed");
+            } else {
+                println!("No dev-recap hook installed; recorded commits (if any) removed");
+            }
+        }
+        HookAction::Record { repo } => git::commit_index::record_head(repo)?,
+    }
+    Ok(())
+}
+
+/// Handle `history` subcommands
+fn handle_history(action: &HistoryAction) -> Result<()> {
+    let store = HistoryStore::open(&Config::default_data_dir()?.join(history::HISTORY_FILE))?;
+
+    match action {
+        HistoryAction::List { limit } => {
+            let recaps = store.list(*limit)?;
+            if recaps.is_empty() {
+                println!("No recaps saved yet");
+                return Ok(());
+            }
+
+            println!(
+                "{:>5}  {:<16}  {:<23}  {:>5}  Authors",
+                "ID", "Created", "Timespan", "Repos"
+            );
+            for recap in recaps {
+                println!(
+                    "{:>5}  {:<16}  {:<23}  {:>5}  {}",
+                    recap.id,
+                    recap.created_at.with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M"),
+                    format!(
+                        "{} to {}",
+                        recap.timespan_start.format("%Y-%m-%d"),
+                        recap.timespan_end.format("%Y-%m-%d")
+                    ),
+                    recap.repositories,
+                    recap.authors.join(", ")
+                );
+            }
+        }
+        HistoryAction::Show { id, json } => {
+            let recap = store
+                .get(*id)?
+                .ok_or_else(|| DevRecapError::history(format!("no recap with id {}", id)))?;
+            if *json {
+                println!("{}", serde_json::to_string_pretty(&recap)?);
+            } else {
+                print!("{}", recap.report);
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Load a recap by history id, or from a `history show --json` file
+fn load_recap(source: &str) -> Result<StoredRecap> {
+    let path = Path::new(source);
+    if !path.exists() {
+        if let Ok(id) = source.parse::<i64>() {
+            let store =
+                HistoryStore::open(&Config::default_data_dir()?.join(history::HISTORY_FILE))?;
+            return store
+                .get(id)?
+                .ok_or_else(|| DevRecapError::history(format!("no recap with id {}", id)));
+        }
+    }
+
+    let json = std::fs::read_to_string(path)?;
+    serde_json::from_str(&json).map_err(|e| {
+        DevRecapError::other(format!(
+            "{} is not a saved recap (use `dev-recap history show <ID> --json`): {}",
+            path.display(),
+            e
+        ))
+    })
+}
+
+/// Save a recap to the history database, returning its id
+fn save_to_history(recap: &StoredRecap) -> Result<i64> {
+    let mut store = HistoryStore::open(&Config::default_data_dir()?.join(history::HISTORY_FILE))?;
+    store.save(recap)
+}
+
+/// Handle `auth` subcommands
+fn handle_auth(action: &AuthAction, config_path: Option<&Path>) -> Result<()> {
+    use secrets::Secret;
+
+    let secret_for = |github: bool, linear: bool| {
+        if github {
+            Secret::GitHubToken
+        } else if linear {
+            Secret::LinearApiKey
+        } else {
+            Secret::ClaudeApiKey
+        }
+    };
+
+    match action {
+        AuthAction::SetKey { github, linear } => {
+            let secret = secret_for(*github, *linear);
+            let value = prompt_secret(secret.label())?;
+            secrets::set(secret, &value)?;
+            println!("✓ Stored {} in the system keyring", secret.label());
+            println!("  You can now remove {} from your config file", secret.account());
+        }
+        AuthAction::DeleteKey { github, linear } => {
+            let secret = secret_for(*github, *linear);
+            if secrets::delete(secret)? {
+                println!("✓ Removed {} from the system keyring", secret.label());
+            } else {
+                println!("No {} stored in the system keyring", secret.label());
+            }
+        }
+        AuthAction::Status => {
+            let config = match config_path {
+                Some(path) => Config::load_from(path)?,
+                None => Config::load_or_create_default()?,
+            };
+
+            // Report in resolution order: env/config file, credential command, keyring.
+            // The API key is the selected provider's, wherever it was set
+            // (ANTHROPIC_AUTH_TOKEN ends up in [providers.anthropic]).
+            let provider = config.provider_config();
+            let anthropic = config.provider == Provider::Anthropic;
+            let source = if provider.api_key.is_some() {
+                "environment or config file"
+            } else if provider.api_key_cmd.is_some() {
+                "credential command"
+            } else if anthropic && secrets::get(Secret::ClaudeApiKey).is_some() {
+                "system keyring"
+            } else if !config.provider.needs_api_key() {
+                "not needed"
+            } else {
+                "not set"
+            };
+            if anthropic {
+                println!("{}: {}", Secret::ClaudeApiKey.label(), source);
+            } else {
+                println!("API key ({}): {}", config.provider, source);
+            }
+
+            for (secret, configured) in [
+                (Secret::GitHubToken, config.github_token.is_some()),
+                (Secret::LinearApiKey, config.linear_api_key.is_some()),
+            ] {
+                let source = if configured {
+                    "environment or config file"
+                } else if secrets::get(secret).is_some() {
+                    "system keyring"
+                } else {
+                    "not set"
+                };
+                println!("{}: {}", secret.label(), source);
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Write a man page for a command and, recursively, each of its subcommands
+fn write_man_pages(command: &clap::Command, dir: &Path) -> Result<()> {
+    // Building assigns git-style display names (dev-recap-clear-cache),
+    // which clap_mangen uses for the page title and file name
+    let mut command = command.clone();
+    command.build();
+
+    clap_mangen::Man::new(command.clone()).generate_to(dir)?;
+
+    for subcommand in command.get_subcommands() {
+        // Skip clap's generated `help` subcommand
+        if !subcommand.is_hide_set() && subcommand.get_name() != "help" {
+            write_man_pages(subcommand, dir)?;
+        }
     }
+
     Ok(())
 }
 
@@ -405,6 +2304,41 @@ fn prompt_with_default(prompt: &str, default: &str) -> Result<String> {
     }
 }
 
+/// Confirm the API calls of `estimate` before making them
+///
+/// Asks when `can_ask`; otherwise the run needs `--yes`, so scripted runs
+/// never spend without opting in. Returns whether to go ahead.
+fn confirm_cost(cli: &Cli, estimate: &CostEstimate, can_ask: bool) -> Result<bool> {
+    if cli.yes || cli.dry_run || estimate.requests == 0 {
+        return Ok(true);
+    }
+    if !can_ask {
+        return Err(DevRecapError::other(format!(
+            "this run would make {} and can't ask for confirmation; pass --yes to allow it",
+            estimate
+        )));
+    }
+
+    println!("\nAbout to make {}", estimate);
+    if prompt_confirm("Proceed?")? {
+        Ok(true)
+    } else {
+        println!("Aborted. No API calls were made.");
+        Ok(false)
+    }
+}
+
+/// Ask a yes/no question (defaults to no)
+fn prompt_confirm(prompt: &str) -> Result<bool> {
+    print!("{} [y/N]: ", prompt);
+    io::stdout().flush()?;
+
+    let mut input = String::new();
+    io::stdin().read_line(&mut input)?;
+
+    Ok(matches!(input.trim().to_lowercase().as_str(), "y" | "yes"))
+}
+
 /// Prompt user for required value (cannot be empty)
 fn prompt_required(prompt: &str) -> Result<String> {
     loop {
@@ -422,12 +2356,113 @@ fn prompt_required(prompt: &str) -> Result<String> {
     }
 }
 
+/// Prompt for a secret without echoing it (cannot be empty)
+///
+/// Piped input (`echo $KEY | dev-recap auth set-key`) is read as a line.
+fn prompt_secret(prompt: &str) -> Result<String> {
+    if !io::stdin().is_terminal() {
+        let mut input = String::new();
+        io::stdin().read_line(&mut input)?;
+        let input = input.trim().to_string();
+        if input.is_empty() {
+            return Err(DevRecapError::other(format!("no {} on stdin", prompt)));
+        }
+        return Ok(input);
+    }
+
+    loop {
+        let input = rpassword::prompt_password(format!("{}: ", prompt))?;
+        let input = input.trim().to_string();
+        if !input.is_empty() {
+            return Ok(input);
+        }
+        eprintln!("This field is required. Please enter a value.");
+    }
+}
+
+/// The report file: `--output`, or the templated `output_path` from config
+fn resolve_output_path(config: &Config, cli: &Cli) -> Result<Option<PathBuf>> {
+    match cli.output {
+        Some(ref path) => Ok(Some(path.clone())),
+        None => config
+            .output_path
+            .as_deref()
+            .map(|template| config::expand_output_path(template, chrono::Local::now()))
+            .transpose(),
+    }
+}
+
+/// Write a report to `path`, creating its directory, and open it for review
+/// if `open`
+fn write_report(path: &Path, report: &str, open: bool) -> Result<()> {
+    if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(path, report)?;
+    println!("\n✓ Results written to: {}", wsl::display_path(path));
+    if open {
+        open_output(path)?;
+    }
+    Ok(())
+}
+
+/// Open a written output file for review
+///
+/// Text outputs go to `$VISUAL`/`$EDITOR` (waiting for it to exit); HTML/PDF
+/// outputs, or any output when no editor is set, go to the OS default handler.
+fn open_output(path: &Path) -> Result<()> {
+    use std::process::Command;
+
+    let is_document = path
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .map(|ext| matches!(ext.to_lowercase().as_str(), "html" | "htm" | "pdf"))
+        .unwrap_or(false);
+
+    let editor = env::var("VISUAL")
+        .or_else(|_| env::var("EDITOR"))
+        .ok()
+        .filter(|editor| !editor.trim().is_empty());
+
+    let status = match editor {
+        Some(editor) if !is_document => {
+            // Editors are often configured with arguments, e.g. "code --wait"
+            let mut parts = editor.split_whitespace();
+            let program = parts.next().unwrap_or_default();
+            Command::new(program).args(parts).arg(path).status()?
+        }
+        _ => open_with_default_handler(path.as_os_str())?,
+    };
+
+    if !status.success() {
+        tracing::warn!(path = %path.display(), %status, "viewer exited with an error");
+    }
+
+    Ok(())
+}
+
+/// Open a file or URL with the OS default handler
+fn open_with_default_handler(target: &std::ffi::OsStr) -> io::Result<std::process::ExitStatus> {
+    use std::process::Command;
+
+    let mut command = if cfg!(target_os = "macos") {
+        Command::new("open")
+    } else if cfg!(target_os = "windows") {
+        let mut command = Command::new("cmd");
+        command.args(["/C", "start", ""]);
+        command
+    } else {
+        Command::new("xdg-open")
+    };
+    command.arg(target).status()
+}
+
 /// Try to get user email from git config
 fn get_git_user_email() -> Option<String> {
     use std::process::Command;
 
     Command::new("git")
-        .args(&["config", "--get", "user.email"])
+        .args(["config", "--get", "user.email"])
         .output()
         .ok()
         .and_then(|output| {
@@ -452,6 +2487,11 @@ fn apply_cli_overrides(mut config: Config, cli: &Cli) -> Config {
         config.default_timespan_days = days;
     }
 
+    // Override recap format
+    if let Some(format) = cli.format {
+        config.format = format;
+    }
+
     // Override cache setting
     if cli.no_cache {
         config.cache_enabled = false;
@@ -462,5 +2502,85 @@ fn apply_cli_overrides(mut config: Config, cli: &Cli) -> Config {
         config.max_scan_depth = Some(depth);
     }
 
+    // Override commit timestamp
+    if let Some(date) = cli.date {
+        config.commit_date = date;
+    }
+
+    // Override diff stats
+    if cli.no_diff_stats {
+        config.diff_stats_enabled = false;
+    }
+
+    // Override prompt privacy
+    if cli.private_prompts {
+        config.private_prompts = true;
+    }
+
+    // Override trivial commit threshold
+    if let Some(min) = cli.min_changes {
+        config.min_changes = Some(min);
+    }
+
+    // Override commit body count
+    if let Some(count) = cli.commit_bodies {
+        config.commit_bodies = Some(count);
+    }
+
+    // Override watch-outs section
+    if cli.watch_outs {
+        config.watch_outs = true;
+    }
+
+    // Override commit grouping
+    if let Some(group_by) = cli.group_by {
+        config.group_by = Some(group_by);
+    }
+
+    // Override goals file
+    if let Some(ref path) = cli.goals {
+        config.goals_file = Some(path.to_string_lossy().into_owned());
+    }
+
+    // Override achievement limit
+    if let Some(top) = cli.top_achievements {
+        config.top_achievements = Some(top);
+    }
+
+    // Override commit cap
+    if let Some(max) = cli.max_commits_per_repo {
+        config.max_commits_per_repo = Some(max);
+    }
+
+    // Override per-repository timeout
+    if let Some(secs) = cli.repo_timeout {
+        config.repo_timeout_secs = Some(secs);
+    }
+
+    // Override chat message size
+    if let Some(max) = cli.max_chunk_chars {
+        config.max_chunk_chars = Some(max);
+    }
+
+    // Override log file
+    if let Some(ref log_file) = cli.log_file {
+        config.log_file = Some(log_file.clone());
+    }
+
+    // Override file links
+    if cli.file_links {
+        config.file_links = true;
+    }
+
+    // Override color theme
+    if let Some(theme) = cli.theme {
+        config.theme = theme;
+    }
+
+    // Override repository order
+    if let Some(order) = cli.order {
+        config.order = order;
+    }
+
     config
 }
@@ -0,0 +1,427 @@
+//! Model Context Protocol server (`dev-recap mcp`)
+//!
+//! Lets AI assistants (Claude Desktop, IDE agents) use dev-recap as tools
+//! against local checkouts. Messages are JSON-RPC 2.0, one per line on
+//! stdin/stdout; logs go to stderr so they never corrupt the stream.
+//!
+//! Tools:
+//!
+//! - `scan_repos`: find git repositories under a path
+//! - `analyze_repo`: commit statistics of one repository, optionally with
+//!   its AI summary
+//! - `generate_recap`: the full recap of a path, like `POST /recaps`
+
+use crate::error::{DevRecapError, Result};
+use crate::export::RepoExport;
+use crate::git::scanner::Scanner;
+use crate::orchestrator::Orchestrator;
+use crate::server::{RecapRequest, ServerState};
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::path::PathBuf;
+use std::sync::Arc;
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::sync::Mutex;
+use tokio::task::JoinSet;
+
+/// Protocol revisions this server speaks, newest first
+const PROTOCOL_VERSIONS: &[&str] = &["2025-06-18", "2025-03-26", "2024-11-05"];
+
+/// At most this many commits are listed by `analyze_repo`
+const MAX_LISTED_COMMITS: usize = 200;
+
+// JSON-RPC error codes
+const PARSE_ERROR: i64 = -32700;
+const INVALID_REQUEST: i64 = -32600;
+const METHOD_NOT_FOUND: i64 = -32601;
+const INVALID_PARAMS: i64 = -32602;
+
+/// Arguments of `scan_repos`
+#[derive(Debug, Deserialize)]
+struct ScanArgs {
+    path: PathBuf,
+}
+
+/// Arguments of `analyze_repo`
+#[derive(Debug, Deserialize)]
+struct AnalyzeArgs {
+    #[serde(flatten)]
+    request: RecapRequest,
+    /// Also generate the AI summary
+    #[serde(default)]
+    summarize: bool,
+}
+
+/// MCP server answering tool calls with a shared orchestrator
+pub struct McpServer {
+    recaps: ServerState,
+}
+
+impl McpServer {
+    /// Serve tools from `orchestrator`, summarizing up to `jobs` repositories at a time
+    pub fn new(orchestrator: Arc<Orchestrator>, jobs: usize) -> Self {
+        Self {
+            recaps: ServerState::new(orchestrator, jobs),
+        }
+    }
+
+    /// Handle one JSON-RPC message, returning the response (none for notifications)
+    pub async fn handle(&self, message: &str) -> Option<Value> {
+        let message: Value = match serde_json::from_str(message) {
+            Ok(message) => message,
+            Err(e) => return Some(error_response(Value::Null, PARSE_ERROR, &e.to_string())),
+        };
+
+        let Some(method) = message.get("method").and_then(Value::as_str) else {
+            return message
+                .get("id")
+                .map(|id| error_response(id.clone(), INVALID_REQUEST, "missing method"));
+        };
+        // Notifications (no id) never get a response
+        let id = message.get("id")?.clone();
+        let params = message.get("params").cloned().unwrap_or(Value::Null);
+
+        let result = match method {
+            "initialize" => Ok(initialize_result(&params)),
+            "ping" => Ok(json!({})),
+            "tools/list" => Ok(json!({ "tools": tool_definitions() })),
+            "tools/call" => self.call_tool(&params).await,
+            _ => Err((METHOD_NOT_FOUND, format!("unknown method `{}`", method))),
+        };
+
+        Some(match result {
+            Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+            Err((code, message)) => error_response(id, code, &message),
+        })
+    }
+
+    /// Run a tool; tool failures are reported in the result, not as protocol errors
+    async fn call_tool(&self, params: &Value) -> std::result::Result<Value, (i64, String)> {
+        let name = params
+            .get("name")
+            .and_then(Value::as_str)
+            .ok_or((INVALID_PARAMS, "missing tool name".to_string()))?;
+        let arguments = params.get("arguments").cloned().unwrap_or_else(|| json!({}));
+
+        let output = match name {
+            "scan_repos" => self.scan_repos(parse_args(arguments)?).await,
+            "analyze_repo" => self.analyze_repo(parse_args(arguments)?).await,
+            "generate_recap" => self.generate_recap(parse_args(arguments)?).await,
+            _ => return Err((INVALID_PARAMS, format!("unknown tool `{}`", name))),
+        };
+
+        Ok(match output {
+            Ok(value) => json!({
+                "content": [{ "type": "text", "text": pretty(&value) }],
+                "structuredContent": value,
+                "isError": false,
+            }),
+            Err(e) => {
+                tracing::warn!(tool = name, error = %e, "tool failed");
+                json!({
+                    "content": [{ "type": "text", "text": e.to_string() }],
+                    "isError": true,
+                })
+            }
+        })
+    }
+
+    async fn scan_repos(&self, args: ScanArgs) -> Result<Value> {
+        let orchestrator = Arc::clone(self.recaps.orchestrator());
+        let repos = tokio::task::spawn_blocking(move || orchestrator.scan_repositories(&args.path))
+            .await
+            .map_err(|e| DevRecapError::other(format!("scan task failed: {}", e)))??;
+
+        let repositories: Vec<Value> = repos
+            .iter()
+            .map(|path| {
+                let remotes = Scanner::get_remotes(path);
+                json!({
+                    "name": Scanner::get_repo_name(path),
+                    "path": path,
+                    "remote_url": remotes.origin,
+                    "upstream_url": remotes.upstream,
+                })
+            })
+            .collect();
+        Ok(json!({ "repositories": repositories }))
+    }
+
+    async fn analyze_repo(&self, args: AnalyzeArgs) -> Result<Value> {
+        let orchestrator = self.recaps.orchestrator();
+        let timespan = args
+            .request
+            .timespan(orchestrator.config().default_timespan_days)?;
+        let repo = orchestrator
+            .analyze_repository_blocking(args.request.path, args.request.author, timespan)
+            .await?;
+
+        let summary = if args.summarize {
+            Some(orchestrator.generate_summary(&repo).await?)
+        } else {
+            None
+        };
+
+        let commits: Vec<Value> = repo
+            .commits
+            .iter()
+            .take(MAX_LISTED_COMMITS)
+            .map(|commit| {
+                json!({
+                    "hash": commit.short_hash,
+                    "summary": commit.summary,
+                    "author": commit.author.email,
+                    "timestamp": commit.timestamp,
+                    "insertions": commit.insertions,
+                    "deletions": commit.deletions,
+                })
+            })
+            .collect();
+
+        Ok(json!({
+            "stats": RepoExport::new(&repo, None),
+            "commits": commits,
+            "summary": summary,
+        }))
+    }
+
+    async fn generate_recap(&self, request: RecapRequest) -> Result<Value> {
+        let default_days = self.recaps.orchestrator().config().default_timespan_days;
+        let timespan = request.timespan(default_days)?;
+        let result = self.recaps.run(request, timespan).await?;
+        Ok(serde_json::to_value(result)?)
+    }
+}
+
+/// Answer MCP messages on stdin until it closes
+pub async fn serve_stdio(server: Arc<McpServer>) -> Result<()> {
+    let stdout = Arc::new(Mutex::new(tokio::io::stdout()));
+    let mut lines = BufReader::new(tokio::io::stdin()).lines();
+    let mut requests = JoinSet::new();
+
+    // Requests run concurrently, so a long recap doesn't block pings
+    while let Some(line) = lines.next_line().await? {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let server = Arc::clone(&server);
+        let stdout = Arc::clone(&stdout);
+        requests.spawn(async move {
+            if let Some(response) = server.handle(&line).await {
+                let mut stdout = stdout.lock().await;
+                let written = async {
+                    stdout.write_all(format!("{}\n", response).as_bytes()).await?;
+                    stdout.flush().await
+                };
+                if let Err(e) = written.await {
+                    tracing::warn!(error = %e, "failed to write MCP response");
+                }
+            }
+        });
+    }
+
+    // Finish requests still running when the client closed stdin
+    while requests.join_next().await.is_some() {}
+    Ok(())
+}
+
+fn initialize_result(params: &Value) -> Value {
+    // Agree to the client's revision when we speak it, otherwise offer our newest
+    let requested = params.get("protocolVersion").and_then(Value::as_str);
+    let version = requested
+        .filter(|v| PROTOCOL_VERSIONS.contains(v))
+        .unwrap_or(PROTOCOL_VERSIONS[0]);
+
+    json!({
+        "protocolVersion": version,
+        "capabilities": { "tools": {} },
+        "serverInfo": { "name": "dev-recap", "version": env!("CARGO_PKG_VERSION") },
+    })
+}
+
+fn tool_definitions() -> Value {
+    let timespan_properties = json!({
+        "path": { "type": "string", "description": "Local path" },
+        "author": { "type": "string", "description": "Author email to filter commits by (all authors if omitted)" },
+        "days": { "type": "integer", "minimum": 1, "description": "Days to look back" },
+        "since": { "type": "string", "description": "Start date, e.g. 2025-01-01 or \"last monday\"" },
+        "until": { "type": "string", "description": "End date" },
+    });
+    let with = |extra: Value| {
+        let mut properties = timespan_properties.clone();
+        if let (Some(properties), Some(extra)) = (properties.as_object_mut(), extra.as_object()) {
+            properties.extend(extra.clone());
+        }
+        properties
+    };
+
+    json!([
+        {
+            "name": "scan_repos",
+            "description": "Find the git repositories under a local directory",
+            "inputSchema": {
+                "type": "object",
+                "properties": { "path": { "type": "string", "description": "Directory to scan" } },
+                "required": ["path"],
+            },
+        },
+        {
+            "name": "analyze_repo",
+            "description": "Commit statistics (commits, lines, files, authors, weekly activity, topics) of one git repository over a timespan, optionally with an AI summary of the work",
+            "inputSchema": {
+                "type": "object",
+                "properties": with(json!({
+                    "summarize": { "type": "boolean", "description": "Also generate the AI work summary (calls the Claude API)" },
+                })),
+                "required": ["path"],
+            },
+        },
+        {
+            "name": "generate_recap",
+            "description": "AI recap of all git repositories under a directory: per-repository summaries and key achievements, plus overall themes",
+            "inputSchema": {
+                "type": "object",
+                "properties": with(json!({
+                    "dry_run": { "type": "boolean", "description": "Analyze without calling the Claude API" },
+                })),
+                "required": ["path"],
+            },
+        },
+    ])
+}
+
+fn parse_args<T: for<'de> Deserialize<'de>>(arguments: Value) -> std::result::Result<T, (i64, String)> {
+    serde_json::from_value(arguments).map_err(|e| (INVALID_PARAMS, format!("invalid arguments: {}", e)))
+}
+
+fn error_response(id: Value, code: i64, message: &str) -> Value {
+    json!({
+        "jsonrpc": "2.0",
+        "id": id,
+        "error": { "code": code, "message": message },
+    })
+}
+
+fn pretty(value: &Value) -> String {
+    serde_json::to_string_pretty(value).unwrap_or_else(|_| value.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+    use tempfile::TempDir;
+
+    fn test_server() -> McpServer {
+        let config = Config {
+            claude_api_key: Some("sk-ant-test-key".to_string()),
+            cache_enabled: false,
+            ..Config::default()
+        };
+        McpServer::new(Arc::new(Orchestrator::new(config).unwrap()), 2)
+    }
+
+    fn init_repo(path: &std::path::Path) {
+        let repo = git2::Repository::init(path).unwrap();
+        let signature = git2::Signature::now("Test User", "test@example.com").unwrap();
+        std::fs::write(path.join("test.txt"), "Hello, world!\n").unwrap();
+        let mut index = repo.index().unwrap();
+        index.add_path(std::path::Path::new("test.txt")).unwrap();
+        index.write().unwrap();
+        let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+        repo.commit(Some("HEAD"), &signature, &signature, "Add greeting", &tree, &[])
+            .unwrap();
+    }
+
+    async fn call(server: &McpServer, tool: &str, arguments: Value) -> Value {
+        let request = json!({
+            "jsonrpc": "2.0",
+            "id": 7,
+            "method": "tools/call",
+            "params": { "name": tool, "arguments": arguments },
+        });
+        let response = server.handle(&request.to_string()).await.unwrap();
+        assert_eq!(response["id"], 7);
+        response["result"].clone()
+    }
+
+    #[tokio::test]
+    async fn test_protocol_messages() {
+        let server = test_server();
+
+        let response = server
+            .handle(r#"{"jsonrpc":"2.0","id":1,"method":"initialize","params":{"protocolVersion":"2024-11-05","capabilities":{},"clientInfo":{"name":"test","version":"1"}}}"#)
+            .await
+            .unwrap();
+        assert_eq!(response["result"]["protocolVersion"], "2024-11-05");
+        assert_eq!(response["result"]["serverInfo"]["name"], "dev-recap");
+
+        assert!(server
+            .handle(r#"{"jsonrpc":"2.0","method":"notifications/initialized"}"#)
+            .await
+            .is_none());
+
+        let response = server
+            .handle(r#"{"jsonrpc":"2.0","id":2,"method":"tools/list"}"#)
+            .await
+            .unwrap();
+        let names: Vec<&str> = response["result"]["tools"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|tool| tool["name"].as_str().unwrap())
+            .collect();
+        assert_eq!(names, vec!["scan_repos", "analyze_repo", "generate_recap"]);
+
+        let response = server
+            .handle(r#"{"jsonrpc":"2.0","id":3,"method":"resources/list"}"#)
+            .await
+            .unwrap();
+        assert_eq!(response["error"]["code"], METHOD_NOT_FOUND);
+
+        let response = server.handle("not json").await.unwrap();
+        assert_eq!(response["error"]["code"], PARSE_ERROR);
+    }
+
+    #[tokio::test]
+    async fn test_tools() {
+        let server = test_server();
+        let temp_dir = TempDir::new().unwrap();
+        let repo_path = temp_dir.path().join("project");
+        std::fs::create_dir(&repo_path).unwrap();
+        init_repo(&repo_path);
+
+        let result = call(&server, "scan_repos", json!({ "path": temp_dir.path() })).await;
+        assert_eq!(result["isError"], false);
+        assert_eq!(result["structuredContent"]["repositories"][0]["name"], "project");
+
+        let result = call(&server, "analyze_repo", json!({ "path": repo_path, "days": 7 })).await;
+        assert_eq!(result["isError"], false);
+        let analysis = &result["structuredContent"];
+        assert_eq!(analysis["stats"]["commits"], 1);
+        assert_eq!(analysis["commits"][0]["summary"], "Add greeting");
+        assert!(analysis["summary"].is_null());
+
+        let result = call(
+            &server,
+            "generate_recap",
+            json!({ "path": temp_dir.path(), "dry_run": true }),
+        )
+        .await;
+        assert_eq!(result["isError"], false);
+        assert_eq!(result["structuredContent"]["repositories"][0]["commits"], 1);
+
+        // Tool failures are results, not protocol errors
+        let result = call(&server, "analyze_repo", json!({ "path": temp_dir.path().join("missing") })).await;
+        assert_eq!(result["isError"], true);
+
+        let request = json!({
+            "jsonrpc": "2.0",
+            "id": 8,
+            "method": "tools/call",
+            "params": { "name": "analyze_repo", "arguments": {} },
+        });
+        let response = server.handle(&request.to_string()).await.unwrap();
+        assert_eq!(response["error"]["code"], INVALID_PARAMS);
+    }
+}
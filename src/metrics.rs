@@ -0,0 +1,170 @@
+//! Prometheus metrics for `dev-recap serve` and `dev-recap daemon`
+//!
+//! Counters are shared by every orchestrator of a process and rendered in
+//! the Prometheus text format on `GET /metrics`.
+
+use crate::error::Result;
+use axum::extract::State;
+use axum::http::header;
+use axum::response::IntoResponse;
+use axum::routing::get;
+use axum::Router;
+use std::fmt::Write;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use tokio::net::TcpListener;
+
+/// Content type of the Prometheus text exposition format
+const CONTENT_TYPE: &str = "text/plain; version=0.0.4; charset=utf-8";
+
+/// Process-wide counters
+#[derive(Debug, Default)]
+pub struct Metrics {
+    runs_succeeded: AtomicU64,
+    runs_failed: AtomicU64,
+    repos_analyzed: AtomicU64,
+    cache_hits: AtomicU64,
+    cache_misses: AtomicU64,
+    api_requests: AtomicU64,
+    input_tokens: AtomicU64,
+    output_tokens: AtomicU64,
+    errors: AtomicU64,
+}
+
+impl Metrics {
+    /// Create zeroed counters
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// A recap run (one request, one scheduled run) finished
+    pub fn record_run(&self, succeeded: bool) {
+        let counter = if succeeded {
+            &self.runs_succeeded
+        } else {
+            &self.runs_failed
+        };
+        counter.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// A repository's history was parsed
+    pub fn record_repo_analyzed(&self) {
+        self.repos_analyzed.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// A summary cache lookup hit or missed
+    pub fn record_cache_lookup(&self, hit: bool) {
+        let counter = if hit { &self.cache_hits } else { &self.cache_misses };
+        counter.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// A Claude API request was made; token counts are known when the API reports them
+    pub fn record_api_request(&self, input_tokens: u64, output_tokens: u64) {
+        self.api_requests.fetch_add(1, Ordering::Relaxed);
+        self.input_tokens.fetch_add(input_tokens, Ordering::Relaxed);
+        self.output_tokens.fetch_add(output_tokens, Ordering::Relaxed);
+    }
+
+    /// Analyzing or summarizing a repository failed
+    pub fn record_error(&self) {
+        self.errors.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// The counters in the Prometheus text format
+    pub fn render(&self) -> String {
+        let get = |counter: &AtomicU64| counter.load(Ordering::Relaxed);
+        let mut out = String::new();
+
+        let mut counter = |name: &str, help: &str, samples: &[(&str, u64)]| {
+            let _ = writeln!(out, "# HELP dev_recap_{} {}", name, help);
+            let _ = writeln!(out, "# TYPE dev_recap_{} counter", name);
+            for (labels, value) in samples {
+                let _ = writeln!(out, "dev_recap_{}{} {}", name, labels, value);
+            }
+        };
+
+        counter(
+            "runs_total",
+            "Recap runs, by result.",
+            &[
+                ("{result=\"success\"}", get(&self.runs_succeeded)),
+                ("{result=\"failure\"}", get(&self.runs_failed)),
+            ],
+        );
+        counter(
+            "repos_analyzed_total",
+            "Repositories whose history was parsed.",
+            &[("", get(&self.repos_analyzed))],
+        );
+        counter(
+            "cache_hits_total",
+            "Summaries served from the cache.",
+            &[("", get(&self.cache_hits))],
+        );
+        counter(
+            "cache_misses_total",
+            "Summaries not found in the cache.",
+            &[("", get(&self.cache_misses))],
+        );
+        counter(
+            "api_requests_total",
+            "Claude API requests.",
+            &[("", get(&self.api_requests))],
+        );
+        counter(
+            "api_tokens_total",
+            "Claude API tokens used, as reported by the API.",
+            &[
+                ("{type=\"input\"}", get(&self.input_tokens)),
+                ("{type=\"output\"}", get(&self.output_tokens)),
+            ],
+        );
+        counter(
+            "errors_total",
+            "Failed repository analyses and summaries.",
+            &[("", get(&self.errors))],
+        );
+
+        out
+    }
+}
+
+/// `GET /metrics` handler for a router whose state holds the metrics
+pub async fn metrics_handler(State(metrics): State<Arc<Metrics>>) -> impl IntoResponse {
+    ([(header::CONTENT_TYPE, CONTENT_TYPE)], metrics.render())
+}
+
+/// Serve only `/metrics` on `listener` (for `dev-recap daemon`)
+pub async fn serve(metrics: Arc<Metrics>, listener: TcpListener) -> Result<()> {
+    let app = Router::new()
+        .route("/metrics", get(metrics_handler))
+        .with_state(metrics);
+    axum::serve(listener, app).await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render() {
+        let metrics = Metrics::new();
+        metrics.record_run(true);
+        metrics.record_run(false);
+        metrics.record_run(true);
+        metrics.record_cache_lookup(true);
+        metrics.record_api_request(1200, 300);
+        metrics.record_api_request(800, 200);
+
+        let text = metrics.render();
+        assert!(text.contains("# TYPE dev_recap_runs_total counter\n"));
+        assert!(text.contains("dev_recap_runs_total{result=\"success\"} 2\n"));
+        assert!(text.contains("dev_recap_runs_total{result=\"failure\"} 1\n"));
+        assert!(text.contains("dev_recap_cache_hits_total 1\n"));
+        assert!(text.contains("dev_recap_cache_misses_total 0\n"));
+        assert!(text.contains("dev_recap_api_requests_total 2\n"));
+        assert!(text.contains("dev_recap_api_tokens_total{type=\"input\"} 2000\n"));
+        assert!(text.contains("dev_recap_api_tokens_total{type=\"output\"} 500\n"));
+    }
+}
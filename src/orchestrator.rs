@@ -1,22 +1,68 @@
 use crate::ai::cache::SummaryCache;
-use crate::ai::claude::ClaudeClient;
-use crate::ai::prompt::{generate_summary_prompt, parse_response};
-use crate::ai::Summary;
-use crate::config::Config;
+use crate::anonymize::Anonymizer;
+use crate::ai::chat::Chat;
+use crate::ai::claude::{ClaudeClient, Message};
+use crate::ai::cost::CostEstimate;
+use crate::ai::impact;
+use crate::ai::provider::Provider;
+use crate::ai::prompt::{
+    generate_chat_prompt, generate_month_prompt, generate_overall_prompt, generate_refine_prompt,
+    generate_summary_prompt, generate_year_prompt, parse_overall_response, parse_response, rank_demo_order, repo_context,
+    Example, PromptOptions, PromptVersion, RecapFormat,
+};
+use crate::ai::{OverallRecap, Summary, GOAL_ALIGNMENT};
+use crate::config::{expand_home, Config, DEFAULT_HOTSPOT_LIMIT};
+use crate::enrich::{self, Enrichment};
+use crate::goals::{self, Goal};
 use crate::error::{DevRecapError, Result};
-use crate::git::github::parse_github_url;
+use crate::events::{EventSink, NoopEventSink, Stage};
+use crate::git::diff_cache::DiffStatCache;
+use crate::git::about::ProjectInfo;
+use crate::git::github::{parse_github_url, DeliveryMetrics, GitHubClient};
+use crate::git::keywords::format_keywords;
 use crate::git::parser::Parser;
 use crate::git::scanner::Scanner;
-use crate::git::{RepoStats, Repository, Timespan};
+use crate::git::stats::{drop_duplicate_patches, CommitTally, GroupBy, TestClassifier};
+use crate::git::{Commit, GitBackend, RepoStats, Repository, Timespan};
+use crate::linear::{self, IssueRef, LinearClient};
+use crate::metrics::Metrics;
+use crate::redact::Redactor;
+use crate::timings::{Phase, Timings};
+use crate::year::{MonthRecap, HIGHLIGHTS};
+use futures::channel::mpsc;
+use futures::future;
+use futures::stream::{self, Stream, StreamExt};
+use futures::SinkExt;
+use regex::Regex;
+use std::collections::{HashMap, HashSet};
+use std::ops::ControlFlow;
 use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
 
 /// Orchestrator for coordinating the analysis workflow
 pub struct Orchestrator {
-    #[allow(dead_code)]
     config: Config,
     scanner: Scanner,
     cache: Option<SummaryCache>,
+    diff_cache: Option<Arc<DiffStatCache>>,
+    test_classifier: TestClassifier,
+    ignore_patterns: Vec<Regex>,
+    redactor: Redactor,
+    examples: Vec<Example>,
+    goals: Vec<Goal>,
     claude_client: ClaudeClient,
+    github_client: Option<GitHubClient>,
+    /// Linear client when `linear_api_key` is set
+    linear_client: Option<LinearClient>,
+    anonymizer: Option<Anonymizer>,
+    prompt_anonymizer: Option<Anonymizer>,
+    subdirs: Option<String>,
+    /// Authors of the configured team in `--team NAME` runs
+    team_authors: Vec<String>,
+    events: Arc<dyn EventSink>,
+    timings: Arc<Timings>,
+    metrics: Arc<Metrics>,
 }
 
 impl Orchestrator {
@@ -33,154 +79,1351 @@ impl Orchestrator {
             None
         };
 
-        let claude_client = ClaudeClient::with_base_url(
+        let diff_cache = match cache {
+            Some(ref cache) => Some(Arc::new(cache.diff_stat_cache()?)),
+            None => None,
+        };
+
+        if config.collapse_duplicate_commits && config.git_backend == GitBackend::Gix {
+            tracing::warn!("the gix backend computes no patch IDs, so duplicate commits won't be collapsed");
+        }
+
+        let test_classifier = TestClassifier::new(&config.test_patterns)?;
+        let ignore_patterns = Parser::compile_ignore_patterns(&config.ignore_commit_patterns)?;
+        let redactor = Redactor::new(&config.redact_patterns)?;
+        let prompt_anonymizer = config.private_prompts.then(|| Anonymizer::new(&[]));
+        let examples = config
+            .examples
+            .iter()
+            .map(|example| example.load())
+            .collect::<Result<Vec<_>>>()?;
+        let goals = match config.goals_file {
+            Some(ref path) => goals::load(&expand_home(path)?)?,
+            None => Vec::new(),
+        };
+
+        let claude_client = ClaudeClient::for_provider(
+            config.provider,
             config.get_api_key()?,
             config.get_base_url(),
             config.get_model(),
         )?;
 
+        // GitHub responses are revalidated with their ETags to save rate limit
+        let github_client = match config.get_github_token() {
+            Some(token) => {
+                let client = GitHubClient::new(token)?;
+                Some(match cache {
+                    Some(ref cache) => client.with_cache(cache.github_cache()?),
+                    None => client,
+                })
+            }
+            None => None,
+        };
+
+        let linear_client = config
+            .get_linear_api_key()
+            .map(|key| LinearClient::new(key).map(|client| client.with_teams(config.linear_teams.clone())))
+            .transpose()?;
+
         Ok(Self {
             config,
             scanner,
             cache,
+            diff_cache,
+            test_classifier,
+            ignore_patterns,
+            redactor,
+            examples,
+            goals,
             claude_client,
+            github_client,
+            linear_client,
+            anonymizer: None,
+            prompt_anonymizer,
+            subdirs: None,
+            team_authors: Vec::new(),
+            events: Arc::new(NoopEventSink),
+            timings: Arc::new(Timings::new()),
+            metrics: Arc::new(Metrics::new()),
         })
     }
 
-    /// Scan a directory for repositories
+    /// Report progress events to `events` instead of discarding them
+    pub fn with_event_sink(mut self, events: Arc<dyn EventSink>) -> Self {
+        self.events = events;
+        self
+    }
+
+    /// Count into `metrics`, e.g. counters shared by every run of a daemon
+    pub fn with_metrics(mut self, metrics: Arc<Metrics>) -> Self {
+        self.metrics = metrics;
+        self
+    }
+
+    /// Cache summaries in `cache` instead of the default cache directory
+    pub fn with_cache(mut self, cache: SummaryCache) -> Self {
+        self.cache = Some(cache);
+        self
+    }
+
+    /// Replace author identities and repositories with pseudonyms in
+    /// everything analyzed with `analyze_all` or `analyze_repositories`
+    pub fn with_anonymizer(mut self, anonymizer: Anonymizer) -> Self {
+        self.anonymizer = Some(anonymizer);
+        self
+    }
+
+    /// The anonymizer of `--anonymize`, to scrub what the report shows
+    pub fn anonymizer(&self) -> Option<&Anonymizer> {
+        self.anonymizer.as_ref()
+    }
+
+    /// Recap the subdirectories of each repository matching `pattern` (e.g.
+    /// `services/*`) as separate sub-projects
+    ///
+    /// Repositories without a matching subdirectory are recapped whole.
+    pub fn with_subdirs(mut self, pattern: String) -> Self {
+        self.subdirs = Some(pattern);
+        self
+    }
+
+    /// Only analyze commits by one of `authors`, e.g. a configured team's
+    /// members, in addition to any single author filter
+    pub fn with_team_authors(mut self, authors: Vec<String>) -> Self {
+        self.team_authors = authors;
+        self
+    }
+
+    /// Scan a directory for repositories (or their sub-projects, see `with_subdirs`)
     pub fn scan_repositories(&self, path: &Path) -> Result<Vec<PathBuf>> {
-        self.scanner.scan(path)
+        self.events.on_scan_started(path);
+        let mut repos = self.timings.time(Phase::Scan, || self.scanner.scan(path))?;
+        if let Some(ref pattern) = self.subdirs {
+            let mut expanded = Vec::new();
+            for repo in repos {
+                let subprojects = Scanner::subprojects(&repo, pattern)?;
+                if subprojects.is_empty() {
+                    expanded.push(repo);
+                } else {
+                    tracing::debug!(repo = %repo.display(), count = subprojects.len(), "found sub-projects");
+                    expanded.extend(subprojects);
+                }
+            }
+            // A submodule can be both a scanned repository and a match
+            let mut seen = std::collections::HashSet::new();
+            expanded.retain(|repo| seen.insert(repo.clone()));
+            repos = expanded;
+        }
+        self.events.on_scan_finished(&repos);
+        Ok(repos)
     }
 
     /// Analyze a single repository
+    ///
+    /// A directory inside a repository, rather than its root, is analyzed as
+    /// a sub-project: only commits touching it count.
     pub fn analyze_repository(
         &self,
         repo_path: &Path,
         author_email: Option<&str>,
         timespan: &Timespan,
     ) -> Result<Repository> {
+        let (subproject, name) = Self::locate(repo_path);
+        let git_root = subproject.as_ref().map_or(repo_path, |(root, _)| root.as_path());
+
         // Parse commits
-        let parser = Parser::new(author_email.map(String::from), timespan.clone());
-        let commits = parser.parse_commits(repo_path)?;
+        let started = Instant::now();
+        let parser = self.parser(author_email, timespan, subproject.as_ref().map(|(_, subdir)| subdir));
+
+        // Commits past the cap (the oldest) still count toward the statistics,
+        // but only those the prompt lists are kept
+        let max = self.config.max_commits_per_repo.unwrap_or(usize::MAX);
+        let mut commits = Vec::new();
+        let mut capped = false;
+        let mut tally = CommitTally::new(&self.test_classifier, timespan);
+        let mut patch_ids = HashSet::new();
+        let walked = parser.visit_commits(git_root, |commit| {
+            let repeated = self.config.collapse_duplicate_commits
+                && !commit.files_changed.is_empty()
+                && commit.patch_id.as_ref().is_some_and(|id| !patch_ids.insert(id.clone()));
+            if commits.len() < max {
+                commits.push(commit);
+            } else {
+                capped = true;
+                if !repeated {
+                    tally.add(&commit);
+                }
+            }
+            ControlFlow::Continue(())
+        });
+        self.timings.record_repo(&name, started.elapsed());
+        walked?;
+        tracing::debug!(
+            repo = %repo_path.display(),
+            commits = commits.len(),
+            "parsed commits"
+        );
 
         if commits.is_empty() {
             return Err(DevRecapError::NoCommitsFound {
-                author: author_email.unwrap_or("any").to_string(),
+                author: match author_email {
+                    Some(email) => email.to_string(),
+                    None if !self.team_authors.is_empty() => self.team_authors.join(", "),
+                    None => "any".to_string(),
+                },
             });
         }
 
-        // Calculate statistics
-        let stats = RepoStats::from_commits(&commits);
+        // A change cherry-picked between branches is one piece of work
+        if self.config.collapse_duplicate_commits {
+            let duplicates = drop_duplicate_patches(&mut commits, &mut HashSet::new());
+            if duplicates > 0 {
+                tracing::debug!(repo = %repo_path.display(), duplicates, "dropped cherry-picked commits");
+            }
+        }
+
+        let mut repo = Self::build_repository(repo_path, git_root, name, commits);
+        repo.commit_cap = if capped { self.config.max_commits_per_repo } else { None };
+        self.add_stats(&mut repo, tally);
+        Ok(repo)
+    }
+
+    /// Analyze exactly the commits a saved recap listed for a repository
+    /// (`dev-recap regen`), without walking its history again
+    pub fn reanalyze_repository(&self, repo_path: &Path, hashes: &[String], timespan: &Timespan) -> Result<Repository> {
+        let (subproject, name) = Self::locate(repo_path);
+        let git_root = subproject.as_ref().map_or(repo_path, |(root, _)| root.as_path());
+        let commits = self
+            .parser(None, timespan, subproject.as_ref().map(|(_, subdir)| subdir))
+            .commits_by_hash(git_root, hashes)?;
+        if commits.is_empty() {
+            return Err(DevRecapError::NoCommitsFound { author: "any".to_string() });
+        }
+
+        let mut repo = Self::build_repository(repo_path, git_root, name, commits);
+        self.compute_stats(&mut repo, timespan);
+        Ok(repo)
+    }
+
+    /// A sub-project's repository root and directory in it, if `repo_path` is
+    /// one, and the name the repository is shown under
+    fn locate(repo_path: &Path) -> (Option<(PathBuf, PathBuf)>, String) {
+        let subproject = if repo_path.is_dir() {
+            Scanner::split_subproject(repo_path)
+        } else {
+            None
+        };
+        let name = match subproject {
+            Some((ref root, ref subdir)) => {
+                format!("{}/{}", Scanner::get_repo_name(root), subdir.display())
+            }
+            None => Scanner::get_repo_name(repo_path),
+        };
+        (subproject, name)
+    }
+
+    /// Commit parser with the configured filters and options
+    fn parser(&self, author_email: Option<&str>, timespan: &Timespan, subdir: Option<&PathBuf>) -> Parser {
+        let mut parser = Parser::new(author_email.map(String::from), timespan.clone())
+            .with_backend(self.config.git_backend)
+            .with_commit_date(self.config.commit_date)
+            .with_timings(Arc::clone(&self.timings))
+            .with_ignore_patterns(self.ignore_patterns.clone());
+        if !self.team_authors.is_empty() {
+            parser = parser.with_authors(self.team_authors.clone());
+        }
+        if let Some(subdir) = subdir {
+            parser = parser.with_subdir(subdir.clone());
+        }
+        if let Some(budget) = self.time_budget() {
+            parser = parser.with_time_budget(budget);
+        }
+        if let Some(ref diff_cache) = self.diff_cache {
+            parser = parser.with_diff_cache(Arc::clone(diff_cache));
+        }
+        if !self.config.diff_stats_enabled {
+            parser = parser.without_diff_stats();
+        }
+        if self.config.hotspot_limit == 0 {
+            parser = parser.without_file_churn();
+        }
+        parser
+    }
 
-        // Get repository info
-        let name = Scanner::get_repo_name(repo_path);
-        let remote_url = Scanner::get_remote_url(repo_path);
-        let github_info = remote_url
-            .as_ref()
-            .and_then(|url| parse_github_url(url));
+    /// Repository with its remotes and project info, before any statistics;
+    /// links and PRs of a fork are the upstream's
+    fn build_repository(repo_path: &Path, git_root: &Path, name: String, commits: Vec<Commit>) -> Repository {
+        let remotes = Scanner::get_remotes(git_root);
+        let github_info = remotes
+            .upstream
+            .as_deref()
+            .and_then(parse_github_url)
+            .or_else(|| remotes.origin.as_deref().and_then(parse_github_url));
 
-        Ok(Repository {
+        Repository {
             path: repo_path.to_path_buf(),
             name,
-            remote_url,
+            remote_url: remotes.origin,
+            upstream_url: remotes.upstream,
             github_info,
+            about: ProjectInfo::read(repo_path).with_stack(git_root, &commits),
+            commits,
+            hotspots: vec![],
+            velocity: vec![],
+            test_ratio: Default::default(),
+            keywords: vec![],
+            commit_cap: None,
+            stats: RepoStats::default(),
+        }
+    }
+
+    /// Analyze commits read from `git log` text rather than a repository
+    /// (`--stdin-format`), as the repository `name`
+    ///
+    /// The commits are taken as already filtered by author and date; only
+    /// `ignore_commit_patterns` applies. The timespan is the commits' own.
+    pub fn analyze_commits(&self, name: &str, mut commits: Vec<Commit>) -> Result<Repository> {
+        commits.retain(|commit| !self.ignore_patterns.iter().any(|pattern| pattern.is_match(&commit.message)));
+        if let Some(ref anonymizer) = self.anonymizer {
+            anonymizer.assign_authors(&commits);
+            anonymizer.anonymize_commits(&mut commits);
+        }
+        let (Some(start), Some(end)) = (
+            commits.iter().map(|commit| commit.timestamp).min(),
+            commits.iter().map(|commit| commit.timestamp).max(),
+        ) else {
+            return Err(DevRecapError::NoCommitsFound { author: "any".to_string() });
+        };
+        let timespan = Timespan::from_dates(start.to_utc(), end.to_utc());
+
+        let mut repo = Repository {
+            path: PathBuf::from(name),
+            name: match self.anonymizer {
+                Some(ref anonymizer) => anonymizer.repository_name(Path::new(name)),
+                None => name.to_string(),
+            },
+            remote_url: None,
+            upstream_url: None,
+            github_info: None,
+            about: ProjectInfo::default(),
             commits,
-            stats,
+            hotspots: vec![],
+            velocity: vec![],
+            test_ratio: Default::default(),
+            keywords: vec![],
+            commit_cap: None,
+            stats: RepoStats::default(),
+        };
+        self.compute_stats(&mut repo, &timespan);
+        Ok(repo)
+    }
+
+    /// Calculate a repository's statistics from its commits
+    fn compute_stats(&self, repo: &mut Repository, timespan: &Timespan) {
+        self.add_stats(repo, CommitTally::new(&self.test_classifier, timespan));
+    }
+
+    /// Set a repository's statistics from its commits plus those in `tally`
+    fn add_stats(&self, repo: &mut Repository, mut tally: CommitTally) {
+        for commit in &repo.commits {
+            tally.add(commit);
+        }
+        tally.apply(repo, self.config.hotspot_limit);
+    }
+
+    /// Drop commits whose change an earlier repository already reported,
+    /// recording the rest in `seen`
+    fn drop_reported_commits(
+        &self,
+        mut repo: Repository,
+        seen: &mut HashSet<String>,
+        timespan: &Timespan,
+    ) -> Result<Repository> {
+        if !self.config.collapse_duplicate_commits {
+            return Ok(repo);
+        }
+        let duplicates = drop_duplicate_patches(&mut repo.commits, seen);
+        if duplicates == 0 {
+            return Ok(repo);
+        }
+
+        tracing::debug!(repo = %repo.path.display(), duplicates, "dropped commits reported in other repositories");
+        if repo.commits.is_empty() {
+            return Err(DevRecapError::OnlyDuplicateCommits);
+        }
+        // A capped repository's statistics also count the commits past the
+        // cap, which are gone by now, so they're left as they are
+        if repo.commit_cap.is_none() {
+            self.compute_stats(&mut repo, timespan);
+        }
+        Ok(repo)
+    }
+
+    /// Analyze a single repository on the blocking thread pool
+    ///
+    /// Git parsing is CPU and disk bound, so running it off the async executor
+    /// lets several repositories be analyzed concurrently.
+    pub async fn analyze_repository_blocking(
+        self: &Arc<Self>,
+        repo_path: PathBuf,
+        author_email: Option<String>,
+        timespan: Timespan,
+    ) -> Result<Repository> {
+        let orchestrator = Arc::clone(self);
+        tokio::task::spawn_blocking(move || {
+            orchestrator.analyze_repository(&repo_path, author_email.as_deref(), &timespan)
         })
+        .await
+        .map_err(|e| DevRecapError::other(format!("Repository analysis task failed: {}", e)))?
+    }
+
+    /// `reanalyze_repository` on the blocking thread pool
+    pub async fn reanalyze_repository_blocking(
+        self: &Arc<Self>,
+        repo_path: PathBuf,
+        hashes: Vec<String>,
+        timespan: Timespan,
+    ) -> Result<Repository> {
+        let orchestrator = Arc::clone(self);
+        tokio::task::spawn_blocking(move || orchestrator.reanalyze_repository(&repo_path, &hashes, &timespan))
+            .await
+            .map_err(|e| DevRecapError::other(format!("Repository analysis task failed: {}", e)))?
     }
 
     /// Generate summary for a repository using AI
     pub async fn generate_summary(&self, repo: &Repository) -> Result<Summary> {
+        self.summary(repo, self.config.prompt_version, false).await
+    }
+
+    /// Generate a summary with a prompt version other than the configured one
+    /// (`--compare-prompts`)
+    pub async fn generate_summary_with(&self, repo: &Repository, version: PromptVersion) -> Result<Summary> {
+        self.summary(repo, version, false).await
+    }
+
+    /// Generate a fresh summary, replacing the cached one (`dev-recap regen`)
+    pub async fn regenerate_summary(&self, repo: &Repository) -> Result<Summary> {
+        self.summary(repo, self.config.prompt_version, true).await
+    }
+
+    /// Summary from the cache unless `refresh`, otherwise generated and
+    /// cached; key achievements ranked by impact and trimmed to `top_achievements`
+    async fn summary(&self, repo: &Repository, version: PromptVersion, refresh: bool) -> Result<Summary> {
+        let mut summary = self.cached_summary(repo, version, refresh).await?;
+        impact::rank_achievements(&mut summary, repo, self.config.top_achievements);
+        Ok(summary)
+    }
+
+    /// Summary as the model wrote it, from the cache unless `refresh`
+    async fn cached_summary(&self, repo: &Repository, version: PromptVersion, refresh: bool) -> Result<Summary> {
+        self.remember_authors(repo);
+
+        // Enrichment output is part of the prompt, so it's part of the cache
+        // key; so are the Linear issues commits name, shown like an enricher's
+        let mut enrichments = enrich::enrich(&self.config.enrichers, repo).await;
+        enrichments.extend(self.linear_enrichment(repo).await);
+
         // Check cache first
         if let Some(ref cache) = self.cache {
-            let commit_hashes: Vec<String> = repo
-                .commits
-                .iter()
-                .map(|c| c.hash.clone())
-                .collect();
+            let cache_key = self.cache_key(repo, &enrichments, version);
 
-            let cache_key = SummaryCache::generate_key(
-                &repo.path.to_string_lossy(),
-                &commit_hashes,
-            );
+            let latest_key = self.latest_key(repo, version);
+            let commits: Vec<String> = repo.commits.iter().map(|commit| commit.hash.clone()).collect();
 
-            // Try to get from cache
-            if let Some(cached_summary) = cache.get(&cache_key)? {
-                return Ok(cached_summary);
+            // Try to get from cache; the commits are part of the key, so a hit
+            // covers exactly the current ones and needs no staleness note
+            if !refresh {
+                if let Some(cached_summary) = self.timings.time(Phase::Cache, || cache.get(&cache_key))? {
+                    tracing::debug!(repo = %repo.name, "summary cache hit");
+                    self.metrics.record_cache_lookup(true);
+                    self.timings
+                        .time(Phase::Cache, || cache.set_latest(&latest_key, &commits, &cached_summary))?;
+                    return Ok(cached_summary);
+                }
+                tracing::debug!(repo = %repo.name, "summary cache miss");
+                self.metrics.record_cache_lookup(false);
             }
 
-            // Generate new summary
-            let summary = self.generate_summary_uncached(repo).await?;
+            // Generate new summary (see `stale_summary` for when that fails)
+            let summary = self.generate_summary_uncached(repo, &enrichments, version).await?;
 
             // Store in cache
-            cache.set(&cache_key, summary.clone())?;
+            self.timings.time(Phase::Cache, || {
+                cache.set(&cache_key, summary.clone())?;
+                cache.set_latest(&latest_key, &commits, &summary)
+            })?;
 
             Ok(summary)
         } else {
             // No cache, generate directly
-            self.generate_summary_uncached(repo).await
+            self.generate_summary_uncached(repo, &enrichments, version).await
+        }
+    }
+
+    /// Note the authors of a repository whose summary goes into the overall
+    /// prompt, including one summarized by an earlier run (`dev-recap watch`)
+    ///
+    /// Summaries name these authors too; with `private_prompts` the overall
+    /// prompt must not.
+    pub fn remember_authors(&self, repo: &Repository) {
+        if let Some(ref anonymizer) = self.prompt_anonymizer {
+            anonymizer.remember_authors(&repo.commits);
+        }
+    }
+
+    /// Estimate tokens and cost of summarizing the given repositories
+    ///
+    /// Repositories with a cached summary are counted separately since they
+    /// won't trigger an API request. With `overall`, the cross-repository
+    /// recap request is included when there is more than one repository.
+    pub fn estimate_cost(&self, repos: &[&Repository], overall: bool) -> Result<CostEstimate> {
+        self.estimate_cost_with(repos, &[self.config.prompt_version], overall)
+    }
+
+    /// [`Self::estimate_cost`] for summarizing with each of `versions` (`--compare-prompts`)
+    pub fn estimate_cost_with(
+        &self,
+        repos: &[&Repository],
+        versions: &[PromptVersion],
+        overall: bool,
+    ) -> Result<CostEstimate> {
+        let model = self.claude_client.model();
+        let mut estimate = CostEstimate::new(model).with_pricing(self.config.pricing.get(model).copied());
+
+        for repo in repos {
+            for &version in versions {
+                if self.is_cached(repo, version)? {
+                    estimate.add_cached();
+                } else {
+                    let options = PromptOptions { version, ..self.prompt_options() };
+                    estimate.add_prompt(&generate_summary_prompt(repo, &options));
+                }
+            }
+        }
+
+        if overall && repos.len() > 1 {
+            estimate.add_overall(repos.len());
+        }
+
+        Ok(estimate)
+    }
+
+    /// Fetch PR delivery metrics for the GitHub repositories among `repos`
+    ///
+    /// Needs a GitHub token; returns an empty map without one. `author` is a
+    /// GitHub login (`@me` for the token's owner), or `None` for all authors.
+    /// Failures are logged and leave the repository out of the map.
+    pub async fn delivery_metrics(
+        &self,
+        repos: &[&Repository],
+        author: Option<&str>,
+        timespan: &Timespan,
+        jobs: usize,
+    ) -> HashMap<PathBuf, DeliveryMetrics> {
+        let client = match self.github_client {
+            Some(ref client) => client,
+            None => return HashMap::new(),
+        };
+
+        stream::iter(repos.iter().filter_map(|repo| {
+            repo.github_info.as_ref().map(|github| (repo, github))
+        }))
+        .map(|(repo, github)| async move {
+            match client.delivery_metrics(github, author, timespan).await {
+                Ok(metrics) => Some((repo.path.clone(), metrics)),
+                Err(e) => {
+                    tracing::warn!(repo = %repo.name, error = %e, "delivery metrics failed");
+                    None
+                }
+            }
+        })
+        .buffered(jobs.max(1))
+        .filter_map(|entry| async move { entry })
+        .collect()
+        .await
+    }
+
+    /// Synthesize one recap (themes, biggest wins, demo order) across summaries
+    pub async fn generate_overall_recap(&self, summaries: &[&Summary]) -> Result<OverallRecap> {
+        let mut prompt = generate_overall_prompt(summaries);
+        if let Some(ref anonymizer) = self.prompt_anonymizer {
+            prompt = anonymizer.scrub(&prompt);
+        }
+        let response = self.timed_request(prompt).await?;
+        let (themes, biggest_wins, demo_order) = parse_overall_response(&response);
+
+        if themes.is_empty() && biggest_wins.is_empty() && demo_order.is_empty() {
+            return Err(DevRecapError::claude_api(
+                "Overall recap response had no recognizable sections",
+            ));
+        }
+
+        let repositories: Vec<&str> = summaries.iter().map(|summary| summary.repository.as_str()).collect();
+        let demo_order = rank_demo_order(demo_order, &repositories);
+
+        let mut recap = OverallRecap::new(themes, biggest_wins, demo_order);
+        // Judged across repositories: one repository skipping a goal doesn't mean nothing moved it
+        let alignments: Vec<&[String]> = summaries.iter().map(|summary| summary.section(GOAL_ALIGNMENT)).collect();
+        recap.goals_not_addressed = goals::unaddressed(&self.goals, &alignments)
+            .into_iter()
+            .map(|goal| goal.title.clone())
+            .collect();
+        Ok(recap)
+    }
+
+    /// Summary of one month of a year in review (`dev-recap year`), cached by
+    /// the month's commits
+    pub async fn month_summary(&self, month: &MonthRecap) -> Result<Summary> {
+        if let Some(ref anonymizer) = self.prompt_anonymizer {
+            for repo in &month.repositories {
+                anonymizer.remember_authors(&repo.commits);
+            }
+        }
+        let name = month.label();
+        self.cached_response(&format!("month {}", name), &month.cache_key_parts(), name, generate_month_prompt(month))
+            .await
+    }
+
+    /// Estimate tokens and cost of a year in review: one request per month
+    /// with commits (unless cached) and the yearly synthesis
+    pub fn estimate_year_cost(&self, months: &[MonthRecap]) -> Result<CostEstimate> {
+        let model = self.claude_client.model();
+        let mut estimate = CostEstimate::new(model).with_pricing(self.config.pricing.get(model).copied());
+
+        let active: Vec<&MonthRecap> = months.iter().filter(|month| !month.repositories.is_empty()).collect();
+        for month in &active {
+            let key = self.response_key(&format!("month {}", month.label()), &month.cache_key_parts());
+            let cached = match self.cache {
+                Some(ref cache) => cache.get(&key)?.is_some(),
+                None => false,
+            };
+            if cached {
+                estimate.add_cached();
+            } else {
+                estimate.add_prompt(&generate_month_prompt(month));
+            }
+        }
+        // The synthesis is built from the monthly summaries, like an overall recap
+        estimate.add_overall(active.len());
+
+        Ok(estimate)
+    }
+
+    /// Overview, trends and milestones of a year in review, from its monthly
+    /// summaries; cached by what the months say
+    pub async fn year_synthesis(&self, year: i32, months: &[MonthRecap]) -> Result<Summary> {
+        let key_parts: Vec<String> = months
+            .iter()
+            .filter_map(|month| {
+                let summary = month.summary.as_ref()?;
+                Some(format!(
+                    "{}\n{}\n{}",
+                    month.describe(),
+                    summary.work_summary,
+                    summary.section(HIGHLIGHTS).join("\n")
+                ))
+            })
+            .collect();
+        self.cached_response(
+            &format!("year {}", year),
+            &key_parts,
+            year.to_string(),
+            generate_year_prompt(year, months),
+        )
+        .await
+    }
+
+    /// Response to a summary-shaped `prompt` (`## Summary` and sections),
+    /// cached under `key_name` and `key_parts`
+    async fn cached_response(
+        &self,
+        key_name: &str,
+        key_parts: &[String],
+        name: String,
+        prompt: String,
+    ) -> Result<Summary> {
+        let key = self.response_key(key_name, key_parts);
+        if let Some(ref cache) = self.cache {
+            if let Some(cached) = self.timings.time(Phase::Cache, || cache.get(&key))? {
+                tracing::debug!(key = key_name, "summary cache hit");
+                self.metrics.record_cache_lookup(true);
+                return Ok(cached);
+            }
+            self.metrics.record_cache_lookup(false);
+        }
+
+        let prompt = match self.prompt_anonymizer {
+            Some(ref anonymizer) => anonymizer.scrub(&prompt),
+            None => prompt,
+        };
+        let response = self.timed_request(prompt).await?;
+        let (work_summary, sections) = parse_response(&response);
+        if work_summary.is_empty() && sections.is_empty() {
+            return Err(DevRecapError::claude_api(format!(
+                "{} response had no recognizable sections",
+                name
+            )));
+        }
+
+        let summary = Summary::with_sections(name, work_summary, sections);
+        if let Some(ref cache) = self.cache {
+            self.timings.time(Phase::Cache, || cache.set(&key, summary.clone()))?;
+        }
+        Ok(summary)
+    }
+
+    /// Cache key of a response cached by [`Self::cached_response`]
+    fn response_key(&self, key_name: &str, key_parts: &[String]) -> String {
+        let key = SummaryCache::generate_key(key_name, key_parts);
+        match self.anonymizer {
+            Some(_) => format!("{}_anon", key),
+            None => key,
+        }
+    }
+
+    /// Check whether a summary for this repository is already cached
+    fn is_cached(&self, repo: &Repository, version: PromptVersion) -> Result<bool> {
+        // With enrichers or Linear issues, the key depends on output that
+        // isn't known until they run; count the repository as uncached
+        if !self.config.enrichers.is_empty()
+            || self.linear_client.as_ref().is_some_and(|client| client.references_issues(repo))
+        {
+            return Ok(false);
+        }
+        match self.cache {
+            Some(ref cache) => Ok(cache.get(&self.cache_key(repo, &[], version))?.is_some()),
+            None => Ok(false),
+        }
+    }
+
+    /// Cache key for a repository's summary (path + commit hashes + enrichment
+    /// context + few-shot examples + requested sections + prompt version)
+    ///
+    /// Anonymized summaries are cached apart so real names never leak into them.
+    fn cache_key(&self, repo: &Repository, enrichments: &[Enrichment], version: PromptVersion) -> String {
+        let mut commit_hashes: Vec<String> = repo
+            .commits
+            .iter()
+            .map(|c| c.hash.clone())
+            .collect();
+        // Without enrichments the key matches what older versions cached
+        for enrichment in enrichments {
+            commit_hashes.push(format!("{}\n{}", enrichment.name, enrichment.context));
+        }
+        self.key_with_settings(repo, commit_hashes, version)
+    }
+
+    /// Key of a repository's most recent summary: the cache key without the
+    /// commits and enrichment context, which change from run to run
+    fn latest_key(&self, repo: &Repository, version: PromptVersion) -> String {
+        self.key_with_settings(repo, Vec::new(), version)
+    }
+
+    /// Cache key of `commit_hashes` plus everything else the prompt depends on
+    fn key_with_settings(&self, repo: &Repository, mut commit_hashes: Vec<String>, version: PromptVersion) -> String {
+        for example in &self.examples {
+            commit_hashes.push(format!("example\n{}\n{}", example.input, example.output));
+        }
+        if self.config.watch_outs {
+            commit_hashes.push("watch-outs".to_string());
+        }
+        if let Some(group_by) = self.config.group_by {
+            commit_hashes.push(format!("group by {}", group_by));
+        }
+        if let Some(min_changes) = self.config.min_changes {
+            commit_hashes.push(format!("min changes {}", min_changes));
+        }
+        if let Some(commit_bodies) = self.config.commit_bodies {
+            commit_hashes.push(format!("commit bodies {}", commit_bodies));
+        }
+        if self.config.hotspot_limit != DEFAULT_HOTSPOT_LIMIT {
+            commit_hashes.push(format!("hotspot limit {}", self.config.hotspot_limit));
+        }
+        // Without diff stats the prompt has no change sizes at all
+        if !self.config.diff_stats_enabled {
+            commit_hashes.push("no diff stats".to_string());
+        }
+        for goal in &self.goals {
+            commit_hashes.push(format!("goal\n{}", goal.describe()));
+        }
+        // Anthropic keys match what was cached before providers existed
+        if self.config.provider != Provider::Anthropic {
+            commit_hashes.push(format!("provider {}", self.config.provider));
+        }
+        if self.config.format != RecapFormat::Demo {
+            commit_hashes.push(format!("format {}", self.config.format));
+        }
+        for section in &self.config.summary_sections {
+            commit_hashes.push(format!(
+                "section\n{}\n{}\n{}",
+                section.title,
+                section.instruction.as_deref().unwrap_or_default(),
+                section.numbered
+            ));
+        }
+
+        let mut key = SummaryCache::generate_key(&repo.path.to_string_lossy(), &commit_hashes);
+        // v1 keys match what was cached before prompt versions existed
+        if version != PromptVersion::V1 {
+            key = format!("{}_{}", key, version);
+        }
+        match self.anonymizer {
+            Some(_) => format!("{}_anon", key),
+            None => key,
+        }
+    }
+
+    /// Topics of the repository on GitHub, when a token is configured
+    ///
+    /// A failed lookup only costs the prompt some context, so it is logged.
+    async fn github_topics(&self, repo: &Repository) -> Vec<String> {
+        let (Some(client), Some(github)) = (&self.github_client, &repo.github_info) else {
+            return Vec::new();
+        };
+        match client.topics(github).await {
+            Ok(topics) => topics,
+            Err(e) => {
+                tracing::warn!(repo = %repo.name, error = %e, "fetching GitHub topics failed");
+                Vec::new()
+            }
+        }
+    }
+
+    /// Linear issues referenced by each repository's commits, keyed by path
+    ///
+    /// Empty without a `linear_api_key`; a failed lookup is logged and the
+    /// repository left out.
+    pub async fn linear_issues(&self, repos: &[&Repository], jobs: usize) -> HashMap<PathBuf, Vec<IssueRef>> {
+        let client = match self.linear_client {
+            Some(ref client) => client,
+            None => return HashMap::new(),
+        };
+
+        stream::iter(repos.iter())
+            .map(|repo| async move {
+                match client.repo_issues(repo).await {
+                    Ok(issues) if issues.is_empty() => None,
+                    Ok(issues) => Some((repo.path.clone(), issues)),
+                    Err(e) => {
+                        tracing::warn!(repo = %repo.name, error = %e, "Linear lookup failed");
+                        None
+                    }
+                }
+            })
+            .buffered(jobs.max(1))
+            .filter_map(|entry| async move { entry })
+            .collect()
+            .await
+    }
+
+    /// The repository's Linear issues as prompt context, if any
+    async fn linear_enrichment(&self, repo: &Repository) -> Option<Enrichment> {
+        let client = self.linear_client.as_ref()?;
+        match client.repo_issues(repo).await {
+            Ok(issues) if issues.is_empty() => None,
+            Ok(issues) => Some(Enrichment {
+                name: linear::ENRICHMENT_NAME.to_string(),
+                context: linear::format_issues(&issues),
+            }),
+            Err(e) => {
+                tracing::warn!(repo = %repo.name, error = %e, "Linear lookup failed");
+                None
+            }
         }
     }
 
     /// Generate summary without using cache
-    async fn generate_summary_uncached(&self, repo: &Repository) -> Result<Summary> {
+    async fn generate_summary_uncached(
+        &self,
+        repo: &Repository,
+        enrichments: &[Enrichment],
+        version: PromptVersion,
+    ) -> Result<Summary> {
         // Generate prompt
-        let prompt = generate_summary_prompt(repo);
+        let options = PromptOptions {
+            enrichments: enrichments.to_vec(),
+            topics: self.github_topics(repo).await,
+            version,
+            ..self.prompt_options()
+        };
+        let prompt = match self.prompt_anonymizer {
+            // Claude sees pseudonyms and no remote URL, also in enricher output
+            Some(ref anonymizer) => {
+                anonymizer.scrub(&generate_summary_prompt(&anonymizer.anonymize_repository(repo), &options))
+            }
+            None => generate_summary_prompt(repo, &options),
+        };
 
         // Call Claude API
-        let response = self.claude_client.generate_summary(prompt).await?;
+        let response = self.timed_request(prompt).await?;
 
         // Parse response
-        let (work_summary, key_achievements, presentation_tips) = parse_response(&response);
+        let (work_summary, sections) = parse_response(&response);
 
-        Ok(Summary::new(
-            repo.name.clone(),
-            work_summary,
-            key_achievements,
-            presentation_tips,
-        ))
+        Ok(Summary::with_sections(repo.name.clone(), work_summary, sections)
+            .with_prompt_version(version)
+            .with_model(self.claude_client.model()))
     }
 
-    /// Analyze multiple repositories
-    #[allow(dead_code)]
-    pub async fn analyze_repositories(
+    /// Revise a summary following the user's instruction (`--refine`)
+    ///
+    /// Works from the summary alone, so nothing is read from git; the result
+    /// isn't cached since it depends on the instruction.
+    pub async fn refine_summary(&self, summary: &Summary, instruction: &str) -> Result<Summary> {
+        let response = self
+            .timed_request(generate_refine_prompt(summary, instruction))
+            .await?;
+        let (work_summary, sections) = parse_response(&response);
+        let refined = Summary::with_sections(summary.repository.clone(), work_summary, sections)
+            .with_model(self.claude_client.model());
+        Ok(Summary {
+            prompt_version: summary.prompt_version,
+            ..refined
+        })
+    }
+
+    /// Analyze many repositories (git only, no API calls), up to `jobs` at a time
+    ///
+    /// Results are returned in input order.
+    pub async fn analyze_all(
+        self: &Arc<Self>,
+        repo_paths: Vec<PathBuf>,
+        author_email: Option<String>,
+        timespan: &Timespan,
+        jobs: usize,
+    ) -> Vec<(PathBuf, Result<Repository>)> {
+        self.events.on_stage_started(Stage::Analyzing, repo_paths.len());
+        let mut analyzed: Vec<_> = self
+            .analysis_stream(repo_paths, author_email, timespan.clone(), jobs)
+            .collect()
+            .await;
+        self.events.on_stage_finished(Stage::Analyzing);
+        self.anonymize_analyzed(&mut analyzed);
+        analyzed
+    }
+
+    /// With an anonymizer, replace authors and repository names with
+    /// pseudonyms, assigned in sorted order over every repository at once so
+    /// a run maps them the same way whatever order repositories finish in
+    fn anonymize_analyzed(&self, analyzed: &mut [(PathBuf, Result<Repository>)]) {
+        let Some(ref anonymizer) = self.anonymizer else {
+            return;
+        };
+        anonymizer.assign_authors(
+            analyzed
+                .iter()
+                .filter_map(|(_, result)| result.as_ref().ok())
+                .flat_map(|repo| &repo.commits),
+        );
+        anonymizer.assign_repositories(analyzed.iter().map(|(path, _)| path.as_path()));
+        for repo in analyzed.iter_mut().filter_map(|(_, result)| result.as_mut().ok()) {
+            anonymizer.anonymize_commits(&mut repo.commits);
+            repo.name = anonymizer.repository_name(&repo.path);
+        }
+    }
+
+    /// Summarize analyzed repositories, up to `jobs` requests in flight
+    ///
+    /// Repositories that failed analysis are passed through with their error.
+    /// In `dry_run` mode no API calls are made.
+    pub async fn summarize_all(
         &self,
-        repo_paths: &[PathBuf],
-        author_email: Option<&str>,
+        analyzed: Vec<(PathBuf, Result<Repository>)>,
+        jobs: usize,
+        dry_run: bool,
+    ) -> Vec<(Repository, Result<Summary>)> {
+        let total = analyzed.iter().filter(|(_, result)| result.is_ok()).count();
+        self.events.on_stage_started(Stage::Summarizing, total);
+        let mut results: Vec<_> = stream::iter(analyzed)
+            .map(|(repo_path, repo_result)| self.summarize_analyzed(repo_path, repo_result, dry_run))
+            .buffered(jobs)
+            .collect()
+            .await;
+        self.events.on_stage_finished(Stage::Summarizing);
+
+        self.retry_failed(&mut results, jobs).await;
+        results
+    }
+
+    /// Analyze and summarize repositories as a pipeline
+    ///
+    /// Git parsing runs on the blocking thread pool and feeds a bounded channel,
+    /// while already-parsed repositories are summarized concurrently, so neither
+    /// the disk nor the network sits idle. Results are returned in input order.
+    /// With an anonymizer, every repository is parsed before the first is
+    /// summarized.
+    pub async fn analyze_repositories(
+        self: &Arc<Self>,
+        repo_paths: Vec<PathBuf>,
+        author_email: Option<String>,
         timespan: &Timespan,
+        jobs: usize,
+        dry_run: bool,
     ) -> Vec<(Repository, Result<Summary>)> {
-        let mut results = Vec::new();
+        // Pseudonyms are assigned once every repository is parsed
+        if self.anonymizer.is_some() {
+            let analyzed = self.analyze_all(repo_paths, author_email, timespan, jobs).await;
+            return self.summarize_all(analyzed, jobs, dry_run).await;
+        }
 
-        for repo_path in repo_paths {
-            // Analyze repository
-            let repo_result = self.analyze_repository(repo_path, author_email, timespan);
+        self.events.on_stage_started(Stage::Summarizing, repo_paths.len());
+        let (mut tx, rx) = mpsc::channel(jobs);
 
-            match repo_result {
-                Ok(repo) => {
-                    // Generate summary
-                    let summary_result = self.generate_summary(&repo).await;
-                    results.push((repo, summary_result));
-                }
-                Err(e) => {
-                    // Create a minimal repository for error reporting
-                    let repo = Repository {
-                        path: repo_path.clone(),
-                        name: Scanner::get_repo_name(repo_path),
-                        remote_url: None,
-                        github_info: None,
-                        commits: vec![],
-                        stats: RepoStats::default(),
-                    };
-                    results.push((repo, Err(e)));
+        // Producer: parse repositories ahead of the summarizer
+        let parsed = self.analysis_stream(repo_paths, author_email, timespan.clone(), jobs);
+        let producer = tokio::spawn(async move {
+            let mut parsed = Box::pin(parsed);
+            while let Some(item) = parsed.next().await {
+                if tx.send(item).await.is_err() {
+                    break;
                 }
             }
+        });
+
+        let mut results: Vec<_> = rx
+            .map(|(repo_path, repo_result)| self.summarize_analyzed(repo_path, repo_result, dry_run))
+            .buffered(jobs)
+            .collect()
+            .await;
+
+        if let Err(e) = producer.await {
+            tracing::error!(error = %e, "repository analysis task failed");
         }
 
+        self.events.on_stage_finished(Stage::Summarizing);
+
+        self.retry_failed(&mut results, jobs).await;
         results
     }
 
+    /// Stream of analyzed repositories, parsed `jobs` at a time, in input order
+    ///
+    /// Corrupted repositories are reported to the event sink and left out.
+    /// A change found in several repositories (a cherry-pick or backport) is
+    /// kept only in the first one.
+    fn analysis_stream(
+        self: &Arc<Self>,
+        repo_paths: Vec<PathBuf>,
+        author_email: Option<String>,
+        timespan: Timespan,
+        jobs: usize,
+    ) -> impl Stream<Item = (PathBuf, Result<Repository>)> + Send + 'static {
+        let orchestrator = Arc::clone(self);
+        let settle = Arc::clone(self);
+        let settle_timespan = timespan.clone();
+        let mut seen = HashSet::new();
+
+        stream::iter(repo_paths)
+            .map(move |repo_path| {
+                let orchestrator = Arc::clone(&orchestrator);
+                let author_email = author_email.clone();
+                let timespan = timespan.clone();
+                async move {
+                    orchestrator.events.on_repo_started(&repo_path);
+                    let repo_result = orchestrator
+                        .analyze_repository_blocking(repo_path.clone(), author_email, timespan)
+                        .await;
+                    (repo_path, repo_result)
+                }
+            })
+            .buffered(jobs)
+            // In input order, so which repository keeps a duplicate is deterministic
+            .filter_map(move |(repo_path, repo_result)| {
+                let repo_result = repo_result
+                    .and_then(|repo| settle.drop_reported_commits(repo, &mut seen, &settle_timespan));
+                future::ready(settle.report_analysis(repo_path, repo_result))
+            })
+    }
+
+    /// Report an analysis outcome to metrics and the event sink, leaving out
+    /// corrupted repositories
+    fn report_analysis(
+        &self,
+        repo_path: PathBuf,
+        repo_result: Result<Repository>,
+    ) -> Option<(PathBuf, Result<Repository>)> {
+        match repo_result {
+            Ok(ref repo) => {
+                self.metrics.record_repo_analyzed();
+                self.events.on_repo_analyzed(repo);
+            }
+            Err(ref e) if e.is_corruption() => {
+                tracing::warn!(repo = %repo_path.display(), error = %e, "skipping corrupted repository");
+                self.metrics.record_error();
+                self.events.on_repo_skipped(&repo_path, e);
+                return None;
+            }
+            Err(ref e) => {
+                tracing::warn!(repo = %repo_path.display(), error = %e, "repository analysis failed");
+                if !e.is_no_commits() {
+                    self.metrics.record_error();
+                }
+                self.events.on_error(&repo_path, e);
+            }
+        }
+        Some((repo_path, repo_result))
+    }
+
+    /// Summarize one analyzed repository (or pass its analysis error through)
+    async fn summarize_analyzed(
+        &self,
+        repo_path: PathBuf,
+        repo_result: Result<Repository>,
+        dry_run: bool,
+    ) -> (Repository, Result<Summary>) {
+        match repo_result {
+            Ok(repo) if dry_run => {
+                // Dry run: skip API call, create dummy success result
+                let mut highlights = vec![format!("{} files changed", repo.stats.total_files_changed)];
+                if !repo.keywords.is_empty() {
+                    highlights.push(format!("Topics: {}", format_keywords(&repo.keywords)));
+                }
+                match self.estimate_cost(&[&repo], false) {
+                    Ok(estimate) => highlights.push(format!("Estimated: {}", estimate.tokens_and_cost())),
+                    Err(e) => tracing::warn!(repo = %repo.name, error = %e, "cost estimate failed"),
+                }
+                let summary = Summary::new(
+                    repo.name.clone(),
+                    format!("[Dry run] Would analyze {} commits", repo.stats.total_commits),
+                    highlights,
+                    vec![],
+                );
+                self.events.on_summary_ready(&repo, &summary);
+                (repo, Ok(summary))
+            }
+            Ok(repo) => {
+                let summary_result = self.summarize_repo(&repo).await;
+                (repo, summary_result)
+            }
+            Err(e) => {
+                // Create a minimal repository for error reporting (the sink
+                // already received this error when analysis failed)
+                let repo = Repository {
+                    name: match self.anonymizer {
+                        Some(ref anonymizer) => anonymizer.repository_name(&repo_path),
+                        None => Scanner::get_repo_name(&repo_path),
+                    },
+                    path: repo_path,
+                    remote_url: None,
+                    upstream_url: None,
+                    github_info: None,
+                    about: Default::default(),
+                    commits: vec![],
+                    hotspots: vec![],
+                    velocity: vec![],
+                    test_ratio: Default::default(),
+                    keywords: vec![],
+                    commit_cap: None,
+                    stats: RepoStats::default(),
+                };
+                (repo, Err(e))
+            }
+        }
+    }
+
+    /// Generate a summary within the time budget, reporting the outcome
+    async fn summarize_repo(&self, repo: &Repository) -> Result<Summary> {
+        let summary_result = match self.time_budget() {
+            Some(budget) => tokio::time::timeout(budget, self.generate_summary(repo))
+                .await
+                .unwrap_or(Err(DevRecapError::RepoTimeout {
+                    seconds: budget.as_secs(),
+                })),
+            None => self.generate_summary(repo).await,
+        };
+
+        match summary_result {
+            Ok(ref summary) => self.events.on_summary_ready(repo, summary),
+            Err(ref e) => {
+                tracing::warn!(repo = %repo.name, error = %e, "summary generation failed");
+                self.metrics.record_error();
+                self.events.on_error(&repo.path, e);
+            }
+        }
+
+        summary_result
+    }
+
+    /// Retry, once, every summary that failed with a transient error
+    ///
+    /// Runs after all other repositories are done, so a rate limit or network
+    /// blip has had time to clear. Results are merged in place.
+    async fn retry_failed(&self, results: &mut [(Repository, Result<Summary>)], jobs: usize) {
+        // Repositories without commits failed analysis, not summarization
+        let failed: Vec<usize> = results
+            .iter()
+            .enumerate()
+            .filter(|(_, (repo, result))| {
+                !repo.commits.is_empty()
+                    && result.as_ref().err().is_some_and(DevRecapError::is_transient)
+            })
+            .map(|(index, _)| index)
+            .collect();
+
+        if failed.is_empty() {
+            return;
+        }
+
+        tracing::info!(count = failed.len(), "retrying failed summaries");
+        self.events.on_stage_started(Stage::Retrying, failed.len());
+
+        let completed: &[(Repository, Result<Summary>)] = results;
+        let retried: Vec<(usize, Result<Summary>)> = stream::iter(failed)
+            .map(|index| async move { (index, self.summarize_repo(&completed[index].0).await) })
+            .buffered(jobs)
+            .collect()
+            .await;
+
+        for (index, summary_result) in retried {
+            results[index].1 = match summary_result {
+                Err(e) if e.is_transient() => self.stale_summary(&results[index].0, e),
+                result => result,
+            };
+        }
+
+        self.events.on_stage_finished(Stage::Retrying);
+    }
+
+    /// The last cached summary of `repo`, marked with the commits it misses,
+    /// standing in for one that failed again with `error` when retried
+    ///
+    /// An older summary beats none for an error a rerun may fix; the error is
+    /// still reported. Without one (or without a cache), `error` is returned.
+    fn stale_summary(&self, repo: &Repository, error: DevRecapError) -> Result<Summary> {
+        let Some(ref cache) = self.cache else {
+            return Err(error);
+        };
+        let latest_key = self.latest_key(repo, self.config.prompt_version);
+        let commits: Vec<String> = repo.commits.iter().map(|commit| commit.hash.clone()).collect();
+        let mut summary = match self.timings.time(Phase::Cache, || cache.latest(&latest_key, &commits)) {
+            Ok(Some(summary)) => summary,
+            Ok(None) => return Err(error),
+            Err(e) => {
+                tracing::debug!(repo = %repo.name, error = %e, "reading the last cached summary failed");
+                return Err(error);
+            }
+        };
+
+        tracing::warn!(
+            repo = %repo.name,
+            error = %error,
+            new_commits = summary.new_commits,
+            "summarizing failed; serving the last cached summary"
+        );
+        summary.fallback_error = Some(Arc::new(error));
+        impact::rank_achievements(&mut summary, repo, self.config.top_achievements);
+        Ok(summary)
+    }
+
+    /// Send a prompt to Claude with secrets redacted, recording the latency
+    async fn timed_request(&self, prompt: String) -> Result<String> {
+        self.timed_conversation(None, vec![Message::user(prompt)]).await
+    }
+
+    /// [`Self::timed_request`] for a conversation with a system prompt
+    async fn timed_conversation(&self, system: Option<String>, messages: Vec<Message>) -> Result<String> {
+        let mut redacted = 0;
+        let mut redact = |text: &str| {
+            let (text, count) = self.redactor.redact(text);
+            redacted += count;
+            text
+        };
+        let system = system.map(|system| redact(&system));
+        let messages: Vec<Message> = messages
+            .into_iter()
+            .map(|message| Message {
+                content: redact(&message.content),
+                ..message
+            })
+            .collect();
+        if redacted > 0 {
+            tracing::info!(redacted, "redacted secrets from prompt");
+        }
+
+        let started = Instant::now();
+        let response = self.claude_client.converse(system, messages).await;
+        self.timings.record(Phase::Api, started.elapsed());
+        let completion = response?;
+        self.metrics
+            .record_api_request(completion.input_tokens, completion.output_tokens);
+
+        // Pseudonyms in private prompts come back as real names
+        Ok(match self.prompt_anonymizer {
+            Some(ref anonymizer) => anonymizer.restore(&completion.text),
+            None => completion.text,
+        })
+    }
+
+    /// Start a conversation about a finished recap, with the context of
+    /// every repository that has commits
+    pub fn start_chat(&self, repos: &[&Repository], report: &str) -> Chat {
+        let options = self.prompt_options();
+        let contexts: Vec<String> = repos
+            .iter()
+            .filter(|repo| !repo.commits.is_empty())
+            .map(|repo| match self.prompt_anonymizer {
+                Some(ref anonymizer) => repo_context(&anonymizer.anonymize_repository(repo), &options),
+                None => repo_context(repo, &options),
+            })
+            .collect();
+        Chat::new(generate_chat_prompt(report, &contexts))
+    }
+
+    /// Answer a follow-up question and add it to the conversation
+    pub async fn ask(&self, chat: &mut Chat, question: &str) -> Result<String> {
+        let scrub = |text: &str| match self.prompt_anonymizer {
+            Some(ref anonymizer) => anonymizer.scrub(text),
+            None => text.to_string(),
+        };
+        let messages = chat
+            .messages(question)
+            .into_iter()
+            .map(|message| Message {
+                content: scrub(&message.content),
+                ..message
+            })
+            .collect();
+
+        let answer = self
+            .timed_conversation(Some(scrub(chat.system())), messages)
+            .await?;
+        chat.record(question.to_string(), answer.clone());
+        Ok(answer)
+    }
+
+    /// Time spent in each phase so far
+    pub fn timings(&self) -> &Timings {
+        &self.timings
+    }
+
+    /// Counters exposed on `/metrics`
+    pub fn metrics(&self) -> &Arc<Metrics> {
+        &self.metrics
+    }
+
+    /// Prompt options from config
+    fn prompt_options(&self) -> PromptOptions {
+        PromptOptions {
+            min_changes: self.config.min_changes,
+            commit_bodies: self.config.commit_bodies,
+            version: self.config.prompt_version,
+            examples: self.examples.clone(),
+            watch_outs: self.config.watch_outs,
+            sections: self.config.summary_sections.clone(),
+            format: self.config.format,
+            goals: self.goals.clone(),
+            timeline: self.config.group_by == Some(GroupBy::Day),
+            ..Default::default()
+        }
+    }
+
+    /// Per-repository time budget from config
+    fn time_budget(&self) -> Option<Duration> {
+        self.config.repo_timeout_secs.map(Duration::from_secs)
+    }
+
     /// Get a reference to the config
-    #[allow(dead_code)]
     pub fn config(&self) -> &Config {
         &self.config
     }
@@ -189,22 +1432,95 @@ impl Orchestrator {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::error_report::ErrorReport;
     use std::fs;
     use std::io::Write;
     use tempfile::TempDir;
 
+    #[derive(Default)]
+    struct RecordingSink {
+        events: std::sync::Mutex<Vec<String>>,
+    }
+
+    impl RecordingSink {
+        fn record(&self, event: &str) {
+            self.events.lock().unwrap().push(event.to_string());
+        }
+    }
+
+    impl EventSink for RecordingSink {
+        fn on_stage_started(&self, stage: Stage, _total: usize) {
+            self.record(&format!("stage_started {:?}", stage));
+        }
+        fn on_stage_finished(&self, stage: Stage) {
+            self.record(&format!("stage_finished {:?}", stage));
+        }
+        fn on_summary_ready(&self, _repo: &Repository, _summary: &Summary) {
+            self.record("summary_ready");
+        }
+        fn on_error(&self, _repo_path: &Path, _error: &DevRecapError) {
+            self.record("error");
+        }
+        fn on_repo_skipped(&self, _repo_path: &Path, _reason: &DevRecapError) {
+            self.record("skipped");
+        }
+    }
+
     fn create_test_config() -> Config {
         Config {
             default_author_email: Some("test@example.com".to_string()),
+            provider: Default::default(),
             claude_api_key: Some("sk-ant-test-key".to_string()),
+            claude_api_key_cmd: None,
             claude_api_base_url: None,
             claude_model: None,
             default_timespan_days: 14,
             exclude_patterns: vec!["node_modules".to_string()],
             max_scan_depth: None,
+            repo_timeout_secs: None,
+            git_backend: Default::default(),
+            commit_date: Default::default(),
+            diff_stats_enabled: true,
+            collapse_duplicate_commits: true,
+            max_commits_per_repo: None,
+            hotspot_limit: 5,
+            ignore_commit_patterns: vec![],
+            min_changes: None,
+            commit_bodies: None,
+            prompt_version: Default::default(),
+            format: Default::default(),
+            watch_outs: false,
+            group_by: None,
+            goals_file: None,
+            top_achievements: None,
+            summary_sections: Vec::new(),
+            redact_patterns: Vec::new(),
+            private_prompts: false,
+            test_patterns: crate::config::default_test_patterns(),
             cache_enabled: false,
             cache_ttl_hours: 168,
             github_token: None,
+            linear_api_key: None,
+            linear_teams: Vec::new(),
+            log_file: None,
+            output_path: None,
+            file_links: false,
+            theme: Default::default(),
+            order: Default::default(),
+            schedule: None,
+            slack_webhook_url: None,
+            email_to: vec![],
+            max_chunk_chars: None,
+            slack_signing_secret: None,
+            serve_token: None,
+            history_enabled: false,
+            enrichers: Vec::new(),
+            examples: Vec::new(),
+            commit_links: Vec::new(),
+            sprint: None,
+            pricing: Default::default(),
+            providers: Default::default(),
+            teams: Default::default(),
         }
     }
 
@@ -243,6 +1559,51 @@ mod tests {
         Ok(())
     }
 
+    /// Commit a new file `name` on top of HEAD
+    fn add_test_commit(dir: &Path, name: &str, message: &str) {
+        let repo = git2::Repository::open(dir).unwrap();
+        fs::write(dir.join(name), format!("{}\n", message)).unwrap();
+        let mut index = repo.index().unwrap();
+        index.add_path(Path::new(name)).unwrap();
+        index.write().unwrap();
+        let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+        let parent = repo.head().unwrap().peel_to_commit().unwrap();
+        let signature = repo.signature().unwrap();
+        repo.commit(Some("HEAD"), &signature, &signature, message, &tree, &[&parent])
+            .unwrap();
+    }
+
+    /// Request bodies a [`mock_messages_api`] received, in order
+    type Requests = Arc<std::sync::Mutex<Vec<serde_json::Value>>>;
+
+    /// Stand-in for the Messages API on a local port, answering the nth
+    /// request (from 1) with `reply(n)`; returns its base URL and the
+    /// requests it records
+    async fn mock_messages_api(reply: impl Fn(usize) -> String + Send + Sync + 'static) -> (String, Requests) {
+        use axum::routing::post;
+        use axum::Json;
+
+        let requests: Requests = Arc::default();
+        let recorded = Arc::clone(&requests);
+        let reply = Arc::new(reply);
+        let messages = move |Json(body): Json<serde_json::Value>| {
+            let (recorded, reply) = (Arc::clone(&recorded), Arc::clone(&reply));
+            async move {
+                let count = {
+                    let mut recorded = recorded.lock().unwrap();
+                    recorded.push(body);
+                    recorded.len()
+                };
+                Json(serde_json::json!({ "content": [{ "type": "text", "text": reply(count) }] }))
+            }
+        };
+        let app = axum::Router::new().route("/v1/messages", post(messages));
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base_url = format!("http://{}", listener.local_addr().unwrap());
+        tokio::spawn(async move { axum::serve(listener, app).await });
+        (base_url, requests)
+    }
+
     #[test]
     fn test_orchestrator_creation() {
         let config = create_test_config();
@@ -299,4 +1660,470 @@ mod tests {
 
         assert!(result.is_err());
     }
+
+    #[test]
+    fn test_subprojects() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path().join("mono");
+        let repo = git2::Repository::init(&root).unwrap();
+        let signature = git2::Signature::now("Test User", "test@example.com").unwrap();
+
+        // One commit per service, plus one touching both
+        let mut parent: Option<git2::Oid> = None;
+        for (files, message) in [
+            (&["services/api/main.rs"][..], "Add api"),
+            (&["services/web/index.html"][..], "Add web"),
+            (&["services/api/lib.rs", "services/web/app.js"][..], "Wire api into web"),
+        ] {
+            for file in files {
+                let path = root.join(file);
+                fs::create_dir_all(path.parent().unwrap()).unwrap();
+                fs::write(&path, "content\n").unwrap();
+            }
+            let mut index = repo.index().unwrap();
+            index.add_all(["*"], git2::IndexAddOption::DEFAULT, None).unwrap();
+            index.write().unwrap();
+            let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+            let parents: Vec<git2::Commit> = parent.iter().map(|id| repo.find_commit(*id).unwrap()).collect();
+            let parent_refs: Vec<&git2::Commit> = parents.iter().collect();
+            parent = Some(
+                repo.commit(Some("HEAD"), &signature, &signature, message, &tree, &parent_refs)
+                    .unwrap(),
+            );
+        }
+
+        let orchestrator = Orchestrator::new(create_test_config())
+            .unwrap()
+            .with_subdirs("services/*".to_string());
+        let paths = orchestrator.scan_repositories(temp_dir.path()).unwrap();
+        assert_eq!(paths, vec![root.join("services/api"), root.join("services/web")]);
+
+        let api = orchestrator
+            .analyze_repository(&paths[0], None, &Timespan::days_back(1))
+            .unwrap();
+        assert_eq!(api.name, "mono/services/api");
+        let summaries: Vec<&str> = api.commits.iter().map(|c| c.summary.as_str()).collect();
+        assert_eq!(summaries.len(), 2);
+        assert!(summaries.contains(&"Add api") && summaries.contains(&"Wire api into web"));
+
+        // Stats only cover the sub-project, with paths relative to it
+        let wiring = api.commits.iter().find(|c| c.summary == "Wire api into web").unwrap();
+        assert_eq!(wiring.files_changed, vec!["lib.rs"]);
+        assert_eq!(wiring.insertions, 1);
+    }
+
+    #[test]
+    fn test_estimate_cost() {
+        let config = create_test_config();
+        let orchestrator = Orchestrator::new(config).unwrap();
+
+        let temp_dir = TempDir::new().unwrap();
+        create_test_repo_with_commits(temp_dir.path()).unwrap();
+
+        let timespan = Timespan::days_back(1);
+        let repo = orchestrator
+            .analyze_repository(temp_dir.path(), None, &timespan)
+            .unwrap();
+
+        let estimate = orchestrator.estimate_cost(&[&repo], true).unwrap();
+        assert_eq!(estimate.requests, 1);
+        assert_eq!(estimate.cached, 0);
+        assert!(estimate.input_tokens > 0);
+
+        // Two repositories add the overall recap request
+        let estimate = orchestrator.estimate_cost(&[&repo, &repo], true).unwrap();
+        assert_eq!(estimate.requests, 3);
+
+        // A year: one request per month with commits, plus the synthesis
+        let month = |repositories| MonthRecap { year: 2024, month: 1, repositories, summary: None };
+        let estimate = orchestrator.estimate_year_cost(&[month(vec![repo.clone()]), month(vec![])]).unwrap();
+        assert_eq!(estimate.requests, 2);
+    }
+
+    #[tokio::test]
+    async fn test_analyze_repository_blocking() {
+        let config = create_test_config();
+        let orchestrator = Arc::new(Orchestrator::new(config).unwrap());
+
+        let temp_dir = TempDir::new().unwrap();
+        create_test_repo_with_commits(temp_dir.path()).unwrap();
+
+        let repo = orchestrator
+            .analyze_repository_blocking(
+                temp_dir.path().to_path_buf(),
+                Some("test@example.com".to_string()),
+                Timespan::days_back(1),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(repo.commits.len(), 1);
+
+        // The same commits, read by hash whatever the author filter was
+        let hashes: Vec<String> = repo.commits.iter().map(|commit| commit.hash.clone()).collect();
+        let again = orchestrator
+            .reanalyze_repository_blocking(temp_dir.path().to_path_buf(), hashes, Timespan::days_back(1))
+            .await
+            .unwrap();
+        assert_eq!((again.name.as_str(), again.commits[0].hash.as_str()), (repo.name.as_str(), repo.commits[0].hash.as_str()));
+        assert_eq!(again.stats.total_insertions, repo.stats.total_insertions);
+    }
+
+    #[tokio::test]
+    async fn test_analyze_repositories_pipeline() {
+        let config = create_test_config();
+
+        let repo_dir = TempDir::new().unwrap();
+        create_test_repo_with_commits(repo_dir.path()).unwrap();
+        let missing = repo_dir.path().join("does-not-exist");
+
+        let events = Arc::new(RecordingSink::default());
+        let orchestrator = Arc::new(
+            Orchestrator::new(config)
+                .unwrap()
+                .with_event_sink(events.clone()),
+        );
+
+        let results = orchestrator
+            .analyze_repositories(
+                vec![repo_dir.path().to_path_buf(), missing.clone()],
+                Some("test@example.com".to_string()),
+                &Timespan::days_back(1),
+                2,
+                true,
+            )
+            .await;
+
+        assert_eq!(results.len(), 2);
+        let events = events.events.lock().unwrap();
+        assert_eq!(events.first().map(String::as_str), Some("stage_started Summarizing"));
+        assert_eq!(events.last().map(String::as_str), Some("stage_finished Summarizing"));
+        assert_eq!(events.iter().filter(|e| *e == "summary_ready").count(), 1);
+        assert_eq!(events.iter().filter(|e| *e == "error").count(), 1);
+
+        // Input order is preserved; the dry run summarizes without API calls
+        assert_eq!(results[0].0.path, repo_dir.path());
+        assert!(results[0].1.is_ok());
+        assert_eq!(results[1].0.path, missing);
+        assert!(results[1].1.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_cherry_picks_reported_once() {
+        let config = create_test_config();
+        let orchestrator = Arc::new(Orchestrator::new(config).unwrap());
+
+        // Three checkouts with the same initial change; `backports` adds its own
+        let dirs: Vec<TempDir> = (0..3).map(|_| TempDir::new().unwrap()).collect();
+        for dir in &dirs {
+            create_test_repo_with_commits(dir.path()).unwrap();
+        }
+        add_test_commit(dirs[1].path(), "notes.txt", "Add notes");
+
+        let paths: Vec<PathBuf> = dirs.iter().map(|dir| dir.path().to_path_buf()).collect();
+        let results = orchestrator
+            .analyze_all(paths.clone(), None, &Timespan::days_back(1), 3)
+            .await;
+
+        // The first repository in input order keeps the shared change
+        let first = results[0].1.as_ref().unwrap();
+        assert_eq!(first.stats.total_commits, 1);
+        let second = results[1].1.as_ref().unwrap();
+        assert_eq!(second.stats.total_commits, 1);
+        assert_eq!(second.commits[0].summary, "Add notes");
+        assert!(matches!(results[2].1, Err(DevRecapError::OnlyDuplicateCommits)));
+
+        // Every commit is kept when collapsing is turned off
+        let mut config = create_test_config();
+        config.collapse_duplicate_commits = false;
+        let orchestrator = Arc::new(Orchestrator::new(config).unwrap());
+        let results = orchestrator.analyze_all(paths, None, &Timespan::days_back(1), 3).await;
+        let commits: Vec<usize> = results.iter().map(|(_, repo)| repo.as_ref().unwrap().commits.len()).collect();
+        assert_eq!(commits, vec![1, 2, 1]);
+    }
+
+    #[test]
+    fn test_commit_cap_counts_all_commits() {
+        let mut config = create_test_config();
+        config.max_commits_per_repo = Some(1);
+        let orchestrator = Orchestrator::new(config).unwrap();
+
+        let dir = TempDir::new().unwrap();
+        create_test_repo_with_commits(dir.path()).unwrap();
+        add_test_commit(dir.path(), "notes.txt", "Add notes");
+
+        // Only the most recent commit is kept, but the statistics cover both
+        let repo = orchestrator
+            .analyze_repository(dir.path(), None, &Timespan::days_back(1))
+            .unwrap();
+        assert_eq!(repo.commits.len(), 1);
+        assert_eq!(repo.commits[0].summary, "Add notes");
+        assert_eq!(repo.commit_cap, Some(1));
+        assert_eq!(repo.stats.total_commits, 2);
+        assert_eq!(repo.velocity.iter().map(|week| week.commits).sum::<u32>(), 2);
+    }
+
+    #[test]
+    fn test_cache_key_settings() {
+        let dir = TempDir::new().unwrap();
+        create_test_repo_with_commits(dir.path()).unwrap();
+        let key = |config: Config| {
+            let orchestrator = Orchestrator::new(config).unwrap();
+            let repo = orchestrator
+                .analyze_repository(dir.path(), None, &Timespan::days_back(1))
+                .unwrap();
+            orchestrator.cache_key(&repo, &[], PromptVersion::V1)
+        };
+
+        let default = key(create_test_config());
+        assert_eq!(key(create_test_config()), default);
+        let min_changes = key(Config {
+            min_changes: Some(5),
+            ..create_test_config()
+        });
+        assert_ne!(min_changes, default);
+        let commit_bodies = key(Config {
+            commit_bodies: Some(5),
+            ..create_test_config()
+        });
+        assert_ne!(commit_bodies, default);
+        assert_ne!(commit_bodies, min_changes);
+        let no_diff_stats = key(Config {
+            diff_stats_enabled: false,
+            ..create_test_config()
+        });
+        assert_ne!(no_diff_stats, default);
+        let no_hotspots = key(Config {
+            hotspot_limit: 0,
+            ..create_test_config()
+        });
+        assert_ne!(no_hotspots, default);
+    }
+
+    #[test]
+    fn test_linear_issues_not_counted_as_cached() {
+        let dir = TempDir::new().unwrap();
+        let cache_dir = TempDir::new().unwrap();
+        create_test_repo_with_commits(dir.path()).unwrap();
+        add_test_commit(dir.path(), "login.txt", "ENG-1: Fix login");
+
+        let orchestrator = Orchestrator::new(create_test_config())
+            .unwrap()
+            .with_cache(SummaryCache::new(cache_dir.path(), 24).unwrap());
+        let repo = orchestrator
+            .analyze_repository(dir.path(), None, &Timespan::days_back(1))
+            .unwrap();
+        let version = orchestrator.config.prompt_version;
+        let summary = Summary::new(repo.name.clone(), "Fixed login".to_string(), vec![], vec![]);
+        let key = orchestrator.cache_key(&repo, &[], version);
+        orchestrator.cache.as_ref().unwrap().set(&key, summary).unwrap();
+        assert!(orchestrator.is_cached(&repo, version).unwrap());
+        // sled allows one open handle per database
+        drop(orchestrator);
+
+        // The issues are part of the key, and unknown until looked up
+        let config = Config {
+            linear_api_key: Some("lin_api_test".to_string()),
+            ..create_test_config()
+        };
+        let orchestrator = Orchestrator::new(config)
+            .unwrap()
+            .with_cache(SummaryCache::new(cache_dir.path(), 24).unwrap());
+        assert!(!orchestrator.is_cached(&repo, version).unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_corrupted_repository_skipped() {
+        let config = create_test_config();
+
+        let good = TempDir::new().unwrap();
+        create_test_repo_with_commits(good.path()).unwrap();
+        let corrupted = TempDir::new().unwrap();
+        create_test_repo_with_commits(corrupted.path()).unwrap();
+
+        // Overwrite the HEAD commit's loose object with garbage
+        let head = git2::Repository::open(corrupted.path())
+            .unwrap()
+            .head()
+            .unwrap()
+            .target()
+            .unwrap()
+            .to_string();
+        let object = corrupted.path().join(".git/objects").join(&head[..2]).join(&head[2..]);
+        fs::remove_file(&object).unwrap();
+        fs::write(&object, b"not a zlib stream").unwrap();
+
+        let events = Arc::new(RecordingSink::default());
+        let orchestrator = Arc::new(
+            Orchestrator::new(config)
+                .unwrap()
+                .with_event_sink(events.clone()),
+        );
+
+        let analyzed = orchestrator
+            .analyze_all(
+                vec![corrupted.path().to_path_buf(), good.path().to_path_buf()],
+                None,
+                &Timespan::days_back(1),
+                2,
+            )
+            .await;
+
+        assert_eq!(analyzed.len(), 1);
+        assert_eq!(analyzed[0].0, good.path());
+        assert!(analyzed[0].1.is_ok());
+        let events = events.events.lock().unwrap();
+        assert_eq!(events.iter().filter(|e| *e == "skipped").count(), 1);
+        assert!(!events.iter().any(|e| e == "error"));
+    }
+
+    #[tokio::test]
+    async fn test_private_prompts() {
+        let (base_url, requests) = mock_messages_api(|_| {
+            "## Summary\nDev A shipped the greeting.\n\n## Key Achievements\n- Greeting by Dev A\n".to_string()
+        })
+        .await;
+
+        let repo_dir = TempDir::new().unwrap();
+        create_test_repo_with_commits(repo_dir.path()).unwrap();
+        let repo = git2::Repository::open(repo_dir.path()).unwrap();
+        repo.remote("origin", "https://github.com/acme/secret-project.git").unwrap();
+
+        let config = Config {
+            claude_api_base_url: Some(base_url),
+            private_prompts: true,
+            ..create_test_config()
+        };
+        let orchestrator = Orchestrator::new(config).unwrap();
+        let analyzed = orchestrator
+            .analyze_repository(repo_dir.path(), None, &Timespan::days_back(1))
+            .unwrap();
+        let summary = orchestrator.generate_summary(&analyzed).await.unwrap();
+
+        let prompt = requests.lock().unwrap()[0]["messages"][0]["content"].as_str().unwrap().to_string();
+        assert!(!prompt.contains("Test User"));
+        assert!(!prompt.contains("test@example.com"));
+        assert!(!prompt.contains("acme/secret-project"));
+
+        // The report still names the author
+        assert_eq!(summary.work_summary, "Test User shipped the greeting.");
+        assert_eq!(summary.key_achievements(), ["Greeting by Test User"]);
+    }
+
+    #[tokio::test]
+    async fn test_chat_keeps_conversation() {
+        let (base_url, requests) = mock_messages_api(|count| format!("Answer {}", count)).await;
+
+        let repo_dir = TempDir::new().unwrap();
+        create_test_repo_with_commits(repo_dir.path()).unwrap();
+        let config = Config {
+            claude_api_base_url: Some(base_url),
+            ..create_test_config()
+        };
+        let orchestrator = Orchestrator::new(config).unwrap();
+        let analyzed = orchestrator
+            .analyze_repository(repo_dir.path(), None, &Timespan::days_back(1))
+            .unwrap();
+
+        let mut chat = orchestrator.start_chat(&[&analyzed], "# Dev Recap\n\nShipped the greeting.\n");
+        assert_eq!(orchestrator.ask(&mut chat, "Expand on the greeting").await.unwrap(), "Answer 1");
+        assert_eq!(orchestrator.ask(&mut chat, "Shorter, please").await.unwrap(), "Answer 2");
+
+        let requests = requests.lock().unwrap();
+        let system = requests[1]["system"].as_str().unwrap();
+        assert!(system.contains("Shipped the greeting."));
+        assert!(system.contains("Initial commit"));
+        let turns: Vec<&str> = requests[1]["messages"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|message| message["content"].as_str().unwrap())
+            .collect();
+        assert_eq!(turns, vec!["Expand on the greeting", "Answer 1", "Shorter, please"]);
+    }
+
+    #[tokio::test]
+    async fn test_stale_summary_served_when_summarizing_fails() {
+        let (base_url, _) = mock_messages_api(|_| "## Summary\nShipped the greeting.\n".to_string()).await;
+
+        let repo_dir = TempDir::new().unwrap();
+        let cache_dir = TempDir::new().unwrap();
+        create_test_repo_with_commits(repo_dir.path()).unwrap();
+
+        let config = Config {
+            claude_api_base_url: Some(base_url),
+            ..create_test_config()
+        };
+        let orchestrator = Orchestrator::new(config)
+            .unwrap()
+            .with_cache(SummaryCache::new(cache_dir.path(), 24).unwrap());
+        let analyzed = orchestrator
+            .analyze_repository(repo_dir.path(), None, &Timespan::days_back(1))
+            .unwrap();
+        assert_eq!(orchestrator.generate_summary(&analyzed).await.unwrap().new_commits, 0);
+        // sled allows one open handle per database
+        drop(orchestrator);
+
+        // A new commit, and the API is down
+        add_test_commit(repo_dir.path(), "farewell.txt", "Add farewell");
+        let config = Config {
+            claude_api_base_url: Some("http://127.0.0.1:1".to_string()),
+            ..create_test_config()
+        };
+        let orchestrator = Orchestrator::new(config)
+            .unwrap()
+            .with_cache(SummaryCache::new(cache_dir.path(), 24).unwrap());
+        // A timespan ending now, after the new commit (seconds are truncated)
+        let analyzed = orchestrator
+            .analyze_repository(repo_dir.path(), None, &Timespan::days_back(1))
+            .unwrap();
+        assert_eq!(analyzed.commits.len(), 2);
+
+        // Only once the retry has failed too, and the failure is still reported
+        assert!(orchestrator.generate_summary(&analyzed).await.is_err());
+        let results = orchestrator
+            .summarize_all(vec![(repo_dir.path().to_path_buf(), Ok(analyzed.clone()))], 1, false)
+            .await;
+        let summary = results[0].1.as_ref().unwrap();
+        assert_eq!(summary.work_summary, "Shipped the greeting.");
+        assert_eq!(summary.new_commits, 1);
+        assert!(summary.fallback_error.as_ref().is_some_and(|e| e.is_transient()));
+        let report = ErrorReport::new(results.iter().map(|(repo, result)| (repo, result)));
+        assert!(report.failures[0].cached_summary_shown);
+
+        // Regenerating never falls back
+        assert!(orchestrator.regenerate_summary(&analyzed).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_failed_summaries_retried_once() {
+        let mut config = create_test_config();
+        // Nothing listens here, so every request fails with a transient HTTP error
+        config.claude_api_base_url = Some("http://127.0.0.1:1".to_string());
+
+        let events = Arc::new(RecordingSink::default());
+        let orchestrator = Arc::new(
+            Orchestrator::new(config)
+                .unwrap()
+                .with_event_sink(events.clone()),
+        );
+
+        let repo_dir = TempDir::new().unwrap();
+        create_test_repo_with_commits(repo_dir.path()).unwrap();
+
+        let results = orchestrator
+            .analyze_repositories(
+                vec![repo_dir.path().to_path_buf()],
+                None,
+                &Timespan::days_back(1),
+                1,
+                false,
+            )
+            .await;
+
+        assert!(matches!(results[0].1, Err(DevRecapError::Http(_))));
+        let events = events.events.lock().unwrap();
+        assert!(events.contains(&"stage_started Retrying".to_string()));
+        assert_eq!(events.iter().filter(|e| *e == "error").count(), 2);
+    }
 }
@@ -0,0 +1,216 @@
+//! Live HTML preview of the report (`--preview`)
+//!
+//! Serves the markdown report rendered as HTML on localhost. The page polls
+//! `/version` and reloads itself when the report is replaced, either because
+//! the config file changed or because another `dev-recap --preview` run (with
+//! different flags) handed its report over with `PUT /report`.
+
+use crate::error::{DevRecapError, Result};
+use axum::extract::State;
+use axum::http::StatusCode;
+use axum::response::Html;
+use axum::routing::{get, put};
+use axum::Router;
+use pulldown_cmark::{html, CowStr, Event, Options, Parser, Tag};
+use std::sync::{Arc, RwLock};
+use tokio::net::TcpListener;
+
+/// Port `--preview` listens on unless `--preview-port` is given
+pub const DEFAULT_PORT: u16 = 4000;
+
+/// How often the page checks for a new report, in milliseconds
+const RELOAD_POLL_MS: u32 = 1000;
+
+/// The report currently previewed
+pub struct PreviewState {
+    report: RwLock<(u64, String)>,
+}
+
+impl PreviewState {
+    /// Preview `markdown`
+    pub fn new(markdown: String) -> Self {
+        Self {
+            report: RwLock::new((1, markdown)),
+        }
+    }
+
+    /// Replace the report, so open pages reload
+    pub fn update(&self, markdown: String) {
+        let mut report = self.report.write().unwrap_or_else(|e| e.into_inner());
+        report.0 += 1;
+        report.1 = markdown;
+    }
+
+    /// Incremented by every update
+    pub fn version(&self) -> u64 {
+        self.report.read().unwrap_or_else(|e| e.into_inner()).0
+    }
+
+    fn page(&self) -> String {
+        let report = self.report.read().unwrap_or_else(|e| e.into_inner());
+        render_page(&report.1, report.0)
+    }
+}
+
+/// Routes of the preview server
+pub fn router(state: Arc<PreviewState>) -> Router {
+    Router::new()
+        .route("/", get(page))
+        .route("/version", get(version))
+        .route("/report", put(replace_report))
+        .with_state(state)
+}
+
+/// Serve the preview on `listener` until the process exits
+pub async fn serve(state: Arc<PreviewState>, listener: TcpListener) -> Result<()> {
+    axum::serve(listener, router(state)).await?;
+    Ok(())
+}
+
+/// Hand a report to a preview already running on `port`
+///
+/// Returns `false` when nothing listens there, so the caller can start one.
+pub async fn push_report(port: u16, markdown: &str) -> Result<bool> {
+    let response = reqwest::Client::new()
+        .put(format!("http://127.0.0.1:{}/report", port))
+        .body(markdown.to_string())
+        .send()
+        .await;
+
+    match response {
+        Ok(response) if response.status().is_success() => Ok(true),
+        Ok(response) => Err(DevRecapError::other(format!(
+            "port {} is in use by something other than a dev-recap preview ({}); pass --preview-port",
+            port,
+            response.status()
+        ))),
+        Err(e) if e.is_connect() => Ok(false),
+        Err(e) => Err(e.into()),
+    }
+}
+
+async fn page(State(state): State<Arc<PreviewState>>) -> Html<String> {
+    Html(state.page())
+}
+
+async fn version(State(state): State<Arc<PreviewState>>) -> String {
+    state.version().to_string()
+}
+
+async fn replace_report(State(state): State<Arc<PreviewState>>, markdown: String) -> StatusCode {
+    state.update(markdown);
+    StatusCode::NO_CONTENT
+}
+
+/// HTML page for a markdown report, reloading itself when `version` changes
+fn render_page(markdown: &str, version: u64) -> String {
+    // Commit messages and summaries end up in the report; show any HTML in
+    // them as text instead of rendering it, and drop link targets that would
+    // run script. Header fields are one per line.
+    let events = Parser::new_ext(markdown, Options::ENABLE_TABLES | Options::ENABLE_STRIKETHROUGH).map(
+        |event| match event {
+            Event::Html(raw) | Event::InlineHtml(raw) => Event::Text(raw),
+            Event::SoftBreak => Event::HardBreak,
+            Event::Start(Tag::Link { link_type, dest_url, title, id }) => Event::Start(Tag::Link {
+                link_type,
+                dest_url: safe_url(dest_url),
+                title,
+                id,
+            }),
+            Event::Start(Tag::Image { link_type, dest_url, title, id }) => Event::Start(Tag::Image {
+                link_type,
+                dest_url: safe_url(dest_url),
+                title,
+                id,
+            }),
+            event => event,
+        },
+    );
+    let mut body = String::new();
+    html::push_html(&mut body, events);
+
+    format!(
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>Dev Recap (preview)</title>\n\
+         <style>body {{ font-family: sans-serif; max-width: 50em; margin: 2em auto; line-height: 1.5; }} \
+         table {{ border-collapse: collapse; }} th, td {{ border: 1px solid #ccc; padding: 0.2em 0.6em; }} \
+         code {{ background: #f4f4f4; }}</style>\n</head>\n<body>\n{body}\
+         <script>\n\
+         const version = {version};\n\
+         setInterval(async () => {{\n\
+           try {{\n\
+             const response = await fetch('/version');\n\
+             if (response.ok && Number(await response.text()) !== version) location.reload();\n\
+           }} catch (e) {{}}\n\
+         }}, {RELOAD_POLL_MS});\n\
+         </script>\n</body>\n</html>\n"
+    )
+}
+
+/// `url` if it is relative or uses a harmless scheme (http, https, mailto),
+/// otherwise `#`, so `javascript:` and `data:` links in commits can't run
+fn safe_url(url: CowStr<'_>) -> CowStr<'_> {
+    // Browsers ignore whitespace and control characters inside a scheme
+    let cleaned: String = url
+        .chars()
+        .filter(|c| !c.is_ascii_whitespace() && !c.is_control())
+        .collect::<String>()
+        .to_ascii_lowercase();
+    let scheme = cleaned
+        .split_once(':')
+        .map(|(scheme, _)| scheme)
+        .filter(|scheme| !scheme.contains(['/', '?', '#']));
+    match scheme {
+        None | Some("http" | "https" | "mailto") => url,
+        Some(_) => CowStr::Borrowed("#"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_page() {
+        let page = render_page(
+            "# Dev Recap\n\n| Author | Commits |\n|---|---:|\n| alice | 3 |\n\n- Fix <script>alert(1)</script>\n",
+            7,
+        );
+        assert!(page.contains("<h1>Dev Recap</h1>"));
+        assert!(page.contains("<table>"));
+        assert!(page.contains("&lt;script&gt;alert(1)&lt;/script&gt;"));
+        assert!(page.contains("const version = 7;"));
+
+        let page = render_page(
+            "[a](javascript:alert(1)) [b](<JaVa Script:alert(1)>) ![c](data:text/html,x) [d](https://example.com/a:b) [e](#top)",
+            1,
+        );
+        assert!(!page.to_lowercase().contains("=\"java"));
+        assert!(!page.contains("src=\"data:"));
+        assert!(page.contains("href=\"#\""));
+        assert!(page.contains("href=\"https://example.com/a:b\""));
+        assert!(page.contains("href=\"#top\""));
+    }
+
+    #[tokio::test]
+    async fn test_push_report_reloads() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let state = Arc::new(PreviewState::new("# First\n".to_string()));
+        tokio::spawn(serve(Arc::clone(&state), listener));
+
+        assert!(push_report(port, "# Second\n").await.unwrap());
+        assert_eq!(state.version(), 2);
+
+        let base = format!("http://127.0.0.1:{}", port);
+        let page = reqwest::get(&base).await.unwrap().text().await.unwrap();
+        assert!(page.contains("<h1>Second</h1>"));
+        let version = reqwest::get(format!("{}/version", base)).await.unwrap().text().await.unwrap();
+        assert_eq!(version, "2");
+
+        // Nothing listens on a freshly released port
+        let unused = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let unused_port = unused.local_addr().unwrap().port();
+        drop(unused);
+        assert!(!push_report(unused_port, "# Third\n").await.unwrap());
+    }
+}
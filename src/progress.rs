@@ -0,0 +1,129 @@
+use dev_recap::events::{EventSink, Stage};
+use dev_recap::git::scanner::Scanner;
+use dev_recap::theme::Palette;
+use dev_recap::{DevRecapError, Repository, Summary};
+use indicatif::{ProgressBar, ProgressStyle};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::Duration;
+
+/// Terminal progress display: the only place indicatif is used
+pub struct ProgressReporter {
+    dry_run: bool,
+    palette: Palette,
+    state: Mutex<Option<(Stage, ProgressBar)>>,
+    spinner: Mutex<Option<ProgressBar>>,
+}
+
+impl ProgressReporter {
+    /// Create a reporter (dry runs get their own completion message)
+    pub fn new(dry_run: bool) -> Self {
+        Self {
+            dry_run,
+            palette: Palette::PLAIN,
+            state: Mutex::new(None),
+            spinner: Mutex::new(None),
+        }
+    }
+
+    /// Color the spinner and bars with `palette`
+    pub fn with_palette(mut self, palette: Palette) -> Self {
+        self.palette = palette;
+        self
+    }
+
+    /// Update the current bar's message, optionally advancing it
+    fn update(&self, message: String, advance: bool) {
+        if let Some((_, ref progress)) = *self.state.lock().unwrap() {
+            progress.set_message(message);
+            if advance {
+                progress.inc(1);
+            }
+        }
+    }
+
+    /// Whether the current stage is `stage`
+    fn in_stage(&self, stage: Stage) -> bool {
+        matches!(*self.state.lock().unwrap(), Some((current, _)) if current == stage)
+    }
+}
+
+impl EventSink for ProgressReporter {
+    fn on_scan_started(&self, _root: &Path) {
+        let spinner = ProgressBar::new_spinner();
+        spinner.set_style(
+            ProgressStyle::default_spinner()
+                .template(&self.palette.spinner_template())
+                .unwrap(),
+        );
+        spinner.set_message("Scanning for git repositories...");
+        spinner.enable_steady_tick(Duration::from_millis(100));
+        *self.spinner.lock().unwrap() = Some(spinner);
+    }
+
+    fn on_scan_finished(&self, repos: &[PathBuf]) {
+        if let Some(spinner) = self.spinner.lock().unwrap().take() {
+            spinner.finish_with_message(format!("Found {} repositories", repos.len()));
+        }
+    }
+
+    fn on_stage_started(&self, stage: Stage, total: usize) {
+        let progress = ProgressBar::new(total as u64);
+        progress.set_style(
+            ProgressStyle::default_bar()
+                .template(&self.palette.bar_template())
+                .unwrap()
+                .progress_chars("=>-"),
+        );
+        progress.set_message(match stage {
+            Stage::Analyzing => "Analyzing repositories...",
+            Stage::Summarizing => "Summarizing repositories...",
+            Stage::Retrying => "Retrying failed repositories...",
+        });
+        *self.state.lock().unwrap() = Some((stage, progress));
+    }
+
+    fn on_stage_finished(&self, stage: Stage) {
+        if let Some((_, progress)) = self.state.lock().unwrap().take() {
+            progress.finish_with_message(match stage {
+                Stage::Analyzing => "Analysis complete",
+                Stage::Summarizing if self.dry_run => "Dry run complete",
+                Stage::Summarizing => "Summaries complete",
+                Stage::Retrying => "Retries complete",
+            });
+        }
+    }
+
+    fn on_repo_started(&self, repo_path: &Path) {
+        self.update(format!("Analyzing {}", Scanner::get_repo_name(repo_path)), false);
+    }
+
+    fn on_repo_analyzed(&self, repo: &Repository) {
+        // In pipeline mode the bar counts summaries, not parsed repositories
+        let advance = self.in_stage(Stage::Analyzing);
+        self.update(format!("Analyzed {}", repo.name), advance);
+    }
+
+    fn on_summary_ready(&self, repo: &Repository, _summary: &Summary) {
+        self.update(format!("Summarized {}", repo.name), true);
+    }
+
+    fn on_error(&self, repo_path: &Path, _error: &DevRecapError) {
+        self.update(format!("Failed {}", Scanner::get_repo_name(repo_path)), true);
+    }
+
+    fn on_repo_skipped(&self, repo_path: &Path, reason: &DevRecapError) {
+        let warning = format!(
+            "⚠ Skipping {}: the repository looks corrupted ({}). Run `git fsck` in {} for details.",
+            Scanner::get_repo_name(repo_path),
+            reason,
+            repo_path.display()
+        );
+        if let Some((_, ref progress)) = *self.state.lock().unwrap() {
+            progress.println(warning);
+        } else {
+            eprintln!("{}", warning);
+        }
+        self.update(format!("Skipped {}", Scanner::get_repo_name(repo_path)), true);
+    }
+}
@@ -0,0 +1,185 @@
+//! Provenance footer of a repository's recap
+//!
+//! States exactly what a summary was written from (the commit range, the
+//! filters applied, the model and the dev-recap version), so the recap can
+//! be reproduced or audited later.
+
+use crate::config::Config;
+use crate::git::scanner::Scanner;
+use crate::git::{CommitDate, Repository, Timespan};
+
+/// Where a repository's recap came from
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Provenance {
+    /// Full hashes of the oldest and newest commit analyzed (none without commits)
+    pub range: Option<(String, String)>,
+    /// Commits analyzed
+    pub commits: usize,
+    /// Authors the commits were filtered by (as shown in the report)
+    pub authors: Vec<String>,
+    /// Timespan the commits were filtered by, as RFC 3339 timestamps
+    pub since: String,
+    pub until: String,
+    /// Other filters that changed which commits were analyzed or sent, as
+    /// `key=value` in config spelling
+    pub filters: Vec<String>,
+    /// Model the summary was generated with (unknown for older cached summaries)
+    pub model: Option<String>,
+    /// dev-recap version that wrote the recap
+    pub version: &'static str,
+}
+
+impl Provenance {
+    /// Provenance of `repo`'s recap, analyzed for `authors` over `timespan`
+    /// with `config`
+    pub fn new(repo: &Repository, authors: &[String], timespan: &Timespan, config: &Config, model: Option<&str>) -> Self {
+        // Commits are newest first
+        let range = match (repo.commits.last(), repo.commits.first()) {
+            (Some(oldest), Some(newest)) => Some((oldest.hash.clone(), newest.hash.clone())),
+            _ => None,
+        };
+
+        let mut filters = Vec::new();
+        // A sub-project's commits are only those touching its directory
+        if let Some((_, subdir)) = repo.path.is_dir().then(|| Scanner::split_subproject(&repo.path)).flatten() {
+            filters.push(format!("subdir={}", subdir.display()));
+        }
+        if config.commit_date == CommitDate::Committer {
+            filters.push("commit_date=committer".to_string());
+        }
+        if !config.ignore_commit_patterns.is_empty() {
+            filters.push(format!("ignore_commit_patterns={:?}", config.ignore_commit_patterns));
+        }
+        if let Some(cap) = repo.commit_cap {
+            filters.push(format!("max_commits_per_repo={}", cap));
+        }
+        if config.collapse_duplicate_commits {
+            filters.push("collapse_duplicate_commits=true".to_string());
+        }
+        if let Some(min_changes) = config.min_changes {
+            filters.push(format!("min_changes={}", min_changes));
+        }
+        // Counted rather than listed: the patterns themselves name what's private
+        if !config.redact_patterns.is_empty() {
+            filters.push(format!("redact_patterns={}", config.redact_patterns.len()));
+        }
+
+        Self {
+            range,
+            commits: repo.commits.len(),
+            authors: authors.to_vec(),
+            since: timespan.start.to_rfc3339(),
+            until: timespan.end.to_rfc3339(),
+            filters,
+            model: model.map(str::to_string),
+            version: env!("CARGO_PKG_VERSION"),
+        }
+    }
+
+    /// Add a filter the config doesn't show, e.g. where the commits came from
+    pub fn with_filter(mut self, filter: String) -> Self {
+        self.filters.push(filter);
+        self
+    }
+
+    /// The footer on one line of `key: value` fields separated by " | "
+    pub fn to_line(&self) -> String {
+        let range = match self.range {
+            Some((ref oldest, ref newest)) => format!("{}..{}", oldest, newest),
+            None => "none".to_string(),
+        };
+        let filters = if self.filters.is_empty() {
+            "none".to_string()
+        } else {
+            self.filters.join(", ")
+        };
+        format!(
+            "Provenance: commits: {} ({}) | authors: {} | since: {} | until: {} | filters: {} | model: {} | dev-recap: {}",
+            range,
+            self.commits,
+            self.authors.join(", "),
+            self.since,
+            self.until,
+            filters,
+            self.model.as_deref().unwrap_or("unknown"),
+            self.version
+        )
+    }
+
+    /// The footer as a small markdown line
+    pub fn to_markdown(&self) -> String {
+        format!("<sub>{}</sub>\n", self.to_line())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::git::Commit;
+    use chrono::{TimeZone, Utc};
+    use std::path::Path;
+
+    #[test]
+    fn test_provenance() {
+        let commits = vec![Commit::test("ffffffffff", "Work"), Commit::test("0000000000", "Work")];
+        let repo = Repository {
+            commit_cap: Some(500),
+            ..Repository::test("api", commits)
+        };
+        let timespan = Timespan::from_dates(
+            Utc.with_ymd_and_hms(2025, 1, 1, 0, 0, 0).unwrap(),
+            Utc.with_ymd_and_hms(2025, 1, 14, 23, 59, 59).unwrap(),
+        );
+        let config = Config {
+            ignore_commit_patterns: vec!["^chore".to_string()],
+            redact_patterns: vec![r"\.corp\.internal".to_string()],
+            ..Default::default()
+        };
+
+        let provenance = Provenance::new(&repo, &["me@example.com".to_string()], &timespan, &config, Some("gpt-4o"));
+        assert_eq!(
+            provenance.to_line(),
+            format!(
+                "Provenance: commits: 0000000000..ffffffffff (2) | authors: me@example.com | \
+                 since: 2025-01-01T00:00:00+00:00 | until: 2025-01-14T23:59:59+00:00 | \
+                 filters: ignore_commit_patterns=[\"^chore\"], max_commits_per_repo=500, \
+                 collapse_duplicate_commits=true, redact_patterns=1 | model: gpt-4o | dev-recap: {}",
+                env!("CARGO_PKG_VERSION")
+            )
+        );
+
+        let empty = Repository {
+            commits: vec![],
+            commit_cap: None,
+            ..repo
+        };
+        let config = Config {
+            collapse_duplicate_commits: false,
+            ..Default::default()
+        };
+        let provenance = Provenance::new(&empty, &[], &timespan, &config, None);
+        assert!(provenance.to_markdown().starts_with("<sub>Provenance: commits: none (0) |"));
+        assert!(provenance.to_line().contains("| filters: none | model: unknown |"));
+        let provenance = provenance.with_filter("stdin_format=git-log".to_string());
+        assert!(provenance.to_line().contains("| filters: stdin_format=git-log |"));
+    }
+
+    #[test]
+    fn test_provenance_subproject() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        std::fs::create_dir_all(temp_dir.path().join(".git")).unwrap();
+        let service = temp_dir.path().join("services").join("billing");
+        std::fs::create_dir_all(&service).unwrap();
+
+        let repo = Repository {
+            path: service,
+            ..Repository::test("app/services/billing", vec![])
+        };
+        let config = Config {
+            collapse_duplicate_commits: false,
+            ..Default::default()
+        };
+        let provenance = Provenance::new(&repo, &[], &Timespan::days_back(1), &config, None);
+        assert_eq!(provenance.filters, [format!("subdir={}", Path::new("services").join("billing").display())]);
+    }
+}
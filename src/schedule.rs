@@ -0,0 +1,243 @@
+//! Cron schedules for `dev-recap daemon`
+//!
+//! Supports the classic five fields (`minute hour day-of-month month
+//! day-of-week`) with `*`, lists (`1,15`), ranges (`MON-FRI`), steps
+//! (`*/15`, `0-30/10`) and three-letter month and weekday names. As in cron,
+//! when both day fields are restricted a day matching either one fires.
+
+use crate::error::{DevRecapError, Result};
+use chrono::{DateTime, Datelike, Duration, NaiveDate, NaiveTime, TimeZone, Timelike};
+use std::fmt;
+
+/// Days searched for the next run (covers Feb 29 in the next leap year)
+const MAX_SEARCH_DAYS: i64 = 366 * 4 + 1;
+
+const MONTHS: [&str; 12] = [
+    "JAN", "FEB", "MAR", "APR", "MAY", "JUN", "JUL", "AUG", "SEP", "OCT", "NOV", "DEC",
+];
+const WEEKDAYS: [&str; 7] = ["SUN", "MON", "TUE", "WED", "THU", "FRI", "SAT"];
+
+/// A parsed cron expression
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Schedule {
+    expression: String,
+    minutes: Vec<u32>,
+    hours: Vec<u32>,
+    days_of_month: Vec<u32>,
+    months: Vec<u32>,
+    /// 0 = Sunday
+    days_of_week: Vec<u32>,
+    any_day_of_month: bool,
+    any_day_of_week: bool,
+}
+
+impl Schedule {
+    /// Parse a five-field cron expression, e.g. `"0 17 * * FRI"`
+    pub fn parse(expression: &str) -> Result<Self> {
+        let fields: Vec<&str> = expression.split_whitespace().collect();
+        if fields.len() != 5 {
+            return Err(invalid(
+                expression,
+                format!("expected 5 fields (minute hour day month weekday), got {}", fields.len()),
+            ));
+        }
+
+        let field = |index: usize, min: u32, max: u32, names: &[&str]| {
+            parse_field(fields[index], min, max, names).map_err(|e| invalid(expression, e))
+        };
+
+        // 7 is accepted as Sunday, as in most crons
+        let mut days_of_week: Vec<u32> = field(4, 0, 7, &WEEKDAYS)?
+            .into_iter()
+            .map(|day| day % 7)
+            .collect();
+        days_of_week.sort_unstable();
+        days_of_week.dedup();
+
+        Ok(Self {
+            expression: fields.join(" "),
+            minutes: field(0, 0, 59, &[])?,
+            hours: field(1, 0, 23, &[])?,
+            days_of_month: field(2, 1, 31, &[])?,
+            months: field(3, 1, 12, &MONTHS)?,
+            days_of_week,
+            any_day_of_month: fields[2] == "*",
+            any_day_of_week: fields[4] == "*",
+        })
+    }
+
+    /// First time strictly after `after` that matches the schedule
+    ///
+    /// Times skipped by a DST change never fire; an ambiguous time fires at
+    /// its first occurrence.
+    pub fn next_after<Tz: TimeZone>(&self, after: &DateTime<Tz>) -> Option<DateTime<Tz>> {
+        let tz = after.timezone();
+        // The next whole minute
+        let local = after.naive_local();
+        let start = local.date().and_hms_opt(local.hour(), local.minute(), 0)? + Duration::minutes(1);
+
+        for offset in 0..MAX_SEARCH_DAYS {
+            let day = start.date() + Duration::days(offset);
+            if !self.matches_day(day) {
+                continue;
+            }
+            let earliest = if offset == 0 { start.time() } else { NaiveTime::MIN };
+
+            for &hour in &self.hours {
+                for &minute in &self.minutes {
+                    let time = NaiveTime::from_hms_opt(hour, minute, 0)?;
+                    if time < earliest {
+                        continue;
+                    }
+                    if let Some(next) = tz.from_local_datetime(&day.and_time(time)).earliest() {
+                        if next > *after {
+                            return Some(next);
+                        }
+                    }
+                }
+            }
+        }
+        None
+    }
+
+    fn matches_day(&self, day: NaiveDate) -> bool {
+        if !self.months.contains(&day.month()) {
+            return false;
+        }
+        let day_of_month = self.days_of_month.contains(&day.day());
+        let day_of_week = self.days_of_week.contains(&day.weekday().num_days_from_sunday());
+        match (self.any_day_of_month, self.any_day_of_week) {
+            (true, true) => true,
+            (true, false) => day_of_week,
+            (false, true) => day_of_month,
+            (false, false) => day_of_month || day_of_week,
+        }
+    }
+}
+
+impl fmt::Display for Schedule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.expression)
+    }
+}
+
+fn invalid(expression: &str, reason: String) -> DevRecapError {
+    DevRecapError::Config(format!("invalid schedule `{}`: {}", expression, reason))
+}
+
+/// Parse one field into its sorted values
+fn parse_field(field: &str, min: u32, max: u32, names: &[&str]) -> std::result::Result<Vec<u32>, String> {
+    let mut values = Vec::new();
+
+    for part in field.split(',') {
+        let (range, step) = match part.split_once('/') {
+            Some((range, step)) => {
+                let step: u32 = step.parse().map_err(|_| format!("invalid step `{}`", step))?;
+                if step == 0 {
+                    return Err("step cannot be 0".to_string());
+                }
+                (range, step)
+            }
+            None => (part, 1),
+        };
+
+        let (low, high) = if range == "*" {
+            (min, max)
+        } else if let Some((low, high)) = range.split_once('-') {
+            (parse_value(low, min, max, names)?, parse_value(high, min, max, names)?)
+        } else {
+            let value = parse_value(range, min, max, names)?;
+            // `5/15` means every 15 starting at 5
+            (value, if step > 1 { max } else { value })
+        };
+
+        if low > high {
+            return Err(format!("range `{}` is backwards", range));
+        }
+        values.extend((low..=high).step_by(step as usize));
+    }
+
+    values.sort_unstable();
+    values.dedup();
+    Ok(values)
+}
+
+fn parse_value(value: &str, min: u32, max: u32, names: &[&str]) -> std::result::Result<u32, String> {
+    let upper = value.to_ascii_uppercase();
+    let parsed = match names.iter().position(|name| *name == upper) {
+        // Month names start at 1, weekday names at 0
+        Some(index) => index as u32 + min.min(1),
+        None => value.parse().map_err(|_| format!("invalid value `{}`", value))?,
+    };
+    if parsed < min || parsed > max {
+        return Err(format!("`{}` is out of range {}-{}", value, min, max));
+    }
+    Ok(parsed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+
+    fn at(input: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(input).unwrap().with_timezone(&Utc)
+    }
+
+    #[test]
+    fn test_parse_fields() {
+        let schedule = Schedule::parse("*/15 9-17 1,15 * mon-fri").unwrap();
+        assert_eq!(schedule.minutes, vec![0, 15, 30, 45]);
+        assert_eq!(schedule.hours, (9..=17).collect::<Vec<_>>());
+        assert_eq!(schedule.days_of_month, vec![1, 15]);
+        assert_eq!(schedule.months.len(), 12);
+        assert_eq!(schedule.days_of_week, vec![1, 2, 3, 4, 5]);
+
+        let schedule = Schedule::parse("0 0 * JAN,dec 7").unwrap();
+        assert_eq!(schedule.months, vec![1, 12]);
+        assert_eq!(schedule.days_of_week, vec![0]);
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert!(Schedule::parse("0 17 * *").is_err());
+        assert!(Schedule::parse("60 17 * * FRI").is_err());
+        assert!(Schedule::parse("0 17 * * FUN").is_err());
+        assert!(Schedule::parse("*/0 * * * *").is_err());
+        assert!(Schedule::parse("0 17-9 * * *").is_err());
+    }
+
+    #[test]
+    fn test_next_after() {
+        // 2025-01-15 is a Wednesday
+        let schedule = Schedule::parse("0 17 * * FRI").unwrap();
+        assert_eq!(
+            schedule.next_after(&at("2025-01-15T10:00:00Z")),
+            Some(at("2025-01-17T17:00:00Z"))
+        );
+        // Exactly on a run time, the next one is a week later
+        assert_eq!(
+            schedule.next_after(&at("2025-01-17T17:00:00Z")),
+            Some(at("2025-01-24T17:00:00Z"))
+        );
+
+        let schedule = Schedule::parse("30 * * * *").unwrap();
+        assert_eq!(
+            schedule.next_after(&at("2025-01-15T23:45:10Z")),
+            Some(at("2025-01-16T00:30:00Z"))
+        );
+
+        // Either day field matches when both are restricted
+        let schedule = Schedule::parse("0 9 1 * MON").unwrap();
+        assert_eq!(
+            schedule.next_after(&at("2025-01-15T10:00:00Z")),
+            Some(at("2025-01-20T09:00:00Z"))
+        );
+
+        let schedule = Schedule::parse("0 0 29 2 *").unwrap();
+        assert_eq!(
+            schedule.next_after(&at("2025-03-01T00:00:00Z")),
+            Some(at("2028-02-29T00:00:00Z"))
+        );
+    }
+}
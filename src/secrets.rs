@@ -0,0 +1,94 @@
+use crate::error::Result;
+use keyring::Entry;
+
+/// Keyring service name all dev-recap secrets are stored under
+const SERVICE: &str = "dev-recap";
+
+/// Secrets dev-recap can keep in the OS keyring
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Secret {
+    /// Claude API key
+    ClaudeApiKey,
+    /// GitHub token
+    GitHubToken,
+    /// Linear API key
+    LinearApiKey,
+}
+
+impl Secret {
+    /// Keyring account name (matches the config key it replaces)
+    pub fn account(&self) -> &'static str {
+        match self {
+            Secret::ClaudeApiKey => "claude_api_key",
+            Secret::GitHubToken => "github_token",
+            Secret::LinearApiKey => "linear_api_key",
+        }
+    }
+
+    /// Human-readable name
+    pub fn label(&self) -> &'static str {
+        match self {
+            Secret::ClaudeApiKey => "Claude API key",
+            Secret::GitHubToken => "GitHub token",
+            Secret::LinearApiKey => "Linear API key",
+        }
+    }
+}
+
+/// Keyring entry of `secret`
+///
+/// Tests get an in-memory mock keyring, so they neither read a developer's
+/// real secrets nor write to the OS keyring.
+fn entry(secret: Secret) -> keyring::Result<Entry> {
+    #[cfg(test)]
+    {
+        static MOCK: std::sync::Once = std::sync::Once::new();
+        MOCK.call_once(|| keyring::set_default_credential_builder(keyring::mock::default_credential_builder()));
+    }
+    Entry::new(SERVICE, secret.account())
+}
+
+/// Read a secret from the OS keyring
+///
+/// Returns None if it isn't stored or no keyring is available (e.g. headless
+/// Linux without a Secret Service), so callers can fall through to other sources.
+pub fn get(secret: Secret) -> Option<String> {
+    let entry = entry(secret).ok()?;
+    match entry.get_password() {
+        Ok(value) => Some(value),
+        Err(keyring::Error::NoEntry) => None,
+        Err(e) => {
+            tracing::debug!(secret = secret.account(), error = %e, "keyring unavailable");
+            None
+        }
+    }
+}
+
+/// Store a secret in the OS keyring
+pub fn set(secret: Secret, value: &str) -> Result<()> {
+    entry(secret)?.set_password(value)?;
+    Ok(())
+}
+
+/// Remove a secret from the OS keyring (returns false if it wasn't stored)
+pub fn delete(secret: Secret) -> Result<bool> {
+    match entry(secret)?.delete_credential() {
+        Ok(()) => Ok(true),
+        Err(keyring::Error::NoEntry) => Ok(false),
+        Err(e) => Err(e.into()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mock_keyring() {
+        // Mock entries start empty and keep nothing between calls, so a
+        // stored secret is never seen by later lookups or other tests
+        set(Secret::GitHubToken, "ghp_test").unwrap();
+        assert!(get(Secret::GitHubToken).is_none());
+        assert!(!delete(Secret::GitHubToken).unwrap());
+    }
+}
@@ -0,0 +1,766 @@
+//! HTTP server mode (`dev-recap serve`)
+//!
+//! Lets internal tools and bots request recaps on demand:
+//!
+//! - `POST /recaps` starts a recap for a path, author and timespan and
+//!   returns its id (`202 Accepted`)
+//! - `GET /recaps/{id}` returns its status and, once done, the result as
+//!   JSON, or as an HTML page with `?format=html`
+//! - `GET /health` answers `ok`
+//! - `GET /metrics` returns Prometheus counters (see [`crate::metrics`])
+//! - `POST /slack/commands` answers the `/recap` Slack slash command, when
+//!   a signing secret is configured (see [`crate::slack`])
+//!
+//! Recaps run in the background on a shared orchestrator, one at a time;
+//! at most [`MAX_QUEUED_RECAPS`] wait their turn and only the most recent
+//! [`MAX_STORED_RECAPS`] are kept. With a token set, `/recaps` requests need
+//! `Authorization: Bearer <token>`.
+
+use crate::ai::{OverallRecap, Summary};
+use crate::deliver;
+use crate::error::{DevRecapError, Result};
+use crate::git::Timespan;
+use crate::history::RepoRecap;
+use crate::orchestrator::Orchestrator;
+use crate::slack::{self, RecapArgs, SlashCommand};
+use axum::body::Bytes;
+use axum::extract::{Path as UrlPath, Query, State};
+use axum::http::header::AUTHORIZATION;
+use axum::http::{HeaderMap, StatusCode};
+use axum::response::{Html, IntoResponse, Response};
+use axum::routing::{get, post};
+use axum::{Json, Router};
+use chrono::{DateTime, Utc};
+use hmac::{Hmac, Mac};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use sha2::Sha256;
+use std::collections::BTreeMap;
+use std::net::SocketAddr;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+
+/// Number of recaps kept for fetching; older ones are dropped
+pub const MAX_STORED_RECAPS: usize = 100;
+
+/// Number of recaps running or waiting to run; further requests are
+/// rejected until one finishes
+pub const MAX_QUEUED_RECAPS: usize = 8;
+
+/// Body of `POST /recaps`
+#[derive(Debug, Clone, Deserialize)]
+pub struct RecapRequest {
+    /// Path to scan for git repositories
+    pub path: PathBuf,
+    /// Author email to filter commits (all authors if omitted)
+    #[serde(default)]
+    pub author: Option<String>,
+    /// Number of days to look back (default_timespan_days if omitted)
+    #[serde(default)]
+    pub days: Option<u32>,
+    /// Start date, as accepted by `--since`
+    #[serde(default)]
+    pub since: Option<String>,
+    /// End date, as accepted by `--until`
+    #[serde(default)]
+    pub until: Option<String>,
+    /// Interpret dates without an offset as UTC instead of server local time
+    #[serde(default)]
+    pub utc: bool,
+    /// Analyze without making API calls
+    #[serde(default)]
+    pub dry_run: bool,
+}
+
+impl RecapRequest {
+    /// Resolve the requested timespan, like the `--days`/`--since`/`--until` flags
+    pub(crate) fn timespan(&self, default_days: u32) -> Result<Timespan> {
+        if self.since.is_none() && self.until.is_none() {
+            return Ok(Timespan::days_back(self.days.unwrap_or(default_days)));
+        }
+
+        let start = match self.since {
+            Some(ref since) => Timespan::parse_bound(since, false, self.utc)?,
+            None => DateTime::<Utc>::UNIX_EPOCH,
+        };
+        let end = match self.until {
+            Some(ref until) => Timespan::parse_bound(until, true, self.utc)?,
+            None => Utc::now(),
+        };
+
+        if start > end {
+            return Err(DevRecapError::InvalidTimespan(
+                "since is after until".to_string(),
+            ));
+        }
+        Ok(Timespan::from_dates(start, end))
+    }
+}
+
+/// State of a requested recap
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum RecapStatus {
+    Running,
+    Done { result: RecapResult },
+    Failed { error: String },
+}
+
+/// Finished recap
+#[derive(Debug, Clone, Serialize)]
+pub struct RecapResult {
+    pub path: PathBuf,
+    pub author: Option<String>,
+    pub timespan_start: DateTime<Utc>,
+    pub timespan_end: DateTime<Utc>,
+    pub overall: Option<OverallRecap>,
+    pub repositories: Vec<RepoRecap>,
+}
+
+/// Shared state of the server
+pub struct ServerState {
+    orchestrator: Arc<Orchestrator>,
+    jobs: usize,
+    next_id: AtomicU64,
+    recaps: Mutex<BTreeMap<u64, RecapStatus>>,
+    /// Slots of the queue, held from request until the recap is done
+    queue: Arc<Semaphore>,
+    /// Recaps run one after another, each summarizing `jobs` repositories at a time
+    running: Semaphore,
+    token: Option<String>,
+    slack: Option<SlackBot>,
+}
+
+/// Settings of the Slack slash-command endpoint
+struct SlackBot {
+    signing_secret: String,
+    scan_path: PathBuf,
+}
+
+impl ServerState {
+    /// Serve recaps from `orchestrator`, summarizing up to `jobs` repositories at a time
+    pub fn new(orchestrator: Arc<Orchestrator>, jobs: usize) -> Self {
+        Self {
+            orchestrator,
+            jobs: jobs.max(1),
+            next_id: AtomicU64::new(1),
+            recaps: Mutex::new(BTreeMap::new()),
+            queue: Arc::new(Semaphore::new(MAX_QUEUED_RECAPS)),
+            running: Semaphore::new(1),
+            token: None,
+            slack: None,
+        }
+    }
+
+    /// Require `Authorization: Bearer <token>` on `/recaps` requests
+    pub fn with_token(mut self, token: String) -> Self {
+        self.token = Some(token);
+        self
+    }
+
+    /// Answer Slack slash commands on `POST /slack/commands`, recapping `scan_path`
+    pub fn with_slack(mut self, signing_secret: String, scan_path: PathBuf) -> Self {
+        self.slack = Some(SlackBot {
+            signing_secret,
+            scan_path,
+        });
+        self
+    }
+
+    /// Orchestrator recaps run on
+    pub(crate) fn orchestrator(&self) -> &Arc<Orchestrator> {
+        &self.orchestrator
+    }
+
+    /// Queue a new recap and allocate its id, unless the queue is full
+    fn begin(&self) -> Option<(u64, OwnedSemaphorePermit)> {
+        let slot = Arc::clone(&self.queue).try_acquire_owned().ok()?;
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        self.set_status(id, RecapStatus::Running);
+        Some((id, slot))
+    }
+
+    /// Run recap `id` once the previous ones are done and store its final
+    /// status, then free its queue slot
+    async fn complete(
+        &self,
+        id: u64,
+        _slot: OwnedSemaphorePermit,
+        request: RecapRequest,
+        timespan: Timespan,
+    ) -> RecapStatus {
+        let result = match self.running.acquire().await {
+            Ok(_turn) => self.run(request, timespan).await,
+            Err(e) => Err(DevRecapError::Other(format!("recap queue closed: {}", e))),
+        };
+        self.orchestrator.metrics().record_run(result.is_ok());
+        let status = match result {
+            Ok(result) => RecapStatus::Done { result },
+            Err(e) => {
+                tracing::warn!(id, error = %e, "recap failed");
+                RecapStatus::Failed { error: e.to_string() }
+            }
+        };
+        self.set_status(id, status.clone());
+        status
+    }
+
+    /// Store the status of a recap, dropping the oldest beyond the limit
+    fn set_status(&self, id: u64, status: RecapStatus) {
+        let mut recaps = self.recaps.lock().unwrap_or_else(|e| e.into_inner());
+        recaps.insert(id, status);
+        while recaps.len() > MAX_STORED_RECAPS {
+            recaps.pop_first();
+        }
+    }
+
+    /// Whether `headers` carry the bearer token, if one is required
+    fn authorized(&self, headers: &HeaderMap) -> bool {
+        let Some(ref token) = self.token else {
+            return true;
+        };
+        headers
+            .get(AUTHORIZATION)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.strip_prefix("Bearer "))
+            .is_some_and(|given| token_matches(given, token))
+    }
+
+    fn status(&self, id: u64) -> Option<RecapStatus> {
+        let recaps = self.recaps.lock().unwrap_or_else(|e| e.into_inner());
+        recaps.get(&id).cloned()
+    }
+
+    /// Scan, analyze and summarize, like a non-interactive CLI run
+    pub(crate) async fn run(&self, request: RecapRequest, timespan: Timespan) -> Result<RecapResult> {
+        let orchestrator = Arc::clone(&self.orchestrator);
+        let path = request.path.clone();
+        let repo_paths = tokio::task::spawn_blocking(move || orchestrator.scan_repositories(&path))
+            .await
+            .map_err(|e| DevRecapError::Other(format!("scan task failed: {}", e)))??;
+
+        let results = self
+            .orchestrator
+            .analyze_repositories(
+                repo_paths,
+                request.author.clone(),
+                &timespan,
+                self.jobs,
+                request.dry_run,
+            )
+            .await;
+
+        let summaries: Vec<&Summary> = results
+            .iter()
+            .filter_map(|(_, result)| result.as_ref().ok())
+            .collect();
+        let overall = if !request.dry_run && summaries.len() > 1 {
+            match self.orchestrator.generate_overall_recap(&summaries).await {
+                Ok(recap) => Some(recap),
+                Err(e) => {
+                    tracing::warn!(error = %e, "overall recap failed");
+                    None
+                }
+            }
+        } else {
+            None
+        };
+
+        // Repositories without matching commits aren't worth reporting
+        let repositories = results
+            .into_iter()
+            .filter(|(_, result)| !result.as_ref().is_err_and(DevRecapError::is_no_commits))
+            .map(|(repo, result)| RepoRecap::new(&repo, &result))
+            .collect();
+
+        Ok(RecapResult {
+            path: request.path,
+            author: request.author,
+            timespan_start: timespan.start,
+            timespan_end: timespan.end,
+            overall,
+            repositories,
+        })
+    }
+}
+
+/// Whether `given` is the bearer `token`, compared in constant time: HMACs
+/// of both are compared, so neither the token nor its length leaks through
+/// how long a wrong guess takes
+fn token_matches(given: &str, token: &str) -> bool {
+    let mac = |value: &str| {
+        let mut mac = Hmac::<Sha256>::new_from_slice(token.as_bytes()).expect("HMAC takes keys of any length");
+        mac.update(value.as_bytes());
+        mac
+    };
+    mac(token).verify_slice(&mac(given).finalize().into_bytes()).is_ok()
+}
+
+/// Routes of the server
+pub fn router(state: Arc<ServerState>) -> Router {
+    Router::new()
+        .route("/health", get(|| async { "ok" }))
+        .route("/metrics", get(metrics))
+        .route("/recaps", post(create_recap))
+        .route("/recaps/:id", get(get_recap))
+        .route("/slack/commands", post(slack_command))
+        .with_state(state)
+}
+
+/// Listen on `addr` until the process is stopped
+pub async fn serve(state: Arc<ServerState>, addr: SocketAddr) -> Result<()> {
+    let listener = tokio::net::TcpListener::bind(addr).await?;
+    tracing::info!(addr = %listener.local_addr()?, "serving recaps");
+    axum::serve(listener, router(state)).await?;
+    Ok(())
+}
+
+/// Error response: `{"error": "..."}`
+struct ApiError(StatusCode, String);
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        (self.0, Json(json!({ "error": self.1 }))).into_response()
+    }
+}
+
+#[derive(Serialize)]
+struct Created {
+    id: u64,
+    status: &'static str,
+}
+
+/// Reject requests without the server's bearer token
+fn check_token(state: &ServerState, headers: &HeaderMap) -> std::result::Result<(), ApiError> {
+    if state.authorized(headers) {
+        Ok(())
+    } else {
+        Err(ApiError(StatusCode::UNAUTHORIZED, "missing or invalid bearer token".to_string()))
+    }
+}
+
+/// Error response when [`MAX_QUEUED_RECAPS`] recaps are already queued
+fn queue_full() -> ApiError {
+    ApiError(
+        StatusCode::TOO_MANY_REQUESTS,
+        format!("{} recaps are already queued; try again later", MAX_QUEUED_RECAPS),
+    )
+}
+
+async fn create_recap(
+    State(state): State<Arc<ServerState>>,
+    headers: HeaderMap,
+    Json(request): Json<RecapRequest>,
+) -> std::result::Result<(StatusCode, Json<Created>), ApiError> {
+    check_token(&state, &headers)?;
+    if !request.path.is_dir() {
+        return Err(ApiError(
+            StatusCode::BAD_REQUEST,
+            format!("not a directory: {}", request.path.display()),
+        ));
+    }
+    let timespan = request
+        .timespan(state.orchestrator.config().default_timespan_days)
+        .map_err(|e| ApiError(StatusCode::BAD_REQUEST, e.to_string()))?;
+
+    let (id, slot) = state.begin().ok_or_else(queue_full)?;
+    tracing::info!(id, path = %request.path.display(), "recap requested");
+
+    let task_state = Arc::clone(&state);
+    tokio::spawn(async move { task_state.complete(id, slot, request, timespan).await });
+
+    Ok((StatusCode::ACCEPTED, Json(Created { id, status: "running" })))
+}
+
+/// `/recap` slash command: acknowledge now, post the recap when it's done
+async fn slack_command(
+    State(state): State<Arc<ServerState>>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> std::result::Result<Json<serde_json::Value>, ApiError> {
+    let bot = state
+        .slack
+        .as_ref()
+        .ok_or_else(|| ApiError(StatusCode::NOT_FOUND, "Slack bot is not configured".to_string()))?;
+
+    let header = |name: &str| headers.get(name).and_then(|v| v.to_str().ok()).unwrap_or_default();
+    if !slack::verify_signature(
+        &bot.signing_secret,
+        header("x-slack-request-timestamp"),
+        &body,
+        header("x-slack-signature"),
+        Utc::now().timestamp(),
+    ) {
+        return Err(ApiError(StatusCode::UNAUTHORIZED, "invalid Slack signature".to_string()));
+    }
+
+    let command: SlashCommand = serde_urlencoded::from_bytes(&body)
+        .map_err(|e| ApiError(StatusCode::BAD_REQUEST, e.to_string()))?;
+    let args = match RecapArgs::parse(&command.text) {
+        Ok(args) => args,
+        // Only the caller sees usage errors
+        Err(e) => return Ok(Json(json!({ "response_type": "ephemeral", "text": e.to_string() }))),
+    };
+
+    let request = RecapRequest {
+        path: bot.scan_path.clone(),
+        author: args.author,
+        days: args.days,
+        since: None,
+        until: None,
+        utc: false,
+        dry_run: false,
+    };
+    let days = request
+        .days
+        .unwrap_or(state.orchestrator.config().default_timespan_days);
+    let timespan = Timespan::days_back(days);
+
+    let Some((id, slot)) = state.begin() else {
+        return Ok(Json(json!({ "response_type": "ephemeral", "text": queue_full().1 })));
+    };
+    tracing::info!(id, user = %command.user_name, "recap requested from Slack");
+
+    let task_state = Arc::clone(&state);
+    tokio::spawn(async move {
+        let text = match task_state.complete(id, slot, request, timespan).await {
+            RecapStatus::Done { result } => slack::format_recap(&result),
+            RecapStatus::Failed { error } => format!("Recap failed: {}", error),
+            RecapStatus::Running => return,
+        };
+        let max_chunk_chars = task_state.orchestrator.config().max_chunk_chars;
+        let messages = slack::limit_responses(deliver::slack_messages(&text, max_chunk_chars), id);
+        for message in messages {
+            if let Err(e) = slack::respond(&command.response_url, &message).await {
+                tracing::warn!(id, error = %e, "posting recap to Slack failed");
+                break;
+            }
+        }
+    });
+
+    Ok(Json(json!({
+        "response_type": "in_channel",
+        "text": format!("Generating a recap of the last {} days (#{})…", days, id),
+    })))
+}
+
+async fn metrics(State(state): State<Arc<ServerState>>) -> Response {
+    crate::metrics::metrics_handler(State(Arc::clone(state.orchestrator.metrics())))
+        .await
+        .into_response()
+}
+
+#[derive(Deserialize)]
+struct FormatQuery {
+    format: Option<String>,
+}
+
+async fn get_recap(
+    State(state): State<Arc<ServerState>>,
+    headers: HeaderMap,
+    UrlPath(id): UrlPath<u64>,
+    Query(query): Query<FormatQuery>,
+) -> std::result::Result<Response, ApiError> {
+    check_token(&state, &headers)?;
+    let status = state
+        .status(id)
+        .ok_or_else(|| ApiError(StatusCode::NOT_FOUND, format!("no recap with id {}", id)))?;
+
+    match query.format.as_deref() {
+        None | Some("json") => Ok(Json(status).into_response()),
+        Some("html") => Ok(Html(render_html(id, &status)).into_response()),
+        Some(other) => Err(ApiError(
+            StatusCode::BAD_REQUEST,
+            format!("unknown format '{}' (expected json or html)", other),
+        )),
+    }
+}
+
+/// Render a recap as a standalone HTML page
+fn render_html(id: u64, status: &RecapStatus) -> String {
+    let mut body = String::new();
+    match status {
+        RecapStatus::Running => body.push_str("<p>Recap is still running; reload to check again.</p>\n"),
+        RecapStatus::Failed { error } => {
+            body.push_str(&format!("<p>Recap failed: {}</p>\n", escape_html(error)));
+        }
+        RecapStatus::Done { result } => {
+            body.push_str(&format!(
+                "<p><strong>Path:</strong> {}<br><strong>Author:</strong> {}<br><strong>Timespan:</strong> {} to {}</p>\n",
+                escape_html(&result.path.display().to_string()),
+                escape_html(result.author.as_deref().unwrap_or("all authors")),
+                result.timespan_start.format("%Y-%m-%d"),
+                result.timespan_end.format("%Y-%m-%d"),
+            ));
+
+            if let Some(ref overall) = result.overall {
+                body.push_str("<h2>Overall Recap</h2>\n");
+                push_list(&mut body, "Themes", &overall.themes);
+                push_list(&mut body, "Biggest Wins", &overall.biggest_wins);
+                push_list(&mut body, "Suggested Demo Order", &overall.demo_order);
+                push_list(&mut body, "Goals Not Addressed", &overall.goals_not_addressed);
+            }
+
+            if result.repositories.is_empty() {
+                body.push_str("<p>No commits found.</p>\n");
+            }
+            for repo in &result.repositories {
+                body.push_str(&format!(
+                    "<h2>{}</h2>\n<p>{} commits, +{} / -{} lines</p>\n",
+                    escape_html(&repo.name),
+                    repo.commits,
+                    repo.insertions,
+                    repo.deletions
+                ));
+                if let Some(ref summary) = repo.summary {
+                    body.push_str(&format!(
+                        "<div class=\"summary\">{}</div>\n",
+                        escape_html(&summary.work_summary)
+                    ));
+                    for section in &summary.sections {
+                        push_list(&mut body, &section.title, &section.items);
+                    }
+                }
+                if let Some(ref error) = repo.error {
+                    body.push_str(&format!("<p>Failed: {}</p>\n", escape_html(error)));
+                }
+            }
+        }
+    }
+
+    format!(
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>Dev Recap #{id}</title>\n\
+         <style>body {{ font-family: sans-serif; max-width: 50em; margin: 2em auto; }} \
+         .summary {{ white-space: pre-wrap; }}</style>\n</head>\n<body>\n<h1>Dev Recap #{id}</h1>\n{body}</body>\n</html>\n"
+    )
+}
+
+fn push_list(html: &mut String, title: &str, items: &[String]) {
+    if items.is_empty() {
+        return;
+    }
+    html.push_str(&format!("<h3>{}</h3>\n<ul>\n", escape_html(title)));
+    for item in items {
+        html.push_str(&format!("<li>{}</li>\n", escape_html(item)));
+    }
+    html.push_str("</ul>\n");
+}
+
+fn escape_html(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+    use std::time::Duration;
+    use tempfile::TempDir;
+
+    fn test_state() -> Arc<ServerState> {
+        let config = Config {
+            claude_api_key: Some("sk-ant-test-key".to_string()),
+            cache_enabled: false,
+            ..Config::default()
+        };
+        Arc::new(ServerState::new(Arc::new(Orchestrator::new(config).unwrap()), 2))
+    }
+
+    fn init_repo(path: &std::path::Path) {
+        let repo = git2::Repository::init(path).unwrap();
+        let signature = git2::Signature::now("Test User", "test@example.com").unwrap();
+        std::fs::write(path.join("test.txt"), "Hello, world!\n").unwrap();
+        let mut index = repo.index().unwrap();
+        index.add_path(std::path::Path::new("test.txt")).unwrap();
+        index.write().unwrap();
+        let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+        repo.commit(Some("HEAD"), &signature, &signature, "Add <greeting>", &tree, &[])
+            .unwrap();
+    }
+
+    #[test]
+    fn test_request_timespan() {
+        let request: RecapRequest = serde_json::from_value(serde_json::json!({
+            "path": "/tmp",
+            "since": "2025-01-01",
+            "until": "2025-01-31",
+            "utc": true,
+        }))
+        .unwrap();
+        let timespan = request.timespan(14).unwrap();
+        assert_eq!(timespan.start.to_rfc3339(), "2025-01-01T00:00:00+00:00");
+        assert_eq!(timespan.end.to_rfc3339(), "2025-01-31T23:59:59+00:00");
+
+        let reversed = RecapRequest {
+            since: request.until.clone(),
+            until: request.since.clone(),
+            ..request
+        };
+        assert!(reversed.timespan(14).is_err());
+    }
+
+    #[test]
+    fn test_stored_recaps_are_capped() {
+        let state = test_state();
+        for id in 0..MAX_STORED_RECAPS as u64 + 5 {
+            state.set_status(id, RecapStatus::Running);
+        }
+        assert!(state.status(0).is_none());
+        assert!(state.status(MAX_STORED_RECAPS as u64 + 4).is_some());
+    }
+
+    #[test]
+    fn test_queue_is_bounded() {
+        let state = test_state();
+        let slots: Vec<_> = (0..MAX_QUEUED_RECAPS).map(|_| state.begin().unwrap()).collect();
+        assert!(state.begin().is_none());
+
+        drop(slots);
+        assert!(state.begin().is_some());
+    }
+
+    #[test]
+    fn test_bearer_token() {
+        let open = test_state();
+        let state = ServerState::new(Arc::clone(open.orchestrator()), 1).with_token("s3cret".to_string());
+        let mut headers = HeaderMap::new();
+        assert!(open.authorized(&headers));
+        assert!(!state.authorized(&headers));
+
+        headers.insert(AUTHORIZATION, "Bearer wrong".parse().unwrap());
+        assert!(!state.authorized(&headers));
+        headers.insert(AUTHORIZATION, "Bearer s3cret".parse().unwrap());
+        assert!(state.authorized(&headers));
+    }
+
+    #[test]
+    fn test_escape_html() {
+        assert_eq!(escape_html("<b>\"Tom\" & 'Jerry'</b>"), "&lt;b&gt;&quot;Tom&quot; &amp; &#39;Jerry&#39;&lt;/b&gt;");
+    }
+
+    #[tokio::test]
+    async fn test_recap_endpoints() {
+        let repo_dir = TempDir::new().unwrap();
+        init_repo(repo_dir.path());
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base = format!("http://{}", listener.local_addr().unwrap());
+        tokio::spawn(async move { axum::serve(listener, router(test_state())).await });
+
+        let client = reqwest::Client::new();
+        let health = client.get(format!("{}/health", base)).send().await.unwrap();
+        assert_eq!(health.text().await.unwrap(), "ok");
+
+        let missing = client
+            .post(format!("{}/recaps", base))
+            .json(&serde_json::json!({ "path": repo_dir.path().join("missing") }))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(missing.status(), reqwest::StatusCode::BAD_REQUEST);
+
+        let created: serde_json::Value = client
+            .post(format!("{}/recaps", base))
+            .json(&serde_json::json!({
+                "path": repo_dir.path(),
+                "author": "test@example.com",
+                "days": 1,
+                "dry_run": true,
+            }))
+            .send()
+            .await
+            .unwrap()
+            .json()
+            .await
+            .unwrap();
+        let url = format!("{}/recaps/{}", base, created["id"]);
+
+        let mut recap = serde_json::Value::Null;
+        for _ in 0..100 {
+            recap = client.get(&url).send().await.unwrap().json().await.unwrap();
+            if recap["status"] != "running" {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(50)).await;
+        }
+        assert_eq!(recap["status"], "done", "{}", recap);
+        let repos = recap["result"]["repositories"].as_array().unwrap();
+        assert_eq!(repos.len(), 1);
+        assert_eq!(repos[0]["commits"], 1);
+
+        let html = client
+            .get(format!("{}?format=html", url))
+            .send()
+            .await
+            .unwrap()
+            .text()
+            .await
+            .unwrap();
+        assert!(html.contains("<h2>"));
+
+        let metrics = client
+            .get(format!("{}/metrics", base))
+            .send()
+            .await
+            .unwrap()
+            .text()
+            .await
+            .unwrap();
+        assert!(metrics.contains("dev_recap_runs_total{result=\"success\"} 1\n"));
+        assert!(metrics.contains("dev_recap_repos_analyzed_total 1\n"));
+
+        let unknown = client.get(format!("{}/recaps/999", base)).send().await.unwrap();
+        assert_eq!(unknown.status(), reqwest::StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn test_slack_command() {
+        use hmac::{Hmac, Mac};
+
+        let secret = "test-signing-secret";
+        let state = ServerState::new(Arc::clone(&test_state().orchestrator), 1)
+            .with_slack(secret.to_string(), PathBuf::from("."));
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/slack/commands", listener.local_addr().unwrap());
+        tokio::spawn(async move { axum::serve(listener, router(Arc::new(state))).await });
+
+        let body = "command=%2Frecap&text=soon&user_name=alice&response_url=http%3A%2F%2F127.0.0.1%3A1%2F";
+        let timestamp = Utc::now().timestamp().to_string();
+        let mut mac = Hmac::<sha2::Sha256>::new_from_slice(secret.as_bytes()).unwrap();
+        mac.update(format!("v0:{}:{}", timestamp, body).as_bytes());
+        let signature = format!("v0={}", hex::encode(mac.finalize().into_bytes()));
+
+        let client = reqwest::Client::new();
+        let unsigned = client.post(&url).body(body).send().await.unwrap();
+        assert_eq!(unsigned.status(), reqwest::StatusCode::UNAUTHORIZED);
+
+        // A malformed command gets the usage, visible only to the caller
+        let reply: serde_json::Value = client
+            .post(&url)
+            .header("X-Slack-Request-Timestamp", &timestamp)
+            .header("X-Slack-Signature", &signature)
+            .body(body)
+            .send()
+            .await
+            .unwrap()
+            .json()
+            .await
+            .unwrap();
+        assert_eq!(reply["response_type"], "ephemeral");
+        assert_eq!(reply["text"], slack::USAGE);
+    }
+}
@@ -0,0 +1,269 @@
+//! Slack slash-command bot (`/recap 7d`), served by `dev-recap serve`
+//!
+//! Slack signs every request with the app's signing secret; unsigned or
+//! stale requests are rejected. The command is acknowledged right away and
+//! the recap is posted to the command's `response_url` once it's done.
+
+use crate::ai::KEY_ACHIEVEMENTS;
+use crate::error::{DevRecapError, Result};
+use crate::server::RecapResult;
+use hmac::{Hmac, Mac};
+use serde::Deserialize;
+use serde_json::json;
+use sha2::Sha256;
+
+/// Requests older than this (or this far in the future) are replayed or forged
+const MAX_REQUEST_AGE_SECS: u64 = 5 * 60;
+
+/// Longest span `/recap` accepts (about a century), well inside what chrono
+/// can subtract from now
+const MAX_DAYS: u32 = 36_600;
+
+/// Usage shown for malformed commands
+pub const USAGE: &str = "Usage: /recap [DAYS]d|[WEEKS]w [author-email], e.g. `/recap 7d` or `/recap 2w alice@example.com`";
+
+/// Form fields of a slash command request (the ones dev-recap uses)
+#[derive(Debug, Clone, Deserialize)]
+pub struct SlashCommand {
+    #[serde(default)]
+    pub text: String,
+    pub response_url: String,
+    #[serde(default)]
+    pub user_name: String,
+}
+
+/// Parsed arguments of `/recap`
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct RecapArgs {
+    pub days: Option<u32>,
+    pub author: Option<String>,
+}
+
+impl RecapArgs {
+    /// Parse the command text: an optional span (`7d`, `2w`) and author email, in any order
+    pub fn parse(text: &str) -> Result<Self> {
+        let mut args = Self::default();
+        for word in text.split_whitespace() {
+            if word.contains('@') && args.author.is_none() {
+                args.author = Some(word.to_string());
+                continue;
+            }
+
+            let (number, multiplier) = if let Some(number) = word.strip_suffix(['d', 'D']) {
+                (number, 1)
+            } else if let Some(number) = word.strip_suffix(['w', 'W']) {
+                (number, 7)
+            } else {
+                return Err(DevRecapError::other(USAGE));
+            };
+            let days = number
+                .parse::<u32>()
+                .ok()
+                .and_then(|n| n.checked_mul(multiplier))
+                .filter(|days| (1..=MAX_DAYS).contains(days));
+            match days {
+                Some(days) if args.days.is_none() => args.days = Some(days),
+                _ => return Err(DevRecapError::other(USAGE)),
+            }
+        }
+        Ok(args)
+    }
+}
+
+/// Check Slack's `X-Slack-Signature` for a request body
+///
+/// The signature is `v0=` + hex HMAC-SHA256 of `v0:{timestamp}:{body}`.
+pub fn verify_signature(
+    signing_secret: &str,
+    timestamp: &str,
+    body: &[u8],
+    signature: &str,
+    now: i64,
+) -> bool {
+    let Ok(sent_at) = timestamp.parse::<i64>() else {
+        return false;
+    };
+    // The timestamp isn't authenticated yet, so no arithmetic on it may overflow
+    if now.abs_diff(sent_at) > MAX_REQUEST_AGE_SECS {
+        return false;
+    }
+    let Some(expected) = signature.strip_prefix("v0=").and_then(|hex| hex::decode(hex).ok()) else {
+        return false;
+    };
+
+    let Ok(mut mac) = Hmac::<Sha256>::new_from_slice(signing_secret.as_bytes()) else {
+        return false;
+    };
+    mac.update(format!("v0:{}:", timestamp).as_bytes());
+    mac.update(body);
+    // Constant-time comparison
+    mac.verify_slice(&expected).is_ok()
+}
+
+/// Slack message text (mrkdwn) for a finished recap
+pub fn format_recap(result: &RecapResult) -> String {
+    let mut text = format!(
+        "*Dev Recap* for {} ({} to {})\n",
+        result.author.as_deref().unwrap_or("all authors"),
+        result.timespan_start.format("%Y-%m-%d"),
+        result.timespan_end.format("%Y-%m-%d")
+    );
+
+    if let Some(ref overall) = result.overall {
+        for theme in &overall.themes {
+            text.push_str(&format!("• {}\n", theme));
+        }
+    }
+
+    if result.repositories.is_empty() {
+        text.push_str("\nNo commits found.\n");
+    }
+    for repo in &result.repositories {
+        text.push_str(&format!(
+            "\n*{}* — {} commits, +{} / -{}\n",
+            repo.name, repo.commits, repo.insertions, repo.deletions
+        ));
+        if let Some(ref summary) = repo.summary {
+            text.push_str(&summary.work_summary);
+            text.push('\n');
+            for section in summary.content_sections() {
+                if !section.title.eq_ignore_ascii_case(KEY_ACHIEVEMENTS) {
+                    text.push_str(&format!("_{}_\n", section.title));
+                }
+                for item in &section.items {
+                    text.push_str(&format!("• {}\n", item));
+                }
+            }
+        }
+        if let Some(ref error) = repo.error {
+            text.push_str(&format!("_Failed: {}_\n", error));
+        }
+    }
+    text
+}
+
+/// Messages Slack accepts on one command's `response_url`
+pub const MAX_RESPONSES: usize = 5;
+
+/// `messages` cut down to the [`MAX_RESPONSES`] a command can answer with;
+/// the last one then points to recap `id` on the server for the rest
+pub fn limit_responses(mut messages: Vec<String>, id: u64) -> Vec<String> {
+    if messages.len() > MAX_RESPONSES {
+        let omitted = messages.len() - (MAX_RESPONSES - 1);
+        messages.truncate(MAX_RESPONSES - 1);
+        messages.push(format!(
+            "{} more parts don't fit in Slack's {} replies to a command; the full recap is at GET /recaps/{} on the server.",
+            omitted, MAX_RESPONSES, id
+        ));
+    }
+    messages
+}
+
+/// Post a message visible to the whole channel to a command's `response_url`
+pub async fn respond(response_url: &str, text: &str) -> Result<()> {
+    let response = reqwest::Client::new()
+        .post(response_url)
+        .json(&json!({ "response_type": "in_channel", "text": text }))
+        .send()
+        .await?;
+    if !response.status().is_success() {
+        return Err(DevRecapError::delivery(format!(
+            "Slack response_url returned {}",
+            response.status()
+        )));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ai::{Section, Summary, PRESENTATION_TIPS};
+    use crate::history::RepoRecap;
+    use chrono::Utc;
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_limit_responses() {
+        let messages = |count: usize| (1..=count).map(|i| format!("part {}", i)).collect::<Vec<_>>();
+        assert_eq!(limit_responses(messages(5), 7), messages(5));
+
+        let limited = limit_responses(messages(8), 7);
+        assert_eq!(limited.len(), MAX_RESPONSES);
+        assert_eq!(limited[3], "part 4");
+        assert!(limited[4].starts_with("4 more parts") && limited[4].contains("/recaps/7"));
+    }
+
+    #[test]
+    fn test_parse_args() {
+        assert_eq!(RecapArgs::parse("").unwrap(), RecapArgs::default());
+        assert_eq!(RecapArgs::parse("7d").unwrap().days, Some(7));
+        let args = RecapArgs::parse("alice@example.com 2w").unwrap();
+        assert_eq!(args.days, Some(14));
+        assert_eq!(args.author.as_deref(), Some("alice@example.com"));
+
+        assert!(RecapArgs::parse("7").is_err());
+        assert!(RecapArgs::parse("0d").is_err());
+        assert!(RecapArgs::parse("7d 2w").is_err());
+        assert!(RecapArgs::parse("lots").is_err());
+
+        // Overflowing or out-of-range spans are rejected rather than wrapped
+        assert!(RecapArgs::parse("700000000w").is_err());
+        assert!(RecapArgs::parse("4000000000d").is_err());
+        assert_eq!(RecapArgs::parse("36600d").unwrap().days, Some(MAX_DAYS));
+        assert!(RecapArgs::parse("36601d").is_err());
+    }
+
+    #[test]
+    fn test_format_recap_sections() {
+        let summary = Summary::with_sections(
+            "api".to_string(),
+            "Moved to v2.".to_string(),
+            vec![
+                Section::bullets(KEY_ACHIEVEMENTS, vec!["Shipped v2".to_string()]),
+                Section::numbered("Metrics", vec!["p99 down 40%".to_string()]),
+                Section::numbered(PRESENTATION_TIPS, vec!["Demo the dashboard".to_string()]),
+            ],
+        );
+        let result = RecapResult {
+            path: PathBuf::from("/code"),
+            author: None,
+            timespan_start: Utc::now(),
+            timespan_end: Utc::now(),
+            overall: None,
+            repositories: vec![RepoRecap {
+                name: "api".to_string(),
+                path: PathBuf::from("/code/api"),
+                commits: 3,
+                insertions: 30,
+                deletions: 3,
+                summary: Some(summary),
+                error: None,
+                commit_hashes: vec![],
+            }],
+        };
+
+        let text = format_recap(&result);
+        assert!(text.contains("Moved to v2.\n• Shipped v2\n_Metrics_\n• p99 down 40%\n"));
+        assert!(!text.contains("Demo the dashboard"));
+    }
+
+    #[test]
+    fn test_verify_signature() {
+        // Example from Slack's "Verifying requests" documentation
+        let secret = "8f742231b10e8888abcd99yyyzzz85a5";
+        let timestamp = "1531420618";
+        let body = b"token=xyzz0WbapA4vBCDEFasx0q6G&team_id=T1DC2JH3J&team_domain=testteamnow&channel_id=G8PSS9T3V&channel_name=foobar&user_id=U2CERLKJA&user_name=roadrunner&command=%2Fwebhook-collect&text=&response_url=https%3A%2F%2Fhooks.slack.com%2Fcommands%2FT1DC2JH3J%2F397700885554%2F96rGlfmibIGlgcZRskXaIFfN&trigger_id=398738663015.47445629121.803a0bc887a14d10d2c447fce8b6703c";
+        let signature = "v0=a2114d57b48eac39b9ad189dd8316235a7b4a8d21a10bd27519666489c69b503";
+        let now = 1531420618 + 60;
+
+        assert!(verify_signature(secret, timestamp, body, signature, now));
+        assert!(!verify_signature("wrong", timestamp, body, signature, now));
+        assert!(!verify_signature(secret, timestamp, b"tampered", signature, now));
+        assert!(!verify_signature(secret, timestamp, body, signature, now + 3600));
+        assert!(!verify_signature(secret, timestamp, body, "v0=zz", now));
+        for extreme in [i64::MIN, i64::MAX] {
+            assert!(!verify_signature(secret, &extreme.to_string(), body, signature, now));
+        }
+    }
+}
@@ -0,0 +1,153 @@
+//! Slide deck export (`--slides`)
+//!
+//! Writes the recap as a [Marp](https://marp.app) markdown deck: a title
+//! slide, an agenda with the estimated speaking time per repository, and one
+//! slide per summarized repository. The summary and presentation tips become
+//! speaker notes (HTML comments, which Marp shows in presenter view), so the
+//! slides themselves stay short.
+
+use crate::ai::Summary;
+
+/// Speaking pace the time estimates assume
+pub const WORDS_PER_MINUTE: usize = 130;
+
+/// Shortest time estimated for one repository, in seconds
+const MIN_SECONDS: u32 = 60;
+
+/// Estimated time to present one repository, in seconds
+///
+/// Counts the words of the summary and key achievements (what is said out
+/// loud), rounds up to the half minute, and allows at least a minute.
+pub fn speaking_seconds(summary: &Summary) -> u32 {
+    let words = summary.work_summary.split_whitespace().count()
+        + summary
+            .key_achievements()
+            .iter()
+            .map(|achievement| achievement.split_whitespace().count())
+            .sum::<usize>();
+    let seconds = (words * 60).div_ceil(WORDS_PER_MINUTE) as u32;
+    (seconds.div_ceil(30) * 30).max(MIN_SECONDS)
+}
+
+/// Seconds as `m:ss`
+pub fn format_duration(seconds: u32) -> String {
+    format!("{}:{:02}", seconds / 60, seconds % 60)
+}
+
+/// Estimated time for the whole deck, in seconds
+pub fn total_seconds(summaries: &[&Summary]) -> u32 {
+    summaries
+        .iter()
+        .map(|summary| speaking_seconds(summary))
+        .sum()
+}
+
+/// The Marp deck for `summaries`, introduced by `subtitle` (authors and timespan)
+///
+/// With `slot_minutes`, the title slide compares the estimate with the slot.
+pub fn render_deck(subtitle: &str, summaries: &[&Summary], slot_minutes: Option<u32>) -> String {
+    let total = total_seconds(summaries);
+    let mut deck = String::from("---\nmarp: true\npaginate: true\n---\n\n");
+
+    deck.push_str("# Dev Recap\n\n");
+    deck.push_str(subtitle.trim());
+    deck.push_str("\n\n");
+    match slot_minutes.map(|slot| (slot, slot.checked_mul(60))) {
+        Some((slot, Some(slot_seconds))) if total > slot_seconds => deck.push_str(&format!(
+            "<!--\nEstimated {} for a {}-minute slot: {} over, trim before presenting.\n-->\n\n",
+            format_duration(total),
+            slot,
+            format_duration(total - slot_seconds)
+        )),
+        Some((slot, _)) => deck.push_str(&format!(
+            "<!--\nEstimated {} for a {}-minute slot.\n-->\n\n",
+            format_duration(total),
+            slot
+        )),
+        None => deck.push_str(&format!("<!--\nEstimated {}.\n-->\n\n", format_duration(total))),
+    }
+
+    if summaries.len() > 1 {
+        deck.push_str("---\n\n## Agenda\n\n| Repository | Time |\n|---|---:|\n");
+        for summary in summaries {
+            deck.push_str(&format!(
+                "| {} | {} |\n",
+                summary.repository,
+                format_duration(speaking_seconds(summary))
+            ));
+        }
+        deck.push_str(&format!("| **Total** | **{}** |\n\n", format_duration(total)));
+    }
+
+    for summary in summaries {
+        deck.push_str(&format!("---\n\n## {}\n\n", summary.repository));
+        if summary.key_achievements().is_empty() {
+            deck.push_str(summary.work_summary.trim());
+            deck.push('\n');
+        }
+        for achievement in summary.key_achievements() {
+            deck.push_str(&format!("- {}\n", achievement));
+        }
+
+        // Speaker notes; "-->" would end the comment early
+        deck.push_str(&format!(
+            "\n<!--\n~{}\n\n{}\n",
+            format_duration(speaking_seconds(summary)),
+            summary.work_summary.trim().replace("-->", "->")
+        ));
+        if !summary.presentation_tips().is_empty() {
+            deck.push_str("\nTips:\n");
+            for tip in summary.presentation_tips() {
+                deck.push_str(&format!("- {}\n", tip.replace("-->", "->")));
+            }
+        }
+        deck.push_str("-->\n\n");
+    }
+
+    deck
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn summary(name: &str, words: usize) -> Summary {
+        Summary::new(
+            name.to_string(),
+            vec!["word"; words].join(" "),
+            vec!["Shipped the importer".to_string()],
+            vec!["Demo the --> arrow".to_string()],
+        )
+    }
+
+    #[test]
+    fn test_speaking_seconds() {
+        // 3 words: at least a minute
+        assert_eq!(speaking_seconds(&summary("api", 0)), 60);
+        // 260 + 3 words at 130 wpm is just over two minutes
+        assert_eq!(speaking_seconds(&summary("api", 260)), 150);
+        assert_eq!(format_duration(150), "2:30");
+    }
+
+    #[test]
+    fn test_render_deck() {
+        let api = summary("api", 260);
+        let web = summary("web", 10);
+        let deck = render_deck("**Author:** alice", &[&api, &web], Some(3));
+
+        assert!(deck.starts_with("---\nmarp: true\n"));
+        assert!(deck.contains("Estimated 3:30 for a 3-minute slot: 0:30 over"));
+        assert!(deck.contains("| api | 2:30 |\n| web | 1:00 |\n| **Total** | **3:30** |"));
+        assert!(deck.contains("## web\n\n- Shipped the importer\n\n<!--\n~1:00\n\n"));
+        assert!(deck.contains("Tips:\n- Demo the -> arrow\n-->"));
+
+        // One repository: no agenda
+        let deck = render_deck("", &[&web], None);
+        assert!(!deck.contains("## Agenda"));
+        assert!(deck.contains("<!--\nEstimated 1:00.\n-->"));
+
+        // A slot too long to count in seconds is never exceeded
+        let deck = render_deck("", &[&web], Some(u32::MAX));
+        assert!(deck.contains(&format!("Estimated 1:00 for a {}-minute slot.", u32::MAX)));
+    }
+}
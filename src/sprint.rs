@@ -0,0 +1,189 @@
+//! Sprint timespans (`[sprint]` in config, `--sprint`)
+//!
+//! Sprints follow one another without gaps from the configured start date:
+//! sprint 1 starts on `start`, sprint 2 `length_days` later, and so on.
+//! Boundaries are whole days in the local timezone.
+
+use crate::error::{DevRecapError, Result};
+use crate::git::Timespan;
+use chrono::{DateTime, Duration, Local, NaiveDate, NaiveTime, TimeZone, Utc};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Sprint cadence of the team
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SprintConfig {
+    /// First day of sprint 1, as a quoted `"YYYY-MM-DD"` string
+    pub start: NaiveDate,
+    /// Days per sprint, e.g. 14
+    pub length_days: u32,
+}
+
+/// Which sprint `--sprint` asks for
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SprintSelector {
+    /// The sprint running today
+    Current,
+    /// The sprint before the current one
+    Previous,
+    /// Sprint N, counting from 1
+    Number(u32),
+}
+
+impl fmt::Display for SprintSelector {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Current => write!(f, "current"),
+            Self::Previous => write!(f, "previous"),
+            Self::Number(number) => write!(f, "{}", number),
+        }
+    }
+}
+
+impl std::str::FromStr for SprintSelector {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s {
+            "current" => Ok(Self::Current),
+            "previous" => Ok(Self::Previous),
+            _ => match s.parse() {
+                Ok(number) if number > 0 => Ok(Self::Number(number)),
+                _ => Err(format!("unknown sprint '{}' (expected current, previous or a number from 1)", s)),
+            },
+        }
+    }
+}
+
+/// One sprint of the cadence
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Sprint {
+    /// Number of the sprint, counting from 1
+    pub number: u32,
+    /// First day
+    pub first_day: NaiveDate,
+    /// Last day (inclusive)
+    pub last_day: NaiveDate,
+}
+
+impl SprintConfig {
+    /// The sprint `selector` points to on `today`
+    pub fn sprint(&self, selector: SprintSelector, today: NaiveDate) -> Result<Sprint> {
+        if self.length_days == 0 {
+            return Err(DevRecapError::config("sprint.length_days must be > 0"));
+        }
+        let number = match selector {
+            SprintSelector::Number(number) => number,
+            SprintSelector::Current | SprintSelector::Previous => {
+                if today < self.start {
+                    return Err(DevRecapError::InvalidTimespan(format!(
+                        "the first sprint starts on {}",
+                        self.start
+                    )));
+                }
+                let current = ((today - self.start).num_days() / self.length_days as i64) as u32 + 1;
+                match selector {
+                    SprintSelector::Previous if current == 1 => {
+                        return Err(DevRecapError::InvalidTimespan(
+                            "there is no sprint before sprint 1".to_string(),
+                        ))
+                    }
+                    SprintSelector::Previous => current - 1,
+                    _ => current,
+                }
+            }
+        };
+
+        let length = self.length_days as i64;
+        let out_of_range = || DevRecapError::InvalidTimespan(format!("sprint {} is out of range", number));
+        let first_day = Duration::try_days((number as i64 - 1) * length)
+            .and_then(|offset| self.start.checked_add_signed(offset))
+            .ok_or_else(out_of_range)?;
+        let last_day = Duration::try_days(length - 1)
+            .and_then(|offset| first_day.checked_add_signed(offset))
+            .ok_or_else(out_of_range)?;
+        if first_day > today {
+            return Err(DevRecapError::InvalidTimespan(format!(
+                "sprint {} hasn't started yet (it starts on {})",
+                number, first_day
+            )));
+        }
+        Ok(Sprint {
+            number,
+            first_day,
+            last_day,
+        })
+    }
+}
+
+impl Sprint {
+    /// Timespan from the start of the first day to the end of the last one,
+    /// local time; a sprint still running ends at `now`
+    pub fn timespan(&self, now: DateTime<Utc>) -> Result<Timespan> {
+        let start = local_midnight(self.first_day)?;
+        let end = match self.last_day.succ_opt() {
+            Some(next_day) => local_midnight(next_day)? - Duration::seconds(1),
+            None => now,
+        };
+        Ok(Timespan::from_dates(start, end.min(now)))
+    }
+
+    /// e.g. "sprint 12 (2025-03-03 to 2025-03-16)"
+    pub fn describe(&self) -> String {
+        format!("sprint {} ({} to {})", self.number, self.first_day, self.last_day)
+    }
+}
+
+fn local_midnight(day: NaiveDate) -> Result<DateTime<Utc>> {
+    Local
+        .from_local_datetime(&day.and_time(NaiveTime::MIN))
+        .earliest()
+        .map(|local| local.with_timezone(&Utc))
+        .ok_or_else(|| DevRecapError::InvalidTimespan(format!("{} has no midnight in the local timezone", day)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(text: &str) -> NaiveDate {
+        text.parse().unwrap()
+    }
+
+    #[test]
+    fn test_sprint() {
+        let config = SprintConfig {
+            start: date("2025-01-06"),
+            length_days: 14,
+        };
+        let today = date("2025-03-10");
+
+        let current = config.sprint(SprintSelector::Current, today).unwrap();
+        assert_eq!(current.describe(), "sprint 5 (2025-03-03 to 2025-03-16)");
+        let previous = config.sprint(SprintSelector::Previous, today).unwrap();
+        assert_eq!((previous.first_day, previous.last_day), (date("2025-02-17"), date("2025-03-02")));
+        assert_eq!(config.sprint(SprintSelector::Number(5), today).unwrap(), current);
+
+        // The last day still belongs to the sprint
+        assert_eq!(config.sprint(SprintSelector::Current, date("2025-03-16")).unwrap().number, 5);
+        assert_eq!(config.sprint(SprintSelector::Current, date("2025-03-17")).unwrap().number, 6);
+
+        assert!(config.sprint(SprintSelector::Current, date("2025-01-01")).is_err());
+        // Sprints that haven't started, or lie beyond the calendar, are errors
+        assert!(config.sprint(SprintSelector::Number(6), today).is_err());
+        assert!(config.sprint(SprintSelector::Number(100_000_000), today).is_err());
+        assert!(config.sprint(SprintSelector::Previous, date("2025-01-10")).is_err());
+
+        let timespan = previous.timespan(Utc::now()).unwrap();
+        assert_eq!(timespan.start.with_timezone(&Local).date_naive(), date("2025-02-17"));
+        assert_eq!(timespan.end.with_timezone(&Local).date_naive(), date("2025-03-02"));
+        let now = Utc::now();
+        let running = config.sprint(SprintSelector::Current, Local::now().date_naive()).unwrap();
+        assert_eq!(running.timespan(now).unwrap().end, now);
+
+        assert_eq!("previous".parse(), Ok(SprintSelector::Previous));
+        assert_eq!("12".parse(), Ok(SprintSelector::Number(12)));
+        assert!("0".parse::<SprintSelector>().is_err());
+        assert!("next".parse::<SprintSelector>().is_err());
+    }
+}
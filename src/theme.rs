@@ -0,0 +1,178 @@
+//! Terminal color themes (`theme` in config, `--theme`)
+//!
+//! Colors apply to the report printed on a terminal and to the progress
+//! bars; files, the preview and piped output are never colored. Setting
+//! `NO_COLOR` (to anything but an empty string) turns colors off whatever
+//! the theme, see <https://no-color.org>.
+
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// A named set of terminal colors
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Theme {
+    /// Bright colors for dark terminal backgrounds
+    #[default]
+    Default,
+    /// Darker colors that stay readable on light backgrounds
+    Light,
+    /// No colors at all
+    None,
+}
+
+impl Theme {
+    /// Every theme
+    pub const ALL: [Theme; 3] = [Theme::Default, Theme::Light, Theme::None];
+
+    /// Colors for output to a terminal (`is_terminal`), or none when the
+    /// output is redirected or `NO_COLOR` is set
+    pub fn palette(self, is_terminal: bool) -> Palette {
+        let no_color = std::env::var_os("NO_COLOR").is_some_and(|value| !value.is_empty());
+        if !is_terminal || no_color {
+            return Palette::PLAIN;
+        }
+        match self {
+            Self::Default => Palette {
+                heading: Some("1;36"),
+                stat: Some("32"),
+                error: Some("1;31"),
+                bar: Some(("cyan", "blue")),
+            },
+            Self::Light => Palette {
+                heading: Some("1;34"),
+                stat: Some("35"),
+                error: Some("31"),
+                bar: Some(("blue", "black")),
+            },
+            Self::None => Palette::PLAIN,
+        }
+    }
+}
+
+impl fmt::Display for Theme {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Default => write!(f, "default"),
+            Self::Light => write!(f, "light"),
+            Self::None => write!(f, "none"),
+        }
+    }
+}
+
+impl std::str::FromStr for Theme {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|theme| theme.to_string() == s)
+            .ok_or_else(|| format!("unknown theme '{}' (expected default, light or none)", s))
+    }
+}
+
+/// The colors of a theme, as ANSI SGR codes (`None`: uncolored)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Palette {
+    heading: Option<&'static str>,
+    stat: Option<&'static str>,
+    error: Option<&'static str>,
+    /// Filled and empty part of progress bars, as indicatif color names
+    bar: Option<(&'static str, &'static str)>,
+}
+
+impl Palette {
+    /// No colors
+    pub const PLAIN: Palette = Palette {
+        heading: None,
+        stat: None,
+        error: None,
+        bar: None,
+    };
+
+    /// A heading or label, e.g. "Repository: api" or "Hotspots:"
+    pub fn heading(&self, text: &str) -> String {
+        paint(self.heading, text)
+    }
+
+    /// A number or other statistic
+    pub fn stat(&self, text: &str) -> String {
+        paint(self.stat, text)
+    }
+
+    /// An error or warning
+    pub fn error(&self, text: &str) -> String {
+        paint(self.error, text)
+    }
+
+    /// Markdown with its `#` heading lines colored
+    pub fn markdown(&self, text: &str) -> String {
+        if self.heading.is_none() {
+            return text.to_string();
+        }
+        let mut colored = text
+            .lines()
+            .map(|line| {
+                if line.starts_with('#') {
+                    self.heading(line)
+                } else {
+                    line.to_string()
+                }
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+        if text.ends_with('\n') {
+            colored.push('\n');
+        }
+        colored
+    }
+
+    /// indicatif template of a progress bar, with its spinner
+    pub fn bar_template(&self) -> String {
+        match self.bar {
+            Some((filled, empty)) => format!("{{spinner:.{}}} [{{bar:40.{}/{}}}] {{pos}}/{{len}} {{msg}}", filled, filled, empty),
+            None => "{spinner} [{bar:40}] {pos}/{len} {msg}".to_string(),
+        }
+    }
+
+    /// indicatif template of a spinner
+    pub fn spinner_template(&self) -> String {
+        match self.bar {
+            Some((filled, _)) => format!("{{spinner:.{}}} {{msg}}", filled),
+            None => "{spinner} {msg}".to_string(),
+        }
+    }
+}
+
+fn paint(code: Option<&str>, text: &str) -> String {
+    match code {
+        Some(code) => format!("\x1b[{}m{}\x1b[0m", code, text),
+        None => text.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_palette() {
+        assert_eq!("light".parse(), Ok(Theme::Light));
+        assert!("solarized".parse::<Theme>().is_err());
+
+        let plain = Theme::Default.palette(false);
+        assert_eq!(plain, Palette::PLAIN);
+        assert_eq!(plain.heading("Hotspots:"), "Hotspots:");
+        assert_eq!(plain.markdown("# api\n\ntext"), "# api\n\ntext");
+        assert_eq!(plain.bar_template(), "{spinner} [{bar:40}] {pos}/{len} {msg}");
+        assert_eq!(Theme::None.palette(true), Palette::PLAIN);
+
+        // NO_COLOR is checked at runtime, so only compare when it's unset
+        if std::env::var_os("NO_COLOR").is_none() {
+            let palette = Theme::Default.palette(true);
+            assert_eq!(palette.heading("Hotspots:"), "\x1b[1;36mHotspots:\x1b[0m");
+            assert_eq!(palette.markdown("# api\ntext"), "\x1b[1;36m# api\x1b[0m\ntext");
+            assert_eq!(palette.bar_template(), "{spinner:.cyan} [{bar:40.cyan/blue}] {pos}/{len} {msg}");
+        }
+    }
+}
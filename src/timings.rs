@@ -0,0 +1,176 @@
+use std::collections::BTreeMap;
+use std::fmt;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// A phase of a run whose time is measured
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Phase {
+    /// Discovering repositories
+    Scan,
+    /// Parsing one repository's history (includes diff stats)
+    Parse,
+    /// Computing per-commit diff stats
+    DiffStats,
+    /// Waiting on the Claude API
+    Api,
+    /// Reading and writing the summary cache
+    Cache,
+}
+
+impl Phase {
+    /// Label used in the report
+    fn label(&self) -> &'static str {
+        match self {
+            Phase::Scan => "scan",
+            Phase::Parse => "parse",
+            Phase::DiffStats => "diff stats",
+            Phase::Api => "api",
+            Phase::Cache => "cache",
+        }
+    }
+
+    /// What one recorded event of this phase is
+    fn unit(&self) -> &'static str {
+        match self {
+            Phase::Scan => "scan",
+            Phase::Parse => "repo",
+            Phase::DiffStats => "commit",
+            Phase::Api => "request",
+            Phase::Cache => "lookup",
+        }
+    }
+}
+
+/// Accumulated time spent in one phase
+#[derive(Debug, Clone, Copy, Default)]
+pub struct PhaseTiming {
+    /// Total time across all events
+    pub total: Duration,
+    /// Number of events recorded
+    pub count: u32,
+    /// Slowest single event
+    pub max: Duration,
+}
+
+/// Thread-safe collector of per-phase durations for `--timings`
+///
+/// Phases running on concurrent jobs are summed, so their totals can exceed
+/// the wall-clock time of the run.
+#[derive(Debug, Default)]
+pub struct Timings {
+    phases: Mutex<BTreeMap<Phase, PhaseTiming>>,
+    repos: Mutex<Vec<(String, Duration)>>,
+}
+
+impl Timings {
+    /// Create an empty collector
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record one event of `phase` that took `elapsed`
+    pub fn record(&self, phase: Phase, elapsed: Duration) {
+        let mut phases = self.phases.lock().unwrap();
+        let timing = phases.entry(phase).or_default();
+        timing.total += elapsed;
+        timing.count += 1;
+        timing.max = timing.max.max(elapsed);
+    }
+
+    /// Record how long parsing one repository took
+    pub fn record_repo(&self, repo: &str, elapsed: Duration) {
+        self.record(Phase::Parse, elapsed);
+        self.repos.lock().unwrap().push((repo.to_string(), elapsed));
+    }
+
+    /// Run `f`, recording its duration under `phase`
+    pub fn time<T>(&self, phase: Phase, f: impl FnOnce() -> T) -> T {
+        let started = Instant::now();
+        let result = f();
+        self.record(phase, started.elapsed());
+        result
+    }
+
+    /// Accumulated timing for a phase
+    pub fn get(&self, phase: Phase) -> Option<PhaseTiming> {
+        self.phases.lock().unwrap().get(&phase).copied()
+    }
+
+    /// Repositories ordered slowest parse first
+    pub fn slowest_repos(&self, limit: usize) -> Vec<(String, Duration)> {
+        let mut repos = self.repos.lock().unwrap().clone();
+        repos.sort_by_key(|(_, elapsed)| std::cmp::Reverse(*elapsed));
+        repos.truncate(limit);
+        repos
+    }
+}
+
+impl fmt::Display for Timings {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Timings (summed across concurrent jobs):")?;
+
+        for (phase, timing) in self.phases.lock().unwrap().iter() {
+            write!(
+                f,
+                "  {:<11} {:>8.2}s  {} {}(s)",
+                phase.label(),
+                timing.total.as_secs_f64(),
+                timing.count,
+                phase.unit()
+            )?;
+            if timing.count > 1 {
+                write!(
+                    f,
+                    ", avg {:.2}s, max {:.2}s",
+                    timing.total.as_secs_f64() / timing.count as f64,
+                    timing.max.as_secs_f64()
+                )?;
+            }
+            writeln!(f)?;
+        }
+
+        let slowest = self.slowest_repos(5);
+        if !slowest.is_empty() {
+            writeln!(f, "Slowest repositories to parse:")?;
+            for (repo, elapsed) in slowest {
+                writeln!(f, "  {:<30} {:>8.2}s", repo, elapsed.as_secs_f64())?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_record_accumulates() {
+        let timings = Timings::new();
+        timings.record(Phase::Api, Duration::from_millis(100));
+        timings.record(Phase::Api, Duration::from_millis(300));
+
+        let api = timings.get(Phase::Api).unwrap();
+        assert_eq!(api.count, 2);
+        assert_eq!(api.total, Duration::from_millis(400));
+        assert_eq!(api.max, Duration::from_millis(300));
+        assert!(timings.get(Phase::Scan).is_none());
+    }
+
+    #[test]
+    fn test_slowest_repos_and_report() {
+        let timings = Timings::new();
+        timings.record_repo("fast", Duration::from_millis(10));
+        timings.record_repo("slow", Duration::from_millis(900));
+
+        let slowest = timings.slowest_repos(1);
+        assert_eq!(slowest, vec![("slow".to_string(), Duration::from_millis(900))]);
+
+        let report = timings.to_string();
+        assert!(report.contains("parse"));
+        assert!(report.contains("2 repo(s)"));
+        assert!(report.contains("slow"));
+    }
+}
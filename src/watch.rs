@@ -0,0 +1,152 @@
+//! Change detection for `dev-recap watch`
+//!
+//! Repositories are polled rather than watched through filesystem events:
+//! reading each HEAD is cheap, behaves the same on every platform and on
+//! network mounts, and catches commits, pulls, checkouts and rebases alike.
+//! Between updates, repositories whose HEAD didn't move keep their recap.
+
+use crate::ai::Summary;
+use crate::error::Result;
+use crate::git::Repository;
+use git2::Repository as Git2Repository;
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+
+/// The commit HEAD points to in each repository
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Heads(BTreeMap<PathBuf, Option<String>>);
+
+impl Heads {
+    /// HEAD of every repository in `repos` (none for a repository without
+    /// commits or one that can't be read)
+    pub fn read(repos: &[PathBuf]) -> Self {
+        Self(repos.iter().map(|path| (path.clone(), head(path))).collect())
+    }
+
+    /// Repositories whose HEAD moved since `previous`, including repositories
+    /// that appeared or disappeared
+    pub fn changed(&self, previous: &Heads) -> Vec<PathBuf> {
+        let mut changed: Vec<PathBuf> = self
+            .0
+            .iter()
+            .filter(|(path, head)| previous.0.get(*path) != Some(*head))
+            .map(|(path, _)| path.clone())
+            .collect();
+        changed.extend(previous.0.keys().filter(|path| !self.0.contains_key(*path)).cloned());
+        changed
+    }
+
+    /// Number of repositories
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Whether there are no repositories
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+/// Repositories summarized by earlier updates, by path, reused until their
+/// HEAD moves
+#[derive(Debug, Default)]
+pub struct Recapped(HashMap<PathBuf, (Repository, Summary)>);
+
+impl Recapped {
+    /// Forget the recaps of repositories under `changed` (sub-projects
+    /// included), so they're analyzed and summarized again
+    pub fn invalidate(&mut self, changed: &[PathBuf]) {
+        self.0.retain(|path, _| !changed.iter().any(|moved| path.starts_with(moved)));
+    }
+
+    /// The kept recap of the repository at `path`, if any
+    pub fn take(&mut self, path: &Path) -> Option<(Repository, Summary)> {
+        self.0.remove(path)
+    }
+
+    /// Keep the recaps that succeeded; failed ones, including those shown
+    /// with an older cached summary, are tried again next time
+    pub fn keep(&mut self, results: &[(Repository, Result<Summary>)]) {
+        for (repo, result) in results {
+            if let Some(summary) = result.as_ref().ok().filter(|summary| summary.fallback_error.is_none()) {
+                self.0.insert(repo.path.clone(), (repo.clone(), summary.clone()));
+            }
+        }
+    }
+
+    /// Number of kept recaps
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Whether no recap is kept
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+/// HEAD commit of the repository containing `path` (sub-projects of a
+/// monorepo share their repository's HEAD)
+fn head(path: &Path) -> Option<String> {
+    let repo = Git2Repository::discover(path).ok()?;
+    let commit = repo.head().ok()?.peel_to_commit().ok()?;
+    Some(commit.id().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn commit(repo: &Git2Repository, message: &str) {
+        let signature = git2::Signature::now("Test User", "test@example.com").unwrap();
+        let tree_id = repo.index().unwrap().write_tree().unwrap();
+        let tree = repo.find_tree(tree_id).unwrap();
+        let parent = repo.head().ok().and_then(|head| head.peel_to_commit().ok());
+        let parents: Vec<&git2::Commit> = parent.iter().collect();
+        repo.commit(Some("HEAD"), &signature, &signature, message, &tree, &parents)
+            .unwrap();
+    }
+
+    #[test]
+    fn test_heads_changed() {
+        let temp_dir = TempDir::new().unwrap();
+        let (api_path, web_path) = (temp_dir.path().join("api"), temp_dir.path().join("web"));
+        let api = Git2Repository::init(&api_path).unwrap();
+        Git2Repository::init(&web_path).unwrap();
+        commit(&api, "Initial commit");
+
+        let before = Heads::read(&[api_path.clone(), web_path.clone()]);
+        assert_eq!(before.len(), 2);
+        assert!(Heads::read(&[api_path.clone(), web_path.clone()]).changed(&before).is_empty());
+
+        commit(&api, "Add endpoint");
+        let after = Heads::read(&[api_path.clone(), web_path.clone()]);
+        assert_eq!(after.changed(&before), vec![api_path.clone()]);
+
+        // A repository that disappeared from the scan counts as a change
+        assert_eq!(Heads::read(&[api_path]).changed(&after), vec![web_path]);
+    }
+
+    #[test]
+    fn test_recapped_invalidate() {
+        let repo = |path: &str| Repository {
+            path: PathBuf::from(path),
+            ..Repository::test(path, vec![])
+        };
+        let summary = || Summary::new("repo".to_string(), "Done".to_string(), vec![], vec![]);
+        let mut recapped = Recapped::default();
+        recapped.keep(&[
+            (repo("/code/api"), Ok(summary())),
+            (repo("/code/mono/services/auth"), Ok(summary())),
+            (repo("/code/web"), Err(crate::error::DevRecapError::NoCommitsYet)),
+        ]);
+        assert_eq!(recapped.len(), 2);
+
+        // A moved monorepo HEAD invalidates its sub-projects
+        recapped.invalidate(&[PathBuf::from("/code/mono")]);
+        assert!(recapped.take(Path::new("/code/mono/services/auth")).is_none());
+        assert!(recapped.take(Path::new("/code/api")).is_some());
+        assert!(recapped.is_empty());
+    }
+}
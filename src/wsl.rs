@@ -0,0 +1,147 @@
+//! Windows Subsystem for Linux
+//!
+//! Under WSL the Windows drives are mounted at `/mnt/c`, `/mnt/d`, ... over
+//! 9p, where every directory listing and file read is a round trip to
+//! Windows. Scanning a tree of repositories there takes minutes instead of
+//! seconds, so such scans are refused unless `--allow-slow-fs` is given; the
+//! Windows build of dev-recap reads the same directories natively. Paths of
+//! written files are also shown the way Windows tools open them.
+
+use crate::error::{DevRecapError, Result};
+use std::path::{Component, Path, PathBuf};
+
+/// Where WSL mounts the Windows drives (its default `automount.root`)
+const MOUNT_ROOT: &str = "/mnt";
+
+/// Whether this process runs under WSL
+pub fn is_wsl() -> bool {
+    if std::env::var_os("WSL_DISTRO_NAME").is_some() {
+        return true;
+    }
+    std::fs::read_to_string("/proc/sys/kernel/osrelease")
+        .is_ok_and(|release| release.to_lowercase().contains("microsoft"))
+}
+
+/// Whether scanning `path` would read a Windows drive over 9p: under WSL,
+/// `path` is on a Windows drive or contains one (`/` or `/mnt`)
+pub fn is_slow_scan_path(path: &Path) -> bool {
+    if !is_wsl() {
+        return false;
+    }
+    let path = std::fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf());
+    reaches_windows_drive(&path)
+}
+
+/// Fail with `InvalidScanPath` when `path` would be slow to scan (see
+/// `is_slow_scan_path`), unless `allow_slow_fs`
+///
+/// The error suggests running the Windows build on the same directory.
+pub fn check_scan_path(path: &Path, allow_slow_fs: bool) -> Result<()> {
+    if allow_slow_fs || !is_slow_scan_path(path) {
+        return Ok(());
+    }
+    let native = windows_path(path)
+        .filter(|windows| !windows.starts_with(r"\\"))
+        .map(|windows| format!("dev-recap.exe --path '{}'", windows))
+        .unwrap_or_else(|| "dev-recap.exe".to_string());
+    Err(DevRecapError::InvalidScanPath {
+        path: path.to_path_buf(),
+        reason: format!(
+            "Windows drives are very slow to scan from WSL; run the Windows build instead \
+             ({}), move the repositories into the Linux filesystem, or pass --allow-slow-fs",
+            native
+        ),
+    })
+}
+
+/// `path` as Windows tools open it, under WSL: `/mnt/c/Users/me/recap.md` is
+/// `C:\Users\me\recap.md`, and a Linux path is reached through
+/// `\\wsl.localhost\<distro>\...`
+///
+/// `None` outside WSL, or for a Linux path when the distribution is unknown.
+pub fn windows_path(path: &Path) -> Option<String> {
+    if !is_wsl() {
+        return None;
+    }
+    let path = if path.is_absolute() {
+        path.to_path_buf()
+    } else {
+        std::env::current_dir().ok()?.join(path)
+    };
+    let distro = std::env::var("WSL_DISTRO_NAME").ok();
+    to_windows_path(&path, distro.as_deref())
+}
+
+/// `path` for a message, followed by its Windows spelling under WSL
+pub fn display_path(path: &Path) -> String {
+    match windows_path(path) {
+        Some(windows) => format!("{} ({})", path.display(), windows),
+        None => path.display().to_string(),
+    }
+}
+
+/// The drive letter of a `/mnt/<letter>` mount, if `component` is one
+fn drive_letter(component: Option<Component>) -> Option<char> {
+    let name = component?.as_os_str().to_str()?;
+    let mut chars = name.chars();
+    match (chars.next(), chars.next()) {
+        (Some(letter), None) if letter.is_ascii_alphabetic() => Some(letter.to_ascii_uppercase()),
+        _ => None,
+    }
+}
+
+/// Whether absolute `path` is a Windows drive mount, inside one, or one of
+/// their parents
+fn reaches_windows_drive(path: &Path) -> bool {
+    if path == Path::new("/") || path == Path::new(MOUNT_ROOT) {
+        return true;
+    }
+    match path.strip_prefix(MOUNT_ROOT) {
+        Ok(rest) => drive_letter(rest.components().next()).is_some(),
+        Err(_) => false,
+    }
+}
+
+/// The Windows spelling of absolute `path`
+fn to_windows_path(path: &Path, distro: Option<&str>) -> Option<String> {
+    if let Ok(rest) = path.strip_prefix(MOUNT_ROOT) {
+        let mut components = rest.components();
+        if let Some(letter) = drive_letter(components.next()) {
+            let rest: PathBuf = components.collect();
+            let rest = rest.to_string_lossy().replace('/', "\\");
+            return Some(format!("{}:\\{}", letter, rest));
+        }
+    }
+    let rest = path.strip_prefix("/").ok()?.to_string_lossy().replace('/', "\\");
+    Some(format!(r"\\wsl.localhost\{}\{}", distro?, rest))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reaches_windows_drive() {
+        assert!(reaches_windows_drive(Path::new("/mnt/c")));
+        assert!(reaches_windows_drive(Path::new("/mnt/d/code/api")));
+        assert!(reaches_windows_drive(Path::new("/mnt")));
+        assert!(reaches_windows_drive(Path::new("/")));
+
+        assert!(!reaches_windows_drive(Path::new("/home/me/code")));
+        assert!(!reaches_windows_drive(Path::new("/mnt/wsl/shared")));
+    }
+
+    #[test]
+    fn test_to_windows_path() {
+        assert_eq!(
+            to_windows_path(Path::new("/mnt/c/Users/me/recap.md"), Some("Ubuntu")).as_deref(),
+            Some(r"C:\Users\me\recap.md")
+        );
+        assert_eq!(to_windows_path(Path::new("/mnt/d"), None).as_deref(), Some(r"D:\"));
+        assert_eq!(
+            to_windows_path(Path::new("/home/me/recap.md"), Some("Ubuntu")).as_deref(),
+            Some(r"\\wsl.localhost\Ubuntu\home\me\recap.md")
+        );
+        assert_eq!(to_windows_path(Path::new("/home/me/recap.md"), None), None);
+    }
+}
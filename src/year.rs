@@ -0,0 +1,338 @@
+//! Year in review (`dev-recap year`)
+//!
+//! A year of commits is split into calendar months (in the local timezone).
+//! Every month with commits gets a short summary across repositories, cached
+//! like repository summaries, and the months are then synthesized into the
+//! trends and milestones of the whole year.
+
+use crate::ai::Summary;
+use crate::error::{DevRecapError, Result};
+use crate::git::{RepoStats, Repository, Timespan};
+use chrono::{DateTime, Datelike, Local, NaiveDate, TimeZone, Utc};
+use std::collections::HashSet;
+use std::path::Path;
+
+/// Section of a monthly summary with the month's notable work
+pub const HIGHLIGHTS: &str = "Highlights";
+
+/// Section of the yearly synthesis on how the work changed over the year
+pub const TRENDS: &str = "Trends";
+
+/// Section of the yearly synthesis with the year's milestones, by month
+pub const MILESTONES: &str = "Notable Milestones";
+
+/// Calendar year `year` in the local timezone, ending now for the current year
+pub fn year_timespan(year: i32) -> Result<Timespan> {
+    let start = local_midnight(year, 1)?;
+    let now = Utc::now();
+    if start > now {
+        return Err(DevRecapError::InvalidTimespan(format!("{} hasn't started yet", year)));
+    }
+    let next_year = year
+        .checked_add(1)
+        .ok_or_else(|| DevRecapError::InvalidTimespan(format!("{} is not a valid year", year)))?;
+    let end = local_midnight(next_year, 1)? - chrono::Duration::seconds(1);
+    Ok(Timespan::from_dates(start, end.min(now)))
+}
+
+/// One month's work across repositories
+#[derive(Debug, Clone)]
+pub struct MonthRecap {
+    /// Year of the month
+    pub year: i32,
+    /// Month number, 1 to 12
+    pub month: u32,
+    /// Repositories with commits this month, holding only this month's
+    /// commits and statistics
+    pub repositories: Vec<Repository>,
+    /// Summary of the month, once generated (none for a month without commits)
+    pub summary: Option<Summary>,
+}
+
+impl MonthRecap {
+    /// "March 2024"
+    pub fn label(&self) -> String {
+        NaiveDate::from_ymd_opt(self.year, self.month, 1)
+            .map(|date| date.format("%B %Y").to_string())
+            .unwrap_or_else(|| format!("{}-{:02}", self.year, self.month))
+    }
+
+    /// Commits this month, across repositories
+    pub fn commits(&self) -> u32 {
+        self.repositories.iter().map(|repo| repo.stats.total_commits).sum()
+    }
+
+    /// Lines added this month
+    pub fn insertions(&self) -> u64 {
+        self.repositories.iter().map(|repo| u64::from(repo.stats.total_insertions)).sum()
+    }
+
+    /// Lines removed this month
+    pub fn deletions(&self) -> u64 {
+        self.repositories.iter().map(|repo| u64::from(repo.stats.total_deletions)).sum()
+    }
+
+    /// PRs referenced this month, counted once per repository
+    pub fn pr_count(&self) -> u32 {
+        self.repositories.iter().map(|repo| repo.stats.pr_count).sum()
+    }
+
+    /// "42 commits, +1200/-300 lines, 5 PRs in api, web"
+    pub fn describe(&self) -> String {
+        let names: Vec<&str> = self.repositories.iter().map(|repo| repo.name.as_str()).collect();
+        format!(
+            "{} commits, +{}/-{} lines, {} PRs in {}",
+            self.commits(),
+            self.insertions(),
+            self.deletions(),
+            self.pr_count(),
+            names.join(", ")
+        )
+    }
+
+    /// What the month's summary is cached by: each repository's path and commits
+    pub fn cache_key_parts(&self) -> Vec<String> {
+        let mut parts = Vec::new();
+        for repo in &self.repositories {
+            parts.push(repo.path.to_string_lossy().into_owned());
+            parts.extend(repo.commits.iter().map(|commit| commit.hash.clone()));
+        }
+        parts
+    }
+}
+
+/// Split repositories analyzed over `year` into its months, up to the
+/// current month for the current year
+///
+/// Months without commits are kept, so gaps show in the report.
+pub fn split_by_month(year: i32, repos: &[Repository]) -> Vec<MonthRecap> {
+    let today = Local::now();
+    let last_month = if year == today.year() { today.month() } else { 12 };
+
+    (1..=last_month)
+        .map(|month| {
+            let repositories = repos
+                .iter()
+                .filter_map(|repo| {
+                    let commits: Vec<_> = repo
+                        .commits
+                        .iter()
+                        .filter(|commit| {
+                            let local = commit.timestamp.with_timezone(&Local);
+                            local.year() == year && local.month() == month
+                        })
+                        .cloned()
+                        .collect();
+                    if commits.is_empty() {
+                        return None;
+                    }
+                    Some(Repository {
+                        stats: RepoStats::from_commits(&commits),
+                        commits,
+                        ..repo.clone()
+                    })
+                })
+                .collect();
+            MonthRecap {
+                year,
+                month,
+                repositories,
+                summary: None,
+            }
+        })
+        .collect()
+}
+
+/// A year in review: monthly summaries and their synthesis
+#[derive(Debug, Clone)]
+pub struct YearRecap {
+    /// The calendar year
+    pub year: i32,
+    /// Every month so far, oldest first
+    pub months: Vec<MonthRecap>,
+    /// Overview, trends and milestones of the year, once generated
+    pub synthesis: Option<Summary>,
+}
+
+impl YearRecap {
+    /// Commits over the year
+    pub fn commits(&self) -> u32 {
+        self.months.iter().map(MonthRecap::commits).sum()
+    }
+
+    /// Lines added over the year
+    pub fn insertions(&self) -> u64 {
+        self.months.iter().map(MonthRecap::insertions).sum()
+    }
+
+    /// Lines removed over the year
+    pub fn deletions(&self) -> u64 {
+        self.months.iter().map(MonthRecap::deletions).sum()
+    }
+
+    /// PRs referenced over the year, counted once per repository even when
+    /// referenced in several months
+    pub fn pr_count(&self) -> u32 {
+        let prs: HashSet<(&Path, u32)> = self
+            .months
+            .iter()
+            .flat_map(|month| &month.repositories)
+            .flat_map(|repo| {
+                repo.commits
+                    .iter()
+                    .flat_map(|commit| &commit.pr_numbers)
+                    .map(|pr| (repo.path.as_path(), *pr))
+            })
+            .collect();
+        prs.len() as u32
+    }
+
+    /// Names of the repositories with commits during the year
+    pub fn repositories(&self) -> Vec<&str> {
+        let mut seen = HashSet::new();
+        self.months
+            .iter()
+            .flat_map(|month| &month.repositories)
+            .map(|repo| repo.name.as_str())
+            .filter(|name| seen.insert(*name))
+            .collect()
+    }
+
+    /// Month with the most commits (the earliest on a tie)
+    pub fn busiest_month(&self) -> Option<&MonthRecap> {
+        self.months
+            .iter()
+            .filter(|month| month.commits() > 0)
+            .rev()
+            .max_by_key(|month| month.commits())
+    }
+
+    /// The year in review as markdown: totals, the synthesis, then month by month
+    pub fn to_markdown(&self) -> String {
+        let mut output = format!("# {} in Review\n\n", self.year);
+
+        output.push_str("## Totals\n\n");
+        output.push_str(&format!("- **Commits:** {}\n", self.commits()));
+        output.push_str(&format!("- **Lines:** +{} / -{}\n", self.insertions(), self.deletions()));
+        output.push_str(&format!("- **PRs:** {}\n", self.pr_count()));
+        output.push_str(&format!("- **Repositories:** {}\n", self.repositories().join(", ")));
+        let active = self.months.iter().filter(|month| month.commits() > 0).count();
+        output.push_str(&format!("- **Active months:** {} of {}\n", active, self.months.len()));
+        if let Some(busiest) = self.busiest_month() {
+            output.push_str(&format!(
+                "- **Busiest month:** {} ({} commits)\n",
+                busiest.label(),
+                busiest.commits()
+            ));
+        }
+        output.push('\n');
+
+        if let Some(ref synthesis) = self.synthesis {
+            output.push_str("## The Year\n\n");
+            output.push_str(&synthesis.work_summary);
+            output.push_str("\n\n");
+            for section in synthesis.sections.iter().filter(|section| !section.items.is_empty()) {
+                output.push_str(&section.to_markdown());
+            }
+        }
+
+        output.push_str("## Month by Month\n\n");
+        for month in &self.months {
+            output.push_str(&format!("### {}\n\n", month.label()));
+            if month.repositories.is_empty() {
+                output.push_str("No commits.\n\n");
+                continue;
+            }
+            output.push_str(&format!("*{}*\n\n", month.describe()));
+            if let Some(ref summary) = month.summary {
+                output.push_str(&summary.work_summary);
+                output.push_str("\n\n");
+                for highlight in summary.section(HIGHLIGHTS) {
+                    output.push_str(&format!("- {}\n", highlight));
+                }
+                if !summary.section(HIGHLIGHTS).is_empty() {
+                    output.push('\n');
+                }
+            }
+        }
+
+        output
+    }
+}
+
+/// Midnight on the first of `month` in the local timezone, as UTC
+fn local_midnight(year: i32, month: u32) -> Result<DateTime<Utc>> {
+    Local
+        .with_ymd_and_hms(year, month, 1, 0, 0, 0)
+        .earliest()
+        .map(|start| start.with_timezone(&Utc))
+        .ok_or_else(|| DevRecapError::InvalidTimespan(format!("{} is not a valid year", year)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::git::Commit;
+
+    fn commit(date: &str, lines: u32, prs: &[u32]) -> Commit {
+        let timestamp = Local
+            .from_local_datetime(&NaiveDate::parse_from_str(date, "%Y-%m-%d").unwrap().and_hms_opt(12, 0, 0).unwrap())
+            .unwrap();
+        Commit {
+            short_hash: date.to_string(),
+            timestamp: timestamp.fixed_offset(),
+            pr_numbers: prs.to_vec(),
+            ..Commit::test(&format!("{}-hash", date), &format!("Work on {}", date)).with_files(&["src/lib.rs"], lines)
+        }
+    }
+
+    #[test]
+    fn test_split_by_month() {
+        let repos = vec![
+            Repository::test("api", vec![commit("2023-01-10", 10, &[1]), commit("2023-03-02", 20, &[2]), commit("2023-03-30", 5, &[2])]),
+            Repository::test("web", vec![commit("2023-03-15", 100, &[])]),
+        ];
+        let recap = YearRecap {
+            year: 2023,
+            months: split_by_month(2023, &repos),
+            synthesis: None,
+        };
+
+        assert_eq!(recap.months.len(), 12);
+        let march = &recap.months[2];
+        assert_eq!(march.label(), "March 2023");
+        assert_eq!(march.commits(), 3);
+        assert_eq!(march.describe(), "3 commits, +125/-0 lines, 1 PRs in api, web");
+        assert!(recap.months[1].repositories.is_empty());
+
+        assert_eq!(recap.commits(), 4);
+        assert_eq!(recap.pr_count(), 2);
+        assert_eq!(recap.repositories(), ["api", "web"]);
+        assert_eq!(recap.busiest_month().map(MonthRecap::label).as_deref(), Some("March 2023"));
+
+        let markdown = recap.to_markdown();
+        assert!(markdown.starts_with("# 2023 in Review\n\n## Totals\n"));
+        assert!(markdown.contains("- **Active months:** 2 of 12\n"));
+        assert!(markdown.contains("### February 2023\n\nNo commits.\n"));
+
+        // A PR referenced in two months is one PR of the year
+        let repos = vec![Repository::test("api", vec![commit("2023-01-10", 10, &[7]), commit("2023-02-10", 10, &[7])])];
+        let recap = YearRecap {
+            year: 2023,
+            months: split_by_month(2023, &repos),
+            synthesis: None,
+        };
+        assert_eq!(recap.months[0].pr_count() + recap.months[1].pr_count(), 2);
+        assert_eq!(recap.pr_count(), 1);
+        assert!(recap.to_markdown().contains("- **PRs:** 1\n"));
+    }
+
+    #[test]
+    fn test_year_timespan() {
+        let timespan = year_timespan(2023).unwrap();
+        assert_eq!(timespan.start.with_timezone(&Local).ordinal(), 1);
+        assert_eq!(timespan.end.with_timezone(&Local).month(), 12);
+        assert!(year_timespan(Local::now().year() + 1).is_err());
+        assert!(year_timespan(i32::MAX).is_err());
+    }
+}